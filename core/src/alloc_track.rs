@@ -0,0 +1,70 @@
+//! Allocation Counting (feature `alloc-tracking`)
+//!
+//! `CountingAlloc` forwards to the system allocator and counts allocations
+//! made by the current thread, so tests running in parallel don't see each
+//! other's. A test binary installs it and measures a closure:
+//!
+//! ```ignore
+//! use gonhanh_core::alloc_track::{allocations, CountingAlloc};
+//!
+//! #[global_allocator]
+//! static ALLOC: CountingAlloc = CountingAlloc;
+//!
+//! let n = allocations(|| engine.on_key(keys::A, false, false));
+//! ```
+//!
+//! Nothing is compiled without the feature; the library never installs the
+//! allocator itself.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// System allocator that counts allocations per thread
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        bump();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        bump();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        bump();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn bump() {
+    // try_with: the slot may be gone while the thread shuts down
+    let _ = COUNT.try_with(|c| c.set(c.get() + 1));
+}
+
+/// Allocations (incl. reallocations) made so far by this thread
+pub fn count() -> usize {
+    COUNT.try_with(Cell::get).unwrap_or(0)
+}
+
+/// Allocations `f` makes on this thread
+///
+/// Only meaningful when `CountingAlloc` is the global allocator; otherwise
+/// always 0.
+pub fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = count();
+    let out = f();
+    let n = count() - before;
+    drop(out);
+    n
+}
@@ -0,0 +1,64 @@
+//! Stable Public API
+//!
+//! The supported surface for Rust integrations. Everything re-exported here
+//! follows semver: breaking changes only in a major version. Other modules
+//! (`engine`, `data`, `input`, `utils`) are implementation details kept public
+//! for the FFI layer and tests; they may change in any release.
+//!
+//! ## Example
+//! ```
+//! use gonhanh_core::api::{Action, Engine};
+//!
+//! let mut engine = Engine::new();
+//! engine.set_method(0); // Telex
+//! let result = engine.on_key(gonhanh_core::api::keys::A, false, false);
+//! assert_eq!(result.action, Action::None as u8);
+//! ```
+
+// Engine + results
+pub use crate::engine::edit::EditOp;
+pub use crate::engine::{
+    Action, Engine, Result, FLAG_KEY_CONSUMED, FLAG_NEW_UNDO_GROUP, FLAG_RESTORE,
+};
+
+// Behavior versions
+pub use crate::engine::behavior::{
+    behavior_changelog, engine_behavior_version, BehaviorChange, BEHAVIOR_CHANGES,
+};
+
+// Events and status
+pub use crate::engine::context::{ContextSync, ResetPolicy, ScreenCheck};
+pub use crate::engine::english::{
+    english_confidence, ConfidenceTier, EnglishConfidence, HIGH_THRESHOLD, MEDIUM_THRESHOLD,
+};
+pub use crate::engine::field::FieldKind;
+pub use crate::engine::log::{LogLevel, LogSink};
+pub use crate::engine::memory::{memory_report, MemoryReport};
+pub use crate::engine::observer::{EngineObserver, RevertKind};
+pub use crate::engine::output::{BadOverrideLine, OutputMethod, OutputRegistry, OutputStrategy};
+pub use crate::engine::profile::{Config, Profile, DEFAULT_PROFILE};
+pub use crate::engine::restore::{RestoreInfo, RestoreReason};
+pub use crate::engine::spell::SpellFlag;
+pub use crate::engine::status::{DisableReason, EngineStatus, InputMode};
+pub use crate::engine::timing::KeyTiming;
+pub use crate::engine::track::{AppliedTransform, TransformKind};
+pub use crate::engine::transform::ToneKeyPolicy;
+pub use crate::engine::validation::ValidationProfile;
+
+// Shortcuts
+pub use crate::engine::shortcut::{
+    CaseMode, InputMethod, Shortcut, ShortcutTable, TriggerCondition,
+};
+pub use crate::engine::shortcut_pack::{pack as pack_shortcuts, PackError, PackedShortcuts};
+
+// Key codes expected by `Engine::on_key*`
+pub use crate::data::keys;
+
+// Text utilities
+pub use crate::data::chars::mark;
+pub use crate::data::legacy::{detect as detect_legacy_encoding, LegacyEncoding};
+pub use crate::text::analysis::{analyze_text, rhyme, tone_class, Span, SpanKind, ToneClass};
+pub use crate::text::diacritics::{mark_of, with_mark, without_mark};
+pub use crate::text::tutor::{keys_for, KeyHint};
+pub use crate::text::{collate, correct_sentence, remove_diacritics, transform_text, Direction};
+pub use crate::utils::{foreign_final_tone_position, tone_position, ForeignFinalTone};
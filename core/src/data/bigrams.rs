@@ -0,0 +1,122 @@
+//! Letter Bigram Language Model
+//!
+//! Log-likelihood ratios of letter bigrams in English words vs raw Telex
+//! keystrokes of Vietnamese syllables. Scores words the dictionaries don't
+//! know and tips dictionary ties ("can", "ban") by how the letters run.
+//!
+//! ## Table
+//! `LOG_RATIO[a][b]` = SCALE × (ln P_en(b | a) − ln P_vn(b | a)), rounded,
+//! over 26 letters plus a word boundary (index 26: start as `a`, end as `b`).
+//! Estimated from the top 20k alphabetic words of tests/data/english_100k.txt
+//! and the Telex side of tests/data/vietnamese_telex_pairs.txt, add-0.5
+//! smoothing. Positive = English-like.
+
+/// Table units per nat
+pub const SCALE: i32 = 4;
+
+/// Word boundary row/column
+const BOUNDARY: usize = 26;
+
+#[rustfmt::skip]
+pub(crate) static LOG_RATIO: [[i8; 27]; 27] = [
+    // a
+    [-19, 27, 4, 0, 17, -8, 7, 18, 4, -17, 22, 33, -1, -1, -7, 2, 12, 4, -2, 7, -2, 24, -10, -8, -4, 18, -4],
+    // b
+    [-3, 16, 9, 10, 1, -2, 4, 2, 1, 12, 2, 24, 10, 10, -3, 2, -2, 22, 19, 13, -5, 7, 4, -2, 14, -2, 17],
+    // c
+    [2, 7, 21, -11, 6, 3, 6, -5, 27, -23, 24, 24, 7, 7, 3, 9, 12, 25, -10, 28, -2, 3, -22, -1, 21, 7, -3],
+    // d
+    [-5, 14, -8, -8, 4, -11, -3, -4, 3, -9, 8, 24, -2, -9, -5, -6, 8, 4, 1, -6, -6, 22, -8, -18, 5, 3, 6],
+    // e
+    [27, 18, 0, 4, -8, -7, 1, -9, 21, -19, 13, 28, -1, -1, -2, 0, 17, 5, 1, 1, -10, 23, 20, -1, 19, 13, 0],
+    // f
+    [4, 9, 11, -14, 4, 28, -15, -17, 7, 5, 9, 27, -12, -18, 4, 5, 5, 27, 18, 25, 6, 5, -20, 5, 1, 5, -8],
+    // g
+    [0, 8, 8, -12, 10, -16, 23, 3, 0, -21, 10, 25, 20, 25, -4, 10, 4, 4, -2, 19, -1, 4, -22, -20, 22, 4, 0],
+    // h
+    [-1, 17, 14, -5, 3, -7, 8, 12, 2, -19, 4, 21, 21, 20, -1, 12, 11, 5, -2, 27, -7, 8, 18, -14, 22, 12, -1],
+    // i
+    [0, 21, 7, -1, -8, -4, 23, 7, 12, -19, 14, 21, 6, 7, 2, 7, 12, -1, 2, 10, -9, 20, -24, -8, -3, 20, -11],
+    // j
+    [5, 17, -10, -9, 6, 12, -13, -6, 1, 17, 12, 17, -7, -12, 6, -8, 12, 17, 17, -15, 11, 12, -17, 12, -6, 12, -8],
+    // k
+    [15, 9, 0, 7, 4, 9, 8, -16, 0, 0, 0, 17, 8, 18, 14, 7, 0, 12, 22, 8, 12, 5, 11, 0, 5, 0, 24],
+    // l
+    [-3, 9, 10, 17, 3, 12, 9, 4, 1, -2, 11, 23, 12, 7, -4, 11, -6, 5, 18, 18, -9, 12, 4, -6, 15, -2, 20],
+    // m
+    [1, 23, 12, -16, 5, -13, 4, 8, 6, -19, 4, 13, 23, 15, 1, 27, 0, -12, -4, 8, -3, 4, -18, -21, 13, 0, -5],
+    // n
+    [2, 15, 30, 3, 3, -6, -3, -14, 9, -12, 22, 20, 18, 25, -2, 15, 17, -11, 3, 34, -1, 23, -15, -16, 21, 15, 2],
+    // o
+    [-5, 25, 0, -2, 1, -8, 0, 19, -5, -17, 23, 31, 3, 2, -5, 3, 11, 6, -3, 2, 15, 26, -5, -6, 22, 15, -6],
+    // p
+    [4, 8, 6, -12, 7, 5, 5, -7, 15, -23, 2, 24, 10, 6, 5, 22, -3, 26, -6, 22, 16, 2, -19, -3, 13, 2, -7],
+    // q
+    [3, 3, 3, 3, 3, 3, 3, 3, 9, 3, 3, 7, 3, 3, 3, 3, 3, 3, 7, 3, 0, 3, 3, 3, 3, 3, 13],
+    // r
+    [0, 18, 22, 0, 5, 16, -5, -7, 3, 4, 19, 19, 0, -7, -1, 18, 5, 23, 26, 25, -7, 19, -12, 6, 4, -2, -3],
+    // s
+    [-3, 14, -2, -13, 2, 17, -14, 3, 4, 4, 19, 22, -2, -15, -3, 1, 15, 14, 28, 4, -1, 7, -11, -1, 1, -1, 1],
+    // t
+    [1, 10, 18, -12, 6, 12, 10, -5, 8, -25, 4, 21, 15, 13, 0, 10, -2, -3, -2, 23, -4, 2, -11, 2, 16, 10, -1],
+    // u
+    [-2, 26, 5, 0, 0, -6, 25, 12, 6, -19, 15, 31, 9, 2, -14, 10, 8, 7, 4, 9, -7, 16, -24, -5, -12, 15, -7],
+    // v
+    [-3, -2, 2, 2, 6, -2, -2, -2, 4, -2, -2, 2, -2, -2, -4, 2, -2, 2, 2, 4, -17, -2, -2, -2, 8, -2, 10],
+    // w
+    [12, 20, -8, -7, 34, -10, -21, 29, 8, -20, 18, 25, -7, -4, -2, -6, 9, 0, -2, -6, -4, 9, 16, -16, 20, 9, -4],
+    // x
+    [-2, 7, 26, -14, 1, 12, -16, 2, 5, 7, 7, 15, -9, -19, -7, 29, 12, 7, 7, 27, -6, 18, -13, 19, 1, 7, -3],
+    // y
+    [-9, 9, 4, -4, -8, -15, 6, 4, 17, -21, -2, 15, 17, 1, 15, 15, -2, -7, -1, 3, -1, -2, 9, -20, -2, 8, 4],
+    // z
+    [7, -13, -13, -13, 9, -13, -9, -5, 4, -13, -13, -3, -13, -9, 1, -13, -13, -9, -9, -9, -1, -9, -9, -13, -2, 0, 4],
+    // ^$
+    [9, 0, 1, -5, 10, 31, -2, -1, 14, 26, -6, -2, 2, -6, 3, 5, -6, 2, 5, -4, 2, -3, 29, -14, 2, 18, 2],
+];
+
+/// Letter index a=0..z=25 for an ASCII letter
+fn index(b: u8) -> Option<usize> {
+    b.is_ascii_alphabetic()
+        .then(|| (b.to_ascii_lowercase() - b'a') as usize)
+}
+
+/// Mean per-bigram log-likelihood ratio (English over Vietnamese Telex) of a
+/// word, in millinats; None unless the word is all ASCII letters
+pub fn english_log_ratio(word: &str) -> Option<i32> {
+    if word.is_empty() {
+        return None;
+    }
+    let mut prev = BOUNDARY;
+    let mut sum = 0i32;
+    for b in word.bytes() {
+        let cur = index(b)?;
+        sum += LOG_RATIO[prev][cur] as i32;
+        prev = cur;
+    }
+    sum += LOG_RATIO[prev][BOUNDARY] as i32;
+    let bigrams = word.len() as i32 + 1;
+    Some(sum * 1000 / (bigrams * SCALE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_vs_telex() {
+        for w in ["hello", "text", "through", "which"] {
+            assert!(english_log_ratio(w).unwrap() > 0, "{w}");
+        }
+        for w in ["vieetj", "nguwowif", "tooi", "dduowcj"] {
+            assert!(english_log_ratio(w).unwrap() < 0, "{w}");
+        }
+    }
+
+    #[test]
+    fn test_non_words() {
+        assert_eq!(english_log_ratio(""), None);
+        assert_eq!(english_log_ratio("v1"), None);
+        assert_eq!(english_log_ratio("Text"), english_log_ratio("text"));
+    }
+}
@@ -35,7 +35,7 @@ pub mod mark {
 
 /// Vietnamese vowel lookup table
 /// Each entry: (base_char, [sắc, huyền, hỏi, ngã, nặng])
-const VOWEL_TABLE: [(char, [char; 5]); 12] = [
+pub(crate) const VOWEL_TABLE: [(char, [char; 5]); 12] = [
     ('a', ['á', 'à', 'ả', 'ã', 'ạ']),
     ('ă', ['ắ', 'ằ', 'ẳ', 'ẵ', 'ặ']),
     ('â', ['ấ', 'ầ', 'ẩ', 'ẫ', 'ậ']),
@@ -58,7 +58,7 @@ const VOWEL_TABLE: [(char, [char; 5]); 12] = [
 ///
 /// # Returns
 /// Base vowel character: a, ă, â, e, ê, i, o, ô, ơ, u, ư, y
-fn get_base_char(key: u16, t: u8) -> Option<char> {
+pub fn base_char(key: u16, t: u8) -> Option<char> {
     match key {
         keys::A => Some(match t {
             tone::CIRCUMFLEX => 'â',
@@ -124,7 +124,7 @@ pub fn to_char(key: u16, caps: bool, tone: u8, mark: u8) -> Option<char> {
         return Some(if caps { 'D' } else { 'd' });
     }
 
-    let base = get_base_char(key, tone)?;
+    let base = base_char(key, tone)?;
     let marked = apply_mark(base, mark);
     Some(if caps { to_upper(marked) } else { marked })
 }
@@ -138,6 +138,41 @@ pub fn get_d(caps: bool) -> char {
     }
 }
 
+// ============================================================
+// COMBINING MARKS (NFD text)
+// ============================================================
+
+/// Diacritic a combining char adds to the letter before it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Combining {
+    /// Vowel modifier (`tone::CIRCUMFLEX`, `tone::HORN`)
+    Tone(u8),
+    /// Tone mark (`mark::SAC`..`mark::NANG`)
+    Mark(u8),
+}
+
+/// Vietnamese meaning of a combining char ('\u{0302}' → circumflex)
+///
+/// Breve and horn both map to `tone::HORN`, as in the buffer.
+pub fn combining(c: char) -> Option<Combining> {
+    Some(match c {
+        '\u{0302}' => Combining::Tone(tone::CIRCUMFLEX),
+        '\u{0306}' | '\u{031B}' => Combining::Tone(tone::HORN),
+        '\u{0301}' => Combining::Mark(mark::SAC),
+        '\u{0300}' => Combining::Mark(mark::HUYEN),
+        '\u{0309}' => Combining::Mark(mark::HOI),
+        '\u{0303}' => Combining::Mark(mark::NGA),
+        '\u{0323}' => Combining::Mark(mark::NANG),
+        _ => return None,
+    })
+}
+
+/// Combining diacritical marks block (U+0300–U+036F)
+#[inline]
+pub fn is_combining(c: char) -> bool {
+    ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
 // ============================================================
 // REVERSE PARSING: Vietnamese char → buffer components
 // ============================================================
@@ -552,4 +587,55 @@ mod tests {
             assert_eq!((p.key, p.tone, p.mark), (key, t, m), "Failed for '{}'", ch);
         }
     }
+
+    #[test]
+    fn test_forward_and_reverse_tables_agree() {
+        // Every component combination to_char accepts parses back to itself
+        for key in [keys::A, keys::E, keys::I, keys::O, keys::U, keys::Y] {
+            for caps in [false, true] {
+                for t in [tone::NONE, tone::CIRCUMFLEX, tone::HORN] {
+                    for m in mark::NONE..=mark::NANG {
+                        let ch = to_char(key, caps, t, m).unwrap();
+                        let p = parse_char(ch).unwrap();
+                        assert_eq!((p.key, p.caps, p.mark), (key, caps, m), "{ch}");
+                        // Modifiers a vowel can't take are dropped (breve on 'e')
+                        assert_eq!(base_char(key, p.tone), base_char(key, t), "{ch}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_parsed_char_composes_back() {
+        // And every char parse_char knows comes back out of to_char/get_d
+        let mut vowels = 0;
+        for ch in (0..0x2000).filter_map(char::from_u32) {
+            let Some(p) = parse_char(ch) else { continue };
+            let back = if p.stroke {
+                get_d(p.caps)
+            } else if keys::is_vowel(p.key) {
+                vowels += 1;
+                to_char(p.key, p.caps, p.tone, p.mark).unwrap()
+            } else {
+                crate::utils::key_to_char(p.key, p.caps).unwrap()
+            };
+            assert_eq!(back, ch, "{:?}", p);
+        }
+        // 12 bases × 6 marks × 2 cases
+        assert_eq!(vowels, 144);
+    }
+
+    #[test]
+    fn test_combining() {
+        assert_eq!(
+            combining('\u{0302}'),
+            Some(Combining::Tone(tone::CIRCUMFLEX))
+        );
+        assert_eq!(combining('\u{0306}'), Some(Combining::Tone(tone::HORN)));
+        assert_eq!(combining('\u{0323}'), Some(Combining::Mark(mark::NANG)));
+        assert_eq!(combining('\u{0308}'), None);
+        assert!(is_combining('\u{0308}'));
+        assert!(!is_combining('a'));
+    }
 }
@@ -77,6 +77,88 @@ pub const VALID_FINALS_2: &[[u16; 2]] = &[
     [keys::N, keys::H], // nh
 ];
 
+/// Initials borrowed from ethnic minority languages (Krông), rejected by the
+/// strict validation profile
+pub const MINORITY_INITIALS_2: &[[u16; 2]] = &[[keys::K, keys::R]];
+
+/// Finals borrowed from ethnic minority languages (Đắk), rejected by the
+/// strict validation profile
+pub const MINORITY_FINALS_1: &[u16] = &[keys::K];
+
+// =============================================================================
+// CONSONANT PAIR BITSETS
+// =============================================================================
+
+/// Letter index a=0..z=25 for a keycode (keycodes follow the keyboard layout,
+/// not the alphabet), or None for non-letters
+const fn letter_index(key: u16) -> Option<usize> {
+    let idx = match key {
+        keys::A => 0,
+        keys::B => 1,
+        keys::C => 2,
+        keys::D => 3,
+        keys::E => 4,
+        keys::F => 5,
+        keys::G => 6,
+        keys::H => 7,
+        keys::I => 8,
+        keys::J => 9,
+        keys::K => 10,
+        keys::L => 11,
+        keys::M => 12,
+        keys::N => 13,
+        keys::O => 14,
+        keys::P => 15,
+        keys::Q => 16,
+        keys::R => 17,
+        keys::S => 18,
+        keys::T => 19,
+        keys::U => 20,
+        keys::V => 21,
+        keys::W => 22,
+        keys::X => 23,
+        keys::Y => 24,
+        keys::Z => 25,
+        _ => return None,
+    };
+    Some(idx)
+}
+
+/// 26×26 bitset of letter pairs: row = first letter, bit = second letter
+///
+/// O(1) replacement for `.contains(&[a, b])` scans over pair tables.
+pub struct PairSet([u32; 26]);
+
+impl PairSet {
+    /// Build from a pair table at compile time
+    pub const fn from_pairs(pairs: &[[u16; 2]]) -> Self {
+        let mut rows = [0u32; 26];
+        let mut i = 0;
+        while i < pairs.len() {
+            if let (Some(a), Some(b)) = (letter_index(pairs[i][0]), letter_index(pairs[i][1])) {
+                rows[a] |= 1 << b;
+            }
+            i += 1;
+        }
+        Self(rows)
+    }
+
+    /// Check if (first, second) is in the set
+    #[inline]
+    pub fn contains(&self, first: u16, second: u16) -> bool {
+        match (letter_index(first), letter_index(second)) {
+            (Some(a), Some(b)) => self.0[a] & (1 << b) != 0,
+            _ => false,
+        }
+    }
+}
+
+/// `VALID_INITIALS_2` as a bitset
+pub static INITIAL_PAIRS: PairSet = PairSet::from_pairs(VALID_INITIALS_2);
+
+/// `VALID_FINALS_2` as a bitset
+pub static FINAL_PAIRS: PairSet = PairSet::from_pairs(VALID_FINALS_2);
+
 // =============================================================================
 // VALID VOWEL PATTERNS (Whitelist from docs 7.6.1)
 // =============================================================================
@@ -153,6 +235,136 @@ pub const V2_CIRCUMFLEX_REQUIRED: &[[u16; 2]] = &[
     [keys::Y, keys::E], // yê: E (V2) must have circumflex
 ];
 
+/// Triphthongs whose middle 'a' takes a breve though a vowel follows it
+/// (ă is otherwise never followed by a vowel)
+pub const BREVE_TRIPHTHONGS: &[[u16; 3]] = &[
+    [keys::O, keys::A, keys::O], // oăo (ngoằo)
+];
+
+/// Finals that cannot follow breve (ă)
+/// ăc, ăm, ăn, ăng, ăp, ăt exist; ăch and ănh don't
+pub const BREVE_INVALID_FINALS: &[[u16; 2]] = &[
+    [keys::C, keys::H], // ăch ✗
+    [keys::N, keys::H], // ănh ✗
+];
+
+// =============================================================================
+// TONE × CODA COMPATIBILITY
+// =============================================================================
+
+/// Class of a syllable final for tone compatibility
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Coda {
+    /// No final consonant (ma, mai)
+    Open,
+    /// Nasal final: m, n, ng, nh
+    Sonorant,
+    /// Stop final: p, t, c, ch, k (checked syllable)
+    Stop,
+}
+
+impl Coda {
+    /// Classify final consonant keys
+    pub fn of(final_keys: &[u16]) -> Self {
+        match final_keys {
+            [] => Self::Open,
+            [keys::P | keys::T | keys::C | keys::K] | [keys::C, keys::H] => Self::Stop,
+            _ => Self::Sonorant,
+        }
+    }
+}
+
+/// Allowed marks per coda class, indexed by mark value
+/// (ngang, sắc, huyền, hỏi, ngã, nặng)
+///
+/// Checked syllables only take sắc/nặng ("bét", "bẹt"; never "bèt", "hõp").
+/// Ngang on a stop is tolerated so untoned input ("bat", "kêp") is left to
+/// the auto-restore rules rather than rejected here.
+pub const TONE_CODA_MATRIX: [[bool; 6]; 3] = [
+    [true, true, true, true, true, true],    // Open
+    [true, true, true, true, true, true],    // Sonorant
+    [true, true, false, false, false, true], // Stop
+];
+
+/// Check if a mark may sit on a syllable with the given final
+pub fn is_tone_allowed(final_keys: &[u16], mark: u8) -> bool {
+    let row = match Coda::of(final_keys) {
+        Coda::Open => 0,
+        Coda::Sonorant => 1,
+        Coda::Stop => 2,
+    };
+    TONE_CODA_MATRIX[row]
+        .get(mark as usize)
+        .copied()
+        .unwrap_or(false)
+}
+
+// =============================================================================
+// HORN ON U+O COMPOUND
+// =============================================================================
+
+/// Where the horn key goes on an adjacent u+o (or o+u) pair
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UoHorn {
+    /// Not a compound here; use normal horn target selection
+    Phonology,
+    /// Only the second vowel gets the horn
+    Second,
+    /// Horn on 'o' now, on 'u' once a final or glide follows ("duơ" → "dược")
+    Deferred,
+    /// Both vowels get the horn (ươ)
+    Both,
+}
+
+/// Context of an u+o pair when the horn key is typed
+#[derive(Clone, Copy, Debug)]
+pub struct UoContext {
+    /// 'u' follows 'q' (u belongs to the initial)
+    pub qu_initial: bool,
+    /// 'a' follows the pair ("quoa" → "quoă")
+    pub a_after: bool,
+    /// Pair is u+o (not o+u)
+    pub is_uo: bool,
+    /// Any char (final consonant or glide) follows the pair
+    pub has_final: bool,
+}
+
+/// Horn rule: (qu_initial, a_after, is_uo, has_final) → placement
+/// `None` matches either value; the first matching rule wins.
+pub type UoHornRule = (
+    Option<bool>,
+    Option<bool>,
+    Option<bool>,
+    Option<bool>,
+    UoHorn,
+);
+
+/// Horn placement on u+o, in priority order
+pub const UO_HORN_RULES: &[UoHornRule] = &[
+    // quoắt: qu + oă, the key is the breve on 'a'
+    (Some(true), Some(true), None, None, UoHorn::Phonology),
+    // quớ, quới: 'u' is part of the initial
+    (Some(true), None, None, None, UoHorn::Second),
+    // huơ, khuơ: open syllable; dược, hươu once a final/glide follows
+    (None, None, Some(true), Some(false), UoHorn::Deferred),
+    // ươ with final or glide: được, người, hươu
+    (None, None, None, None, UoHorn::Both),
+];
+
+/// Look up horn placement for an u+o pair
+pub fn uo_horn(ctx: UoContext) -> UoHorn {
+    let fits = |want: Option<bool>, have: bool| want.is_none_or(|w| w == have);
+    UO_HORN_RULES
+        .iter()
+        .find(|&&(qu, a, uo, fin, _)| {
+            fits(qu, ctx.qu_initial)
+                && fits(a, ctx.a_after)
+                && fits(uo, ctx.is_uo)
+                && fits(fin, ctx.has_final)
+        })
+        .map_or(UoHorn::Both, |rule| rule.4)
+}
+
 // =============================================================================
 // SPELLING RULES
 // =============================================================================
@@ -215,6 +427,16 @@ pub const OPEN_DIPHTHONGS: &[[u16; 2]] = &[
     [keys::U, keys::U], // ưu - ends with semi-vowel U
 ];
 
+/// Doubled circumflex vowel closed by a final with no tone after it → English
+/// (nucleus, final, preceding keys that make it Vietnamese instead)
+///
+/// Typing "keep" gives "kêp", which reads as English at word boundary.
+/// "aa"/"oo" + p stay Vietnamese (cấp = caaps), so only listed pairs restore.
+pub const CIRCUMFLEX_CLOSED_ENGLISH: &[(u16, u16, &[u16])] = &[
+    // keep, deep, sleep, seep; not iêp (nghiệp, hiệp) or xêp (xếp)
+    (keys::E, keys::P, &[keys::I, keys::X]),
+];
+
 /// Common Vietnamese single-vowel interjections (should NOT be restored)
 /// These standalone vowels with tone marks are valid Vietnamese words
 /// Example: à (ah), ồ (oh!), ừ (yeah)
@@ -264,3 +486,78 @@ pub const COMMON_CIRCUMFLEX_NO_FINAL: &[u16] = &[
 pub const UNCOMMON_CIRCUMFLEX_NO_FINAL: &[u16] = &[
     keys::F, // fê - F is invalid initial anyway
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LETTERS: [u16; 26] = [
+        keys::A,
+        keys::B,
+        keys::C,
+        keys::D,
+        keys::E,
+        keys::F,
+        keys::G,
+        keys::H,
+        keys::I,
+        keys::J,
+        keys::K,
+        keys::L,
+        keys::M,
+        keys::N,
+        keys::O,
+        keys::P,
+        keys::Q,
+        keys::R,
+        keys::S,
+        keys::T,
+        keys::U,
+        keys::V,
+        keys::W,
+        keys::X,
+        keys::Y,
+        keys::Z,
+    ];
+
+    #[test]
+    fn tone_coda_matrix() {
+        use crate::data::chars::mark;
+        assert!(is_tone_allowed(&[], mark::HUYEN));
+        assert!(is_tone_allowed(&[keys::N, keys::G], mark::NGA));
+        assert!(is_tone_allowed(&[keys::T], mark::SAC));
+        assert!(is_tone_allowed(&[keys::C, keys::H], mark::NANG));
+        assert!(!is_tone_allowed(&[keys::T], mark::HUYEN)); // bèt
+        assert!(!is_tone_allowed(&[keys::P], mark::NGA)); // hõp
+        assert!(!is_tone_allowed(&[keys::C, keys::H], mark::HOI));
+    }
+
+    #[test]
+    fn uo_horn_rules() {
+        let ctx = |qu_initial, a_after, is_uo, has_final| UoContext {
+            qu_initial,
+            a_after,
+            is_uo,
+            has_final,
+        };
+        assert_eq!(uo_horn(ctx(false, false, true, false)), UoHorn::Deferred); // huơ
+        assert_eq!(uo_horn(ctx(false, false, true, true)), UoHorn::Both); // dược
+        assert_eq!(uo_horn(ctx(true, false, true, true)), UoHorn::Second); // quới
+        assert_eq!(uo_horn(ctx(true, true, true, true)), UoHorn::Phonology); // quoắt
+        assert_eq!(uo_horn(ctx(false, false, false, false)), UoHorn::Both);
+    }
+
+    #[test]
+    fn pair_sets_match_tables() {
+        for a in LETTERS {
+            for b in LETTERS {
+                assert_eq!(
+                    INITIAL_PAIRS.contains(a, b),
+                    VALID_INITIALS_2.contains(&[a, b])
+                );
+                assert_eq!(FINAL_PAIRS.contains(a, b), VALID_FINALS_2.contains(&[a, b]));
+            }
+        }
+        assert!(!FINAL_PAIRS.contains(keys::N, keys::SPACE));
+    }
+}
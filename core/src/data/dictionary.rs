@@ -3,25 +3,36 @@
 //! Uses HashSet-based word lookup for efficient Vietnamese word validation.
 //! Memory-efficient: ~0.5MB vs ~5.5MB with full Hunspell implementation.
 
+#[cfg(feature = "std")]
 use std::collections::HashSet;
+#[cfg(feature = "std")]
 use std::sync::LazyLock;
 
 // Embed dictionary files into binary
-const DIC_VI: &str = include_str!("dictionaries/vi.dic");
-const DIC_KEEP: &str = include_str!("dictionaries/keep.dic");
+pub(crate) const DIC_VI: &str = include_str!("dictionaries/vi.dic");
+pub(crate) const DIC_KEEP: &str = include_str!("dictionaries/keep.dic");
 
 /// Parse .dic file into HashSet (skip first line which is word count)
+#[cfg(feature = "std")]
 fn parse_dic_to_hashset(dic_content: &'static str) -> HashSet<&'static str> {
     dic_content.lines().skip(1).collect()
 }
 
 /// Lazy-loaded Vietnamese dictionary - ~0.5MB memory
+#[cfg(feature = "std")]
 static DICT_VI: LazyLock<HashSet<&'static str>> = LazyLock::new(|| parse_dic_to_hashset(DIC_VI));
 
 /// Lazy-loaded keep list - words that should not be auto-restored
+#[cfg(feature = "std")]
 static DICT_KEEP: LazyLock<HashSet<&'static str>> =
     LazyLock::new(|| parse_dic_to_hashset(DIC_KEEP));
 
+/// Linear scan of a .dic file (no_std: no lazy statics to hash into)
+#[cfg(not(feature = "std"))]
+fn scan_dic(dic_content: &'static str, word: &str) -> bool {
+    dic_content.lines().skip(1).any(|w| w == word)
+}
+
 /// Check if word starts with foreign consonant (z, w, j, f)
 fn starts_with_foreign_consonant(word: &str) -> bool {
     matches!(
@@ -46,7 +57,22 @@ pub fn is_vietnamese(word: &str, allow_foreign: bool) -> bool {
 
     // Case-insensitive lookup (dictionary stores lowercase)
     let word_lower = word.to_lowercase();
-    DICT_VI.contains(word_lower.as_str())
+    #[cfg(feature = "std")]
+    return DICT_VI.contains(word_lower.as_str());
+    #[cfg(not(feature = "std"))]
+    scan_dic(DIC_VI, &word_lower)
+}
+
+/// Iterate all Vietnamese dictionary words (lowercase)
+#[cfg(feature = "std")]
+pub fn words() -> impl Iterator<Item = &'static str> {
+    DICT_VI.iter().copied()
+}
+
+/// Iterate all Vietnamese dictionary words (lowercase)
+#[cfg(not(feature = "std"))]
+pub fn words() -> impl Iterator<Item = &'static str> {
+    DIC_VI.lines().skip(1)
 }
 
 /// Check if a word is in the keep list (should not be auto-restored)
@@ -55,7 +81,10 @@ pub fn should_keep(word: &str) -> bool {
         return false;
     }
     let word_lower = word.to_lowercase();
-    DICT_KEEP.contains(word_lower.as_str())
+    #[cfg(feature = "std")]
+    return DICT_KEEP.contains(word_lower.as_str());
+    #[cfg(not(feature = "std"))]
+    scan_dic(DIC_KEEP, &word_lower)
 }
 
 #[cfg(test)]
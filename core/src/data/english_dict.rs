@@ -3,13 +3,16 @@
 //! Uses merged dictionary: 10k common words + words with double telex chars.
 //! Only restores to English when raw_input is a known English word.
 
+#[cfg(feature = "std")]
 use std::collections::HashSet;
+#[cfg(feature = "std")]
 use std::sync::LazyLock;
 
 /// Embedded English word list (10k + double telex patterns)
-const ENGLISH_WORDS: &str = include_str!("english_dict_merged.txt");
+pub(crate) const ENGLISH_WORDS: &str = include_str!("english_dict_merged.txt");
 
 /// HashSet for O(1) lookup
+#[cfg(feature = "std")]
 static DICT: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     ENGLISH_WORDS
         .lines()
@@ -20,7 +23,10 @@ static DICT: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
 /// Check if a word is in the English dictionary (case-insensitive)
 pub fn is_english_word(word: &str) -> bool {
     let lower = word.to_lowercase();
-    DICT.contains(lower.as_str())
+    #[cfg(feature = "std")]
+    return DICT.contains(lower.as_str());
+    #[cfg(not(feature = "std"))]
+    ENGLISH_WORDS.lines().any(|line| line == lower)
 }
 
 #[cfg(test)]
@@ -52,6 +52,10 @@ pub const RIGHT: u16 = 124;
 pub const DOWN: u16 = 125;
 pub const UP: u16 = 126;
 
+// Modifiers (modifier-only events, see `Engine::on_key_at`)
+pub const LSHIFT: u16 = 56;
+pub const RSHIFT: u16 = 60;
+
 // Punctuation
 pub const DOT: u16 = 47;
 pub const COMMA: u16 = 43;
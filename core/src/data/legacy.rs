@@ -0,0 +1,381 @@
+//! Legacy Vietnamese Encodings
+//!
+//! Detection and mapping tables for pre-Unicode Vietnamese fonts. Text in these encodings
+//! usually reaches us as Windows-1252 / Latin-1 characters (e.g. copied from
+//! an old .doc in a `.VnTime` font), so tables map the Latin-1 char to Unicode.
+//!
+//! ## TCVN3 (ABC, TCVN 5712:1993)
+//! Single byte per letter. Lowercase letters carry all tones; uppercase forms
+//! exist only for base letters (Ă Â Ê Ô Ơ Ư Đ) because toned capitals live in
+//! separate "H" fonts (.VnTimeH) that reuse the lowercase code points.
+//!
+//! ## VNI (VNI-Windows)
+//! Base letter followed by a modifier byte: "Vieät" = V i e + ä(ệ) t.
+//! Only ơ/ư/đ and the toned i's are single bytes.
+//!
+//! ## VPS
+//! Not mapped yet, tracked on the roadmap (docs/project-overview-pdr.md).
+//! VPS puts capitals in the C0 control range and no verified table is
+//! available (iconv, ICU and the usual codec libraries ship none), so
+//! detection returns `None` for VPS text and leaves it unconverted rather
+//! than guessing. Adding it takes a `VPS` table, C0 capitals included,
+//! checked against a reference converter, plus a `LegacyEncoding::Vps`
+//! candidate in `detect_str`; the scoring stays as is.
+
+use super::chars::{self, mark, tone};
+use super::dictionary;
+use super::keys;
+use crate::prelude::*;
+
+/// Legacy encodings that can be detected and converted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegacyEncoding {
+    /// TCVN3 / ABC (.VnTime fonts)
+    Tcvn3,
+    /// VNI-Windows (VNI-Times fonts)
+    Vni,
+}
+
+/// TCVN3 byte → Unicode char (bytes 0xA1–0xFE; others are plain ASCII/Latin-1)
+pub const TCVN3: [(u8, char); 74] = [
+    // Uppercase base letters
+    (0xA1, 'Ă'),
+    (0xA2, 'Â'),
+    (0xA3, 'Ê'),
+    (0xA4, 'Ô'),
+    (0xA5, 'Ơ'),
+    (0xA6, 'Ư'),
+    (0xA7, 'Đ'),
+    // Lowercase base letters
+    (0xA8, 'ă'),
+    (0xA9, 'â'),
+    (0xAA, 'ê'),
+    (0xAB, 'ô'),
+    (0xAC, 'ơ'),
+    (0xAD, 'ư'),
+    (0xAE, 'đ'),
+    // a
+    (0xB5, 'à'),
+    (0xB6, 'ả'),
+    (0xB7, 'ã'),
+    (0xB8, 'á'),
+    (0xB9, 'ạ'),
+    // ă
+    (0xBB, 'ằ'),
+    (0xBC, 'ẳ'),
+    (0xBD, 'ẵ'),
+    (0xBE, 'ắ'),
+    (0xC6, 'ặ'),
+    // â
+    (0xC7, 'ầ'),
+    (0xC8, 'ẩ'),
+    (0xC9, 'ẫ'),
+    (0xCA, 'ấ'),
+    (0xCB, 'ậ'),
+    // e
+    (0xCC, 'è'),
+    (0xCE, 'ẻ'),
+    (0xCF, 'ẽ'),
+    (0xD0, 'é'),
+    (0xD1, 'ẹ'),
+    // ê
+    (0xD2, 'ề'),
+    (0xD3, 'ể'),
+    (0xD4, 'ễ'),
+    (0xD5, 'ế'),
+    (0xD6, 'ệ'),
+    // i
+    (0xD7, 'ì'),
+    (0xD8, 'ỉ'),
+    (0xDC, 'ĩ'),
+    (0xDD, 'í'),
+    (0xDE, 'ị'),
+    // o
+    (0xDF, 'ò'),
+    (0xE1, 'ỏ'),
+    (0xE2, 'õ'),
+    (0xE3, 'ó'),
+    (0xE4, 'ọ'),
+    // ô
+    (0xE5, 'ồ'),
+    (0xE6, 'ổ'),
+    (0xE7, 'ỗ'),
+    (0xE8, 'ố'),
+    (0xE9, 'ộ'),
+    // ơ
+    (0xEA, 'ờ'),
+    (0xEB, 'ở'),
+    (0xEC, 'ỡ'),
+    (0xED, 'ớ'),
+    (0xEE, 'ợ'),
+    // u
+    (0xEF, 'ù'),
+    (0xF1, 'ủ'),
+    (0xF2, 'ũ'),
+    (0xF3, 'ú'),
+    (0xF4, 'ụ'),
+    // ư
+    (0xF5, 'ừ'),
+    (0xF6, 'ử'),
+    (0xF7, 'ữ'),
+    (0xF8, 'ứ'),
+    (0xF9, 'ự'),
+    // y
+    (0xFA, 'ỳ'),
+    (0xFB, 'ỷ'),
+    (0xFC, 'ỹ'),
+    (0xFD, 'ý'),
+    (0xFE, 'ỵ'),
+];
+
+/// Decode one TCVN3 byte (as a Latin-1 char) to Unicode
+pub fn tcvn3_char(c: char) -> char {
+    let code = c as u32;
+    if !(0xA1..=0xFE).contains(&code) {
+        return c;
+    }
+    TCVN3
+        .iter()
+        .find(|(b, _)| *b as u32 == code)
+        .map(|(_, u)| *u)
+        .unwrap_or(c)
+}
+
+/// Convert TCVN3 text (decoded as Latin-1) to Unicode
+pub fn tcvn3_to_unicode(text: &str) -> String {
+    text.chars().map(tcvn3_char).collect()
+}
+
+/// VNI modifier byte → (tone, mark) applied to the preceding vowel
+///
+/// Uppercase text uses the uppercase Latin-1 form of the same byte (ù → Ù).
+const VNI_MODIFIERS: [(char, u8, u8); 17] = [
+    ('ù', tone::NONE, mark::SAC),
+    ('ø', tone::NONE, mark::HUYEN),
+    ('û', tone::NONE, mark::HOI),
+    ('õ', tone::NONE, mark::NGA),
+    ('ï', tone::NONE, mark::NANG),
+    ('â', tone::CIRCUMFLEX, mark::NONE),
+    ('á', tone::CIRCUMFLEX, mark::SAC),
+    ('à', tone::CIRCUMFLEX, mark::HUYEN),
+    ('å', tone::CIRCUMFLEX, mark::HOI),
+    ('ã', tone::CIRCUMFLEX, mark::NGA),
+    ('ä', tone::CIRCUMFLEX, mark::NANG),
+    ('ê', tone::HORN, mark::NONE),
+    ('é', tone::HORN, mark::SAC),
+    ('è', tone::HORN, mark::HUYEN),
+    ('ú', tone::HORN, mark::HOI),
+    ('ü', tone::HORN, mark::NGA),
+    ('ë', tone::HORN, mark::NANG),
+];
+
+/// VNI single-byte letters (lowercase; uppercase uses the uppercase byte)
+const VNI_LETTERS: [(char, char); 6] = [
+    ('ô', 'ơ'),
+    ('ö', 'ư'),
+    ('ñ', 'đ'),
+    ('æ', 'ỉ'),
+    ('ó', 'ĩ'),
+    ('ò', 'ị'),
+];
+
+/// VNI base vowel: (key, caps, tone) for a char that can take a modifier
+fn vni_base(c: char) -> Option<(u16, bool, u8)> {
+    let caps = c.is_uppercase();
+    let (key, t) = match c.to_ascii_lowercase() {
+        'a' => (keys::A, tone::NONE),
+        'e' => (keys::E, tone::NONE),
+        'i' => (keys::I, tone::NONE),
+        'o' => (keys::O, tone::NONE),
+        'u' => (keys::U, tone::NONE),
+        'y' => (keys::Y, tone::NONE),
+        _ => match c.to_lowercase().next()? {
+            'ô' => (keys::O, tone::HORN),
+            'ö' => (keys::U, tone::HORN),
+            _ => return None,
+        },
+    };
+    Some((key, caps, t))
+}
+
+/// Combine a VNI base vowel with a modifier byte, if the pair is valid
+fn vni_combine(base: (u16, bool, u8), modifier: char) -> Option<char> {
+    let (key, caps, base_tone) = base;
+    let lower = modifier.to_lowercase().next()?;
+    let &(_, t, m) = VNI_MODIFIERS.iter().find(|(c, _, _)| *c == lower)?;
+    let valid = match t {
+        tone::CIRCUMFLEX => base_tone == tone::NONE && matches!(key, keys::A | keys::E | keys::O),
+        tone::HORN => base_tone == tone::NONE && key == keys::A,
+        _ => true,
+    };
+    if !valid {
+        return None;
+    }
+    chars::to_char(key, caps, base_tone.max(t), m)
+}
+
+/// Convert VNI-Windows text (decoded as Latin-1) to Unicode
+pub fn vni_to_unicode(text: &str) -> String {
+    let input: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < input.len() {
+        let c = input[i];
+        if let Some(base) = vni_base(c) {
+            if let Some(combined) = input.get(i + 1).and_then(|&m| vni_combine(base, m)) {
+                out.push(combined);
+                i += 2;
+                continue;
+            }
+        }
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match VNI_LETTERS.iter().find(|(l, _)| *l == lower) {
+            Some(&(_, u)) if c.is_uppercase() => out.extend(u.to_uppercase()),
+            Some(&(_, u)) => out.push(u),
+            None => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Convert legacy text (decoded as Latin-1) to Unicode
+pub fn to_unicode(text: &str, encoding: LegacyEncoding) -> String {
+    match encoding {
+        LegacyEncoding::Tcvn3 => tcvn3_to_unicode(text),
+        LegacyEncoding::Vni => vni_to_unicode(text),
+    }
+}
+
+/// Convert legacy bytes to Unicode (bytes are read as Latin-1)
+pub fn bytes_to_unicode(bytes: &[u8], encoding: LegacyEncoding) -> String {
+    to_unicode(&latin1(bytes), encoding)
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+/// How Vietnamese the decoded text looks: dictionary syllables with
+/// diacritics count up, leftover Latin-1 symbols count down
+fn score(decoded: &str) -> i32 {
+    decoded
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty() && !w.is_ascii())
+        .map(|w| {
+            if dictionary::is_vietnamese(w, false) {
+                2
+            } else if w
+                .chars()
+                .all(|c| c.is_ascii() || chars::parse_char(c).is_some())
+            {
+                0
+            } else {
+                -1
+            }
+        })
+        .sum()
+}
+
+/// Detect the legacy encoding of text decoded as Latin-1
+///
+/// Returns `None` for plain ASCII, real Unicode Vietnamese, or text that
+/// doesn't decode to Vietnamese under any known table.
+pub fn detect_str(text: &str) -> Option<LegacyEncoding> {
+    if text.chars().any(|c| c as u32 > 0xFF) {
+        return None; // Not a Latin-1 view of legacy bytes
+    }
+    let candidates = [LegacyEncoding::Tcvn3, LegacyEncoding::Vni];
+    let (best, best_score) = candidates
+        .iter()
+        .map(|&enc| (enc, score(&to_unicode(text, enc))))
+        .max_by_key(|&(_, s)| s)?;
+    (best_score > score(text)).then_some(best)
+}
+
+/// Detect the legacy encoding of a byte string
+///
+/// Valid UTF-8 with non-ASCII content is treated as Unicode (returns `None`).
+pub fn detect(bytes: &[u8]) -> Option<LegacyEncoding> {
+    if let Ok(s) = core::str::from_utf8(bytes) {
+        if s.chars().any(|c| c as u32 > 0xFF) {
+            return None;
+        }
+    }
+    detect_str(&latin1(bytes))
+}
+
+/// Detect and convert legacy text; text that isn't legacy is returned unchanged
+pub fn auto_to_unicode(text: &str) -> String {
+    match detect_str(text) {
+        Some(enc) => to_unicode(text, enc),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tcvn3_to_unicode() {
+        assert_eq!(tcvn3_to_unicode("TiÕng ViÖt"), "Tiếng Việt");
+        assert_eq!(tcvn3_to_unicode("Hµ Néi"), "Hà Nội");
+        assert_eq!(tcvn3_to_unicode("\u{AE}\u{AD}\u{EA}ng"), "đường");
+        assert_eq!(tcvn3_to_unicode("\u{A7}I HäC"), "ĐI HọC");
+    }
+
+    #[test]
+    fn test_tcvn3_table_is_unique() {
+        for (i, (b, u)) in TCVN3.iter().enumerate() {
+            for (b2, u2) in &TCVN3[i + 1..] {
+                assert_ne!(b, b2);
+                assert_ne!(u, u2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_vni_to_unicode() {
+        assert_eq!(vni_to_unicode("Tieáng Vieät"), "Tiếng Việt");
+        assert_eq!(vni_to_unicode("ñöôøng"), "đường");
+        assert_eq!(vni_to_unicode("Haø Noäi"), "Hà Nội");
+        assert_eq!(
+            vni_to_unicode("ñi hoïc, khuyeán maõi"),
+            "đi học, khuyến mãi"
+        );
+        assert_eq!(vni_to_unicode("aên, aéc, Ñaëng"), "ăn, ắc, Đặng");
+        assert_eq!(vni_to_unicode("TIEÁNG VIEÄT"), "TIẾNG VIỆT");
+        assert_eq!(vni_to_unicode("ó, æ, ò, í"), "ĩ, ỉ, ị, í");
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            detect_str("TiÕng ViÖt lµ ng«n ng÷"),
+            Some(LegacyEncoding::Tcvn3)
+        );
+        assert_eq!(
+            detect_str("Tieáng Vieät laø ngoân ngöõ"),
+            Some(LegacyEncoding::Vni)
+        );
+        assert_eq!(detect_str("Tiếng Việt"), None);
+        assert_eq!(detect_str("hello world"), None);
+        assert_eq!(detect("Tiếng Việt".as_bytes()), None);
+        assert_eq!(
+            detect(&[b'H', 0xB5, b' ', b'N', 0xE9, b'i']),
+            Some(LegacyEncoding::Tcvn3)
+        );
+    }
+
+    #[test]
+    fn test_auto_to_unicode() {
+        assert_eq!(auto_to_unicode("Hµ Néi"), "Hà Nội");
+        assert_eq!(auto_to_unicode("Haø Noäi"), "Hà Nội");
+        assert_eq!(auto_to_unicode("Hà Nội"), "Hà Nội");
+        assert_eq!(
+            bytes_to_unicode(&[b'H', b'a', 0xF8], LegacyEncoding::Vni),
+            "Hà"
+        );
+    }
+}
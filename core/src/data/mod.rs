@@ -5,12 +5,16 @@
 //! - `chars`: Unicode character conversion (includes tone/mark constants)
 //! - `vowel`: Vietnamese vowel phonology system
 //! - `telex_doubles`: English words with Telex double patterns for auto-restore
+//! - `bigrams`: Letter bigram log-ratios, English vs Vietnamese Telex
+//! - `legacy`: Pre-Unicode font encodings (TCVN3, VNI) detection and → Unicode tables
 
+pub mod bigrams;
 pub mod chars;
 pub mod constants;
 pub mod dictionary;
 pub mod english_dict;
 pub mod keys;
+pub mod legacy;
 pub mod telex_doubles;
 pub mod vowel;
 
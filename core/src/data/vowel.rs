@@ -20,6 +20,7 @@
 //! - **Glide (bán nguyên âm)**: i/y, u/o at syllable end (ai, ao, iu, oi)
 
 use super::keys;
+use crate::prelude::*;
 
 /// Vowel modifier type (dấu phụ)
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -0,0 +1,149 @@
+//! Behavior Versions
+//!
+//! The app version says which build a user runs, not which typing rules:
+//! the same keys can give different text after a rule change. Every change
+//! to what a key sequence produces gets a row in `BEHAVIOR_CHANGES` under
+//! the next behavior version, so a debug log or issue report that carries
+//! `engine_behavior_version()` pins down the rule set exactly.
+//!
+//! Rule ids are stable dotted names; a row is never edited after release.
+//! Version "1" is the rule set before this changelog was kept.
+
+use crate::prelude::*;
+
+/// Current behavior version; bump it with the first new row after a release
+pub const BEHAVIOR_VERSION: &str = "2";
+
+/// One behavior-affecting change
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BehaviorChange {
+    /// Behavior version that introduced it
+    pub version: &'static str,
+    /// Stable rule id
+    pub rule: &'static str,
+    /// What typing does differently
+    pub summary: &'static str,
+}
+
+/// Behavior changes, oldest first
+pub const BEHAVIOR_CHANGES: &[BehaviorChange] = &[
+    BehaviorChange {
+        version: "2",
+        rule: "history.restore_across_punctuation",
+        summary: "Backspace into a word restores it across punctuation and newlines",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "mark.single_vowel",
+        summary: "A second tone mark on another vowel is typed as a letter (gisis)",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "breve.final_nh_ch",
+        summary: "Breve is reverted when the final becomes nh or ch (awnh)",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "input.non_ascii_literal",
+        summary: "Words with letters from another layout are typed literally",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "input.reingested_no_restore",
+        summary: "Words re-ingested from the screen are not auto-restored",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "shortcut.capitalize_sentence_start",
+        summary: "Shortcut expansions are capitalized at sentence start",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "breve.oao_triphthong",
+        summary: "Breve is accepted on the oăo of ngoằo",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "tone.noop_key_policy",
+        summary: "Tone keys that change nothing follow ToneKeyPolicy",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "uo_horn.undo_when_invalid",
+        summary: "The automatic ơ of ư+o is taken back when the word turns invalid (uwoman)",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "defer.transition_table",
+        summary: "Reverts and edits drop a pending horn or breve (huoww + c, huowa)",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "restore.tie_break",
+        summary: "A word ESC-restored earlier is restored again when valid in both readings (her)",
+    },
+    BehaviorChange {
+        version: "2",
+        rule: "restore.preceding_words",
+        summary:
+            "Words valid in both readings follow the language of the two words before (tell her)",
+    },
+];
+
+/// Behavior version of this build, for debug logs and issue reports
+pub fn engine_behavior_version() -> &'static str {
+    BEHAVIOR_VERSION
+}
+
+/// Changelog as text, one `version rule` line per change
+pub fn behavior_changelog() -> String {
+    let mut text = String::new();
+    for change in BEHAVIOR_CHANGES {
+        text.push_str(change.version);
+        text.push(' ');
+        text.push_str(change.rule);
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version_number(version: &str) -> u32 {
+        version.parse().expect("versions are whole numbers")
+    }
+
+    #[test]
+    fn test_rows_are_ordered_and_current() {
+        let versions: Vec<u32> = BEHAVIOR_CHANGES
+            .iter()
+            .map(|c| version_number(c.version))
+            .collect();
+        assert!(versions.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(
+            versions.last().copied(),
+            Some(version_number(BEHAVIOR_VERSION))
+        );
+    }
+
+    #[test]
+    fn test_rule_ids_are_unique() {
+        for (i, change) in BEHAVIOR_CHANGES.iter().enumerate() {
+            assert!(
+                BEHAVIOR_CHANGES[..i].iter().all(|c| c.rule != change.rule),
+                "duplicate rule id {}",
+                change.rule
+            );
+            assert!(!change.rule.contains(char::is_whitespace));
+        }
+    }
+
+    #[test]
+    fn test_changelog_lines() {
+        let text = behavior_changelog();
+        assert_eq!(text.lines().count(), BEHAVIOR_CHANGES.len());
+        assert!(text.lines().any(|l| l == "2 uo_horn.undo_when_invalid"));
+    }
+}
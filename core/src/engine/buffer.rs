@@ -1,6 +1,11 @@
 //! Typing buffer
 
+use crate::prelude::*;
+
 pub const MAX: usize = 256;
+/// Longest word the buffer holds: its length must fit a result's u8
+/// backspace count
+pub const MAX_WORD: usize = u8::MAX as usize;
 
 use crate::utils;
 
@@ -39,11 +44,36 @@ impl Char {
     }
 }
 
+/// One keystroke of the current word, as typed
+///
+/// `ch` is the character the keystroke produced when it was recorded.
+/// Restores output it as is instead of mapping the keycode back to a char.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawKeystroke {
+    pub key: u16,
+    pub caps: bool,
+    pub shift: bool,
+    pub ch: Option<char>,
+}
+
+impl RawKeystroke {
+    pub fn new(key: u16, caps: bool, shift: bool) -> Self {
+        Self {
+            key,
+            caps,
+            shift,
+            ch: utils::key_to_char_ext(key, caps, shift),
+        }
+    }
+}
+
 /// Typing buffer
 #[derive(Clone)]
 pub struct Buffer {
     data: [Char; MAX],
     len: usize,
+    /// Caret inside the word; None when it is at the end
+    cursor: Option<usize>,
 }
 
 impl Default for Buffer {
@@ -57,11 +87,12 @@ impl Buffer {
         Self {
             data: [Char::default(); MAX],
             len: 0,
+            cursor: None,
         }
     }
 
     pub fn push(&mut self, c: Char) {
-        if self.len < MAX {
+        if self.len < MAX_WORD {
             self.data[self.len] = c;
             self.len += 1;
         }
@@ -70,6 +101,7 @@ impl Buffer {
     pub fn pop(&mut self) -> Option<Char> {
         if self.len > 0 {
             self.len -= 1;
+            self.cursor = self.cursor.filter(|&c| c < self.len);
             Some(self.data[self.len])
         } else {
             None
@@ -78,6 +110,14 @@ impl Buffer {
 
     pub fn clear(&mut self) {
         self.len = 0;
+        self.cursor = None;
+    }
+
+    /// Clear and overwrite every slot (see `Engine::wipe`)
+    pub fn zeroize(&mut self) {
+        super::wipe::zero_slice(&mut self.data, Char::default());
+        self.len = 0;
+        self.cursor = None;
     }
 
     pub fn len(&self) -> usize {
@@ -120,9 +160,67 @@ impl Buffer {
                 self.data[i] = self.data[i + 1];
             }
             self.len -= 1;
+            self.cursor = match self.cursor {
+                Some(c) if index < c => Some(c - 1),
+                c => c.filter(|&c| c < self.len),
+            };
         }
     }
 
+    /// Insert element at index, shifting subsequent elements right
+    pub fn insert(&mut self, index: usize, c: Char) {
+        if index <= self.len && self.len < MAX_WORD {
+            for i in (index..self.len).rev() {
+                self.data[i + 1] = self.data[i];
+            }
+            self.data[index] = c;
+            self.len += 1;
+            if let Some(cursor) = self.cursor.as_mut() {
+                if index < *cursor {
+                    *cursor += 1;
+                }
+            }
+        }
+    }
+
+    /// Caret position (chars from the start of the word)
+    pub fn cursor(&self) -> usize {
+        self.cursor.unwrap_or(self.len)
+    }
+
+    /// Whether the caret is at the end, where `push` and `pop` work
+    pub fn cursor_at_end(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    /// Move the caret; positions at or past the end put it at the end
+    pub fn set_cursor(&mut self, pos: usize) {
+        self.cursor = (pos < self.len).then_some(pos);
+    }
+
+    /// Insert at the caret and move it past the new char
+    pub fn insert_at_cursor(&mut self, c: Char) {
+        match self.cursor {
+            None => self.push(c),
+            Some(pos) if self.len < MAX_WORD => {
+                self.insert(pos, c);
+                self.cursor = Some(pos + 1);
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Remove the char before the caret (Backspace)
+    pub fn remove_before_cursor(&mut self) -> Option<Char> {
+        let pos = self.cursor();
+        if pos == 0 {
+            return None;
+        }
+        let c = self.data[pos - 1];
+        self.remove(pos - 1);
+        Some(c)
+    }
+
     /// Find indices of vowels in buffer
     pub fn find_vowels(&self) -> Vec<usize> {
         use crate::data::keys;
@@ -140,7 +238,7 @@ impl Buffer {
     }
 
     /// Iterate over chars
-    pub fn iter(&self) -> std::slice::Iter<'_, Char> {
+    pub fn iter(&self) -> core::slice::Iter<'_, Char> {
         self.data[..self.len].iter()
     }
 
@@ -165,22 +263,33 @@ impl Buffer {
     /// This includes tone marks (sắc/huyền/hỏi/ngã/nặng), vowel marks (circumflex/horn/breve),
     /// and stroked consonants (đ). Use this for shortcut matching to ensure exact comparison.
     pub fn to_full_string(&self) -> String {
+        self.chars().collect()
+    }
+
+    /// Displayed chars with diacritics, without building a String
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
         use crate::data::{chars, keys};
-        self.data[..self.len]
-            .iter()
-            .filter_map(|c| {
-                // Handle đ/Đ (stroked D)
-                if c.key == keys::D && c.stroke {
-                    return Some(chars::get_d(c.caps));
-                }
-                // Try to get full Vietnamese character with diacritics
-                if let Some(ch) = chars::to_char(c.key, c.caps, c.tone, c.mark) {
-                    return Some(ch);
-                }
-                // Fallback to basic character
-                utils::key_to_char(c.key, c.caps)
-            })
-            .collect()
+        self.data[..self.len].iter().filter_map(|c| {
+            // Handle đ/Đ (stroked D)
+            if c.key == keys::D && c.stroke {
+                return Some(chars::get_d(c.caps));
+            }
+            // Try to get full Vietnamese character with diacritics
+            if let Some(ch) = chars::to_char(c.key, c.caps, c.tone, c.mark) {
+                return Some(ch);
+            }
+            // Fallback to basic character
+            utils::key_to_char(c.key, c.caps)
+        })
+    }
+
+    /// Check if the displayed text equals `text`
+    ///
+    /// Same as `to_full_string() == text` but stops at the first difference
+    /// and allocates nothing (runs on every restore check).
+    pub fn eq_chars(&self, text: &[char]) -> bool {
+        // Every buffer char displays as at most one char
+        text.len() <= self.len && self.chars().eq(text.iter().copied())
     }
 }
 
@@ -204,4 +313,73 @@ mod tests {
         buf.clear();
         assert!(buf.is_empty());
     }
+
+    #[test]
+    fn test_eq_chars() {
+        use crate::data::{chars::mark, keys};
+
+        let mut buf = Buffer::new();
+        buf.push(Char::new(keys::D, false));
+        buf.push(Char::new(keys::A, false));
+        assert!(buf.eq_chars(&['d', 'a']));
+        assert!(!buf.eq_chars(&['d']));
+        assert!(!buf.eq_chars(&['d', 'a', 'y']));
+
+        buf.get_mut(1).unwrap().mark = mark::SAC;
+        assert!(buf.eq_chars(&['d', 'á']));
+        assert!(!buf.eq_chars(&['d', 'a']));
+        assert_eq!(buf.chars().collect::<String>(), buf.to_full_string());
+    }
+
+    #[test]
+    fn test_insert() {
+        use crate::data::keys;
+
+        let mut buf = Buffer::new();
+        buf.push(Char::new(keys::A, false));
+        buf.push(Char::new(keys::N, false));
+        buf.insert(1, Char::new(keys::W, false));
+        assert_eq!(buf.to_full_string(), "awn");
+        buf.insert(3, Char::new(keys::H, false));
+        assert_eq!(buf.to_full_string(), "awnh");
+        buf.insert(9, Char::new(keys::H, false));
+        assert_eq!(buf.len(), 4);
+    }
+
+    #[test]
+    fn test_cursor() {
+        use crate::data::keys;
+
+        let mut buf = Buffer::new();
+        for key in [keys::V, keys::I, keys::T] {
+            buf.push(Char::new(key, false));
+        }
+        assert!(buf.cursor_at_end());
+        buf.set_cursor(2);
+        assert_eq!(buf.cursor(), 2);
+        buf.insert_at_cursor(Char::new(keys::E, false));
+        assert_eq!(buf.to_full_string(), "viet");
+        assert_eq!(buf.cursor(), 3);
+        buf.insert(0, Char::new(keys::X, false));
+        assert_eq!(buf.cursor(), 4);
+        assert_eq!(buf.remove_before_cursor().map(|c| c.key), Some(keys::E));
+        assert_eq!(buf.to_full_string(), "xvit");
+        buf.remove(0);
+        assert_eq!(buf.cursor(), 2);
+        buf.pop();
+        assert!(buf.cursor_at_end());
+        buf.set_cursor(1);
+        buf.clear();
+        assert!(buf.cursor_at_end());
+    }
+
+    #[test]
+    fn test_raw_keystroke_char() {
+        use crate::data::keys;
+
+        assert_eq!(RawKeystroke::new(keys::A, true, true).ch, Some('A'));
+        assert_eq!(RawKeystroke::new(keys::N6, false, false).ch, Some('6'));
+        assert_eq!(RawKeystroke::new(keys::N6, false, true).ch, Some('^'));
+        assert_eq!(RawKeystroke::new(keys::DELETE, false, false).ch, None);
+    }
 }
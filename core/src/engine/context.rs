@@ -0,0 +1,79 @@
+//! Surrounding Text Sync
+//!
+//! The engine sizes every edit from what it believes is on screen: the word
+//! being typed, or the last committed word plus the separators typed after it
+//! (backspace-after-space). When the app's text changed behind its back — a
+//! CRLF for Enter, an emoji from a picker, a click elsewhere — those counts
+//! delete the wrong characters. Hosts pass the text before the cursor and the
+//! engine checks its view against it, one grapheme per backspace.
+
+use crate::prelude::*;
+use crate::text::grapheme;
+
+/// Outcome of `Engine::sync_context`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextSync {
+    /// Screen matches the engine's view (or there was nothing to check)
+    InSync = 0,
+    /// Same word, but the separators after it differ: counts were adjusted
+    Adjusted = 1,
+    /// Screen doesn't show the engine's word: composing state was dropped
+    Reset = 2,
+}
+
+/// Outcome of `Engine::verify_result`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScreenCheck {
+    /// The chars the edit deletes are on screen (or the engine can't tell)
+    Confirmed = 0,
+    /// Same word, other separators after it: the edit was resized
+    Adjusted = 1,
+    /// The screen doesn't show what the edit deletes: it was dropped
+    Aborted = 2,
+}
+
+/// How `Engine::reset_external` handles an edit the engine can't follow
+///
+/// Multi-cursor typing, a collaborator's change in a shared document, an
+/// app rewriting the field: the engine can't tell what is on screen anymore.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResetPolicy {
+    /// Commit the word as it is on screen and forget the text before it
+    #[default]
+    Commit = 0,
+    /// Same, then report `needs_context` until the host calls `sync_context`
+    Resync = 1,
+}
+
+impl ResetPolicy {
+    /// Policy for an FFI code (0 or 1)
+    pub fn from_u8(code: u8) -> Option<Self> {
+        [Self::Commit, Self::Resync]
+            .into_iter()
+            .find(|p| *p as u8 == code)
+    }
+}
+
+/// Clusters that end a word (spaces, line breaks, ASCII punctuation)
+pub(crate) fn is_separator(cluster: &str) -> bool {
+    cluster
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_whitespace() || c.is_ascii_punctuation())
+}
+
+/// Whether the clusters end with `word`, one cluster per char
+///
+/// Context shorter than the word only has to match the word's end: hosts
+/// may pass just the last few graphemes.
+pub(crate) fn ends_with_word(clusters: &[&str], word: &str) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    let n = chars.len().min(clusters.len());
+    clusters[clusters.len() - n..]
+        .iter()
+        .zip(&chars[chars.len() - n..])
+        .all(|(cluster, &c)| grapheme::compose(cluster) == Some(c))
+}
@@ -0,0 +1,169 @@
+//! Deferred Diacritics
+//!
+//! Some diacritics wait for the next key before they land:
+//! - Horn on the 'u' of "uơ": "huow" → "huơ" stays open, "huow" + c → "hươc"
+//! - Breve on 'a' in an open syllable: "aw" waits for a final ("trăm")
+//!
+//! While one is pending, every key is one `DeferEvent` and `DEFER_RULES`
+//! says what happens to the pending diacritic. One table instead of checks
+//! spread over the letter, tone, mark, remove and backspace paths, so a revert in
+//! between ("huoww" + c) can't leave a stale pending state behind.
+
+use crate::data::keys;
+
+/// Diacritic waiting for the next key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeferKind {
+    /// Horn on 'u' of "uơ"
+    UHorn,
+    /// Breve on 'a'
+    Breve,
+}
+
+/// Pending diacritic and the position of the vowel it goes on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deferred {
+    pub kind: DeferKind,
+    pub pos: usize,
+}
+
+/// Key typed while a diacritic is pending
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeferEvent {
+    /// Letter that can start a final consonant: c, k, m, n, p, t
+    Final,
+    /// Vowel that can close "ươ": i, u ("người", "hươu")
+    Glide,
+    /// Any other vowel
+    Vowel,
+    /// Any other consonant, or a digit typed as text
+    Consonant,
+    /// Tone mark key (sắc, huyền, ...)
+    Mark,
+    /// Tone key putting a different diacritic on the vowels ("huơ" + o)
+    Switch,
+    /// The same modifier key again, taking the diacritic back ("huơ" + w)
+    Undo,
+    /// Remove key (Telex z, VNI 0)
+    Remove,
+    /// Backspace
+    Delete,
+}
+
+/// What happens to the pending diacritic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeferStep {
+    /// Stay pending
+    Keep,
+    /// Put the diacritic on its vowel now
+    Apply,
+    /// Forget it; the key is handled as if nothing was pending
+    Drop,
+    /// Forget it and swallow the key
+    Cancel,
+}
+
+impl DeferEvent {
+    /// Event for a letter or digit added to the word
+    pub fn of_letter(key: u16) -> Self {
+        match key {
+            keys::C | keys::K | keys::M | keys::N | keys::P | keys::T => Self::Final,
+            keys::I | keys::U => Self::Glide,
+            _ if keys::is_vowel(key) => Self::Vowel,
+            _ => Self::Consonant,
+        }
+    }
+}
+
+/// Transition table: one row per (pending diacritic, event)
+pub const DEFER_RULES: &[(DeferKind, DeferEvent, DeferStep)] = &[
+    // duơ + c → dươc, duơ + n → dươn
+    (DeferKind::UHorn, DeferEvent::Final, DeferStep::Apply),
+    // nguơ + i → ngươi, huơ + u → hươu
+    (DeferKind::UHorn, DeferEvent::Glide, DeferStep::Apply),
+    // "ươa" is not a rhyme: huơ + a stays huơa
+    (DeferKind::UHorn, DeferEvent::Vowel, DeferStep::Drop),
+    (DeferKind::UHorn, DeferEvent::Consonant, DeferStep::Drop),
+    // huờ + c → hườc: the mark doesn't settle the open syllable
+    (DeferKind::UHorn, DeferEvent::Mark, DeferStep::Keep),
+    // huơ + o → huô: no horn left to complete
+    (DeferKind::UHorn, DeferEvent::Switch, DeferStep::Drop),
+    // huoww → huow, then c → huowc
+    (DeferKind::UHorn, DeferEvent::Undo, DeferStep::Drop),
+    (DeferKind::UHorn, DeferEvent::Remove, DeferStep::Drop),
+    // huơ + Backspace → hu: the 'ơ' it followed is gone
+    (DeferKind::UHorn, DeferEvent::Delete, DeferStep::Drop),
+    // traw + m → trăm (k for Đắk Lắk)
+    (DeferKind::Breve, DeferEvent::Final, DeferStep::Apply),
+    (DeferKind::Breve, DeferEvent::Glide, DeferStep::Drop),
+    (DeferKind::Breve, DeferEvent::Vowel, DeferStep::Drop),
+    // May still be followed by a final
+    (DeferKind::Breve, DeferEvent::Consonant, DeferStep::Keep),
+    // aws → ắ: a mark confirms Vietnamese
+    (DeferKind::Breve, DeferEvent::Mark, DeferStep::Apply),
+    (DeferKind::Breve, DeferEvent::Switch, DeferStep::Drop),
+    // aww → aw
+    (DeferKind::Breve, DeferEvent::Undo, DeferStep::Cancel),
+    (DeferKind::Breve, DeferEvent::Remove, DeferStep::Drop),
+    (DeferKind::Breve, DeferEvent::Delete, DeferStep::Drop),
+];
+
+/// Look up what `event` does to a pending `kind`
+pub fn step(kind: DeferKind, event: DeferEvent) -> DeferStep {
+    DEFER_RULES
+        .iter()
+        .find(|&&(k, e, _)| k == kind && e == event)
+        .map_or(DeferStep::Drop, |rule| rule.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [DeferKind; 2] = [DeferKind::UHorn, DeferKind::Breve];
+    const EVENTS: [DeferEvent; 9] = [
+        DeferEvent::Final,
+        DeferEvent::Glide,
+        DeferEvent::Vowel,
+        DeferEvent::Consonant,
+        DeferEvent::Mark,
+        DeferEvent::Switch,
+        DeferEvent::Undo,
+        DeferEvent::Remove,
+        DeferEvent::Delete,
+    ];
+
+    #[test]
+    fn test_table_is_exhaustive() {
+        assert_eq!(DEFER_RULES.len(), KINDS.len() * EVENTS.len());
+        for kind in KINDS {
+            for event in EVENTS {
+                let rows = DEFER_RULES
+                    .iter()
+                    .filter(|&&(k, e, _)| k == kind && e == event)
+                    .count();
+                assert_eq!(rows, 1, "{kind:?} {event:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_only_breve_swallows_keys() {
+        for &(kind, _, step) in DEFER_RULES {
+            assert!(step != DeferStep::Cancel || kind == DeferKind::Breve);
+        }
+    }
+
+    #[test]
+    fn test_of_letter() {
+        assert_eq!(DeferEvent::of_letter(keys::C), DeferEvent::Final);
+        assert_eq!(DeferEvent::of_letter(keys::K), DeferEvent::Final);
+        assert_eq!(DeferEvent::of_letter(keys::I), DeferEvent::Glide);
+        assert_eq!(DeferEvent::of_letter(keys::U), DeferEvent::Glide);
+        assert_eq!(DeferEvent::of_letter(keys::A), DeferEvent::Vowel);
+        assert_eq!(DeferEvent::of_letter(keys::Y), DeferEvent::Vowel);
+        assert_eq!(DeferEvent::of_letter(keys::D), DeferEvent::Consonant);
+        assert_eq!(DeferEvent::of_letter(keys::W), DeferEvent::Consonant);
+        assert_eq!(DeferEvent::of_letter(keys::N2), DeferEvent::Consonant);
+    }
+}
@@ -0,0 +1,117 @@
+//! Minimal Edits
+//!
+//! Hosts apply every edit as backspaces before the caret followed by new
+//! text. Given the text before and after a change, `diff` finds the smallest
+//! such edit: chars shared at the start are kept, and so is the text after
+//! the caret, which backspaces can't reach.
+
+use crate::prelude::*;
+use crate::text::grapheme;
+
+/// What one backspace deletes in the host app
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unit {
+    /// One char (code point), what `Result::backspace` counts
+    #[default]
+    Char,
+    /// One grapheme cluster ("e" + combining circumflex is one)
+    Grapheme,
+}
+
+/// Delete `deleted` before the caret, then type `inserted`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Edit {
+    pub deleted: Vec<char>,
+    pub inserted: Vec<char>,
+}
+
+impl Edit {
+    /// Backspaces that delete `deleted` in `unit`s
+    pub fn backspaces(&self, unit: Unit) -> usize {
+        match unit {
+            Unit::Char => self.deleted.len(),
+            Unit::Grapheme => {
+                let text: String = self.deleted.iter().collect();
+                grapheme::graphemes(&text).len()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deleted.is_empty() && self.inserted.is_empty()
+    }
+}
+
+/// Smallest edit turning `old` into `new` with the caret at `caret` in `old`
+///
+/// Text after the caret must come through unchanged (`new` ends with it);
+/// None otherwise, since backspaces before the caret can't change it. With
+/// the caret at the end this is the plain common-prefix diff.
+pub fn diff(old: &[char], new: &[char], caret: usize) -> Option<Edit> {
+    let caret = caret.min(old.len());
+    let after = &old[caret..];
+    if !new.ends_with(after) {
+        return None;
+    }
+    let new_before = &new[..new.len() - after.len()];
+    let same = old[..caret]
+        .iter()
+        .zip(new_before)
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some(Edit {
+        deleted: old[same..caret].to_vec(),
+        inserted: new_before[same..].to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn edit(old: &str, new: &str, caret: usize) -> Option<(String, String)> {
+        diff(&chars(old), &chars(new), caret).map(|e| {
+            (
+                e.deleted.into_iter().collect(),
+                e.inserted.into_iter().collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn test_diff_at_end() {
+        assert_eq!(
+            edit("tiêng", "tiếng", 5),
+            Some(("êng".into(), "ếng".into()))
+        );
+        assert_eq!(edit("ba", "ban", 2), Some(("".into(), "n".into())));
+        assert_eq!(edit("viet", "viet", 4), Some(("".into(), "".into())));
+        assert_eq!(edit("tẽt", "text ", 3), Some(("ẽt".into(), "ext ".into())));
+    }
+
+    #[test]
+    fn test_diff_before_caret() {
+        // "vi|t" + e → "vie|t"
+        assert_eq!(edit("vit", "viet", 2), Some(("".into(), "e".into())));
+        // "ho|a" + s → "hó|a"
+        assert_eq!(edit("hoa", "hóa", 2), Some(("o".into(), "ó".into())));
+        // Changes after the caret can't be made
+        assert_eq!(edit("hoa", "hoá", 2), None);
+    }
+
+    #[test]
+    fn test_backspace_units() {
+        let e = Edit {
+            deleted: chars("e\u{0302}t"),
+            inserted: vec![],
+        };
+        assert_eq!(e.backspaces(Unit::Char), 3);
+        assert_eq!(e.backspaces(Unit::Grapheme), 2);
+        assert!(!e.is_empty());
+        assert!(Edit::default().is_empty());
+    }
+}
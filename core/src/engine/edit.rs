@@ -0,0 +1,88 @@
+//! Semantic Edits
+//!
+//! `Result` describes every edit as backspaces plus chars, which any host can
+//! replay. Hosts with higher-level text APIs (accessibility value
+//! replacement, IMKit marked text) do better knowing what the edit means:
+//! text appended at the cursor, the end of the word rewritten, or the whole
+//! word swapped for another. `Engine::edit_op` reads a key's `Result` that way;
+//! the backspace + chars form stays authoritative for hosts that ignore it.
+
+use super::{Action, Result};
+use crate::prelude::*;
+
+/// Meaning of a `Result` (see `Engine::edit_op`)
+///
+/// Only the engine's edit: a break key that isn't consumed is still typed by
+/// the app after it, as with the plain `Result`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditOp {
+    /// Append text at the cursor
+    InsertText(String),
+    /// Replace the last `n_chars` chars before the cursor with `text`
+    ReplaceTail { n_chars: usize, text: String },
+    /// Replace the engine's whole word before the cursor, including the
+    /// separators typed after a committed word ("tẽt" → "text ")
+    ReplaceWord { old: String, new: String },
+}
+
+impl EditOp {
+    /// Kind code for FFI (1=insert, 2=replace tail, 3=replace word)
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::InsertText(_) => 1,
+            Self::ReplaceTail { .. } => 2,
+            Self::ReplaceWord { .. } => 3,
+        }
+    }
+}
+
+/// Read `result` against `before`, the engine's word on screen before the key
+///
+/// `text` is the full output (`Result::chars` stops at 255).
+pub(crate) fn describe(result: &Result, text: String, before: &str) -> Option<EditOp> {
+    if result.action == Action::None as u8 {
+        return None;
+    }
+    let n_chars = result.backspace as usize;
+    if n_chars == 0 {
+        return (!text.is_empty()).then_some(EditOp::InsertText(text));
+    }
+    if n_chars == before.chars().count() {
+        return Some(EditOp::ReplaceWord {
+            old: before.to_string(),
+            new: text,
+        });
+    }
+    Some(EditOp::ReplaceTail { n_chars, text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(backspace: u8, text: &str, before: &str) -> Option<EditOp> {
+        let chars: Vec<char> = text.chars().collect();
+        describe(&Result::send(backspace, &chars), text.to_string(), before)
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(op(0, "á", "ch"), Some(EditOp::InsertText("á".into())));
+        assert_eq!(
+            op(1, "ế", "tiê"),
+            Some(EditOp::ReplaceTail {
+                n_chars: 1,
+                text: "ế".into()
+            })
+        );
+        assert_eq!(
+            op(3, "text ", "tẽt"),
+            Some(EditOp::ReplaceWord {
+                old: "tẽt".into(),
+                new: "text ".into()
+            })
+        );
+        assert_eq!(op(0, "", ""), None);
+        assert_eq!(describe(&Result::none(), String::new(), "a"), None);
+    }
+}
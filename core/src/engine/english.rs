@@ -0,0 +1,152 @@
+//! English Confidence Scoring
+//!
+//! Auto-restore makes a yes/no call on each word. This exposes the graded
+//! signal behind such a call: how likely a raw Telex keystroke word is English
+//! rather than Vietnamese, as a 0-100 score with tiers, so hosts can build
+//! their own policy (e.g. ask the user at medium confidence).
+//!
+//! ## Signals
+//! - Raw word is an English dictionary word (+)
+//! - Raw word is a known English word with Telex patterns ("coffee") (+)
+//! - Telex reading is a Vietnamese dictionary syllable (-)
+//! - Telex reading isn't a well-formed Vietnamese syllable (+)
+//! - Letter bigrams read more like English than Vietnamese Telex (±, capped)
+
+use super::validation;
+use crate::data::{bigrams, chars, dictionary, english_dict, telex_doubles};
+use crate::prelude::*;
+use crate::text::{transform_text, Direction};
+
+/// Scores at or above this are `Medium`
+pub const MEDIUM_THRESHOLD: u8 = 35;
+
+/// Scores at or above this are `High`
+pub const HIGH_THRESHOLD: u8 = 70;
+
+/// Score with no evidence either way
+pub(crate) const NEUTRAL: i32 = 50;
+const ENGLISH_WORD: i32 = 45;
+const TELEX_DOUBLE_WORD: i32 = 10;
+const VIETNAMESE_WORD: i32 = -40;
+const INVALID_SYLLABLE: i32 = 15;
+/// Points per nat of mean bigram log-ratio, and their cap either way
+const BIGRAM_WEIGHT: i32 = 10;
+const BIGRAM_CAP: i32 = 20;
+
+/// Confidence tier of an English score
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfidenceTier {
+    /// Most likely Vietnamese
+    Low = 0,
+    /// Ambiguous: both readings are plausible ("can", "ban")
+    Medium = 1,
+    /// Most likely English
+    High = 2,
+}
+
+impl ConfidenceTier {
+    /// Tier for a score
+    pub fn from_score(score: u8) -> Self {
+        if score >= HIGH_THRESHOLD {
+            Self::High
+        } else if score >= MEDIUM_THRESHOLD {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// How likely a raw keystroke word is English
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnglishConfidence {
+    /// 0 (surely Vietnamese) to 100 (surely English)
+    pub score: u8,
+    pub tier: ConfidenceTier,
+}
+
+/// Score a raw Telex keystroke word ("text", "vieetj")
+///
+/// Tokens other than ASCII letters score neutral: digits and symbols have no
+/// Telex reading, and letters from other layouts ("café", "Müller") are
+/// typed literally, so neither model has evidence about them.
+pub fn english_confidence(raw: &str) -> EnglishConfidence {
+    let lower = raw.to_ascii_lowercase();
+    let mut score = NEUTRAL;
+    if !lower.is_empty() && lower.bytes().all(|b| b.is_ascii_alphabetic()) {
+        if english_dict::is_english_word(&lower) {
+            score += ENGLISH_WORD;
+        }
+        if telex_doubles::contains(&lower) {
+            score += TELEX_DOUBLE_WORD;
+        }
+        let reading = transform_text(&lower, Direction::Telex);
+        if dictionary::is_vietnamese(&reading, true) {
+            score += VIETNAMESE_WORD;
+        }
+        if !is_valid_syllable(&reading) {
+            score += INVALID_SYLLABLE;
+        }
+        if let Some(ratio) = bigrams::english_log_ratio(&lower) {
+            score += (ratio * BIGRAM_WEIGHT / 1000).clamp(-BIGRAM_CAP, BIGRAM_CAP);
+        }
+    }
+    let score = score.clamp(0, 100) as u8;
+    EnglishConfidence {
+        score,
+        tier: ConfidenceTier::from_score(score),
+    }
+}
+
+/// Whether a composed word ("tẽt") is a well-formed Vietnamese syllable
+fn is_valid_syllable(word: &str) -> bool {
+    let Some(parsed) = word
+        .chars()
+        .map(chars::parse_char)
+        .collect::<Option<Vec<_>>>()
+    else {
+        return false;
+    };
+    let keys: Vec<u16> = parsed.iter().map(|p| p.key).collect();
+    let tones: Vec<u8> = parsed.iter().map(|p| p.tone).collect();
+    validation::is_valid_with_tones(&keys, &tones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers() {
+        assert_eq!(english_confidence("text").tier, ConfidenceTier::High);
+        assert_eq!(english_confidence("hello").tier, ConfidenceTier::High);
+        assert_eq!(english_confidence("vieetj").tier, ConfidenceTier::Low);
+        assert_eq!(english_confidence("nguwowif").tier, ConfidenceTier::Low);
+        // Both an English word and a Vietnamese syllable
+        assert_eq!(english_confidence("can").tier, ConfidenceTier::Medium);
+    }
+
+    #[test]
+    fn test_case_and_non_words() {
+        assert_eq!(english_confidence("Text"), english_confidence("text"));
+        assert_eq!(english_confidence("").score, NEUTRAL as u8);
+        assert_eq!(english_confidence("v1.2").score, NEUTRAL as u8);
+        assert_eq!(english_confidence("café").score, NEUTRAL as u8);
+        assert_eq!(english_confidence("Müller").score, NEUTRAL as u8);
+    }
+
+    #[test]
+    fn test_tier_from_score() {
+        assert_eq!(ConfidenceTier::from_score(0), ConfidenceTier::Low);
+        assert_eq!(
+            ConfidenceTier::from_score(MEDIUM_THRESHOLD),
+            ConfidenceTier::Medium
+        );
+        assert_eq!(
+            ConfidenceTier::from_score(HIGH_THRESHOLD - 1),
+            ConfidenceTier::Medium
+        );
+        assert_eq!(ConfidenceTier::from_score(100), ConfidenceTier::High);
+    }
+}
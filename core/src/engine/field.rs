@@ -0,0 +1,79 @@
+//! Field Kinds
+//!
+//! Hosts know what the focused field takes from its accessibility role (a
+//! secure text field, a URL bar, a code editor). `Engine::set_field_kind`
+//! turns that into switches the engine already has: secure mode, a
+//! `DisableReason`, a `ValidationProfile`. The user's own settings stay as
+//! they are and apply again once focus is back in a normal field.
+
+use super::validation::ValidationProfile;
+
+/// Kind of text field the host reports focus in
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Prose: the user's settings apply unchanged
+    #[default]
+    Normal = 0,
+    /// Email address: input off, no auto-capitalize
+    Email = 1,
+    /// URL or path: input off, no auto-capitalize
+    Url = 2,
+    /// Code editor or terminal: Strict validation, no auto-capitalize
+    Code = 3,
+    /// Password: secure mode, nothing typed is kept
+    Password = 4,
+}
+
+impl FieldKind {
+    pub const ALL: [Self; 5] = [
+        Self::Normal,
+        Self::Email,
+        Self::Url,
+        Self::Code,
+        Self::Password,
+    ];
+
+    /// Kind for an FFI code (0..=4)
+    pub fn from_u8(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| *k as u8 == code)
+    }
+
+    /// Vietnamese input is off in the field (`DisableReason::Field`);
+    /// shortcuts still expand, as with input turned off by the user
+    pub fn disables_input(self) -> bool {
+        matches!(self, Self::Email | Self::Url)
+    }
+
+    /// Validation the field imposes over the user's profile
+    pub fn validation_profile(self) -> Option<ValidationProfile> {
+        (self == Self::Code).then_some(ValidationProfile::Strict)
+    }
+
+    /// Sentence starts are auto-capitalized (when the user has it on)
+    pub fn capitalizes(self) -> bool {
+        self == Self::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_u8() {
+        for kind in FieldKind::ALL {
+            assert_eq!(FieldKind::from_u8(kind as u8), Some(kind));
+        }
+        assert_eq!(FieldKind::from_u8(5), None);
+    }
+
+    #[test]
+    fn test_only_prose_keeps_user_settings() {
+        for kind in FieldKind::ALL {
+            let unchanged =
+                !kind.disables_input() && kind.validation_profile().is_none() && kind.capitalizes();
+            assert_eq!(unchanged, kind == FieldKind::Normal, "{kind:?}");
+        }
+    }
+}
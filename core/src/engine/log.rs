@@ -0,0 +1,97 @@
+//! Diagnostic Log
+//!
+//! "Why did it restore that word?" and "why are my keys not transformed?"
+//! are hard to answer from a bug report. Hosts that install a `LogSink` get
+//! one record per engine decision (restores, expansions, mode changes,
+//! timing rules) at runtime, to forward to their own log.
+//!
+//! Without a sink nothing is formatted. Records describe decisions, never
+//! the typed text: turning logs on for a report doesn't capture what the
+//! user typed, and nothing is logged in secure mode. Builds without the
+//! `diagnostics` feature drop the records at compile time.
+
+/// Importance of a record
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Per-key decisions (expansions, timing rules)
+    Debug = 0,
+    /// State changes a user would notice (restores, input on/off)
+    Info = 1,
+}
+
+/// Receiver for diagnostic records (see `Engine::set_log_sink`)
+///
+/// Called synchronously inside the key handler: keep it cheap and don't
+/// call back into the engine.
+pub trait LogSink: Send {
+    /// `target` names the subsystem: "restore", "shortcut", "mode",
+    /// "secure", "timing", "history", "context"
+    fn log(&mut self, level: LogLevel, target: &'static str, message: &str);
+}
+
+#[cfg(all(test, feature = "diagnostics"))]
+mod tests {
+    use super::*;
+    use crate::engine::Engine;
+    use crate::utils::type_word;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects records as "target: message" lines
+    struct Collector(Arc<Mutex<Vec<String>>>);
+
+    impl LogSink for Collector {
+        fn log(&mut self, _level: LogLevel, target: &'static str, message: &str) {
+            self.0.lock().unwrap().push(format!("{target}: {message}"));
+        }
+    }
+
+    fn engine_with_collector() -> (Engine, Arc<Mutex<Vec<String>>>) {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let mut e = Engine::new();
+        e.set_log_sink(Box::new(Collector(lines.clone())));
+        (e, lines)
+    }
+
+    fn take(lines: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+        core::mem::take(&mut *lines.lock().unwrap())
+    }
+
+    #[test]
+    fn test_decisions_are_logged() {
+        let (mut e, lines) = engine_with_collector();
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "vieetj user ");
+        assert_eq!(take(&lines), ["restore: EnglishWord, 2 chars to 4"]);
+
+        e.set_enabled(false);
+        assert_eq!(
+            take(&lines),
+            ["mode: enabled=false method=0", "mode: disabled by User"]
+        );
+    }
+
+    #[test]
+    fn test_no_typed_text_in_records() {
+        let (mut e, lines) = engine_with_collector();
+        e.set_english_auto_restore(true);
+        e.shortcuts_mut()
+            .add(crate::engine::shortcut::Shortcut::new("vn", "Việt Nam"));
+        type_word(&mut e, "user vn mootj <<");
+        let lines = take(&lines);
+        assert!(!lines.is_empty());
+        for word in ["user", "uẻ", "vn", "Việt", "một"] {
+            assert!(lines.iter().all(|l| !l.contains(word)), "{lines:?}");
+        }
+    }
+
+    #[test]
+    fn test_nothing_logged_in_secure_mode() {
+        let (mut e, lines) = engine_with_collector();
+        e.set_secure_mode(true);
+        take(&lines);
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "text ");
+        assert!(take(&lines).is_empty());
+    }
+}
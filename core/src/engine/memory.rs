@@ -0,0 +1,89 @@
+//! Memory Budget
+//!
+//! The engine keeps its per-keystroke state inline and its hot lookup tables
+//! small. The ceilings below fail the build when a struct or table outgrows
+//! them, so raising one is a reviewed change rather than silent drift;
+//! `memory_report()` gives the actual sizes.
+
+use super::buffer::{Buffer, Char, RawKeystroke};
+use super::{Engine, Result};
+use crate::data::{bigrams, chars, constants, dictionary, english_dict, telex_doubles};
+use core::mem::{size_of, size_of_val};
+
+/// Ceiling for `Engine` (inline state, heap excluded)
+pub const ENGINE_CEILING: usize = 2560;
+/// Ceiling for the word `Buffer`
+pub const BUFFER_CEILING: usize = 1600;
+/// Ceiling for the FFI `Result` (256 UTF-32 chars + header)
+pub const RESULT_CEILING: usize = 1032;
+/// Ceiling for the hot lookup tables (~1.4KB)
+pub const TABLE_CEILING: usize = 1434;
+
+const _: () = assert!(size_of::<Engine>() <= ENGINE_CEILING);
+const _: () = assert!(size_of::<Buffer>() <= BUFFER_CEILING);
+const _: () = assert!(size_of::<Result>() <= RESULT_CEILING);
+const _: () = assert!(size_of::<Char>() <= 8);
+const _: () = assert!(size_of::<RawKeystroke>() <= 8);
+const _: () = assert!(table_bytes() <= TABLE_CEILING);
+
+/// Bytes of the tables consulted on every keystroke
+const fn table_bytes() -> usize {
+    size_of_val(&bigrams::LOG_RATIO)
+        + size_of_val(&chars::VOWEL_TABLE)
+        + size_of_val(&constants::INITIAL_PAIRS)
+        + size_of_val(&constants::FINAL_PAIRS)
+        + size_of_val(&constants::TONE_CODA_MATRIX)
+}
+
+/// Sizes in bytes, from `memory_report()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// `Engine` struct (inline; shortcuts and history live on the heap)
+    pub engine: usize,
+    /// Word buffer inside the engine
+    pub buffer: usize,
+    /// One FFI `Result`
+    pub result: usize,
+    /// One composed char / one raw keystroke
+    pub char: usize,
+    pub raw_keystroke: usize,
+    /// Hot lookup tables (bigrams, vowels, consonant pairs, tone/coda)
+    pub tables: usize,
+    /// Embedded word lists (Vietnamese, keep, English, Telex doubles)
+    pub word_lists: usize,
+}
+
+/// Actual struct and table sizes of this build
+pub fn memory_report() -> MemoryReport {
+    let doubles = size_of_val(&telex_doubles::WORDS)
+        + telex_doubles::WORDS.iter().map(|w| w.len()).sum::<usize>();
+    MemoryReport {
+        engine: size_of::<Engine>(),
+        buffer: size_of::<Buffer>(),
+        result: size_of::<Result>(),
+        char: size_of::<Char>(),
+        raw_keystroke: size_of::<RawKeystroke>(),
+        tables: table_bytes(),
+        word_lists: dictionary::DIC_VI.len()
+            + dictionary::DIC_KEEP.len()
+            + english_dict::ENGLISH_WORDS.len()
+            + doubles,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_within_ceilings() {
+        let r = memory_report();
+        assert!(r.engine <= ENGINE_CEILING);
+        assert!(r.buffer <= BUFFER_CEILING);
+        assert!(r.result <= RESULT_CEILING);
+        assert!(r.tables <= TABLE_CEILING);
+        // The engine holds the buffer inline
+        assert!(r.engine > r.buffer);
+        assert!(r.word_lists > r.tables);
+    }
+}
@@ -10,11 +10,30 @@
 //! 3. **Shortcut Support**: User-defined abbreviations with priority
 //! 4. **Longest-Match-First**: For diacritic placement
 
+pub mod behavior;
 pub mod buffer;
+pub mod context;
+pub mod defer;
+pub mod diff;
+pub mod edit;
+pub mod english;
+pub mod field;
+pub mod log;
+pub mod memory;
+pub mod observer;
+pub mod output;
+pub mod profile;
+pub mod restore;
 pub mod shortcut;
+pub mod shortcut_pack;
+pub mod spell;
+pub mod status;
 pub mod syllable;
+pub mod timing;
+pub mod track;
 pub mod transform;
 pub mod validation;
+mod wipe;
 
 use crate::data::{
     chars::{self, mark, tone},
@@ -22,12 +41,31 @@ use crate::data::{
     vowel::{Phonology, Vowel},
 };
 use crate::input::{self, ToneType};
-use crate::utils;
-use buffer::{Buffer, Char, MAX};
+use crate::prelude::*;
+use crate::text::{self, grapheme};
+use crate::utils::{self, ForeignFinalTone};
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use buffer::{Buffer, Char, RawKeystroke, MAX, MAX_WORD};
+use context::{ContextSync, ResetPolicy, ScreenCheck};
+use defer::{DeferEvent, DeferKind, DeferStep, Deferred};
+use diff::{Edit, Unit};
+use edit::EditOp;
+use field::FieldKind;
+use log::{LogLevel, LogSink};
+use observer::{EngineObserver, RevertKind};
+use output::{OutputRegistry, OutputStrategy};
+use profile::{Config, Profile, DEFAULT_PROFILE};
+use restore::{RestoreFilter, RestoreInfo, RestoreReason, TieBreak, WordChoices, WordLanguage};
 use shortcut::{InputMethod, ShortcutTable};
+use spell::SpellFlag;
+use status::{DisableReason, EngineStatus, InputMode};
+use timing::KeyTiming;
+use track::{AppliedTransform, TransformKind};
+use transform::ToneKeyPolicy;
 use validation::{
-    is_foreign_word_pattern, is_valid, is_valid_for_transform_with_foreign, is_valid_with_foreign,
-    is_valid_with_tones, is_valid_with_tones_and_foreign,
+    has_invalid_breve_final, is_circumflex_closed_english, is_foreign_word_pattern, is_valid,
+    is_valid_for_transform_with_profile, is_valid_mark_placement, is_valid_with_profile,
+    is_valid_with_tones, is_valid_with_tones_and_profile, ValidationProfile,
 };
 
 /// Engine action result
@@ -48,12 +86,32 @@ pub struct Result {
     pub count: u8,
     /// Flags byte:
     /// - bit 0 (0x01): key_consumed - if set, the trigger key should NOT be passed through
-    ///   Used for shortcuts where the trigger key is part of the replacement
+    ///   Used for shortcuts where the trigger key is part of the replacement,
+    ///   and for keys swallowed without output
+    /// - bit 1 (0x02): is_restore - output is the raw keystrokes (auto-restore, ESC)
+    /// - bit 2 (0x04): reserved
+    /// - bit 3 (0x08): new_undo_group - edit starts a new undo group (new word,
+    ///   restore, shortcut expansion); otherwise it joins the previous one
     pub flags: u8,
 }
 
 /// Flag: key was consumed by shortcut, don't pass through
 pub const FLAG_KEY_CONSUMED: u8 = 0x01;
+/// Flag: output restores the raw keystrokes (auto-restore, ESC)
+pub const FLAG_RESTORE: u8 = 0x02;
+/// Flag: start a new host undo group instead of joining the previous edit
+pub const FLAG_NEW_UNDO_GROUP: u8 = 0x08;
+
+/// Backspace count for deleting `n` chars
+///
+/// Results carry the count in a u8. Words are capped at `MAX_WORD` chars
+/// and shortcut triggers at the same length, so counts taken from them fit;
+/// a larger `n` is a bug. Debug builds assert, release builds clamp rather
+/// than wrap around to a small count that deletes the wrong chars.
+fn backspaces(n: usize) -> u8 {
+    debug_assert!(n <= MAX_WORD, "backspace count {n} overflows u8");
+    n.min(MAX_WORD) as u8
+}
 
 impl Result {
     pub fn none() -> Self {
@@ -82,6 +140,11 @@ impl Result {
         result
     }
 
+    /// Send a minimal edit (see `diff`)
+    pub(crate) fn edit(edit: &Edit) -> Self {
+        Self::send(backspaces(edit.backspaces(Unit::Char)), &edit.inserted)
+    }
+
     /// Send with key_consumed flag set (shortcut consumed the trigger key)
     pub fn send_consumed(backspace: u8, chars: &[char]) -> Self {
         let mut result = Self::send(backspace, chars);
@@ -89,10 +152,32 @@ impl Result {
         result
     }
 
+    /// Swallow the key without output (replaces the old `send(0, &[])` convention)
+    pub fn consumed() -> Self {
+        Self::send_consumed(0, &[])
+    }
+
+    /// Send raw keystrokes back in place of the transformed word
+    pub fn restore(backspace: u8, chars: &[char]) -> Self {
+        let mut result = Self::send(backspace, chars);
+        result.flags = FLAG_RESTORE | FLAG_NEW_UNDO_GROUP;
+        result
+    }
+
     /// Check if key was consumed (should not be passed through)
     pub fn key_consumed(&self) -> bool {
         self.flags & FLAG_KEY_CONSUMED != 0
     }
+
+    /// Check if output is a restore to raw keystrokes
+    pub fn is_restore(&self) -> bool {
+        self.flags & FLAG_RESTORE != 0
+    }
+
+    /// Check if the edit should start a new undo group
+    pub fn new_undo_group(&self) -> bool {
+        self.flags & FLAG_NEW_UNDO_GROUP != 0
+    }
 }
 
 /// Transform type for revert tracking
@@ -116,52 +201,122 @@ enum Transform {
     BracketAsVowel,
 }
 
-/// Word history ring buffer capacity (stores last N committed words)
+/// Default word history capacity (stores last N committed words)
 const HISTORY_CAPACITY: usize = 10;
 
-/// Ring buffer for word history (stack-allocated, O(1) push/pop)
+/// Upper bound for `Engine::set_history_capacity`
+pub const MAX_HISTORY_CAPACITY: usize = 64;
+
+/// Bounded word history (O(1) push/pop, oldest dropped when full)
 ///
 /// Used for backspace-after-space feature: when user presses backspace
 /// immediately after committing a word with space, restore the previous
 /// buffer state to allow editing.
+///
+/// Each entry is tagged with the separators typed after it (" ", ". ", "\n"),
+/// so chained backspaces know how many chars to delete before the next
+/// restore. Empty when unknown.
 struct WordHistory {
-    data: [Buffer; HISTORY_CAPACITY],
-    head: usize,
-    len: usize,
+    data: VecDeque<HistoryEntry>,
+    capacity: usize,
+}
+
+/// A committed word with the keystrokes that produced it
+///
+/// Restoring the word brings back the original raw input (modifier keys and
+/// caps flags included), so ESC and auto-restore behave as before the commit.
+struct HistoryEntry {
+    buf: Buffer,
+    /// raw_input at commit time
+    raw: Vec<RawKeystroke>,
+    /// First letter was capitalized by auto-capitalize
+    auto_capitalized: bool,
+    /// Separators typed after the word
+    trailer: String,
 }
 
 impl WordHistory {
     fn new() -> Self {
         Self {
-            data: std::array::from_fn(|_| Buffer::new()),
-            head: 0,
-            len: 0,
+            data: VecDeque::with_capacity(HISTORY_CAPACITY),
+            capacity: HISTORY_CAPACITY,
         }
     }
 
-    /// Push buffer to history (overwrites oldest if full)
-    fn push(&mut self, buf: Buffer) {
-        self.data[self.head] = buf;
-        self.head = (self.head + 1) % HISTORY_CAPACITY;
-        if self.len < HISTORY_CAPACITY {
-            self.len += 1;
+    /// Push entry to history (drops oldest if full)
+    fn push(&mut self, entry: HistoryEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.data.len() == self.capacity {
+            self.data.pop_front();
         }
+        self.data.push_back(entry);
     }
 
-    /// Pop most recent buffer from history
-    fn pop(&mut self) -> Option<Buffer> {
-        if self.len == 0 {
-            return None;
+    /// Pop most recent entry from history
+    fn pop(&mut self) -> Option<HistoryEntry> {
+        self.data.pop_back()
+    }
+
+    /// Clear, overwriting every entry's keystrokes and text
+    fn zeroize(&mut self) {
+        for entry in self.data.iter_mut() {
+            entry.buf.zeroize();
+            wipe::zero_vec(&mut entry.raw, RawKeystroke::new(0, false, false));
+            wipe::zero_string(&mut entry.trailer);
         }
-        self.head = (self.head + HISTORY_CAPACITY - 1) % HISTORY_CAPACITY;
-        self.len -= 1;
-        Some(self.data[self.head].clone())
+        self.data.clear();
     }
 
+    /// Most recent buffer without removing it
+    fn last(&self) -> Option<&Buffer> {
+        self.data.back().map(|entry| &entry.buf)
+    }
+
+    /// Separators typed after the most recent buffer
+    fn last_trailer(&self) -> Option<&str> {
+        self.data.back().map(|entry| entry.trailer.as_str())
+    }
+
+    /// Tag the most recent buffer with the separators typed after it
+    fn set_last_trailer(&mut self, trailer: &str) {
+        if let Some(entry) = self.data.back_mut() {
+            entry.trailer.clear();
+            entry.trailer.push_str(trailer);
+        }
+    }
+
+    /// Buffer `depth` entries back (0 = most recent)
+    fn peek(&self, depth: usize) -> Option<&Buffer> {
+        self.data.iter().rev().nth(depth).map(|entry| &entry.buf)
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Change capacity, dropping the oldest entries if shrinking
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.data.len() > capacity {
+            self.data.pop_front();
+        }
+    }
+
+    /// Clear, overwriting the entries' text (same as `zeroize`)
     fn clear(&mut self) {
-        self.len = 0;
-        self.head = 0;
+        self.zeroize();
+    }
+}
+
+/// Separator a break key leaves on screen after a word: punctuation, or a
+/// newline for Enter. None for navigation keys (Tab, arrows, ESC).
+fn boundary_char(key: u16, shift: bool) -> Option<char> {
+    if key == keys::RETURN || key == keys::ENTER {
+        return Some('\n');
     }
+    break_key_to_char(key, shift)
 }
 
 /// Check if key is sentence-ending punctuation (. ! ?) but NOT Enter
@@ -173,6 +328,31 @@ fn is_sentence_ending_punctuation(key: u16, shift: bool) -> bool {
         || (shift && key == keys::SLASH) // ?
 }
 
+/// Change the case of the first letter, skipping leading symbols
+/// ("“xin chào" → "“Xin chào")
+fn set_first_letter_case(text: &str, upper: bool) -> String {
+    match text.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((i, c)) => {
+            let mut out = String::with_capacity(text.len());
+            out.push_str(&text[..i]);
+            if upper {
+                out.extend(c.to_uppercase());
+            } else {
+                out.extend(c.to_lowercase());
+            }
+            out.push_str(&text[i + c.len_utf8()..]);
+            out
+        }
+        None => text.to_string(),
+    }
+}
+
+/// More than one letter, all uppercase ("DC", not "D" or "Dc")
+fn is_all_caps(text: &str) -> bool {
+    let mut letters = text.chars().filter(|c| c.is_alphabetic());
+    letters.clone().nth(1).is_some() && letters.all(char::is_uppercase)
+}
+
 /// Check if a break key should reset pending_capitalize
 /// Neutral keys like quotes, parentheses, arrows should NOT reset (preserve pending)
 /// Word-breaking keys like comma should reset
@@ -254,10 +434,14 @@ pub struct Engine {
     buf: Buffer,
     method: u8,
     enabled: bool,
+    /// `DisableReason` bits holding input off (`enabled` iff none)
+    disabled_by: u8,
+    /// Secure field focused: keys are not looked at or recorded
+    secure: bool,
     last_transform: Option<Transform>,
     shortcuts: ShortcutTable,
-    /// Raw keystroke history for ESC restore (key, caps, shift)
-    raw_input: Vec<(u16, bool, bool)>,
+    /// Raw keystroke history for ESC restore
+    raw_input: Vec<RawKeystroke>,
     /// True if current word has non-letter characters before letters
     /// Used to prevent false shortcut matches (e.g., "149k" should not match "k")
     has_non_letter_prefix: bool,
@@ -265,6 +449,15 @@ pub struct Engine {
     /// When true, typing 'w' stays as 'w' instead of converting to 'ư'
     /// Horn modifier (try_tone) still works: "ow" → "ơ", "uw" → "ư"
     skip_w_shortcut: bool,
+    /// Turn 'o' after 'ư' into 'ơ' automatically ("uwo" → "ươ", "u7o" → "ươ")
+    /// When false, "ưo" stays until the horn key is typed on the 'o'
+    auto_uo_horn: bool,
+    /// Compose Vietnamese in the word after '#' or '@' ("#chaof" → "#chào")
+    /// When false, that word is typed as is, like the rest of a handle or address
+    tag_composition: bool,
+    /// Position of the 'ơ' the current word got automatically from "ưo"
+    /// Reverted to 'o' when a later letter makes the word invalid ("ưoman")
+    auto_horn_pos: Option<usize>,
     /// Enable bracket shortcuts: ] → ư, [ → ơ (Issue #159)
     bracket_shortcut: bool,
     /// Enable ESC key to restore raw ASCII (undo Vietnamese transforms)
@@ -286,15 +479,49 @@ pub struct Engine {
     /// Number of spaces typed after committing a word (for backspace tracking)
     /// When this reaches 0 on backspace, we restore the committed word
     spaces_after_commit: u8,
-    /// Pending breve position: position of 'a' that has deferred breve
-    /// Breve on 'a' in open syllables (like "raw") is invalid Vietnamese
-    /// We defer applying breve until a valid final consonant is typed
-    pending_breve_pos: Option<usize>,
-    /// Issue #133: Pending horn position on 'u' in "uơ" pattern
-    /// When "uo" + 'w' is typed at end of syllable, only 'o' gets horn initially.
-    /// If a final consonant/vowel is added, also apply horn to 'u'.
-    /// Examples: "huow" → "huơ" (stays), "duow" + "c" → "dược" (u gets horn)
-    pending_u_horn_pos: Option<usize>,
+    /// Separator chars typed after the committed word (one per `spaces_after_commit`)
+    /// Only trusted when its length matches `spaces_after_commit`
+    commit_trailer: String,
+    /// An external edit asked for the host's text (see `reset_external`)
+    needs_context: bool,
+    /// Lifecycle event receiver (host UI, statistics)
+    observer: Option<Box<dyn EngineObserver>>,
+    /// Diagnostic record receiver
+    log_sink: Option<Box<dyn LogSink>>,
+    /// Run spell check on committed words
+    spell_check: bool,
+    /// User dictionary for spell check (lowercase)
+    user_words: BTreeSet<String>,
+    /// Last spell check flag, taken by the host after a commit
+    spell_flag: Option<SpellFlag>,
+    /// Untruncated text of the shortcut expanded by the last key
+    /// (`Result` holds at most 255 chars)
+    expansion: Vec<char>,
+    /// Details of the restore done by the last key
+    last_restore: Option<RestoreInfo>,
+    /// Engine's word on screen when the last key started (`edit_op`)
+    edit_before: String,
+    /// Per-app output rules and the focused app/role they're looked up for
+    output: OutputRegistry,
+    focus: (String, Option<String>),
+    /// Character the layout produced for the key being processed
+    /// (`on_key_typed`), recorded with its raw keystroke
+    typed_char: Option<char>,
+    /// Time windows and rules of `on_key_at`
+    timing: KeyTiming,
+    /// Last key given to `on_key_at` and its timestamp
+    last_key_at: Option<(u16, u64)>,
+    /// Timestamp of a Shift tap waiting for its second tap
+    shift_tap_at: Option<u64>,
+    /// Word so far came only from `push_composed`: it is the app's text
+    /// already, so ending it never auto-restores and re-ingesting the
+    /// engine's own output leaves it unchanged
+    composed_word: bool,
+    /// Diacritic waiting for the next key (see `defer::DEFER_RULES`)
+    /// - Issue #133: horn on 'u' of "uơ": "huow" → "huơ" (stays),
+    ///   "duow" + "c" → "dược" (u gets horn)
+    /// - Issue #44: breve on 'a' in an open syllable, until a final is typed
+    deferred: Option<Deferred>,
     /// Tracks if stroke was reverted in current word (ddd → dd)
     /// When true, subsequent 'd' keys are treated as normal letters, not stroke triggers
     /// This prevents "ddddd" from oscillating between đ and dd states
@@ -352,13 +579,39 @@ pub struct Engine {
     /// Tracks if auto-capitalize was just used on the current word
     /// Used to restore pending_capitalize when user deletes the capitalized letter
     auto_capitalize_used: bool,
+    /// shortcut_prefix was started at a sentence start ("ok. #sig")
+    /// The prefix symbol cancels pending_capitalize, but an expansion of the
+    /// prefixed trigger still starts the sentence and gets capitalized
+    prefix_capitalize: bool,
     /// Tracks if we just saw sentence-ending punctuation (. ! ?)
     /// Only set pending_capitalize when space/Enter follows
     /// Issue #185: don't capitalize immediately after punctuation (e.g., google.com)
     saw_sentence_ending: bool,
-    /// Allow foreign consonants (z, w, j, f) as valid initial consonants
-    /// When true, these letters are accepted as Vietnamese consonants for loanwords
-    allow_foreign_consonants: bool,
+    /// Which non-standard spellings count as Vietnamese
+    /// Tolerant accepts foreign consonants (z, w, j, f) as initials for loanwords
+    validation_profile: ValidationProfile,
+    /// Short words auto-restore leaves as typed
+    restore_filter: RestoreFilter,
+    /// How the user last committed recent keystroke words (restore tie-break)
+    word_choices: WordChoices,
+    /// Language of the last two committed words, most recent first
+    previous_languages: [Option<WordLanguage>; 2],
+    /// Kind of field the host reports focus in (see `set_field_kind`)
+    field_kind: FieldKind,
+    /// Tone/mark keys that change nothing: typed or swallowed
+    tone_key_policy: ToneKeyPolicy,
+    /// Tone keys on syllables with a non-Vietnamese final ("gel")
+    foreign_final_tone: ForeignFinalTone,
+    /// Word holding a non-ASCII letter from another layout ("café", "Müller"):
+    /// its text as on screen. The rest of the word is typed literally - no
+    /// transforms, no restore - until the next word boundary.
+    literal_word: Option<String>,
+    /// Left/Right arrows move within the word being typed instead of ending it
+    mid_word_editing: bool,
+    /// Name of the active profile (its state lives in the fields above)
+    profile_name: String,
+    /// Inactive profiles by name
+    profiles: BTreeMap<String, Profile>,
 }
 
 impl Default for Engine {
@@ -373,11 +626,16 @@ impl Engine {
             buf: Buffer::new(),
             method: 0,
             enabled: true,
+            disabled_by: 0,
+            secure: false,
             last_transform: None,
             shortcuts: ShortcutTable::with_defaults(),
             raw_input: Vec::with_capacity(64),
             has_non_letter_prefix: false,
             skip_w_shortcut: false,
+            auto_uo_horn: true,
+            tag_composition: true,
+            auto_horn_pos: None,
             bracket_shortcut: false,    // Default: OFF (Issue #159)
             esc_restore_enabled: false, // Default: OFF (user request)
             free_tone_enabled: false,
@@ -385,8 +643,24 @@ impl Engine {
             english_auto_restore: false, // Default: OFF (experimental feature)
             word_history: WordHistory::new(),
             spaces_after_commit: 0,
-            pending_breve_pos: None,
-            pending_u_horn_pos: None,
+            commit_trailer: String::new(),
+            needs_context: false,
+            observer: None,
+            log_sink: None,
+            spell_check: false,
+            user_words: BTreeSet::new(),
+            spell_flag: None,
+            expansion: Vec::new(),
+            last_restore: None,
+            edit_before: String::new(),
+            output: OutputRegistry::new(),
+            focus: (String::new(), None),
+            typed_char: None,
+            timing: KeyTiming::default(),
+            last_key_at: None,
+            shift_tap_at: None,
+            composed_word: false,
+            deferred: None,
             stroke_reverted: false,
             had_mark_revert: false,
             pending_mark_revert_pop: false,
@@ -403,22 +677,196 @@ impl Engine {
             auto_capitalize: false, // Default: OFF
             pending_capitalize: false,
             auto_capitalize_used: false,
+            prefix_capitalize: false,
             saw_sentence_ending: false,
-            allow_foreign_consonants: false, // Default: OFF
+            validation_profile: ValidationProfile::Standard,
+            restore_filter: RestoreFilter::default(),
+            word_choices: WordChoices::default(),
+            previous_languages: [None; 2],
+            field_kind: FieldKind::Normal,
+            tone_key_policy: ToneKeyPolicy::Smart,
+            foreign_final_tone: ForeignFinalTone::Reject,
+            literal_word: None,
+            mid_word_editing: false,
+            profile_name: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::new(),
         }
     }
 
     pub fn set_method(&mut self, method: u8) {
+        let changed = self.method != method;
         self.method = method;
+        if changed {
+            self.notify_mode_changed();
+        }
     }
 
+    /// Turn Vietnamese input on or off as the user (`DisableReason::User`)
     pub fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-        if !enabled {
+        self.set_enabled_for(enabled, DisableReason::User);
+    }
+
+    /// Turn input off for `reason`, or lift `reason` (`enabled` = true)
+    ///
+    /// Input is on only when no reason holds it off: enabling for one reason
+    /// leaves the others in place.
+    pub fn set_enabled_for(&mut self, enabled: bool, reason: DisableReason) {
+        let before = self.disable_reason();
+        if enabled {
+            self.disabled_by &= !reason.bit();
+        } else {
+            self.disabled_by |= reason.bit();
+        }
+        let changed = self.enabled != (self.disabled_by == 0);
+        self.enabled = self.disabled_by == 0;
+        if !self.enabled {
             self.buf.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
         }
+        if changed {
+            self.notify_mode_changed();
+        }
+        let reason = self.disable_reason();
+        if reason != before {
+            match reason {
+                Some(r) => self.log(LogLevel::Info, "mode", format_args!("disabled by {r:?}")),
+                None => self.log(LogLevel::Info, "mode", format_args!("enabled")),
+            }
+            if let Some(o) = self.observer.as_deref_mut() {
+                o.on_disable_reason_changed(reason);
+            }
+        }
+    }
+
+    /// Enter or leave secure mode, for password and other secure fields
+    ///
+    /// Beyond disabling input (`DisableReason::SecureField`), secure mode
+    /// wipes everything the engine kept about recent typing (see `wipe`)
+    /// and, until it is left, every key entry point returns
+    /// `Result::none()` before reading the key: nothing typed in the field
+    /// is stored, matched against shortcuts or reported to the observer.
+    /// Host-supplied text (`push_composed`, `restore_word`,
+    /// `sync_context`) is ignored the same way.
+    ///
+    /// A password field (`FieldKind::Password`) keeps secure mode on:
+    /// turning it off there is ignored until the field kind changes.
+    pub fn set_secure_mode(&mut self, secure: bool) {
+        let secure = secure || self.field_kind == FieldKind::Password;
+        self.set_enabled_for(!secure, DisableReason::SecureField);
+        if secure != self.secure {
+            let state = if secure { "on" } else { "off" };
+            self.log(
+                LogLevel::Info,
+                "secure",
+                format_args!("secure mode {state}"),
+            );
+        }
+        self.secure = secure;
+        if secure {
+            self.wipe();
+        }
+    }
+
+    pub fn is_secure_mode(&self) -> bool {
+        self.secure
+    }
+
+    /// Forget all typed text the engine holds, overwriting its memory
+    ///
+    /// Composing buffer, raw keystrokes, word history, shortcut prefix and
+    /// the per-key leftovers (last expansion, restore, spell flag, edit
+    /// context, key timing) are zeroed in place rather than just cleared.
+    /// Settings, shortcuts and the user dictionary are kept.
+    pub fn wipe(&mut self) {
+        self.log(LogLevel::Info, "secure", format_args!("typed text wiped"));
+        self.buf.zeroize();
+        wipe::zero_vec(&mut self.raw_input, RawKeystroke::new(0, false, false));
+        self.word_history.zeroize();
+        self.word_choices.zeroize();
+        wipe::zero_vec(&mut self.expansion, '\0');
+        for text in [
+            &mut self.shortcut_prefix,
+            &mut self.commit_trailer,
+            &mut self.edit_before,
+        ] {
+            wipe::zero_string(text);
+        }
+        for text in [self.literal_word.as_mut(), self.telex_double_raw.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            wipe::zero_string(text);
+        }
+        if let Some(info) = self.last_restore.as_mut() {
+            wipe::zero_string(&mut info.transformed);
+            wipe::zero_string(&mut info.raw);
+        }
+        if let Some(flag) = self.spell_flag.as_mut() {
+            wipe::zero_string(&mut flag.word);
+            flag.suggestions.iter_mut().for_each(wipe::zero_string);
+        }
+        self.clear_all();
+        self.last_restore = None;
+        self.typed_char = None;
+        self.spell_flag = None;
+        self.last_key_at = None;
+        self.shift_tap_at = None;
+        self.auto_capitalize_used = false;
+    }
+
+    /// Why input is off: the most specific reason holding it (secure field,
+    /// then app policy, then the user). None while input is on.
+    pub fn disable_reason(&self) -> Option<DisableReason> {
+        DisableReason::ALL
+            .into_iter()
+            .rev()
+            .find(|r| self.disabled_by & r.bit() != 0)
+    }
+
+    /// Register an observer for lifecycle events (replaces any previous one)
+    pub fn set_observer(&mut self, observer: Box<dyn EngineObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Remove the registered observer, returning it
+    pub fn take_observer(&mut self) -> Option<Box<dyn EngineObserver>> {
+        self.observer.take()
+    }
+
+    /// Install a receiver for diagnostic records (replaces any previous one)
+    pub fn set_log_sink(&mut self, sink: Box<dyn LogSink>) {
+        self.log_sink = Some(sink);
+    }
+
+    /// Remove the installed log sink, returning it
+    pub fn take_log_sink(&mut self) -> Option<Box<dyn LogSink>> {
+        self.log_sink.take()
+    }
+
+    /// Send a record to the log sink; formats only when one is installed
+    ///
+    /// Compiled out without the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    fn log(&mut self, level: LogLevel, target: &'static str, args: core::fmt::Arguments) {
+        if let Some(sink) = self.log_sink.as_deref_mut() {
+            sink.log(level, target, &format!("{args}"));
+        }
+    }
+
+    #[cfg(not(feature = "diagnostics"))]
+    fn log(&mut self, _level: LogLevel, _target: &'static str, _args: core::fmt::Arguments) {}
+
+    fn notify_mode_changed(&mut self) {
+        let (enabled, method) = (self.enabled, self.method);
+        self.log(
+            LogLevel::Info,
+            "mode",
+            format_args!("enabled={enabled} method={method}"),
+        );
+        if let Some(o) = self.observer.as_deref_mut() {
+            o.on_mode_changed(enabled, method);
+        }
     }
 
     /// Set whether to skip w→ư shortcut in Telex mode
@@ -426,11 +874,53 @@ impl Engine {
         self.skip_w_shortcut = skip;
     }
 
+    /// Set whether 'o' after 'ư' gets the horn automatically ("uwo" → "ươ")
+    pub fn set_auto_uo_horn(&mut self, enabled: bool) {
+        self.auto_uo_horn = enabled;
+    }
+
+    /// Set whether the word after '#' or '@' is composed ("#chào")
+    /// or typed as is ("#tieengs", "user@domain")
+    pub fn set_tag_composition(&mut self, enabled: bool) {
+        self.tag_composition = enabled;
+    }
+
     /// Set whether bracket shortcuts are enabled: ] → ư, [ → ơ (Issue #159)
     pub fn set_bracket_shortcut(&mut self, enabled: bool) {
         self.bracket_shortcut = enabled;
     }
 
+    /// Let Left/Right arrows move within the word being typed
+    ///
+    /// Off (default): arrows end the word, as any navigation key does. On:
+    /// the engine follows the caret inside the word, and keys typed there
+    /// edit the word in place ("vit", Left, "e" → "viet"; "e" again → "viêt"),
+    /// so a missing letter can be added without retyping the word. Arrows
+    /// with Shift (selection) and moves past either end still end it.
+    pub fn set_mid_word_editing(&mut self, enabled: bool) {
+        self.mid_word_editing = enabled;
+        self.buf.set_cursor(usize::MAX);
+    }
+
+    /// Set how many committed words backspace can restore (0 disables,
+    /// capped at `MAX_HISTORY_CAPACITY`)
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.word_history
+            .set_capacity(capacity.min(MAX_HISTORY_CAPACITY));
+    }
+
+    /// Committed word `depth` entries back (0 = most recent), as displayed
+    ///
+    /// Lets hosts see which words backspace can still restore.
+    pub fn peek_history(&self, depth: usize) -> Option<String> {
+        self.word_history.peek(depth).map(Buffer::to_full_string)
+    }
+
+    /// Number of committed words backspace can restore
+    pub fn history_len(&self) -> usize {
+        self.word_history.len()
+    }
+
     /// Set whether ESC key restores raw ASCII
     pub fn set_esc_restore(&mut self, enabled: bool) {
         self.esc_restore_enabled = enabled;
@@ -451,6 +941,20 @@ impl Engine {
         self.english_auto_restore = enabled;
     }
 
+    /// Set the fewest keystrokes a word needs to be auto-restored (0 = any)
+    pub fn set_restore_min_length(&mut self, len: u8) {
+        self.restore_filter.min_length = len;
+    }
+
+    /// Exempt a word (its keystrokes, "per") from the minimum restore length
+    pub fn add_restore_exception(&mut self, word: &str) {
+        self.restore_filter.add_exception(word);
+    }
+
+    pub fn clear_restore_exceptions(&mut self) {
+        self.restore_filter.clear_exceptions();
+    }
+
     /// Set whether to enable auto-capitalize after sentence-ending punctuation
     pub fn set_auto_capitalize(&mut self, enabled: bool) {
         self.auto_capitalize = enabled;
@@ -461,13 +965,201 @@ impl Engine {
     }
 
     /// Set whether to allow foreign consonants (z, w, j, f) as valid initials
+    ///
+    /// Shorthand for the Tolerant profile; disabling it only leaves Tolerant.
     pub fn set_allow_foreign_consonants(&mut self, enabled: bool) {
-        self.allow_foreign_consonants = enabled;
+        if enabled {
+            self.validation_profile = ValidationProfile::Tolerant;
+        } else if self.validation_profile == ValidationProfile::Tolerant {
+            self.validation_profile = ValidationProfile::Standard;
+        }
     }
 
     /// Get whether foreign consonants are allowed
     pub fn allow_foreign_consonants(&self) -> bool {
-        self.allow_foreign_consonants
+        self.validation_profile.allows_foreign_initials()
+    }
+
+    /// Set which non-standard spellings (loanword initials, minority clusters) are valid
+    pub fn set_validation_profile(&mut self, profile: ValidationProfile) {
+        self.validation_profile = profile;
+    }
+
+    /// Get the validation profile
+    pub fn validation_profile(&self) -> ValidationProfile {
+        self.validation_profile
+    }
+
+    /// Validation profile in effect: the field's, else the user's
+    fn active_validation_profile(&self) -> ValidationProfile {
+        self.field_kind
+            .validation_profile()
+            .unwrap_or(self.validation_profile)
+    }
+
+    /// Auto-capitalize is on and the focused field takes prose
+    fn capitalizes(&self) -> bool {
+        self.auto_capitalize && self.field_kind.capitalizes()
+    }
+
+    /// Adjust input to the kind of field the host reports focus in
+    ///
+    /// One switch for what the field decides (see `FieldKind`): a password
+    /// field enters secure mode, email and URL fields turn input off for
+    /// `DisableReason::Field`, code fields validate with the Strict profile,
+    /// and only normal fields auto-capitalize. The user's own settings are
+    /// kept and apply again in a normal field.
+    pub fn set_field_kind(&mut self, kind: FieldKind) {
+        let previous = self.field_kind;
+        if kind == previous {
+            return;
+        }
+        self.field_kind = kind;
+        self.log(
+            LogLevel::Info,
+            "mode",
+            format_args!("field {previous:?} -> {kind:?}"),
+        );
+        if (kind == FieldKind::Password) != (previous == FieldKind::Password) {
+            self.set_secure_mode(kind == FieldKind::Password);
+        }
+        self.set_enabled_for(!kind.disables_input(), DisableReason::Field);
+        if !kind.capitalizes() {
+            self.pending_capitalize = false;
+            self.saw_sentence_ending = false;
+        }
+        self.clear();
+    }
+
+    /// Kind of field focus is in
+    pub fn field_kind(&self) -> FieldKind {
+        self.field_kind
+    }
+
+    /// Set what tone and mark keys that change nothing do (typed or swallowed)
+    pub fn set_tone_key_policy(&mut self, policy: ToneKeyPolicy) {
+        self.tone_key_policy = policy;
+    }
+
+    /// Get the tone key policy
+    pub fn tone_key_policy(&self) -> ToneKeyPolicy {
+        self.tone_key_policy
+    }
+
+    /// Set where tone keys on syllables with a non-Vietnamese final go
+    pub fn set_foreign_final_tone(&mut self, policy: ForeignFinalTone) {
+        self.foreign_final_tone = policy;
+    }
+
+    pub fn foreign_final_tone(&self) -> ForeignFinalTone {
+        self.foreign_final_tone
+    }
+
+    /// Set whether committed words are spell checked
+    pub fn set_spell_check(&mut self, enabled: bool) {
+        self.spell_check = enabled;
+        if !enabled {
+            self.spell_flag = None;
+        }
+    }
+
+    /// Add a word to the user dictionary (accepted by spell check)
+    pub fn add_user_word(&mut self, word: &str) {
+        self.user_words.insert(word.to_lowercase());
+    }
+
+    /// Remove a word from the user dictionary
+    pub fn remove_user_word(&mut self, word: &str) {
+        self.user_words.remove(&word.to_lowercase());
+    }
+
+    /// Take the spell check flag raised by the last commit, if any
+    pub fn take_spell_flag(&mut self) -> Option<SpellFlag> {
+        self.spell_flag.take()
+    }
+
+    /// Full text of the shortcut expanded by the last key, if any
+    ///
+    /// Same text as the returned `Result`, without its 255-char cap.
+    pub fn last_expansion(&self) -> Option<&[char]> {
+        (!self.expansion.is_empty()).then_some(self.expansion.as_slice())
+    }
+
+    /// Details of the restore done by the last key (auto-restore, ESC), if any
+    pub fn last_restore(&self) -> Option<&RestoreInfo> {
+        self.last_restore.as_ref()
+    }
+
+    /// What the last key's `result` does to the text, for hosts that edit
+    /// through higher-level APIs than backspace + chars (see `edit::EditOp`)
+    pub fn edit_op(&self, result: &Result) -> Option<EditOp> {
+        let text = match self.last_expansion() {
+            Some(full) => full.iter().collect(),
+            None => (0..result.count as usize)
+                .filter_map(|i| char::from_u32(result.chars[i]))
+                .collect(),
+        };
+        edit::describe(result, text, &self.edit_before)
+    }
+
+    /// Per-app output rules and user overrides
+    pub fn output_registry(&self) -> &OutputRegistry {
+        &self.output
+    }
+
+    pub fn output_registry_mut(&mut self) -> &mut OutputRegistry {
+        &mut self.output
+    }
+
+    /// Record the focused app (bundle id, exe name, WM class) and the
+    /// accessibility role of its focused element, for `output_strategy`
+    pub fn set_focus(&mut self, app_id: &str, role: Option<&str>) {
+        self.focus = (app_id.to_string(), role.map(str::to_string));
+    }
+
+    /// How the host should deliver results to the focused app
+    pub fn output_strategy(&self) -> OutputStrategy {
+        let (app_id, role) = &self.focus;
+        self.output.lookup(app_id, role.as_deref())
+    }
+
+    /// Save the engine's word on screen (the word being typed, or the last
+    /// committed word and the separators typed after it) into `edit_before`,
+    /// reusing its allocation
+    fn save_screen_word(&mut self) {
+        let mut text = core::mem::take(&mut self.edit_before);
+        text.clear();
+        if let Some(literal) = &self.literal_word {
+            text.push_str(literal);
+        } else if !self.buf.is_empty() {
+            text.extend(self.buf.chars());
+        } else if let Some(last) = self.word_history.last() {
+            if self.spaces_after_commit > 0
+                && self.commit_trailer.chars().count() == self.spaces_after_commit as usize
+            {
+                text.extend(last.chars());
+                text.push_str(&self.commit_trailer);
+            }
+        }
+        self.edit_before = text;
+    }
+
+    /// Build the Result for a shortcut expansion, keeping the full text
+    fn send_expansion(&mut self, backspace: u8, output: Vec<char>, consumed: bool) -> Result {
+        let mut result = if consumed {
+            Result::send_consumed(backspace, &output)
+        } else {
+            Result::send(backspace, &output)
+        };
+        result.flags |= FLAG_NEW_UNDO_GROUP;
+        let n = output.len();
+        self.log(
+            LogLevel::Debug,
+            "shortcut",
+            format_args!("expanded to {n} chars"),
+        );
+        self.expansion = output;
+        result
     }
 
     pub fn shortcuts(&self) -> &ShortcutTable {
@@ -478,6 +1170,116 @@ impl Engine {
         &mut self.shortcuts
     }
 
+    /// Current settings
+    pub fn config(&self) -> Config {
+        Config {
+            method: self.method,
+            skip_w_shortcut: self.skip_w_shortcut,
+            auto_uo_horn: self.auto_uo_horn,
+            tag_composition: self.tag_composition,
+            bracket_shortcut: self.bracket_shortcut,
+            esc_restore: self.esc_restore_enabled,
+            free_tone: self.free_tone_enabled,
+            modern_tone: self.modern_tone,
+            english_auto_restore: self.english_auto_restore,
+            restore_min_length: self.restore_filter.min_length,
+            auto_capitalize: self.auto_capitalize,
+            spell_check: self.spell_check,
+            validation_profile: self.validation_profile,
+            tone_key_policy: self.tone_key_policy,
+            foreign_final_tone: self.foreign_final_tone,
+            history_capacity: self.word_history.capacity,
+            mid_word_editing: self.mid_word_editing,
+            key_timing: self.timing,
+        }
+    }
+
+    /// Apply all settings at once, as the individual setters would
+    pub fn set_config(&mut self, config: &Config) {
+        self.set_method(config.method);
+        self.set_skip_w_shortcut(config.skip_w_shortcut);
+        self.set_auto_uo_horn(config.auto_uo_horn);
+        self.set_tag_composition(config.tag_composition);
+        self.set_bracket_shortcut(config.bracket_shortcut);
+        self.set_esc_restore(config.esc_restore);
+        self.set_free_tone(config.free_tone);
+        self.set_modern_tone(config.modern_tone);
+        self.set_english_auto_restore(config.english_auto_restore);
+        self.set_restore_min_length(config.restore_min_length);
+        self.set_auto_capitalize(config.auto_capitalize);
+        self.set_spell_check(config.spell_check);
+        self.set_validation_profile(config.validation_profile);
+        self.set_tone_key_policy(config.tone_key_policy);
+        self.set_foreign_final_tone(config.foreign_final_tone);
+        self.set_history_capacity(config.history_capacity);
+        self.set_mid_word_editing(config.mid_word_editing);
+        self.set_key_timing(config.key_timing);
+    }
+
+    /// Re-derive diacritics in selected text typed with the IME off, using
+    /// this engine's method and tone settings (see
+    /// `text::convert::transform_selection`). Doesn't touch the word in progress.
+    pub fn transform_selection(&self, raw_text: &str) -> String {
+        text::convert::transform_selection(raw_text, &self.config())
+    }
+
+    /// Name of the active profile
+    pub fn profile(&self) -> &str {
+        &self.profile_name
+    }
+
+    /// Names of all profiles, the active one included (sorted)
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.push(self.profile_name.clone());
+        names.sort();
+        names
+    }
+
+    /// Switch to profile `name`, creating it with default settings if new
+    ///
+    /// The active profile's settings, shortcuts and user dictionary are kept
+    /// under its name for switching back. The word being typed is dropped
+    /// like on a focus change: call `flush` first to finalize it.
+    /// Returns whether the profile already existed.
+    pub fn set_profile(&mut self, name: &str) -> bool {
+        if name == self.profile_name {
+            return true;
+        }
+        let next = self.profiles.remove(name);
+        let existed = next.is_some();
+        let current = Profile {
+            config: self.config(),
+            shortcuts: core::mem::take(&mut self.shortcuts),
+            user_words: core::mem::take(&mut self.user_words),
+        };
+        let previous = core::mem::replace(&mut self.profile_name, name.to_string());
+        self.profiles.insert(previous, current);
+        self.load_profile(next.unwrap_or_default());
+        self.reset_context();
+        existed
+    }
+
+    /// Add or replace a profile; replacing the active one applies it now
+    pub fn add_profile(&mut self, name: &str, profile: Profile) {
+        if name == self.profile_name {
+            self.load_profile(profile);
+        } else {
+            self.profiles.insert(name.to_string(), profile);
+        }
+    }
+
+    /// Remove an inactive profile (the active one can't be removed)
+    pub fn remove_profile(&mut self, name: &str) -> Option<Profile> {
+        self.profiles.remove(name)
+    }
+
+    fn load_profile(&mut self, profile: Profile) {
+        self.set_config(&profile.config);
+        self.shortcuts = profile.shortcuts;
+        self.user_words = profile.user_words;
+    }
+
     /// Debug: get buffer length
     pub fn debug_buffer_len(&self) -> usize {
         self.buf.len()
@@ -519,7 +1321,7 @@ impl Engine {
     pub fn debug_raw_input(&self) -> String {
         self.raw_input
             .iter()
-            .map(|(k, c, s)| format!("({},{},{})", k, c, s))
+            .map(|r| format!("({},{},{},{:?})", r.key, r.caps, r.shift, r.ch))
             .collect::<Vec<_>>()
             .join(" ")
     }
@@ -566,6 +1368,13 @@ impl Engine {
         shift: bool,
         ch: Option<char>,
     ) -> Result {
+        if self.secure {
+            return Result::none();
+        }
+        self.expansion.clear();
+        self.last_restore = None;
+        self.save_screen_word();
+
         // No character provided → fall back to normal processing
         let Some(ch) = ch else {
             return self.on_key_ext(key, caps, ctrl, shift);
@@ -583,6 +1392,12 @@ impl Engine {
             // Fall through to shortcut accumulation below
         }
 
+        // Non-ASCII letter from another layout: the keystroke model can't
+        // describe it, so the word continues literally
+        if !ctrl && !ch.is_ascii() && ch.is_alphabetic() {
+            self.enter_literal_word(ch);
+        }
+
         // Accumulate character for suffix matching
         self.shortcut_prefix.push(ch);
 
@@ -597,9 +1412,12 @@ impl Engine {
                 input_method,
             ) {
                 let output: Vec<char> = m.output.chars().collect();
-                let backspace_count = (m.backspace_count as u8).saturating_sub(1);
+                let backspace_count = backspaces(m.backspace_count).saturating_sub(1);
+                if let Some(o) = self.observer.as_deref_mut() {
+                    o.on_shortcut_expanded(suffix, &m.output);
+                }
                 self.shortcut_prefix.clear();
-                return Result::send_consumed(backspace_count, &output);
+                return self.send_expansion(backspace_count, output, true);
             }
         }
 
@@ -607,6 +1425,255 @@ impl Engine {
         Result::none()
     }
 
+    /// Handle key event with the character the keyboard layout produced
+    ///
+    /// Processed like `on_key_ext`. `ch` is recorded with the keystroke, so
+    /// restores put back what the layout typed (AZERTY "é" on the 2 key)
+    /// rather than the US character of the keycode.
+    pub fn on_key_typed(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+        ch: char,
+    ) -> Result {
+        self.typed_char = Some(ch);
+        let result = self.on_key_ext(key, caps, ctrl, shift);
+        self.typed_char = None;
+        result
+    }
+
+    /// Handle key event with its timestamp (ms, any monotonic clock)
+    ///
+    /// Processed like `on_key_ext`, plus the rules of `KeyTiming`: an
+    /// auto-repeated letter ends transforms for the word, two quick spaces
+    /// after a word type ". ", and two quick taps of `keys::LSHIFT` /
+    /// `keys::RSHIFT` (press and release with no other key, reported as their
+    /// own events) toggle Vietnamese input. With `idle_commit_ms` set, a key
+    /// arriving after a long pause first finishes the word being typed, as
+    /// `flush` does, and the returned edit covers both. Hosts should send every key
+    /// through this method once they use it; mixing in untimed keys only
+    /// weakens the rules.
+    pub fn on_key_at(
+        &mut self,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+        time_ms: u64,
+    ) -> Result {
+        if self.secure {
+            return Result::none();
+        }
+        let last = self.last_key_at.replace((key, time_ms));
+        if key == keys::LSHIFT || key == keys::RSHIFT {
+            self.on_shift_tap(time_ms);
+            return Result::none();
+        }
+        self.shift_tap_at = None;
+
+        // Word left alone for a while: finish it before this key
+        let idle_ms = self.timing.idle_commit_ms;
+        let idle = last.is_some_and(|(_, t)| time_ms > t && !timing::within(t, time_ms, idle_ms));
+        if !ctrl && idle_ms > 0 && idle && !self.buf.is_empty() {
+            self.log(
+                LogLevel::Debug,
+                "timing",
+                format_args!("idle: word finished"),
+            );
+            let commit = self.flush();
+            let result = self.on_key_ext(key, caps, ctrl, shift);
+            return Self::after_commit(commit, result, key, caps, shift);
+        }
+
+        let again = last
+            .filter(|&(last_key, _)| last_key == key)
+            .map(|(_, t)| t);
+        if !ctrl {
+            if let Some(t) = again {
+                if timing::within(t, time_ms, self.timing.repeat_ms) {
+                    if let Some(result) = self.on_key_repeat(key, caps) {
+                        return result;
+                    }
+                }
+                if key == keys::SPACE
+                    && self.timing.double_space_period
+                    && timing::within(t, time_ms, self.timing.double_space_ms)
+                {
+                    if let Some(result) = self.double_space_period() {
+                        return result;
+                    }
+                }
+            }
+        }
+        self.on_key_ext(key, caps, ctrl, shift)
+    }
+
+    /// Fold the edit of a word finished before a key into that key's result
+    fn after_commit(commit: Result, result: Result, key: u16, caps: bool, shift: bool) -> Result {
+        if commit.action == Action::None as u8 {
+            return result;
+        }
+        let mut chars: Vec<char> = (0..commit.count as usize)
+            .filter_map(|i| char::from_u32(commit.chars[i]))
+            .collect();
+        let mut backspace = commit.backspace;
+        let mut flags = commit.flags;
+        let mut erase = |n: u8, chars: &mut Vec<char>| {
+            for _ in 0..n {
+                if chars.pop().is_none() {
+                    backspace = backspace.saturating_add(1);
+                }
+            }
+        };
+        if result.action == Action::Send as u8 {
+            erase(result.backspace, &mut chars);
+            chars
+                .extend((0..result.count as usize).filter_map(|i| char::from_u32(result.chars[i])));
+            flags |= result.flags;
+        } else if key == keys::DELETE {
+            erase(1, &mut chars);
+            flags |= FLAG_KEY_CONSUMED;
+        } else if let Some(ch) = utils::key_to_char_ext(key, caps, shift).or_else(|| match key {
+            keys::SPACE => Some(' '),
+            _ => boundary_char(key, shift),
+        }) {
+            chars.push(ch);
+            flags |= FLAG_KEY_CONSUMED;
+        }
+        let mut merged = Result::send(backspace, &chars);
+        merged.flags = flags;
+        merged
+    }
+
+    /// Time windows and rules of `on_key_at`
+    pub fn key_timing(&self) -> KeyTiming {
+        self.timing
+    }
+
+    pub fn set_key_timing(&mut self, timing: KeyTiming) {
+        self.timing = timing;
+    }
+
+    /// Shift tapped alone: the second quick tap toggles Vietnamese input
+    fn on_shift_tap(&mut self, time_ms: u64) {
+        if !self.timing.double_shift_toggle {
+            return;
+        }
+        match self.shift_tap_at.take() {
+            Some(t) if timing::within(t, time_ms, self.timing.double_shift_ms) => {
+                self.set_enabled(!self.enabled);
+            }
+            _ => self.shift_tap_at = Some(time_ms),
+        }
+    }
+
+    /// Auto-repeated letter: put back the word's keys and type the rest of
+    /// the word literally, so holding "a" gives "aaaa" rather than toggling
+    /// "â". None when there's no composition to end.
+    fn on_key_repeat(&mut self, key: u16, caps: bool) -> Option<Result> {
+        if !self.enabled || self.buf.is_empty() || self.literal_word.is_some() {
+            return None;
+        }
+        let ch = self
+            .typed_char
+            .or_else(|| utils::key_to_char(key, caps))
+            .filter(char::is_ascii_alphabetic)?;
+        self.expansion.clear();
+        self.last_restore = None;
+        self.save_screen_word();
+
+        self.log(
+            LogLevel::Debug,
+            "timing",
+            format_args!("key repeat: word left literal"),
+        );
+        let restore = self.restore_to_raw();
+        let mut chars = if restore.action == Action::None as u8 {
+            self.buf.to_full_string().chars().collect()
+        } else {
+            self.typed_chars()
+        };
+        chars.push(ch);
+        let backspace = backspaces(self.buf.len());
+        self.clear();
+        self.word_history.clear();
+        self.spaces_after_commit = 0;
+        self.literal_word = Some(chars.iter().collect());
+        if restore.action == Action::None as u8 {
+            return Some(Result::none());
+        }
+        let mut result = Result::restore(backspace, &chars);
+        result.flags |= FLAG_KEY_CONSUMED;
+        Some(result)
+    }
+
+    /// Second quick space right after a word: replace the first with ". "
+    fn double_space_period(&mut self) -> Option<Result> {
+        if !self.enabled
+            || !self.buf.is_empty()
+            || self.spaces_after_commit != 1
+            || self.commit_trailer != " "
+        {
+            return None;
+        }
+        self.expansion.clear();
+        self.last_restore = None;
+        self.save_screen_word();
+
+        self.log(
+            LogLevel::Debug,
+            "timing",
+            format_args!("double space: period"),
+        );
+        self.spaces_after_commit = 2;
+        self.commit_trailer = ". ".to_string();
+        if self.capitalizes() {
+            self.saw_sentence_ending = true;
+            self.pending_capitalize = true;
+        }
+        Some(Result::send_consumed(1, &['.', ' ']))
+    }
+
+    /// Start (or extend) a literal word with a non-ASCII letter
+    ///
+    /// The composed text so far is already on screen and stays as is; the
+    /// engine drops its buffer and history, whose keystrokes no longer
+    /// describe the word (a later restore or backspace-edit would be off).
+    fn enter_literal_word(&mut self, ch: char) {
+        let mut text = match self.literal_word.take() {
+            Some(text) => text,
+            None => self.buf.to_full_string(),
+        };
+        text.push(ch);
+        self.clear();
+        self.word_history.clear();
+        self.spaces_after_commit = 0;
+        self.literal_word = Some(text);
+    }
+
+    /// Key typed inside a literal word: letters pass through untransformed;
+    /// None for a word boundary, which ends the literal word
+    fn on_literal_key(&mut self, key: u16, caps: bool, shift: bool) -> Option<Result> {
+        let text = self.literal_word.as_mut()?;
+        if key == keys::DELETE {
+            text.pop();
+            if text.is_empty() {
+                self.literal_word = None;
+            }
+            return Some(Result::none());
+        }
+        if keys::is_break_ext(key, shift) {
+            self.literal_word = None;
+            return None;
+        }
+        if let Some(c) = utils::key_to_char(key, caps) {
+            text.push(c);
+        }
+        Some(Result::none())
+    }
+
     /// Check if key+shift combo is a raw mode prefix character
     /// Raw prefixes: @ # : /
     #[allow(dead_code)] // TEMP DISABLED
@@ -635,6 +1702,128 @@ impl Engine {
     /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
     /// * `shift` - true if Shift key is pressed (for symbols like @, #, $)
     pub fn on_key_ext(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        if self.secure {
+            return Result::none();
+        }
+        self.expansion.clear();
+        self.last_restore = None;
+        self.save_screen_word();
+
+        if !ctrl && self.literal_word.is_some() {
+            if let Some(result) = self.on_literal_key(key, caps, shift) {
+                return result;
+            }
+        }
+
+        if !ctrl && self.mid_word_editing && self.enabled && !self.buf.is_empty() {
+            if let Some(result) = self.on_mid_word_key(key, caps, shift) {
+                return result;
+            }
+        }
+
+        // A word longer than the buffer holds: edits sized from part of it
+        // would delete the wrong chars, so the rest is a new word
+        if self.buf.len() >= MAX_WORD {
+            self.clear();
+        }
+
+        let starts_word = self.buf.is_empty();
+        let mut result = self.process_key(key, caps, ctrl, shift);
+        // First char of a new composition opens its own undo group
+        if starts_word && !self.buf.is_empty() {
+            result.flags |= FLAG_NEW_UNDO_GROUP;
+        }
+        result
+    }
+
+    /// Arrow keys inside the word, and keys typed with the caret there
+    ///
+    /// None when the key should be processed as usual (caret at the end).
+    fn on_mid_word_key(&mut self, key: u16, caps: bool, shift: bool) -> Option<Result> {
+        let cursor = self.buf.cursor();
+        match key {
+            keys::LEFT if !shift && cursor > 0 => {
+                self.buf.set_cursor(cursor - 1);
+                return Some(Result::none());
+            }
+            keys::RIGHT if !shift && !self.buf.cursor_at_end() => {
+                self.buf.set_cursor(cursor + 1);
+                return Some(Result::none());
+            }
+            _ => {}
+        }
+        if self.buf.cursor_at_end() {
+            return None;
+        }
+        let word: Vec<char> = self.buf.to_full_string().chars().collect();
+        let (before, after) = word.split_at(cursor);
+
+        if key == keys::DELETE {
+            // The app deletes the char before the caret; follow it
+            if cursor == 0 {
+                self.clear();
+                return None;
+            }
+            let rest: Vec<char> = before[..cursor - 1].iter().chain(after).copied().collect();
+            self.rebuild_word(&rest);
+            self.buf.set_cursor(cursor - 1);
+            return Some(Result::none());
+        }
+        if keys::is_break_ext(key, shift) || !keys::is_letter(key) {
+            // Word split or caret leaves: start over from here
+            self.clear();
+            return None;
+        }
+
+        let (before, after) = (before.to_vec(), after.to_vec());
+        // A mark or tone key lands where it would for the whole word, as
+        // long as the change stays before the caret ("ho|a" + s → "hó|a")
+        self.rebuild_word(&word);
+        self.process_key(key, caps, false, shift);
+        let new: Vec<char> = self.buf.to_full_string().chars().collect();
+        if new.len() == word.len() {
+            if let Some(edit) = diff::diff(&word, &new, cursor) {
+                self.buf.set_cursor(cursor);
+                return Some(Result::edit(&edit));
+            }
+        }
+
+        // Otherwise retype the word with the key at the caret
+        self.rebuild_word(&before);
+        self.process_key(key, caps, false, shift);
+        for &c in &after {
+            // Final consonants are retyped so pending horns and marks settle
+            // as they would have ("nuo" + w + "c" → "nươc"); letters that
+            // could act as modifiers are pushed as they are
+            match utils::char_to_key(c.to_ascii_lowercase()) {
+                key @ (keys::C | keys::G | keys::H | keys::M | keys::N | keys::P | keys::T)
+                    if c.is_ascii() =>
+                {
+                    self.process_key(key, c.is_uppercase(), false, false);
+                }
+                _ => self.push_composed(c),
+            }
+        }
+        let new: Vec<char> = self.buf.to_full_string().chars().collect();
+        let Some(edit) = diff::diff(&word, &new, cursor) else {
+            // The edit reaches past the caret, which backspaces can't do
+            self.clear();
+            return Some(Result::none());
+        };
+        self.buf.set_cursor(new.len() - after.len());
+        Some(Result::edit(&edit))
+    }
+
+    /// Replace the composing state with `chars`, as if they were typed
+    fn rebuild_word(&mut self, chars: &[char]) {
+        self.clear();
+        for &c in chars {
+            self.push_composed(c);
+        }
+    }
+
+    /// Key processing behind `on_key_ext`
+    fn process_key(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
         // Issue #129: Process shortcuts even when IME is disabled
         // Only bypass completely for Ctrl/Cmd modifier keys
         if ctrl {
@@ -664,15 +1853,18 @@ impl Engine {
                         input_method,
                     ) {
                         let output: Vec<char> = m.output.chars().collect();
-                        let backspace_count = m.backspace_count as u8;
+                        let backspace_count = backspaces(m.backspace_count);
+                        if let Some(o) = self.observer.as_deref_mut() {
+                            o.on_shortcut_expanded(&self.shortcut_prefix, &m.output);
+                        }
                         self.shortcut_prefix.clear();
                         // For Space, include space in output; for Enter, don't
                         if key == keys::SPACE {
                             let mut output_with_space = output;
                             output_with_space.push(' ');
-                            return Result::send(backspace_count, &output_with_space);
+                            return self.send_expansion(backspace_count, output_with_space, false);
                         } else {
-                            return Result::send(backspace_count, &output);
+                            return self.send_expansion(backspace_count, output, false);
                         }
                     }
                 }
@@ -707,9 +1899,12 @@ impl Engine {
                             input_method,
                         ) {
                             let output: Vec<char> = m.output.chars().collect();
-                            let backspace_count = m.backspace_count as u8;
+                            let backspace_count = backspaces(m.backspace_count);
+                            if let Some(o) = self.observer.as_deref_mut() {
+                                o.on_shortcut_expanded(&self.shortcut_prefix, &m.output);
+                            }
                             self.shortcut_prefix.clear();
-                            return Result::send(backspace_count, &output);
+                            return self.send_expansion(backspace_count, output, false);
                         }
                     }
 
@@ -723,9 +1918,12 @@ impl Engine {
                         input_method,
                     ) {
                         let output: Vec<char> = m.output.chars().collect();
-                        let backspace_count = (m.backspace_count as u8).saturating_sub(1);
+                        let backspace_count = backspaces(m.backspace_count).saturating_sub(1);
+                        if let Some(o) = self.observer.as_deref_mut() {
+                            o.on_shortcut_expanded(&self.shortcut_prefix, &m.output);
+                        }
                         self.shortcut_prefix.clear();
-                        return Result::send_consumed(backspace_count, &output);
+                        return self.send_expansion(backspace_count, output, true);
                     }
                     return Result::none();
                 }
@@ -772,6 +1970,7 @@ impl Engine {
             // Auto-restore: if buffer has transforms but is invalid Vietnamese,
             // restore to raw English (like ESC but triggered by space)
             let restore_result = self.try_auto_restore_on_space();
+            self.notify_restore(&restore_result, false);
 
             // If auto-restore happened, repopulate buffer with plain chars from raw_input
             // This ensures word_history stores the correct restored word (not transformed)
@@ -779,24 +1978,28 @@ impl Engine {
             // After this, buffer has "restore" (7 chars) for correct history
             if restore_result.action != 0 {
                 self.buf.clear();
-                for &(key, caps, _) in &self.raw_input {
+                for &RawKeystroke { key, caps, .. } in &self.raw_input {
                     self.buf.push(Char::new(key, caps));
                 }
             }
 
             // Push buffer to history before clearing (for backspace-after-space feature)
             if !self.buf.is_empty() {
-                self.word_history.push(self.buf.clone());
+                self.notify_commit();
+                self.push_history();
                 self.spaces_after_commit = 1; // First space after word
+                self.commit_trailer.clear();
+                self.commit_trailer.push(' ');
             } else if self.spaces_after_commit > 0 {
                 // Additional space after commit - increment counter
                 self.spaces_after_commit = self.spaces_after_commit.saturating_add(1);
+                self.commit_trailer.push(' ');
             }
             self.auto_capitalize_used = false; // Reset on word commit
 
             // Issue #185: Set pending_capitalize on space AFTER sentence-ending punctuation
             // This ensures "google.com" doesn't capitalize, but "ok. ban" does
-            if self.auto_capitalize && self.saw_sentence_ending {
+            if self.capitalizes() && self.saw_sentence_ending {
                 self.pending_capitalize = true;
                 // Keep saw_sentence_ending for multiple spaces (e.g., "ok.  ban")
             }
@@ -813,6 +2016,12 @@ impl Engine {
             } else {
                 Result::none()
             };
+            self.notify_restore(&result, true);
+            if result.action != 0 {
+                self.word_choices
+                    .record(&self.get_raw_input_string(), WordLanguage::English);
+                self.previous_languages = [Some(WordLanguage::English), self.previous_languages[0]];
+            }
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
@@ -836,8 +2045,9 @@ impl Engine {
             // This allows shortcuts like "#fne", "->", "=>" to work.
             // BUT: if there's word history (user just typed "du "), break chars should
             // clear history as before, not accumulate.
-            let at_true_start =
-                self.buf.is_empty() && self.word_history.len == 0 && self.spaces_after_commit == 0;
+            let at_true_start = self.buf.is_empty()
+                && self.word_history.len() == 0
+                && self.spaces_after_commit == 0;
 
             // Also continue accumulating if we already started a prefix
             let continuing_prefix = self.buf.is_empty() && !self.shortcut_prefix.is_empty();
@@ -851,6 +2061,9 @@ impl Engine {
                 // from the restored buffer instead of undoing the 2nd break char.
                 if continuing_prefix && self.spaces_after_commit > 0 {
                     self.spaces_after_commit = self.spaces_after_commit.saturating_add(1);
+                    if let Some(ch) = break_key_to_char(key, shift) {
+                        self.commit_trailer.push(ch);
+                    }
                 }
 
                 // Reset has_non_letter_prefix when starting a new shortcut at true start
@@ -875,16 +2088,19 @@ impl Engine {
                         // Note: backspace_count - 1 because current key hasn't been typed yet
                         // Example: "->" trigger has backspace_count=2, but only '-' is on screen
                         let output: Vec<char> = m.output.chars().collect();
-                        let backspace_count = (m.backspace_count as u8).saturating_sub(1);
+                        let backspace_count = backspaces(m.backspace_count).saturating_sub(1);
+                        if let Some(o) = self.observer.as_deref_mut() {
+                            o.on_shortcut_expanded(&self.shortcut_prefix, &m.output);
+                        }
                         self.shortcut_prefix.clear();
-                        return Result::send_consumed(backspace_count, &output);
+                        return self.send_expansion(backspace_count, output, true);
                     }
 
                     // Issue #185: Only set saw_sentence_ending for punctuation (not Enter)
                     // pending_capitalize will be set when space follows
-                    if self.auto_capitalize && is_sentence_ending_punctuation(key, shift) {
+                    if self.capitalizes() && is_sentence_ending_punctuation(key, shift) {
                         self.saw_sentence_ending = true;
-                    } else if self.auto_capitalize && (key == keys::RETURN || key == keys::ENTER) {
+                    } else if self.capitalizes() && (key == keys::RETURN || key == keys::ENTER) {
                         // Enter = newline = immediate capitalize (no space needed)
                         self.pending_capitalize = true;
                         self.saw_sentence_ending = false;
@@ -893,21 +2109,24 @@ impl Engine {
                 }
             }
 
+            // A symbol starting the sentence may be the prefix of a shortcut
+            let prefix_capitalize =
+                self.pending_capitalize && self.buf.is_empty() && self.shortcut_prefix.is_empty();
+
             // Issue #185: Only set saw_sentence_ending for punctuation (not Enter)
             // pending_capitalize will be set when space follows
-            if self.auto_capitalize && is_sentence_ending_punctuation(key, shift) {
+            if self.capitalizes() && is_sentence_ending_punctuation(key, shift) {
                 self.saw_sentence_ending = true;
-            } else if self.auto_capitalize && (key == keys::RETURN || key == keys::ENTER) {
+            } else if self.capitalizes() && (key == keys::RETURN || key == keys::ENTER) {
                 // Enter = newline = immediate capitalize (no space needed)
                 self.pending_capitalize = true;
                 self.saw_sentence_ending = false;
-            } else if self.auto_capitalize && should_reset_pending_capitalize(key, shift) {
+            } else if self.capitalizes() && should_reset_pending_capitalize(key, shift) {
                 // Reset pending for word-breaking keys (comma, semicolon, etc.)
                 // But preserve pending for neutral keys (quotes, parentheses, brackets)
                 self.pending_capitalize = false;
                 self.saw_sentence_ending = false;
             }
-            self.auto_capitalize_used = false; // Reset on word boundary
 
             // Issue #167: Check for word boundary shortcuts on punctuation and ENTER
             // Example: "ko." → "không." or "ko<Enter>" → "không<Enter>"
@@ -928,6 +2147,7 @@ impl Engine {
             }
 
             let restore_result = self.try_auto_restore_on_break();
+            self.notify_restore(&restore_result, false);
 
             // Push buffer to history before clearing (like SPACE handler)
             // This enables backspace-after-break to restore the word
@@ -936,31 +2156,41 @@ impl Engine {
                 // If auto-restore happened, repopulate buffer with plain chars first
                 if restore_result.action != 0 {
                     self.buf.clear();
-                    for &(key, caps, _) in &self.raw_input {
+                    for &RawKeystroke { key, caps, .. } in &self.raw_input {
                         self.buf.push(Char::new(key, caps));
                     }
                 }
-                self.word_history.push(self.buf.clone());
+                self.notify_commit();
+                self.push_history();
                 self.spaces_after_commit = 1; // Break char counts as 1 space for restore
-            } else if self.spaces_after_commit > 0 && break_key_to_char(key, shift).is_some() {
+                self.commit_trailer.clear();
+                if let Some(ch) = boundary_char(key, shift) {
+                    self.commit_trailer.push(ch);
+                }
+            } else if let Some(ch) =
+                boundary_char(key, shift).filter(|_| self.spaces_after_commit > 0)
+            {
                 // Buffer is empty but we recently committed a word (via space or break),
-                // AND this break key produces a visible character (punctuation like ; , .).
+                // AND this break key leaves a visible separator (punctuation, newline).
                 // Increment counter so backspace can undo all separators before restoring.
-                // Navigation keys (TAB, RETURN, arrows) still clear history since they
+                // Navigation keys (TAB, arrows) still clear history since they
                 // indicate the user has moved away from the word.
                 self.spaces_after_commit = self.spaces_after_commit.saturating_add(1);
+                self.commit_trailer.push(ch);
             } else {
                 self.word_history.clear();
                 self.spaces_after_commit = 0;
             }
 
             self.clear();
+            self.auto_capitalize_used = false; // Reset on word boundary
 
             // Issue #130: After clearing buffer, store break char as potential shortcut prefix
             // This allows shortcuts like "->" to work after "abc->" (where "-" clears "abc")
             // Example: type "→abc->" should produce "→abc→"
             if let Some(ch) = break_key_to_char(key, shift) {
                 self.shortcut_prefix.push(ch);
+                self.prefix_capitalize = prefix_capitalize;
             }
 
             return restore_result;
@@ -971,13 +2201,21 @@ impl Engine {
             // Track spaces typed after commit, restore word when counter reaches 0
             if self.spaces_after_commit > 0 && self.buf.is_empty() {
                 self.spaces_after_commit -= 1;
+                self.commit_trailer.pop();
                 if self.spaces_after_commit == 0 {
                     // All spaces deleted - restore the word buffer
-                    if let Some(restored_buf) = self.word_history.pop() {
-                        // Restore raw_input from buffer (for ESC restore to work)
-                        self.restore_raw_input_from_buffer(&restored_buf);
-                        self.buf = restored_buf;
-                        // Re-detect pending_u_horn_pos for "uơ" pattern at end of buffer
+                    if let Some(entry) = self.word_history.pop() {
+                        self.log(
+                            LogLevel::Debug,
+                            "history",
+                            format_args!("backspace restored the previous word"),
+                        );
+                        // Restore the original keystrokes (for ESC restore to work)
+                        self.raw_input = entry.raw;
+                        self.buf = entry.buf;
+                        // Deleting the restored word re-arms auto-capitalize
+                        self.auto_capitalize_used = entry.auto_capitalized;
+                        // Re-detect the pending horn for "uơ" pattern at end of buffer
                         // This state is lost on clear() but needed for correct horn placement
                         // Example: "duơ" restored → type "c" → should become "dươc"
                         self.re_detect_pending_u_horn();
@@ -1034,7 +2272,7 @@ impl Engine {
             if self.had_any_transform && !self.buf.is_empty() && !self.raw_input.is_empty() {
                 let remaining_has_no_mark = self.buf.last().is_some_and(|c| c.mark == 0);
                 if remaining_has_no_mark && self.raw_input.len() >= 2 {
-                    let (last_key, _, _) = self.raw_input[self.raw_input.len() - 1];
+                    let last_key = self.raw_input[self.raw_input.len() - 1].key;
                     let mark_keys = [keys::S, keys::F, keys::R, keys::X, keys::J];
                     if mark_keys.contains(&last_key) {
                         // Stale mark key - pop it
@@ -1050,7 +2288,7 @@ impl Engine {
             if char_has_circumflex && !self.buf.is_empty() && !self.raw_input.is_empty() {
                 let remaining_has_no_tone = self.buf.last().is_some_and(|c| c.tone == 0);
                 if remaining_has_no_tone && self.raw_input.len() >= 2 {
-                    let (last_key, _, _) = self.raw_input[self.raw_input.len() - 1];
+                    let last_key = self.raw_input[self.raw_input.len() - 1].key;
                     // Circumflex vowels are a, e, o
                     if matches!(last_key, keys::A | keys::E | keys::O) {
                         // Stale vowel from circumflex pattern - pop it
@@ -1065,6 +2303,8 @@ impl Engine {
                 self.raw_input.clear();
             }
             self.last_transform = None;
+            // "huơ" + Backspace + c → "huc": the horn's 'o' is gone
+            self.defer_step(DeferEvent::Delete);
             // Reset stroke_reverted on backspace so user can re-trigger stroke
             // e.g., "ddddd" → "dddd", then backspace×3 → "d", then "d" → "đ"
             self.stroke_reverted = false;
@@ -1086,8 +2326,12 @@ impl Engine {
                 // user typed any letter (which clears the flag), the chain breaks.
                 // Example: "dươc vẫn " → bs restores "vẫn" → bs×3 deletes it →
                 //          bs restores "dươc" → "j" applies mark → "được"
-                if self.restored_pending_clear && self.word_history.len > 0 {
-                    self.spaces_after_commit = 1;
+                if self.restored_pending_clear && self.word_history.len() > 0 {
+                    // Separators typed after that word (". ", "\n"); assume one if unknown
+                    let trailer = self.word_history.last_trailer().unwrap_or_default();
+                    self.spaces_after_commit =
+                        trailer.chars().count().clamp(1, u8::MAX as usize) as u8;
+                    self.commit_trailer = trailer.to_string();
                 }
                 self.restored_pending_clear = false;
                 // Restore pending_capitalize if user deleted the auto-capitalized letter
@@ -1121,7 +2365,7 @@ impl Engine {
                 // Vietnamese: clear only on consonant that's not a modifier
                 keys::is_consonant(key) && !is_modifier
             };
-            if should_clear && self.pending_u_horn_pos.is_none() {
+            if should_clear && self.deferred_pos(DeferKind::UHorn).is_none() {
                 self.clear();
             }
             // Reset flags regardless - user is now actively typing
@@ -1160,7 +2404,17 @@ impl Engine {
 
         // Record raw keystroke for ESC restore (letters and numbers only)
         if keys::is_letter(key) || keys::is_number(key) {
-            self.raw_input.push((key, effective_caps, shift));
+            let mut raw = RawKeystroke::new(key, effective_caps, shift);
+            if let Some(ch) = self.typed_char {
+                // Letters take the engine's case (auto-capitalize)
+                raw.ch = if ch.is_alphabetic() && effective_caps {
+                    ch.to_uppercase().next()
+                } else {
+                    Some(ch)
+                };
+            }
+            self.raw_input.push(raw);
+            self.composed_word = false;
         }
 
         let result = self.process(key, effective_caps, shift);
@@ -1178,6 +2432,13 @@ impl Engine {
 
     /// Main processing pipeline - pattern-based
     fn process(&mut self, key: u16, caps: bool, shift: bool) -> Result {
+        // The word after '#' or '@' (kept in the shortcut prefix while it is
+        // typed) is a tag, handle or address: typed as is unless composed
+        if !self.tag_composition && self.shortcut_prefix.ends_with(['#', '@']) {
+            self.buf.push(Char::new(key, caps));
+            return Result::none();
+        }
+
         let m = input::get(self.method);
 
         // Handle pending mark revert pop: if previous key was a mark revert,
@@ -1191,6 +2452,11 @@ impl Engine {
             // (both set in revert_mark). Don't modify raw_input here.
         }
 
+        // A horn key typed after the automatic "ươ" confirms it
+        if m.tone(key) == Some(ToneType::Horn) {
+            self.auto_horn_pos = None;
+        }
+
         // Revert short-pattern stroke when new letter creates invalid Vietnamese
         // This handles: "ded" → "đe" (stroke applied), then 'i' → "dedi" (invalid, revert)
         // IMPORTANT: This check must happen BEFORE any modifiers (tone, mark, etc.)
@@ -1219,7 +2485,7 @@ impl Engine {
             && matches!(self.last_transform, Some(Transform::ShortPatternStroke))
         {
             // Build buffer_keys from raw_input (which already includes current key)
-            let raw_keys: Vec<u16> = self.raw_input.iter().map(|&(k, _, _)| k).collect();
+            let raw_keys: Vec<u16> = self.raw_input.iter().map(|r| r.key).collect();
 
             // Also check if the buffer (with stroke) + new key would be valid Vietnamese
             // This handles delayed stroke patterns like "dadu" → "đau":
@@ -1239,11 +2505,14 @@ impl Engine {
                 // Invalid pattern - revert stroke and rebuild from raw_input
                 if let Some(raw_chars) = self.build_raw_chars() {
                     // Calculate backspace: screen shows buffer content (e.g., "đe")
-                    let backspace = self.buf.len() as u8;
+                    let backspace = backspaces(self.buf.len());
 
                     // Rebuild buffer from raw_input (plain chars, no stroke)
                     self.buf.clear();
-                    for &(k, c, _) in &self.raw_input {
+                    for &RawKeystroke {
+                        key: k, caps: c, ..
+                    } in &self.raw_input
+                    {
                         self.buf.push(Char::new(k, c));
                     }
                     self.last_transform = None;
@@ -1295,8 +2564,7 @@ impl Engine {
                     0 => false, // First consonant after vowel - could be valid
                     1 => {
                         // Second consonant: only valid if forms ch/ng/nh
-                        let pair = [consonants_after[0], key];
-                        !constants::VALID_FINALS_2.contains(&pair)
+                        !constants::FINAL_PAIRS.contains(consonants_after[0], key)
                     }
                     _ => true, // 3+ consonants is always invalid
                 };
@@ -1313,11 +2581,14 @@ impl Engine {
                     let mark_val = self.buf.get(vowel_pos).map(|c| c.mark).unwrap_or(0);
 
                     // Calculate backspace: clear current displayed buffer
-                    let backspace = self.buf.len() as u8;
+                    let backspace = backspaces(self.buf.len());
 
                     // Rebuild buffer from raw_input (plain chars with trigger vowel)
                     self.buf.clear();
-                    for &(k, c, _) in &self.raw_input {
+                    for &RawKeystroke {
+                        key: k, caps: c, ..
+                    } in &self.raw_input
+                    {
                         self.buf.push(Char::new(k, c));
                     }
 
@@ -1413,8 +2684,66 @@ impl Engine {
             }
         }
 
+        // A modifier or mark key that found nothing to act on
+        if self.tone_key_policy == ToneKeyPolicy::Consume
+            && !skip_vni_modifiers
+            && (m.mark(key).is_some() || (m.tone(key).is_some() && !keys::is_vowel(key)))
+            && self.buf.iter().any(|c| keys::is_vowel(c.key))
+        {
+            return Result::consumed();
+        }
+
         // Not a modifier - normal letter
-        self.handle_normal_letter(key, caps)
+        let result = self.handle_normal_letter(key, caps);
+        self.undo_auto_horn_if_invalid(result)
+    }
+
+    /// Take back the automatic 'ơ' of "ưo" once the word stops being Vietnamese
+    ///
+    /// "uwo" → "ươ" (valid start of "người", "được"), but "uwoma" → "ưoma":
+    /// loanwords and names keep the 'o' they were typed with. Only a letter
+    /// that was added as typed (no transform of its own) triggers it, and an
+    /// explicit horn key on the 'o' makes the 'ơ' permanent.
+    fn undo_auto_horn_if_invalid(&mut self, result: Result) -> Result {
+        let Some(pos) = self.auto_horn_pos else {
+            return result;
+        };
+        if result.action != Action::None as u8 {
+            return result;
+        }
+        if !self
+            .buf
+            .get(pos)
+            .is_some_and(|c| c.key == keys::O && c.tone == tone::HORN)
+        {
+            self.auto_horn_pos = None;
+            return result;
+        }
+        let keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+        if is_valid_with_profile(&keys, self.active_validation_profile()) {
+            return result;
+        }
+        self.auto_horn_pos = None;
+        if let Some(c) = self.buf.get_mut(pos) {
+            c.tone = tone::NONE;
+        }
+        self.rebuild_from_after_insert(pos)
+    }
+
+    /// A tone/mark key repeating a diacritic the word already has (`applied`
+    /// chars carry it): swallowed
+    ///
+    /// Under `ToneKeyPolicy::Literal` only while the key hasn't been typed
+    /// more often than that: "uwow" still confirms the ơ that "ưo" got
+    /// automatically, but a further 'w' is typed.
+    fn absorb_redundant_key(&self, key: u16, applied: usize) -> Option<Result> {
+        if self.tone_key_policy == ToneKeyPolicy::Literal {
+            let typed = self.raw_input.iter().filter(|r| r.key == key).count();
+            if typed > applied {
+                return None;
+            }
+        }
+        Some(Result::consumed())
     }
 
     /// Try word boundary shortcuts (triggered by space, punctuation, etc.)
@@ -1433,11 +2762,16 @@ impl Engine {
         }
 
         // Build full trigger string including shortcut_prefix if present
-        let full_trigger = if self.shortcut_prefix.is_empty() {
+        let mut full_trigger = if self.shortcut_prefix.is_empty() {
             self.buf.to_full_string()
         } else {
             format!("{}{}", self.shortcut_prefix, self.buf.to_full_string())
         };
+        // The auto-capitalized first letter isn't the user's casing: "ok. q"
+        // matches like "q", not "Q" (which would uppercase the whole output)
+        if self.auto_capitalize_used && !is_all_caps(&full_trigger) {
+            full_trigger = set_first_letter_case(&full_trigger, false);
+        }
 
         let input_method = self.current_input_method();
 
@@ -1450,13 +2784,29 @@ impl Engine {
         } else {
             None // Punctuation: don't append, let platform type it
         };
-        if let Some(m) =
+        if let Some(mut m) =
             self.shortcuts
                 .try_match_for_method(&full_trigger, key_char, true, input_method)
         {
+            // Sentence start: the trigger's first letter was auto-capitalized,
+            // or it began with a symbol typed right after ". " ("#sig")
+            let prefix_capitalize = self.prefix_capitalize && !self.shortcut_prefix.is_empty();
+            if self.auto_capitalize_used || self.pending_capitalize || prefix_capitalize {
+                m.output = set_first_letter_case(&m.output, true);
+                self.pending_capitalize = false;
+                self.saw_sentence_ending = false;
+                self.auto_capitalize_used = false;
+            }
             let output: Vec<char> = m.output.chars().collect();
+            if let Some(o) = self.observer.as_deref_mut() {
+                // Report the replacement without the appended trigger key
+                let replacement = key_char
+                    .and_then(|c| m.output.strip_suffix(c))
+                    .unwrap_or(&m.output);
+                o.on_shortcut_expanded(&full_trigger, replacement);
+            }
             // backspace_count = trigger.len() which already includes prefix (e.g., "#fne" = 4)
-            return Result::send(m.backspace_count as u8, &output);
+            return self.send_expansion(backspaces(m.backspace_count), output, false);
         }
 
         Result::none()
@@ -1479,7 +2829,7 @@ impl Engine {
         // Issue #44: If breve is pending (deferred due to open syllable),
         // don't convert w→ư. Let w be added as regular letter.
         // Example: "aw" → breve deferred → should stay "aw", not become "aư"
-        if self.pending_breve_pos.is_some() {
+        if self.deferred_pos(DeferKind::Breve).is_some() {
             return None;
         }
 
@@ -1496,9 +2846,9 @@ impl Engine {
 
         // If we already have a complete ươ compound, swallow the second 'w'
         // This handles "dduwowcj" where the second 'w' should be no-op
-        // Use send(0, []) to intercept and consume the key without output
+        // Consume the key without output
         if self.has_complete_uo_compound() {
-            return Some(Result::send(0, &[]));
+            return self.absorb_redundant_key(keys::W, 2);
         }
 
         // Check revert: ww → w (skip shortcut)
@@ -1607,6 +2957,7 @@ impl Engine {
                     // Store length AFTER modification - for "daddy": [d,a,d] → len=3
                     // Subsequent chars (y) start at position 3
                     self.telex_double_raw_len = self.raw_input.len();
+                    self.notify_revert(RevertKind::Stroke);
                     // Use rebuild_from_after_insert because the new 'd' was just pushed
                     // and hasn't been displayed on screen yet
                     return Some(self.rebuild_from_after_insert(pos));
@@ -1644,6 +2995,7 @@ impl Engine {
                     }
                     // Store length AFTER modification
                     self.telex_double_raw_len = self.raw_input.len();
+                    self.notify_revert(RevertKind::Stroke);
                     // Use rebuild_from_after_insert because the new 'd' was just pushed
                     // and hasn't been displayed on screen yet
                     return Some(self.rebuild_from_after_insert(pos));
@@ -1734,7 +3086,7 @@ impl Engine {
                 // BUT: Allow circumflex trigger patterns even if they look invalid now
                 // ALSO: Allow Vietnamese triple-o words (đoòng) which have literal double-o
                 if !has_circumflex_trigger_pattern
-                    && !is_valid_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+                    && !is_valid_with_profile(&buffer_keys, self.active_validation_profile())
                     && !self.is_vietnamese_triple_o_word()
                 {
                     return None;
@@ -1802,7 +3154,7 @@ impl Engine {
             && has_vowel
             && !has_circumflex_trigger_pattern
             && !self.is_vietnamese_triple_o_word()
-            && !is_valid_for_transform_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+            && !is_valid_for_transform_with_profile(&buffer_keys, self.active_validation_profile())
         {
             return None;
         }
@@ -1837,19 +3189,23 @@ impl Engine {
 
         // Issue #44: Cancel pending breve if same modifier pressed again ("aww" → "aw")
         // When breve was deferred and user presses 'w' again, cancel without adding another 'w'
-        if self.pending_breve_pos.is_some()
+        if self.deferred_pos(DeferKind::Breve).is_some()
             && (tone_type == ToneType::Horn || tone_type == ToneType::Breve)
+            && matches!(
+                self.defer_step(DeferEvent::Undo),
+                Some((_, DeferStep::Cancel))
+            )
         {
-            // Cancel the pending breve - user doesn't want Vietnamese
-            self.pending_breve_pos = None;
             // Return "consumed but no change" to prevent 'w' from being typed
             // action=Send with 0 backspace and 0 chars effectively consumes the key
-            return Some(Result::send(0, &[]));
+            return Some(Result::consumed());
         }
 
         // Check revert first (same key pressed twice)
         if let Some(Transform::Tone(last_key, _)) = self.last_transform {
             if last_key == key {
+                // "huoww" → "huow": the 'u' no longer waits for its horn
+                self.defer_step(DeferEvent::Undo);
                 return Some(self.revert_tone(key, caps));
             }
         }
@@ -1865,7 +3221,7 @@ impl Engine {
         let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
 
         if !self.free_tone_enabled
-            && !is_valid_for_transform_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+            && !is_valid_for_transform_with_profile(&buffer_keys, self.active_validation_profile())
         {
             return None;
         }
@@ -1896,6 +3252,10 @@ impl Engine {
             .buf
             .iter()
             .any(|c| targets.contains(&c.key) && c.tone != tone::NONE && c.tone != tone_val);
+        if is_switching {
+            // "huơ" + o → "huô": no horn left for the 'u' to follow
+            self.defer_step(DeferEvent::Switch);
+        }
 
         // Scan buffer for eligible target vowels
         let mut target_positions = Vec::new();
@@ -1907,46 +3267,37 @@ impl Engine {
                 if let (Some(c1), Some(c2)) = (self.buf.get(pos1), self.buf.get(pos2)) {
                     // Only apply compound when BOTH vowels have no tone
                     if c1.tone == tone::NONE && c2.tone == tone::NONE {
-                        // Issue #133: Check if "uo" pattern is at end of syllable (no final)
-                        // If no final consonant/vowel after "uo", only apply horn to 'o'
-                        // Examples: "huow" → "huơ", "khuow" → "khuơ"
-                        // But: "duowc" → "dược", "muowif" → "mười" (both get horn)
-                        let is_uo_pattern = c1.key == keys::U && c2.key == keys::O;
-                        let has_final = self.buf.get(pos2 + 1).is_some();
-
-                        // Check if 'u' is preceded by 'Q' (qu-initial consonant cluster)
-                        // In "Qu-", the 'u' is part of the initial and should not get horn
-                        // Examples: "Quoiws" → "Quới" (not "Qưới"), "quốc" (not "qước")
-                        let preceded_by_q =
-                            pos1 > 0 && self.buf.get(pos1 - 1).map(|c| c.key) == Some(keys::Q);
-
-                        // Check for "quoa" pattern: Q + U + O + A
-                        // In this case, skip U+O compound and let phonology rules handle O+A
-                        // W should apply breve to A, not horn to O
-                        // Example: "quoắt" = qu + oă + t
-                        let has_a_after_o = self
-                            .buf
-                            .get(pos2 + 1)
-                            .map(|c| c.key == keys::A)
-                            .unwrap_or(false);
-
-                        if preceded_by_q && has_a_after_o {
-                            // Skip compound handling - let find_horn_target_with_switch handle it
-                            // This will trigger O+A breve pattern in phonology rules
-                        } else if preceded_by_q {
-                            // "Qu-" pattern - only second vowel gets horn
-                            target_positions.push(pos2);
-                            self.pending_u_horn_pos = None;
-                        } else if is_uo_pattern && !has_final {
-                            // "uơ" pattern - only 'o' gets horn initially
-                            // Set pending so 'u' gets horn if final consonant/vowel is added
-                            target_positions.push(pos2);
-                            self.pending_u_horn_pos = Some(pos1);
-                        } else {
-                            // "ươ" pattern (or has final) - both get horn
-                            target_positions.push(pos1);
-                            target_positions.push(pos2);
-                            self.pending_u_horn_pos = None;
+                        // Placement comes from constants::UO_HORN_RULES:
+                        // - "huow" → "huơ" (deferred), "duowc" → "dược" (both)
+                        // - "Quoiws" → "Quới": u of qu- is part of the initial
+                        // - "quoaw" → "quoă": W is the breve on A, not a horn on O
+                        let ctx = constants::UoContext {
+                            qu_initial: pos1 > 0
+                                && self.buf.get(pos1 - 1).map(|c| c.key) == Some(keys::Q),
+                            a_after: self.buf.get(pos2 + 1).is_some_and(|c| c.key == keys::A),
+                            is_uo: c1.key == keys::U && c2.key == keys::O,
+                            has_final: self.buf.get(pos2 + 1).is_some(),
+                        };
+                        match constants::uo_horn(ctx) {
+                            // Let find_horn_target_with_switch handle it
+                            constants::UoHorn::Phonology => {}
+                            constants::UoHorn::Second => {
+                                target_positions.push(pos2);
+                                self.deferred = None;
+                            }
+                            constants::UoHorn::Deferred => {
+                                // 'u' gets its horn if a final consonant/vowel is added
+                                target_positions.push(pos2);
+                                self.deferred = Some(Deferred {
+                                    kind: DeferKind::UHorn,
+                                    pos: pos1,
+                                });
+                            }
+                            constants::UoHorn::Both => {
+                                target_positions.push(pos1);
+                                target_positions.push(pos2);
+                                self.deferred = None;
+                            }
                         }
                     }
                 }
@@ -2015,7 +3366,7 @@ impl Engine {
                         && self.buf.get(1).is_some_and(|c| c.key == keys::I);
 
                     // Exclude I from vowel types if it's part of gi-initial
-                    let unique_vowel_types: std::collections::HashSet<u16> = if is_gi_initial_here {
+                    let unique_vowel_types: BTreeSet<u16> = if is_gi_initial_here {
                         vowel_chars
                             .iter()
                             .filter(|c| c.key != keys::I)
@@ -2234,8 +3585,9 @@ impl Engine {
                                         false,
                                     ),
                                     2 => {
-                                        let pair = [consonants_after[0], consonants_after[1]];
-                                        (constants::VALID_FINALS_2.contains(&pair), true)
+                                        let valid = constants::FINAL_PAIRS
+                                            .contains(consonants_after[0], consonants_after[1]);
+                                        (valid, true)
                                     }
                                     _ => (false, false), // More than 2 consonants is invalid
                                 };
@@ -2302,8 +3654,8 @@ impl Engine {
                                             .take_while(|k| !keys::is_vowel(*k))
                                             .collect();
                                         if initial_keys.len() >= 2 {
-                                            let pair = [initial_keys[0], initial_keys[1]];
-                                            constants::VALID_INITIALS_2.contains(&pair)
+                                            constants::INITIAL_PAIRS
+                                                .contains(initial_keys[0], initial_keys[1])
                                         } else {
                                             false
                                         }
@@ -2542,13 +3894,14 @@ impl Engine {
             let is_w_revert_pending =
                 key == keys::W && matches!(self.last_transform, Some(Transform::WAsVowel));
 
-            let has_tone_already = self
+            let has_tone = self
                 .buf
                 .iter()
-                .any(|c| targets.contains(&c.key) && c.tone == tone_val);
-            if has_tone_already && !is_w_revert_pending {
+                .filter(|c| targets.contains(&c.key) && c.tone == tone_val)
+                .count();
+            if has_tone > 0 && !is_w_revert_pending {
                 // Absorb the key (no-op)
-                return Some(Result::send(0, &[]));
+                return self.absorb_redundant_key(key, has_tone);
             }
             return None;
         }
@@ -2647,7 +4000,7 @@ impl Engine {
             // Don't restore for simple "aw" or "raw" - let breve deferral handle those
             // Only run if english_auto_restore is enabled (experimental feature)
             if self.english_auto_restore && key == keys::W && self.raw_input.len() >= 2 {
-                let (prev_key, _, _) = self.raw_input[self.raw_input.len() - 2];
+                let prev_key = self.raw_input[self.raw_input.len() - 2].key;
                 if prev_key == keys::A {
                     // Check if there are earlier Vietnamese transforms in buffer
                     // (tone marks on OTHER vowels, or circumflex/horn on non-A vowels)
@@ -2664,16 +4017,13 @@ impl Engine {
 
                     if has_earlier_transforms {
                         // "aw" ending is English (like "seesaw") - restore immediately
-                        let raw_chars: Vec<char> = self
-                            .raw_input
-                            .iter()
-                            .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
-                            .collect();
-                        let backspace = self.buf.len() as u8;
+                        let raw_chars: Vec<char> =
+                            self.raw_input.iter().filter_map(|r| r.ch).collect();
+                        let backspace = backspaces(self.buf.len());
                         self.buf.clear();
                         self.raw_input.clear();
                         self.last_transform = None;
-                        return Some(Result::send(backspace, &raw_chars));
+                        return Some(Result::restore(backspace, &raw_chars));
                     }
                 }
             }
@@ -2681,6 +4031,9 @@ impl Engine {
                 if let Some(c) = self.buf.get(pos) {
                     // Check if this is 'a' with horn (breve) followed by another vowel
                     if c.key == keys::A {
+                        if self.in_breve_triphthong(pos) {
+                            return false;
+                        }
                         // Look for any vowel after this position
                         return (pos + 1..self.buf.len()).any(|i| {
                             self.buf
@@ -2693,7 +4046,13 @@ impl Engine {
                 false
             });
 
-            if has_breve_vowel_pattern {
+            // Breve before a final it cannot take: "anh" + 'w' → "ănh" ✗
+            let breve_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+            let breve_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
+            let has_invalid_final =
+                !self.free_tone_enabled && has_invalid_breve_final(&breve_keys, &breve_tones);
+
+            if has_breve_vowel_pattern || has_invalid_final {
                 // Revert: clear applied tones
                 for &pos in &target_positions {
                     if let Some(c) = self.buf.get_mut(pos) {
@@ -2715,7 +4074,10 @@ impl Engine {
                         if c.key == keys::A {
                             c.tone = tone::NONE;
                             // Store position for deferred breve
-                            self.pending_breve_pos = Some(pos);
+                            self.deferred = Some(Deferred {
+                                kind: DeferKind::Breve,
+                                pos,
+                            });
                         }
                     }
                 }
@@ -2797,7 +4159,14 @@ impl Engine {
         // When user types "aws" (Telex) or "a81" (VNI), they want "ắ" (breve + sắc)
         // Breve was deferred due to open syllable, but adding mark confirms Vietnamese input
         let mut had_pending_breve = false;
-        if let Some(breve_pos) = self.pending_breve_pos {
+        if let Some((
+            Deferred {
+                kind: DeferKind::Breve,
+                pos: breve_pos,
+            },
+            DeferStep::Apply,
+        )) = self.defer_step(DeferEvent::Mark)
+        {
             had_pending_breve = true;
             // Try to find and remove the breve modifier from buffer
             // Both Telex 'w' and VNI '8' are stored in buffer (handle_normal_letter adds them)
@@ -2817,7 +4186,6 @@ impl Engine {
                     self.had_any_transform = true;
                 }
             }
-            self.pending_breve_pos = None;
         }
 
         // Telex: Check for delayed circumflex pattern (V + C + V where both V are same)
@@ -2919,10 +4287,7 @@ impl Engine {
                     //   (words like "proposal" with "pr" will be rejected here)
                     let has_valid_vietnamese_initial = match initial_keys.len() {
                         0 | 1 => true,
-                        2 => {
-                            let pair = [initial_keys[0], initial_keys[1]];
-                            constants::VALID_INITIALS_2.contains(&pair)
-                        }
+                        2 => constants::INITIAL_PAIRS.contains(initial_keys[0], initial_keys[1]),
                         _ => false,
                     };
 
@@ -2949,7 +4314,7 @@ impl Engine {
                         let raw_str: String = self
                             .raw_input
                             .iter()
-                            .filter_map(|&(k, caps, _)| utils::key_to_char(k, caps))
+                            .filter_map(|r| r.ch)
                             .collect::<String>()
                             .to_lowercase();
                         if english_dict::is_english_word(&raw_str) {
@@ -3015,10 +4380,21 @@ impl Engine {
         // Also skip validation if free_tone mode is enabled
         let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
         let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
-        if !self.free_tone_enabled
+
+        // A non-Vietnamese final ("gel") under ForeignFinalTone::LastVowel:
+        // the mark goes on the last vowel and the checks below don't apply
+        let foreign_final = match self.foreign_final_tone {
+            ForeignFinalTone::LastVowel => {
+                utils::foreign_final_vowel(&buffer_keys, self.active_validation_profile())
+            }
+            ForeignFinalTone::Reject => None,
+        };
+        let free_tone = self.free_tone_enabled || foreign_final.is_some();
+
+        if !free_tone
             && !has_horn_transforms
             && !has_stroke_transforms
-            && !is_valid_for_transform_with_foreign(&buffer_keys, self.allow_foreign_consonants)
+            && !is_valid_for_transform_with_profile(&buffer_keys, self.active_validation_profile())
         {
             return None;
         }
@@ -3026,7 +4402,7 @@ impl Engine {
         // Check for invalid "-ing" rhyme: Vietnamese uses "-inh", NOT "-ing" with tone marks
         // Examples: "thíng" is invalid (things), but "tính" is valid
         // If vowel is 'i' and final is 'ng', reject marks
-        if !self.free_tone_enabled && !has_horn_transforms && !has_stroke_transforms {
+        if !free_tone && !has_horn_transforms && !has_stroke_transforms {
             let syllable = syllable::parse(&buffer_keys);
             if syllable.vowel.len() == 1 && syllable.final_c.len() == 2 {
                 let vowel_key = buffer_keys[syllable.vowel[0]];
@@ -3045,20 +4421,11 @@ impl Engine {
         // (p, t, c, ch, k) can only carry sắc or nặng. huyền/hỏi/ngã on a
         // stop-final syllable is phonologically impossible ("ỏt", "òc", "ãch"),
         // so reject the mark and let the key fall through as a literal letter.
-        if !self.free_tone_enabled && matches!(mark_val, mark::HUYEN | mark::HOI | mark::NGA) {
+        // See constants::TONE_CODA_MATRIX.
+        if !free_tone {
             let syllable = syllable::parse(&buffer_keys);
-            let is_stop_final = match syllable.final_c.len() {
-                1 => matches!(
-                    buffer_keys[syllable.final_c[0]],
-                    keys::P | keys::T | keys::C | keys::K
-                ),
-                2 => {
-                    buffer_keys[syllable.final_c[0]] == keys::C
-                        && buffer_keys[syllable.final_c[1]] == keys::H
-                }
-                _ => false,
-            };
-            if is_stop_final {
+            let final_keys: Vec<u16> = syllable.final_c.iter().map(|&i| buffer_keys[i]).collect();
+            if !constants::is_tone_allowed(&final_keys, mark_val) {
                 return None;
             }
         }
@@ -3076,7 +4443,7 @@ impl Engine {
         // - "rươu" + 'j' → has horn transforms → DON'T skip, apply mark normally
         // - "đe" + 's' → has stroke transform → DON'T skip, apply mark normally (Issue #48)
         // Skip foreign word detection if free_tone mode is enabled
-        if !self.free_tone_enabled
+        if !free_tone
             && !has_horn_transforms
             && !has_stroke_transforms
             && is_foreign_word_pattern(&buffer_keys, &buffer_tones, key)
@@ -3098,8 +4465,9 @@ impl Engine {
         let has_final = self.has_final_consonant(last_vowel_pos);
         let has_qu = self.has_qu_initial();
         let has_gi = self.has_gi_initial();
-        let pos =
-            Phonology::find_tone_position(&vowels, has_final, self.modern_tone, has_qu, has_gi);
+        let pos = foreign_final.unwrap_or_else(|| {
+            Phonology::find_tone_position(&vowels, has_final, self.modern_tone, has_qu, has_gi)
+        });
 
         // Check if target vowel already has the same mark
         // This handles two cases:
@@ -3120,7 +4488,7 @@ impl Engine {
         let starts_with_w = self
             .raw_input
             .first()
-            .map(|(k, _, _)| *k == keys::W)
+            .map(|r| r.key == keys::W)
             .unwrap_or(false);
 
         if let Some(c) = self.buf.get(pos) {
@@ -3146,11 +4514,18 @@ impl Engine {
                 } else {
                     // Vowels after (not at end): absorb (user double-tapped in same syllable)
                     // "roofif" → "rồi"
-                    return Some(Result::send(0, &[]));
+                    return self.absorb_redundant_key(key, 1);
                 }
             }
         }
 
+        // One tone per syllable: a second tone key must not stack a mark on
+        // another vowel ("gíi" + 's' → "gíí"); type it as a letter instead
+        let marks: Vec<u8> = self.buf.iter().map(|c| c.mark).collect();
+        if !self.free_tone_enabled && !is_valid_mark_placement(&marks, pos) {
+            return None;
+        }
+
         if let Some(c) = self.buf.get_mut(pos) {
             c.mark = mark_val;
             self.last_transform = Some(Transform::Mark(key, mark_val));
@@ -3169,7 +4544,7 @@ impl Engine {
                     .filter_map(|&c| char::from_u32(c))
                     .collect();
                 // Add 1 to backspace for the trigger 'd' that was on screen but removed from buffer
-                return Some(Result::send(result.backspace + 1, &chars));
+                return Some(Result::send(result.backspace.saturating_add(1), &chars));
             }
 
             // If there was pending breve, we need extra backspace
@@ -3183,7 +4558,7 @@ impl Engine {
                     .filter_map(|&c| char::from_u32(c))
                     .collect();
                 // Add 1 to backspace to account for modifier on screen
-                return Some(Result::send(result.backspace + 1, &chars));
+                return Some(Result::send(result.backspace.saturating_add(1), &chars));
             }
 
             // If delayed circumflex was applied, rebuild from earliest vowel position
@@ -3196,7 +4571,7 @@ impl Engine {
                     .filter_map(|&c| char::from_u32(c))
                     .collect();
                 // Add 1 to backspace for the removed trigger vowel still on screen
-                return Some(Result::send(result.backspace + 1, &chars));
+                return Some(Result::send(result.backspace.saturating_add(1), &chars));
             }
 
             return Some(self.rebuild_from(rebuild_pos));
@@ -3213,6 +4588,9 @@ impl Engine {
     ///
     /// Returns Some(position) of the 'o' that was modified, None if no change.
     fn normalize_uo_compound(&mut self) -> Option<usize> {
+        if !self.auto_uo_horn {
+            return None;
+        }
         // Look for pattern: U with horn + O without horn (anywhere in buffer)
         for i in 0..self.buf.len().saturating_sub(1) {
             let c1 = self.buf.get(i)?;
@@ -3233,6 +4611,24 @@ impl Engine {
         None
     }
 
+    /// Position of the vowel waiting for a deferred diacritic of `kind`
+    fn deferred_pos(&self, kind: DeferKind) -> Option<usize> {
+        self.deferred.filter(|d| d.kind == kind).map(|d| d.pos)
+    }
+
+    /// Feed a key to the pending diacritic, if any (see `defer::DEFER_RULES`)
+    ///
+    /// Every step but `Keep` ends the pending state; on `Apply` the caller
+    /// puts the returned diacritic on its vowel.
+    fn defer_step(&mut self, event: DeferEvent) -> Option<(Deferred, DeferStep)> {
+        let deferred = self.deferred?;
+        let step = defer::step(deferred.kind, event);
+        if step != DeferStep::Keep {
+            self.deferred = None;
+        }
+        Some((deferred, step))
+    }
+
     /// Find positions of U+O or O+U compound (adjacent vowels)
     /// Returns Some((first_pos, second_pos)) if found, None otherwise
     fn find_uo_compound_positions(&self) -> Option<(usize, usize)> {
@@ -3266,6 +4662,16 @@ impl Engine {
         false
     }
 
+    /// 'a' at `pos` is the middle of a triphthong that keeps its breve ("ngoằo")
+    fn in_breve_triphthong(&self, pos: usize) -> bool {
+        let key = |i: usize| self.buf.get(i).map(|c| c.key);
+        let (Some(prev), Some(next)) = (pos.checked_sub(1).and_then(key), key(pos + 1)) else {
+            return false;
+        };
+        let vowel_after = (pos + 2..self.buf.len()).any(|i| key(i).is_some_and(keys::is_vowel));
+        !vowel_after && constants::BREVE_TRIPHTHONGS.contains(&[prev, keys::A, next])
+    }
+
     /// Find target position for horn modifier with switching support
     /// Allows selecting vowels that have a different tone (for switching circumflex ↔ horn)
     fn find_horn_target_with_switch(&self, targets: &[u16], new_tone: u8) -> Vec<usize> {
@@ -3372,7 +4778,7 @@ impl Engine {
         let raw_str: String = self
             .raw_input
             .iter()
-            .filter_map(|&(k, caps, _)| utils::key_to_char(k, caps))
+            .filter_map(|r| r.ch)
             .collect::<String>()
             .to_lowercase();
         let is_english_word = english_dict::is_english_word(&raw_str);
@@ -3547,7 +4953,7 @@ impl Engine {
         let raw_str: String = self
             .raw_input
             .iter()
-            .filter_map(|&(key, caps, _)| utils::key_to_char(key, caps))
+            .filter_map(|r| r.ch)
             .collect::<String>()
             .to_lowercase();
         if english_dict::is_english_word(&raw_str) {
@@ -3697,7 +5103,7 @@ impl Engine {
     fn revert_and_rebuild(&mut self, pos: usize, key: u16, caps: bool) -> Result {
         // Calculate backspace BEFORE adding key (based on old buffer state)
         // Use saturating_sub to prevent underflow if pos > buf.len()
-        let backspace = self.buf.len().saturating_sub(pos) as u8;
+        let backspace = backspaces(self.buf.len().saturating_sub(pos));
 
         // Add the reverted key to buffer so validation sees the full sequence
         self.buf.push(Char::new(key, caps));
@@ -3755,7 +5161,9 @@ impl Engine {
                     }
                     // Store length AFTER modification
                     self.telex_double_raw_len = self.raw_input.len();
-                    return self.revert_and_rebuild(pos, key, caps);
+                    let result = self.revert_and_rebuild(pos, key, caps);
+                    self.notify_revert(RevertKind::Tone);
+                    return result;
                 }
             }
         }
@@ -3793,12 +5201,13 @@ impl Engine {
                     self.buf.push(Char::new(key, caps));
 
                     // Calculate backspace and output
-                    let backspace = (self.buf.len() - pos - 1) as u8; // -1 because we added 1 char
+                    let backspace = backspaces(self.buf.len() - pos - 1); // -1 because we added 1 char
                     let output: Vec<char> = (pos..self.buf.len())
                         .filter_map(|i| self.buf.get(i))
                         .filter_map(|c| utils::key_to_char(c.key, c.caps))
                         .collect();
 
+                    self.notify_revert(RevertKind::Mark);
                     return Result::send(backspace, &output);
                 }
             }
@@ -3815,6 +5224,7 @@ impl Engine {
                 // Un-stroked d found at pos - this means we need to add another d
                 let caps = c.caps;
                 self.buf.push(Char::new(key, caps));
+                self.notify_revert(RevertKind::Stroke);
                 return self.rebuild_from(pos);
             }
         }
@@ -3826,6 +5236,7 @@ impl Engine {
     /// When None is returned, the key falls through to handle_normal_letter()
     fn try_remove(&mut self) -> Option<Result> {
         self.last_transform = None;
+        self.defer_step(DeferEvent::Remove);
         for pos in self.buf.find_vowels().into_iter().rev() {
             if let Some(c) = self.buf.get_mut(pos) {
                 if c.mark > mark::NONE {
@@ -3950,7 +5361,7 @@ impl Engine {
                 let raw_str: String = self
                     .raw_input
                     .iter()
-                    .filter_map(|&(k, caps, _)| utils::key_to_char(k, caps))
+                    .filter_map(|r| r.ch)
                     .collect::<String>()
                     .to_lowercase();
                 if english_dict::is_english_word(&raw_str) {
@@ -3983,15 +5394,18 @@ impl Engine {
             // Add the letter/number to buffer
             self.buf.push(Char::new(key, caps));
 
-            // Issue #44 (part 2): Apply deferred breve when valid final consonant is typed
-            // "trawm" → after "traw" (pending breve on 'a'), typing 'm' applies breve → "trăm"
-            if let Some(breve_pos) = self.pending_breve_pos {
-                // Valid final consonants that make breve valid: c, k, m, n, p, t
-                // Note: k is included for ethnic minority words (Đắk Lắk)
-                if matches!(
-                    key,
-                    keys::C | keys::K | keys::M | keys::N | keys::P | keys::T
-                ) {
+            // Pending diacritic: the new letter applies, keeps or drops it
+            // (defer::DEFER_RULES)
+            match self.defer_step(DeferEvent::of_letter(key)) {
+                // Issue #44 (part 2): Apply deferred breve when valid final consonant is typed
+                // "trawm" → after "traw" (pending breve on 'a'), typing 'm' applies breve → "trăm"
+                Some((
+                    Deferred {
+                        kind: DeferKind::Breve,
+                        pos: breve_pos,
+                    },
+                    DeferStep::Apply,
+                )) => {
                     // Find and remove the breve modifier from buffer
                     // Telex uses 'w', VNI uses '8' - it should be right after 'a' at breve_pos
                     let modifier_pos = breve_pos + 1;
@@ -4012,7 +5426,6 @@ impl Engine {
                             self.had_any_transform = true;
                         }
                     }
-                    self.pending_breve_pos = None;
 
                     // Rebuild from breve position: delete "aw" (or "awX"), output "ăX"
                     // Buffer now has: ...ă (at breve_pos) + consonant (just added)
@@ -4020,33 +5433,36 @@ impl Engine {
                     let vowel_char = chars::to_char(keys::A, a_caps, tone::HORN, 0).unwrap_or('ă');
                     let cons_char = crate::utils::key_to_char(key, caps).unwrap_or('?');
                     return Result::send(2, &[vowel_char, cons_char]); // backspace 2 ("aw"), output "ăm"
-                } else if key == keys::W {
-                    // 'w' is the breve modifier - don't clear pending_breve_pos
-                    // It will be added as a regular letter and removed later
-                } else if keys::is_vowel(key) {
-                    // Vowel after "aw" pattern - breve not valid, clear pending
-                    self.pending_breve_pos = None;
-                }
-                // For other consonants (not finals, not W), keep pending_breve_pos
-                // They might be followed by more letters that complete the syllable
-            }
-
-            // Issue #133: Apply deferred horn to 'u' when final consonant/vowel is typed
-            // "duow" → "duơ" (pending on u), then "c" → apply horn to u → "dược"
-            if let Some(u_pos) = self.pending_u_horn_pos {
-                // Apply horn to 'u' at pending position
-                if let Some(c) = self.buf.get_mut(u_pos) {
-                    if c.key == keys::U && c.tone == tone::NONE {
-                        c.tone = tone::HORN;
-                        self.had_any_transform = true;
+                }
+                // Issue #133: Apply deferred horn to 'u' when final consonant/glide is typed
+                // "duow" → "duơ" (pending on u), then "c" → apply horn to u → "dược"
+                Some((
+                    Deferred {
+                        kind: DeferKind::UHorn,
+                        pos: u_pos,
+                    },
+                    DeferStep::Apply,
+                )) => {
+                    if let Some(c) = self.buf.get_mut(u_pos) {
+                        if c.key == keys::U && c.tone == tone::NONE {
+                            c.tone = tone::HORN;
+                            self.had_any_transform = true;
+                        }
                     }
+
+                    // Rebuild from u position: screen has "...uơ...", buffer has "...ươ...+new_char"
+                    // The new char was already pushed above but not yet on screen
+                    // Use rebuild_from_after_insert which accounts for this
+                    return self.rebuild_from_after_insert(u_pos);
                 }
-                self.pending_u_horn_pos = None;
+                _ => {}
+            }
 
-                // Rebuild from u position: screen has "...uơ...", buffer has "...ươ...+new_char"
-                // The new char was already pushed at line 1799 but not yet on screen
-                // Use rebuild_from_after_insert which accounts for this
-                return self.rebuild_from_after_insert(u_pos);
+            // Final resolved to one breve cannot take: "ăn" + 'h' → "awnh"
+            if keys::is_consonant(key) && !self.free_tone_enabled {
+                if let Some(result) = self.revert_invalid_breve() {
+                    return result;
+                }
             }
 
             // Revert mark on B-initial triple-o when invalid consonant follows
@@ -4168,7 +5584,7 @@ impl Engine {
                                     .iter()
                                     .filter_map(|&c| char::from_u32(c))
                                     .collect();
-                                return Result::send(result.backspace + 1, &chars);
+                                return Result::send(result.backspace.saturating_add(1), &chars);
                             }
                         }
                     }
@@ -4178,7 +5594,11 @@ impl Engine {
             // Normalize ưo → ươ immediately when 'o' is typed after 'ư'
             // This ensures "dduwo" → "đươ" (Telex) and "u7o" → "ươ" (VNI)
             // Works for both methods since "ưo" alone is not valid Vietnamese
-            if key == keys::O && self.normalize_uo_compound().is_some() {
+            if let Some(horn_pos) = (key == keys::O)
+                .then(|| self.normalize_uo_compound())
+                .flatten()
+            {
+                self.auto_horn_pos = Some(horn_pos);
                 // ươ compound formed - reposition tone if needed (ư→ơ)
                 if let Some((old_pos, _)) = self.reposition_tone_if_needed() {
                     return self.rebuild_from_after_insert(old_pos);
@@ -4275,7 +5695,7 @@ impl Engine {
                     if prev_has_mark && self.has_english_modifier_pattern(false) {
                         // Clear English pattern detected - restore to raw
                         if let Some(raw_chars) = self.build_raw_chars() {
-                            let backspace = (self.buf.len() - 1) as u8;
+                            let backspace = backspaces(self.buf.len() - 1);
 
                             // Repopulate buffer with restored content (plain chars, no marks)
                             // IMPORTANT: Use raw_chars (collapsed output) not raw_input
@@ -4293,7 +5713,7 @@ impl Engine {
                             // Reset had_any_transform since buffer now has plain chars
                             // This prevents backspace from incorrectly popping stale keys
                             self.had_any_transform = false;
-                            return Result::send(backspace, &raw_chars);
+                            return Result::restore(backspace, &raw_chars);
                         }
                     }
                 }
@@ -4363,6 +5783,42 @@ impl Engine {
         self.rebuild_from_after_insert(first_pos)
     }
 
+    /// Revert a breve whose final turned invalid ("ăn" + 'h' → "awnh")
+    ///
+    /// Clears the breve and puts its modifier key (Telex 'w', VNI '8') back
+    /// after the 'a' as a plain letter. The just-typed key is the last buffer
+    /// char and not yet on screen.
+    fn revert_invalid_breve(&mut self) -> Option<Result> {
+        let keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+        let tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
+        if !has_invalid_breve_final(&keys, &tones) {
+            return None;
+        }
+        let a_pos = self
+            .buf
+            .iter()
+            .position(|c| c.key == keys::A && c.tone == tone::HORN)?;
+        let mut caps = false;
+        if let Some(c) = self.buf.get_mut(a_pos) {
+            c.tone = tone::NONE;
+            caps = c.caps;
+        }
+        let modifier = if self.method == 0 { keys::W } else { keys::N8 };
+        self.buf.insert(a_pos + 1, Char::new(modifier, caps));
+        self.last_transform = None;
+
+        // Screen shows a_pos..len-2 (inserted modifier and new key are not on it)
+        let backspace = backspaces(self.buf.len() - 2 - a_pos);
+        let output: Vec<char> = (a_pos..self.buf.len())
+            .filter_map(|i| self.buf.get(i))
+            .filter_map(|c| {
+                chars::to_char(c.key, c.caps, c.tone, c.mark)
+                    .or_else(|| utils::key_to_char(c.key, c.caps))
+            })
+            .collect();
+        Some(Result::send(backspace, &output))
+    }
+
     /// Collect vowels from buffer
     fn collect_vowels(&self) -> Vec<Vowel> {
         utils::collect_vowels(&self.buf)
@@ -4422,7 +5878,7 @@ impl Engine {
         let mut output = Vec::with_capacity(self.buf.len().saturating_sub(from));
         // Backspace = number of chars from `from` to BEFORE the new char
         // The new char (last in buffer) hasn't been displayed yet
-        let backspace = (self.buf.len().saturating_sub(1).saturating_sub(from)) as u8;
+        let backspace = backspaces(self.buf.len().saturating_sub(1).saturating_sub(from));
 
         for i in from..self.buf.len() {
             if let Some(c) = self.buf.get(i) {
@@ -4454,31 +5910,79 @@ impl Engine {
             self.pending_capitalize = true;
             self.auto_capitalize_used = false;
         }
-        self.buf.clear();
-        self.raw_input.clear();
+        // Overwrite, not just truncate: the word's text doesn't linger in memory
+        self.buf.zeroize();
+        wipe::zero_vec(&mut self.raw_input, RawKeystroke::new(0, false, false));
         self.last_transform = None;
         self.has_non_letter_prefix = false;
-        self.pending_breve_pos = None;
-        self.pending_u_horn_pos = None;
+        self.deferred = None;
+        self.auto_horn_pos = None;
         self.stroke_reverted = false;
         self.had_mark_revert = false;
         self.pending_mark_revert_pop = false;
         self.had_any_transform = false;
+        self.composed_word = false;
         self.had_vowel_triggered_circumflex = false;
         self.had_circumflex_revert = false;
         self.reverted_circumflex_key = None;
         self.had_telex_transform = false;
+        if let Some(text) = self.telex_double_raw.as_mut() {
+            wipe::zero_string(text);
+        }
         self.telex_double_raw = None;
         self.telex_double_raw_len = 0;
         self.restored_pending_clear = false;
         self.restored_is_ascii = false;
-        self.shortcut_prefix.clear();
+        wipe::zero_string(&mut self.shortcut_prefix);
+        self.prefix_capitalize = false;
+        if let Some(text) = self.literal_word.as_mut() {
+            wipe::zero_string(text);
+        }
+        self.literal_word = None;
+    }
+
+    /// Handle a host "delete word" (Option+Backspace, Ctrl+Backspace)
+    ///
+    /// The host deletes the text itself; this keeps the engine in sync so the
+    /// next keystrokes don't diff against a word that is no longer on screen.
+    /// - Composing a new word: it is dropped; the previous commit and its
+    ///   separators are still on screen, so backspace can still restore it.
+    /// - Right after a commit, or editing a restored word: that word and its
+    ///   separators are gone. Its history entry is dropped and tracking moves
+    ///   to the word before it, when the separators after that word are known.
+    pub fn delete_word(&mut self) {
+        let restored = self.restored_pending_clear;
+        if self.buf.is_empty() {
+            if self.spaces_after_commit == 0 {
+                // Nothing tracked before the cursor
+                self.clear();
+                self.word_history.clear();
+                return;
+            }
+            self.word_history.pop();
+        } else if !restored {
+            self.clear();
+            return;
+        }
+        self.clear();
+
+        match self.word_history.last_trailer().filter(|t| !t.is_empty()) {
+            Some(trailer) => {
+                self.spaces_after_commit = trailer.chars().count().min(u8::MAX as usize) as u8;
+                self.commit_trailer = trailer.to_string();
+            }
+            None => {
+                self.word_history.clear();
+                self.spaces_after_commit = 0;
+                self.commit_trailer.clear();
+            }
+        }
     }
 
-    /// Re-detect pending_u_horn_pos by scanning buffer for "u(no tone) + o(horn)" pattern
+    /// Re-detect the pending horn on 'u' by scanning buffer for "u(no tone) + o(horn)" pattern
     /// Used after restoring buffer from word history where this state was lost on clear()
     fn re_detect_pending_u_horn(&mut self) {
-        self.pending_u_horn_pos = None;
+        self.deferred = None;
         let len = self.buf.len();
         if len < 2 {
             return;
@@ -4490,7 +5994,10 @@ impl Engine {
                 && c2.key == keys::O
                 && c2.tone == tone::HORN
             {
-                self.pending_u_horn_pos = Some(len - 2);
+                self.deferred = Some(Deferred {
+                    kind: DeferKind::UHorn,
+                    pos: len - 2,
+                });
             }
         }
     }
@@ -4510,37 +6017,14 @@ impl Engine {
     /// each diacritic back to the modifier key that produces it in the current
     /// method, so editing a restored word behaves like editing it before commit.
     fn re_detect_last_transform(&mut self) {
-        use crate::data::chars::{mark, tone};
-
         self.last_transform = None;
-        let is_vni = self.method == 1;
 
-        // These arms are the inverse of the forward key maps in input/telex.rs and
-        // input/vni.rs — keep them in sync if a method's bindings ever change.
-        //
         // A tone mark (sắc/huyền/hỏi/ngã/nặng) is always the last diacritic applied
         // to a syllable and may sit on a non-final vowel ("bía" marks 'í', not the
         // trailing 'a'), so scan the whole buffer for it. A repeated mark key then
         // reverts it after restore, re-arming whitelist auto-restore ("biass"→"bias").
         if let Some(&c) = self.buf.iter().rev().find(|c| c.mark != mark::NONE) {
-            let key = if is_vni {
-                match c.mark {
-                    mark::HUYEN => keys::N2,
-                    mark::HOI => keys::N3,
-                    mark::NGA => keys::N4,
-                    mark::NANG => keys::N5,
-                    _ => keys::N1, // SAC
-                }
-            } else {
-                match c.mark {
-                    mark::HUYEN => keys::F,
-                    mark::HOI => keys::R,
-                    mark::NGA => keys::X,
-                    mark::NANG => keys::J,
-                    _ => keys::S, // SAC
-                }
-            };
-            self.last_transform = Some(Transform::Mark(key, c.mark));
+            self.last_transform = Some(Transform::Mark(self.mark_key(c.mark), c.mark));
             return;
         }
 
@@ -4550,92 +6034,668 @@ impl Engine {
         // matching continuous typing where "tuân" + 'a' appends instead of reverting.
         let Some(&c) = self.buf.last() else { return };
         if c.tone != tone::NONE {
-            let key = if is_vni {
-                match c.tone {
-                    tone::CIRCUMFLEX => keys::N6,
-                    // HORN on 'a' is breve (key 8); on o/u it is horn (key 7).
-                    _ if c.key == keys::A => keys::N8,
-                    _ => keys::N7,
+            self.last_transform = Some(Transform::Tone(self.tone_key(c.key, c.tone), c.tone));
+        }
+    }
+
+    /// Modifier key that applies `mark` (sắc..nặng) in the current method
+    ///
+    /// These arms are the inverse of the forward key maps in input/telex.rs and
+    /// input/vni.rs — keep them in sync if a method's bindings ever change.
+    fn mark_key(&self, mark_val: u8) -> u16 {
+        if self.method == 1 {
+            match mark_val {
+                mark::HUYEN => keys::N2,
+                mark::HOI => keys::N3,
+                mark::NGA => keys::N4,
+                mark::NANG => keys::N5,
+                _ => keys::N1, // SAC
+            }
+        } else {
+            match mark_val {
+                mark::HUYEN => keys::F,
+                mark::HOI => keys::R,
+                mark::NGA => keys::X,
+                mark::NANG => keys::J,
+                _ => keys::S, // SAC
+            }
+        }
+    }
+
+    /// Modifier key that applies `tone_val` to vowel `key` in the current method
+    fn tone_key(&self, key: u16, tone_val: u8) -> u16 {
+        if self.method == 1 {
+            match tone_val {
+                tone::CIRCUMFLEX => keys::N6,
+                // HORN on 'a' is breve (key 8); on o/u it is horn (key 7).
+                _ if key == keys::A => keys::N8,
+                _ => keys::N7,
+            }
+        } else {
+            match tone_val {
+                tone::CIRCUMFLEX => key, // a/e/o double themselves: aa→â
+                _ => keys::W,            // horn & breve both use 'w'
+            }
+        }
+    }
+
+    /// Diacritics of the composing word, in letter order
+    ///
+    /// Each entry is the letter index, the diacritic and the key of the
+    /// current method that applies it. Derived from the buffer, so reverted
+    /// transforms are gone. Empty for a literal word.
+    pub fn transforms(&self) -> Vec<AppliedTransform> {
+        if self.literal_word.is_some() {
+            return Vec::new();
+        }
+        let mut track = Vec::new();
+        for (pos, c) in self.buf.iter().enumerate() {
+            if c.stroke {
+                let key = if self.method == 1 { keys::N9 } else { keys::D };
+                track.push(AppliedTransform {
+                    pos,
+                    kind: TransformKind::Stroke,
+                    key,
+                });
+            }
+            if c.tone != tone::NONE {
+                let kind = match (c.tone, c.key) {
+                    (tone::CIRCUMFLEX, _) => TransformKind::Circumflex,
+                    (_, keys::A) => TransformKind::Breve,
+                    _ => TransformKind::Horn,
+                };
+                track.push(AppliedTransform {
+                    pos,
+                    kind,
+                    key: self.tone_key(c.key, c.tone),
+                });
+            }
+            if c.mark != mark::NONE {
+                track.push(AppliedTransform {
+                    pos,
+                    kind: TransformKind::Mark(c.mark),
+                    key: self.mark_key(c.mark),
+                });
+            }
+        }
+        track
+    }
+
+    /// Clear everything including word history
+    /// Used when cursor position changes (mouse click, arrow keys, etc.)
+    /// to prevent accidental restore from stale history
+    /// Issue #274: Also reset auto-capitalize state to prevent incorrect
+    /// capitalization after paste/cursor change
+    pub fn clear_all(&mut self) {
+        self.clear();
+        self.word_history.clear();
+        self.previous_languages = [None; 2];
+        self.spaces_after_commit = 0;
+        // Issue #274: Reset auto-capitalize state on cursor change
+        // This prevents incorrect capitalization after copy-paste
+        self.pending_capitalize = false;
+        self.saw_sentence_ending = false;
+    }
+
+    /// Get the full composed buffer as a Vietnamese string with diacritics.
+    ///
+    /// Used for "Select All + Replace" injection method.
+    pub fn get_buffer_string(&self) -> String {
+        self.buf.to_full_string()
+    }
+
+    /// Snapshot of the composing state for status UI
+    pub fn status(&self) -> EngineStatus {
+        let (current_word, raw_word) = match &self.literal_word {
+            Some(text) => (text.clone(), text.clone()),
+            None => (
+                self.buf.to_full_string(),
+                self.get_raw_input_string_preserve_case(),
+            ),
+        };
+        EngineStatus {
+            current_word,
+            raw_word,
+            mode: self.mode(),
+            disable_reason: self.disable_reason(),
+            method: self.method,
+            pending_capitalize: self.pending_capitalize,
+            shortcut_prefix: self.shortcut_prefix.clone(),
+        }
+    }
+
+    /// Current input mode (see `InputMode`)
+    pub fn mode(&self) -> InputMode {
+        if !self.enabled {
+            return InputMode::English;
+        }
+        if self.literal_word.is_some() {
+            return InputMode::Foreign;
+        }
+        if self.buf.is_empty() {
+            return InputMode::Vietnamese;
+        }
+        let keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+        if is_valid_with_profile(&keys, self.active_validation_profile()) {
+            InputMode::Vietnamese
+        } else {
+            InputMode::Foreign
+        }
+    }
+
+    /// Debug: Check if vowel-triggered circumflex flag is set
+    pub fn had_vowel_circumflex(&self) -> bool {
+        self.had_vowel_triggered_circumflex
+    }
+
+    /// Debug: Get raw_input length
+    pub fn raw_input_len(&self) -> usize {
+        self.raw_input.len()
+    }
+
+    /// Debug: Check if raw_input is valid English
+    pub fn is_raw_english(&self) -> bool {
+        self.is_raw_input_valid_english()
+    }
+
+    /// Restore buffer from a Vietnamese word string
+    ///
+    /// Used when native app detects cursor at word boundary and wants to edit.
+    /// Parses Vietnamese characters back to buffer components, one per
+    /// grapheme so decomposed text ("e\u{302}") restores too. A word with a
+    /// grapheme the buffer can't hold (emoji, digits) isn't restored: edits
+    /// sized from a partial buffer would delete the wrong characters.
+    ///
+    /// A prefix ending in ASCII punctuation ("#việt", "user@dômain") stays on
+    /// screen as it is: only the word after the last punctuation is rebuilt,
+    /// and the punctuation right before it becomes the shortcut prefix, as if
+    /// just typed.
+    pub fn restore_word(&mut self, word: &str) {
+        self.clear();
+        if self.secure {
+            return;
+        }
+        let clusters = grapheme::graphemes(word);
+        let is_symbol = |g: &str| g.len() == 1 && g.as_bytes()[0].is_ascii_punctuation();
+        let start = clusters
+            .iter()
+            .rposition(|g| is_symbol(g))
+            .map_or(0, |i| i + 1);
+        let symbols = clusters[..start]
+            .iter()
+            .rev()
+            .take_while(|g| is_symbol(g))
+            .count();
+        self.shortcut_prefix = clusters[start - symbols..start].concat();
+        let word = clusters[start..].concat();
+        let Some(parsed_chars) = clusters[start..]
+            .iter()
+            .map(|g| grapheme::compose(g).and_then(chars::parse_char))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        let mut is_ascii = true;
+        for parsed in parsed_chars {
+            let mut ch = Char::new(parsed.key, parsed.caps);
+            ch.tone = parsed.tone;
+            ch.mark = parsed.mark;
+            ch.stroke = parsed.stroke;
+            self.buf.push(ch);
+            self.raw_input
+                .push(RawKeystroke::new(parsed.key, parsed.caps, false));
+            // Check if this char has any Vietnamese diacritics
+            if parsed.tone != 0 || parsed.mark != 0 || parsed.stroke {
+                is_ascii = false;
+            }
+        }
+        if !is_ascii {
+            // Keystrokes that would have typed the word ("việt" → "vieetj"),
+            // so ESC and auto-restore see its modifier keys
+            if let Some(raw) = self.raw_for_word(&word) {
+                self.raw_input = raw;
+            }
+        }
+        // Mark that buffer was restored from screen - if user types a regular consonant,
+        // clear buffer first (they want fresh word, not append to restored word)
+        // This allows: click on "shortcuts" → type "Nuw" → get "Nư" (not "shortcutsNuw")
+        // But mark/tone keys like 's' will still work to modify the restored word
+        if !self.buf.is_empty() {
+            self.restored_pending_clear = true;
+            self.restored_is_ascii = is_ascii;
+            // Rebuild last_transform so a repeated modifier key toggles the
+            // diacritic off, matching pre-commit editing behavior.
+            self.re_detect_last_transform();
+        }
+    }
+
+    /// Check the engine's view of the text before the cursor against the app's
+    ///
+    /// `before` is the text preceding the cursor as the app has it: at least
+    /// the word being typed, or the last word and the separators after it.
+    /// Call it when the context may have changed behind the engine (focus
+    /// returns, before restoring into a word). Separators are counted in
+    /// graphemes, one per backspace, so a CRLF is one; a word that isn't on
+    /// screen anymore drops the composing state instead of editing blind.
+    pub fn sync_context(&mut self, before: &str) -> ContextSync {
+        if self.secure {
+            return ContextSync::InSync;
+        }
+        self.needs_context = false;
+        let clusters = grapheme::graphemes(before);
+        let composing = match &self.literal_word {
+            Some(text) => Some(text.clone()),
+            None if !self.buf.is_empty() => Some(self.buf.to_full_string()),
+            None => None,
+        };
+        if let Some(word) = composing {
+            if context::ends_with_word(&clusters, &word) {
+                return ContextSync::InSync;
+            }
+            self.reset_context();
+            return ContextSync::Reset;
+        }
+
+        if self.spaces_after_commit == 0 {
+            return ContextSync::InSync;
+        }
+        let Some(last) = self.word_history.last() else {
+            return ContextSync::InSync;
+        };
+        let separators = clusters
+            .iter()
+            .rev()
+            .take_while(|c| context::is_separator(c))
+            .count();
+        let word_end = clusters.len() - separators;
+        if separators == 0
+            || !context::ends_with_word(&clusters[..word_end], &last.to_full_string())
+        {
+            self.reset_context();
+            return ContextSync::Reset;
+        }
+        let trailer = clusters[word_end..].concat();
+        if separators == self.spaces_after_commit as usize && trailer == self.commit_trailer {
+            return ContextSync::InSync;
+        }
+        // A multi-char separator (CRLF) leaves the trailer untrusted for
+        // re-sending: its char count no longer matches the grapheme count
+        self.spaces_after_commit = separators.min(u8::MAX as usize) as u8;
+        self.commit_trailer = trailer;
+        ContextSync::Adjusted
+    }
+
+    /// Finalize the word being typed without a boundary key
+    ///
+    /// For focus changes and app switches: runs the end-of-word checks a break
+    /// key would (auto-restore, spell check, observer) and returns the edit the
+    /// host must still apply to the field being left, e.g. "tẽt" → "text".
+    /// Word shortcuts don't expand since no boundary was typed. Composing state
+    /// and backspace-after-space history are dropped: the next keys go elsewhere.
+    pub fn flush(&mut self) -> Result {
+        self.save_screen_word();
+        let mut result = Result::none();
+        if self.literal_word.is_none() && !self.buf.is_empty() {
+            result = self.try_auto_restore_on_break();
+            self.notify_restore(&result, false);
+            if result.action != 0 {
+                self.buf.clear();
+                for &RawKeystroke { key, caps, .. } in &self.raw_input {
+                    self.buf.push(Char::new(key, caps));
                 }
+            }
+            self.notify_commit();
+        }
+        self.reset_context();
+        result
+    }
+
+    /// Check the `result` of the last key (or of `flush`, `strip_last_word`)
+    /// against the text before the cursor, before the host applies it
+    ///
+    /// Edits are sized from the engine's model of the screen; apps that
+    /// autocomplete or rewrite text as it is typed (terminals, address bars)
+    /// can change it underneath. `before` is the app's text before the
+    /// cursor, at least the chars the edit deletes when the host can read
+    /// them, in graphemes as with `sync_context`. When the deleted word is
+    /// there with other separators after it, the edit is resized to them;
+    /// when it isn't there at all, `result` becomes `Result::none()` (the
+    /// key types itself) and the composing state is dropped.
+    pub fn verify_result(&mut self, result: &mut Result, before: &str) -> ScreenCheck {
+        if self.secure || result.action != Action::Send as u8 || result.backspace == 0 {
+            return ScreenCheck::Confirmed;
+        }
+        let model: Vec<char> = self.edit_before.chars().collect();
+        let n = result.backspace as usize;
+        if n > model.len() {
+            // Deletes past what the engine tracks: nothing to compare
+            return ScreenCheck::Confirmed;
+        }
+        let clusters = grapheme::graphemes(before);
+        let deleted: String = model[model.len() - n..].iter().collect();
+        if context::ends_with_word(&clusters, &deleted) {
+            return ScreenCheck::Confirmed;
+        }
+        if let Some(adjusted) = Self::adjust_separators(result, &clusters, &model) {
+            *result = adjusted;
+            self.log(
+                LogLevel::Info,
+                "context",
+                format_args!("verify: separators differ, edit resized"),
+            );
+            return ScreenCheck::Adjusted;
+        }
+        *result = Result::none();
+        self.reset_context();
+        self.log(
+            LogLevel::Info,
+            "context",
+            format_args!("verify: screen differs, edit dropped"),
+        );
+        ScreenCheck::Aborted
+    }
+
+    /// `result` replacing the whole model word and its separators, resized
+    /// to the separators actually on screen
+    fn adjust_separators(result: &Result, clusters: &[&str], model: &[char]) -> Option<Result> {
+        let trailer_len = model
+            .iter()
+            .rev()
+            .take_while(|c| c.is_whitespace() || c.is_ascii_punctuation())
+            .count();
+        let word: String = model[..model.len() - trailer_len].iter().collect();
+        let output: Vec<char> = (0..result.count as usize)
+            .filter_map(|i| char::from_u32(result.chars[i]))
+            .collect();
+        if trailer_len == 0
+            || word.is_empty()
+            || result.backspace as usize != model.len()
+            || !output.ends_with(&model[model.len() - trailer_len..])
+        {
+            return None;
+        }
+        let separators = clusters
+            .iter()
+            .rev()
+            .take_while(|c| context::is_separator(c))
+            .count();
+        let word_end = clusters.len() - separators;
+        // The whole word must be there: it is deleted in full
+        if separators == 0
+            || word_end < word.chars().count()
+            || !context::ends_with_word(&clusters[..word_end], &word)
+        {
+            return None;
+        }
+        let mut text = output[..output.len() - trailer_len].to_vec();
+        text.extend(clusters[word_end..].concat().chars());
+        let backspace = u8::try_from(word.chars().count() + separators).ok()?;
+        let mut adjusted = Result::send(backspace, &text);
+        adjusted.flags = result.flags;
+        Some(adjusted)
+    }
+
+    /// Handle an edit the engine can't follow (multi-cursor typing, a remote
+    /// collaborator's change, the app rewriting the field)
+    ///
+    /// The word being typed is committed as it is on screen: observers see
+    /// it, but no auto-restore edit is produced since the text around the
+    /// cursor can't be trusted. Composing state, backspace-after-space
+    /// history and sentence state are dropped. With `ResetPolicy::Resync`,
+    /// `needs_context` stays true until the host calls `sync_context`.
+    pub fn reset_external(&mut self, policy: ResetPolicy) {
+        if !self.secure && self.literal_word.is_none() && !self.buf.is_empty() {
+            self.notify_commit();
+        }
+        self.clear_all();
+        self.commit_trailer.clear();
+        self.needs_context = policy == ResetPolicy::Resync;
+        self.log(
+            LogLevel::Info,
+            "context",
+            format_args!("external edit: reset ({policy:?})"),
+        );
+    }
+
+    /// Whether the host should pass its text to `sync_context` (set by
+    /// `reset_external` with `ResetPolicy::Resync`)
+    pub fn needs_context(&self) -> bool {
+        self.needs_context
+    }
+
+    /// Drop composing state and backspace-after-space tracking
+    fn reset_context(&mut self) {
+        self.clear();
+        self.word_history.clear();
+        self.spaces_after_commit = 0;
+        self.commit_trailer.clear();
+    }
+
+    /// Append a pre-composed character to the word being typed
+    ///
+    /// For characters the host inserted itself (another keyboard layout, an
+    /// on-screen picker, dictation): "ế" is decomposed into key + circumflex +
+    /// sắc, and the keystrokes that would type it in the current method are
+    /// recorded, so later keys edit the word as if it had been typed
+    /// ("tiế" + "ng" → "tiếng", "ế" + 's' → "ês", auto-restore sees "ees").
+    /// The character is already on screen, so nothing is sent back.
+    /// Letters outside Vietnamese start a literal word; anything else ends
+    /// the current word.
+    pub fn push_composed(&mut self, ch: char) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(text) = self.literal_word.as_mut() {
+            text.push(ch);
+            return;
+        }
+        let Some(parsed) = chars::parse_char(ch) else {
+            if ch.is_alphabetic() {
+                self.enter_literal_word(ch);
             } else {
-                match c.tone {
-                    tone::CIRCUMFLEX => c.key, // a/e/o double themselves: aa→â
-                    _ => keys::W,              // horn & breve both use 'w'
-                }
-            };
-            self.last_transform = Some(Transform::Tone(key, c.tone));
+                self.clear();
+            }
+            return;
+        };
+        if self.buf.is_empty() {
+            self.composed_word = true;
+        }
+        let mut c = Char::new(parsed.key, parsed.caps);
+        c.tone = parsed.tone;
+        c.mark = parsed.mark;
+        c.stroke = parsed.stroke;
+        self.buf.push(c);
+        self.raw_input
+            .push(RawKeystroke::new(parsed.key, parsed.caps, false));
+        if parsed.stroke {
+            let key = if self.method == 1 { keys::N9 } else { keys::D };
+            self.raw_input
+                .push(RawKeystroke::new(key, parsed.caps, false));
+        }
+        if parsed.tone != 0 {
+            let key = self.tone_key(parsed.key, parsed.tone);
+            // Telex doubles the vowel itself ("EE" → "Ê")
+            let caps = parsed.caps && key == parsed.key;
+            self.raw_input.push(RawKeystroke::new(key, caps, false));
+        }
+        if parsed.mark != 0 {
+            let key = self.mark_key(parsed.mark);
+            self.raw_input.push(RawKeystroke::new(key, false, false));
+        }
+        if parsed.tone != 0 || parsed.mark != 0 || parsed.stroke {
+            self.had_any_transform = true;
         }
+        // The word now continues from here rather than resting on screen
+        self.restored_pending_clear = false;
+        self.re_detect_last_transform();
     }
 
-    /// Clear everything including word history
-    /// Used when cursor position changes (mouse click, arrow keys, etc.)
-    /// to prevent accidental restore from stale history
-    /// Issue #274: Also reset auto-capitalize state to prevent incorrect
-    /// capitalization after paste/cursor change
-    pub fn clear_all(&mut self) {
-        self.clear();
-        self.word_history.clear();
-        self.spaces_after_commit = 0;
-        // Issue #274: Reset auto-capitalize state on cursor change
-        // This prevents incorrect capitalization after copy-paste
-        self.pending_capitalize = false;
-        self.saw_sentence_ending = false;
+    /// Remove diacritics from the current or last committed word
+    ///
+    /// Bindable command for filenames/usernames: "đường" → "duong".
+    /// Works on the word being typed, or on the word just committed when only
+    /// separators (spaces/punctuation) were typed after it. The separators are
+    /// re-sent after the stripped word.
+    pub fn strip_last_word(&mut self) -> Result {
+        self.save_screen_word();
+        if !self.buf.is_empty() {
+            let word = self.buf.to_full_string();
+            let stripped = text::remove_diacritics(&word);
+            if stripped == word {
+                return Result::none();
+            }
+            let len = word.chars().count();
+            self.buf = Self::plain_buffer(&self.buf);
+            self.raw_input = Self::raw_from_buffer(&self.buf);
+            self.last_transform = None;
+            let output: Vec<char> = stripped.chars().collect();
+            return Result::send(backspaces(len), &output);
+        }
+
+        let trailer_len = self.commit_trailer.chars().count();
+        if self.spaces_after_commit == 0 || trailer_len != self.spaces_after_commit as usize {
+            return Result::none();
+        }
+        let Some(last) = self.word_history.last() else {
+            return Result::none();
+        };
+        let word = last.to_full_string();
+        let stripped = text::remove_diacritics(&word);
+        if stripped == word {
+            return Result::none();
+        }
+        let plain = Self::plain_buffer(last);
+        let Some(entry) = self.word_history.pop() else {
+            return Result::none();
+        };
+        self.word_history.push(HistoryEntry {
+            raw: Self::raw_from_buffer(&plain),
+            buf: plain,
+            ..entry
+        });
+
+        // A full-length word plus its separators can exceed what a result
+        // deletes: leave the text as it is
+        let Ok(backspace) = u8::try_from(word.chars().count() + trailer_len) else {
+            return Result::none();
+        };
+        let output: Vec<char> = stripped
+            .chars()
+            .chain(self.commit_trailer.chars())
+            .collect();
+        Result::send(backspace, &output)
     }
 
-    /// Get the full composed buffer as a Vietnamese string with diacritics.
-    ///
-    /// Used for "Select All + Replace" injection method.
-    pub fn get_buffer_string(&self) -> String {
-        self.buf.to_full_string()
+    /// Report the word being committed (buffer holds the final word):
+    /// spell check it and notify the observer
+    fn notify_commit(&mut self) {
+        if !self.spell_check && self.observer.is_none() {
+            return;
+        }
+        let word = self.buf.to_full_string();
+        if self.spell_check {
+            self.spell_flag = spell::check(&word, &self.user_words);
+        }
+        let raw: String = if self.observer.is_some() {
+            self.typed_chars().into_iter().collect()
+        } else {
+            String::new()
+        };
+        if let Some(o) = self.observer.as_deref_mut() {
+            o.on_word_committed(&word);
+            o.on_word_committed_pair(&raw, &word);
+            if let Some(flag) = &self.spell_flag {
+                o.on_spell_flag(flag);
+            }
+        }
     }
 
-    /// Debug: Check if vowel-triggered circumflex flag is set
-    pub fn had_vowel_circumflex(&self) -> bool {
-        self.had_vowel_triggered_circumflex
+    /// Push the committed buffer, first tagging the previous entry with the
+    /// separators typed after it (when they were tracked)
+    fn push_history(&mut self) {
+        let trailer_known = self.spaces_after_commit > 0
+            && self.commit_trailer.chars().count() == self.spaces_after_commit as usize;
+        if trailer_known {
+            self.word_history.set_last_trailer(&self.commit_trailer);
+        }
+        let language = self.committed_language();
+        if self.had_any_transform {
+            if let Some(language) = language {
+                self.word_choices
+                    .record(&self.get_raw_input_string(), language);
+            }
+        }
+        self.previous_languages = [language, self.previous_languages[0]];
+        self.word_history.push(HistoryEntry {
+            buf: self.buf.clone(),
+            raw: self.raw_input.clone(),
+            auto_capitalized: self.auto_capitalize_used,
+            trailer: String::new(),
+        });
     }
 
-    /// Debug: Get raw_input length
-    pub fn raw_input_len(&self) -> usize {
-        self.raw_input.len()
+    /// Language of the word being committed: English when restored (or typed
+    /// as is) after a transform, else what its text reads as
+    fn committed_language(&self) -> Option<WordLanguage> {
+        let transformed = self
+            .buf
+            .iter()
+            .any(|c| c.tone > 0 || c.mark > 0 || c.stroke);
+        if self.had_any_transform && !transformed {
+            return Some(WordLanguage::English);
+        }
+        WordLanguage::of_word(&self.buf.to_full_string())
     }
 
-    /// Debug: Check if raw_input is valid English
-    pub fn is_raw_english(&self) -> bool {
-        self.is_raw_input_valid_english()
+    /// Record and report an auto-restore/ESC restore while the buffer still
+    /// holds the transformed word
+    fn notify_restore(&mut self, result: &Result, escape: bool) {
+        if result.action == 0 {
+            return;
+        }
+        let transformed = self.buf.to_full_string();
+        let raw: String = result.chars[..result.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        let raw = raw.trim_end_matches(' ').to_string();
+        let reason = if escape {
+            RestoreReason::Escape
+        } else if english_dict::is_english_word(&raw) {
+            RestoreReason::EnglishWord
+        } else {
+            RestoreReason::InvalidVietnamese
+        };
+        let (from, to) = (transformed.chars().count(), raw.chars().count());
+        self.log(
+            LogLevel::Info,
+            "restore",
+            format_args!("{reason:?}, {from} chars to {to}"),
+        );
+        if let Some(o) = self.observer.as_deref_mut() {
+            o.on_restore(&transformed, &raw);
+        }
+        self.last_restore = Some(RestoreInfo {
+            transformed,
+            raw,
+            reason,
+        });
     }
 
-    /// Restore buffer from a Vietnamese word string
-    ///
-    /// Used when native app detects cursor at word boundary and wants to edit.
-    /// Parses Vietnamese characters back to buffer components.
-    pub fn restore_word(&mut self, word: &str) {
-        self.clear();
-        let mut is_ascii = true;
-        for c in word.chars() {
-            if let Some(parsed) = chars::parse_char(c) {
-                let mut ch = Char::new(parsed.key, parsed.caps);
-                ch.tone = parsed.tone;
-                ch.mark = parsed.mark;
-                ch.stroke = parsed.stroke;
-                self.buf.push(ch);
-                self.raw_input.push((parsed.key, parsed.caps, false));
-                // Check if this char has any Vietnamese diacritics
-                if parsed.tone != 0 || parsed.mark != 0 || parsed.stroke {
-                    is_ascii = false;
-                }
-            }
+    /// Report a double-key revert (buffer already holds the reverted word)
+    fn notify_revert(&mut self, kind: RevertKind) {
+        if let Some(o) = self.observer.as_deref_mut() {
+            o.on_revert(kind, &self.buf.to_full_string());
         }
-        // Mark that buffer was restored from screen - if user types a regular consonant,
-        // clear buffer first (they want fresh word, not append to restored word)
-        // This allows: click on "shortcuts" → type "Nuw" → get "Nư" (not "shortcutsNuw")
-        // But mark/tone keys like 's' will still work to modify the restored word
-        if !self.buf.is_empty() {
-            self.restored_pending_clear = true;
-            self.restored_is_ascii = is_ascii;
-            // Rebuild last_transform so a repeated modifier key toggles the
-            // diacritic off, matching pre-commit editing behavior.
-            self.re_detect_last_transform();
+    }
+
+    /// Copy of buffer with all tones, marks and strokes removed
+    fn plain_buffer(buf: &Buffer) -> Buffer {
+        let mut plain = Buffer::new();
+        for c in buf.iter() {
+            plain.push(Char::new(c.key, c.caps));
         }
+        plain
     }
 
     /// Check if buffer has transforms and is invalid Vietnamese
@@ -4649,7 +6709,7 @@ impl Engine {
             return None;
         }
 
-        if self.raw_input.is_empty() || self.buf.is_empty() {
+        if self.raw_input.is_empty() || self.buf.is_empty() || self.composed_word {
             return None;
         }
 
@@ -4662,6 +6722,11 @@ impl Engine {
             return None;
         }
 
+        // Short words (below the configured minimum) stay as typed
+        if !self.restore_filter.allows(&self.get_raw_input_string()) {
+            return None;
+        }
+
         // Issue #211: Skip auto-restore for extended character patterns
         // When user types "ơiiiiii", "điiii", "ôiiii", "vàooooo", etc.
         // This is intentional Vietnamese (casual messaging) not English.
@@ -4694,7 +6759,7 @@ impl Engine {
         let has_stroke = self
             .raw_input
             .windows(2)
-            .any(|pair| pair[0].0 == keys::D && pair[1].0 == keys::D)
+            .any(|pair| pair[0].key == keys::D && pair[1].key == keys::D)
             && self.buf.iter().any(|c| c.key == keys::D);
 
         let has_vn_modifier = has_vn_specific_modifier || has_stroke;
@@ -4736,18 +6801,18 @@ impl Engine {
         //   "boos" → "bố" (oo = circumflex, in whitelist) → let whitelist handle
         //   "bore" → "boẻ" (only hỏi tone, no VN-specific mark) → check other logic
         //   "law" → "lă" (W produces breve/HORN) → skip priority, let W restore handle it
-        let has_w_in_raw = self.raw_input.iter().any(|(key, _, _)| *key == keys::W);
+        let has_w_in_raw = self.raw_input.iter().any(|r| r.key == keys::W);
         // Check for telex double patterns:
         // 1. Consecutive same vowels (oo, ee, aa) or dd
         // 2. VCV patterns with same vowel (oto→ôt, ata→ât, ete→êt) - delayed circumflex
         let has_telex_double = self.raw_input.windows(2).any(|pair| {
-            let (k1, _, _) = pair[0];
-            let (k2, _, _) = pair[1];
+            let k1 = pair[0].key;
+            let k2 = pair[1].key;
             k1 == k2 && (k1 == keys::O || k1 == keys::E || k1 == keys::A || k1 == keys::D)
         }) || self.raw_input.windows(3).any(|triple| {
-            let (k1, _, _) = triple[0];
-            let (k2, _, _) = triple[1];
-            let (k3, _, _) = triple[2];
+            let k1 = triple[0].key;
+            let k2 = triple[1].key;
+            let k3 = triple[2].key;
             // VCV pattern: same vowel with consonant in between (delayed circumflex)
             k1 == k3 && keys::is_vowel(k1) && !keys::is_vowel(k2)
         });
@@ -4781,7 +6846,7 @@ impl Engine {
                     .raw_input
                     .iter()
                     .skip(subsequent_start)
-                    .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
+                    .filter_map(|r| r.ch)
                     .collect();
                 format!("{}{}", stored.to_lowercase(), subsequent.to_lowercase())
             } else {
@@ -4813,7 +6878,7 @@ impl Engine {
                 let w_at_end = self
                     .raw_input
                     .last()
-                    .map(|(k, _, _)| *k == keys::W)
+                    .map(|r| r.key == keys::W)
                     .unwrap_or(false);
 
                 // Simple logic: buffer invalid VN + raw in English dict → restore
@@ -5044,8 +7109,8 @@ impl Engine {
 
         // If user typed double TONE modifier (rr) at END of SHORT word, keep reverted form
         if self.had_mark_revert && self.raw_input.len() >= 2 && self.raw_input.len() <= 4 {
-            let (last_key, _, _) = self.raw_input[self.raw_input.len() - 1];
-            let (second_last_key, _, _) = self.raw_input[self.raw_input.len() - 2];
+            let last_key = self.raw_input[self.raw_input.len() - 1].key;
+            let second_last_key = self.raw_input[self.raw_input.len() - 2].key;
             // Double 'rr' at end of short word → keep reverted form
             if last_key == second_last_key && last_key == keys::R {
                 return None;
@@ -5068,8 +7133,8 @@ impl Engine {
             // Find position of doubled modifier in raw_input
             let mut doubled_pos = None;
             for i in 0..self.raw_input.len().saturating_sub(1) {
-                let (k1, _, _) = self.raw_input[i];
-                let (k2, _, _) = self.raw_input[i + 1];
+                let k1 = self.raw_input[i].key;
+                let k2 = self.raw_input[i + 1].key;
                 if tone_mods.contains(&k1) && k1 == k2 {
                     doubled_pos = Some(i);
                     break;
@@ -5083,7 +7148,7 @@ impl Engine {
                 // Check if doubled modifier is RIGHT AFTER initial vowel (like i-ss, o-ff)
                 // Pattern: V + doubled_modifier (position 1)
                 let is_after_initial_vowel = pos == 1 && {
-                    let (first_key, _, _) = self.raw_input[0];
+                    let first_key = self.raw_input[0].key;
                     keys::is_vowel(first_key)
                 };
 
@@ -5099,14 +7164,14 @@ impl Engine {
                 let ends_with_e = self
                     .raw_input
                     .last()
-                    .map(|(k, _, _)| *k == keys::E)
+                    .map(|r| r.key == keys::E)
                     .unwrap_or(false);
                 let is_telex_pattern = chars_after == 1 && ends_with_e;
 
                 // Check if 'w' at start was converted to 'ư' (Telex w-vowel)
                 // Words like "worry" start with 'w' in raw but 'ư' in buffer
                 let w_converted_to_horn = !self.raw_input.is_empty() && {
-                    let (first_key, _, _) = self.raw_input[0];
+                    let first_key = self.raw_input[0].key;
                     first_key == keys::W && self.buf.get(0).map(|c| c.key) != Some(keys::W)
                 };
 
@@ -5130,8 +7195,8 @@ impl Engine {
         let has_circumflex_in_buffer = self.buf.iter().any(|c| c.tone == tone::CIRCUMFLEX);
         let has_mark_in_buffer = self.buf.iter().any(|c| c.mark > 0);
         let has_raw_double_vowel = self.raw_input.windows(2).any(|pair| {
-            let (k1, _, _) = pair[0];
-            let (k2, _, _) = pair[1];
+            let k1 = pair[0].key;
+            let k2 = pair[1].key;
             k1 == k2 && matches!(k1, keys::O | keys::A | keys::E)
         });
         if has_circumflex_in_buffer
@@ -5156,8 +7221,8 @@ impl Engine {
         // Only restore when buffer is INVALID Vietnamese
         if is_word_complete && buffer_invalid_vn && raw_input_valid_en {
             let has_ow_in_raw = self.raw_input.windows(2).any(|w| {
-                let (k1, _, _) = w[0];
-                let (k2, _, _) = w[1];
+                let k1 = w[0].key;
+                let k2 = w[1].key;
                 k1 == keys::O && k2 == keys::W
             });
             let has_horn_o_in_buffer = self
@@ -5172,14 +7237,14 @@ impl Engine {
         // W-START CHECK: If raw input starts with 'w', restore in specific cases
         // Vietnamese doesn't have 'w', so words starting with 'w' are likely English
         if is_word_complete && !self.raw_input.is_empty() {
-            let (first_key, _, _) = self.raw_input[0];
+            let first_key = self.raw_input[0].key;
             if first_key == keys::W {
                 // Case 1: English consonant cluster at start (wr, wh) - ALWAYS restore
                 // These are English-only clusters that don't exist in Vietnamese
                 // Examples: wra, wri, wro (wr-), whi, who (wh-)
                 // But NOT: wng, wn, wm (these are w→ư + final consonant = valid Vietnamese)
                 if self.raw_input.len() >= 2 {
-                    let (second_key, _, _) = self.raw_input[1];
+                    let second_key = self.raw_input[1].key;
                     // Only restore for English consonant clusters: wr, wh
                     // (r and h after w form English onset clusters)
                     if second_key == keys::R || second_key == keys::H {
@@ -5219,30 +7284,29 @@ impl Engine {
                 let w_at_end = self
                     .raw_input
                     .last()
-                    .map(|(k, _, _)| *k == keys::W)
+                    .map(|r| r.key == keys::W)
                     .unwrap_or(false);
 
                 // Find W position and check context
-                let w_pos = self.raw_input.iter().rposition(|(k, _, _)| *k == keys::W);
+                let w_pos = self.raw_input.iter().rposition(|r| r.key == keys::W);
 
                 // Check if there are consonants after the last W in raw_input
                 // Pattern: "banwfg" has W at pos 3, then "fg" (consonants) - Vietnamese pattern
                 // Pattern: "law" has W at end - English pattern
                 let has_consonants_after_w = w_pos.is_some_and(|pos| {
-                    self.raw_input[pos + 1..].iter().any(|(k, _, _)| {
-                        keys::is_consonant(*k)
-                            && !matches!(*k, keys::S | keys::F | keys::R | keys::X | keys::J)
-                    })
+                    self.raw_input[pos + 1..]
+                        .iter()
+                        .any(|RawKeystroke { key: k, .. }| {
+                            keys::is_consonant(*k)
+                                && !matches!(*k, keys::S | keys::F | keys::R | keys::X | keys::J)
+                        })
                 });
 
                 // Check if W comes after a vowel (medial position, not initial)
                 // Pattern: "banwfg" has vowel 'a' before W (pos 1) → medial W → Vietnamese
                 // Pattern: "west" has W at pos 0 (initial) → no vowel before → English
-                let has_vowel_before_w = w_pos.is_some_and(|pos| {
-                    self.raw_input[..pos]
-                        .iter()
-                        .any(|(k, _, _)| keys::is_vowel(*k))
-                });
+                let has_vowel_before_w = w_pos
+                    .is_some_and(|pos| self.raw_input[..pos].iter().any(|r| keys::is_vowel(r.key)));
 
                 if buffer_valid_vn
                     && has_vn_specific_mark
@@ -5326,9 +7390,9 @@ impl Engine {
             let tone_modifiers = [keys::S, keys::F, keys::R, keys::X, keys::J];
             let has_same_modifier_doubled_vowel =
                 (0..self.raw_input.len().saturating_sub(2)).any(|i| {
-                    let (key, _, _) = self.raw_input[i];
-                    let (next_key, _, _) = self.raw_input[i + 1];
-                    let (after_key, _, _) = self.raw_input[i + 2];
+                    let key = self.raw_input[i].key;
+                    let next_key = self.raw_input[i + 1].key;
+                    let after_key = self.raw_input[i + 2].key;
                     tone_modifiers.contains(&key)
                         && key == next_key // Same modifier doubled (rr, ss, ff)
                         && keys::is_vowel(after_key)
@@ -5349,7 +7413,7 @@ impl Engine {
             let raw_vowels: Vec<u16> = self
                 .raw_input
                 .iter()
-                .map(|(k, _, _)| *k)
+                .map(|r| r.key)
                 .filter(|k| keys::is_vowel(*k))
                 .collect();
 
@@ -5421,6 +7485,25 @@ impl Engine {
             }
         }
 
+        // Valid Vietnamese whose keystrokes are also plausible English ("her" → "hẻ"):
+        // weigh the English score against how the user last committed them.
+        // Not invalid isn't enough: a buffer that is itself an English word
+        // ("lawwlessness" → "lawlessness") has no Vietnamese reading to weigh.
+        if is_word_complete
+            && !has_stroke
+            && !self.is_buffer_invalid_vietnamese()
+            && self.is_buffer_valid_syllable()
+        {
+            let tie_break = TieBreak {
+                confidence: english::english_confidence(&self.get_raw_input_string()),
+                history: self.word_choices.get(&self.get_raw_input_string()),
+                context: self.previous_languages,
+            };
+            if tie_break.restores() {
+                return self.build_raw_chars();
+            }
+        }
+
         // Buffer is valid Vietnamese AND no English patterns → KEEP
         None
     }
@@ -5444,8 +7527,8 @@ impl Engine {
             return false;
         }
 
-        let (last_key, _, _) = self.raw_input[self.raw_input.len() - 1];
-        let (second_last_key, _, _) = self.raw_input[self.raw_input.len() - 2];
+        let last_key = self.raw_input[self.raw_input.len() - 1].key;
+        let second_last_key = self.raw_input[self.raw_input.len() - 2].key;
 
         // Must be same key pressed twice
         if last_key != second_last_key {
@@ -5524,17 +7607,26 @@ impl Engine {
     fn get_raw_input_string(&self) -> String {
         self.raw_input
             .iter()
-            .filter_map(|&(key, caps, _)| utils::key_to_char(key, caps))
+            .filter_map(|r| r.ch)
             .collect::<String>()
             .to_lowercase()
     }
 
     /// Get raw_input as ASCII string preserving original case
     fn get_raw_input_string_preserve_case(&self) -> String {
-        self.raw_input
-            .iter()
-            .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
-            .collect()
+        self.raw_input.iter().filter_map(|r| r.ch).collect()
+    }
+
+    /// Check if buffer is a structurally valid Vietnamese syllable, without
+    /// the dictionary shortcuts of `is_buffer_invalid_vietnamese`
+    fn is_buffer_valid_syllable(&self) -> bool {
+        let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+        let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
+        is_valid_with_tones_and_profile(
+            &buffer_keys,
+            &buffer_tones,
+            self.active_validation_profile(),
+        )
     }
 
     /// Check if buffer is NOT valid Vietnamese (for unified auto-restore logic)
@@ -5551,7 +7643,7 @@ impl Engine {
         // If word is in Vietnamese dictionary, it's definitely valid Vietnamese.
         if self.english_auto_restore {
             let buffer_str = self.buf.to_full_string();
-            if dictionary::is_vietnamese(&buffer_str, self.allow_foreign_consonants) {
+            if dictionary::is_vietnamese(&buffer_str, self.allow_foreign_consonants()) {
                 return false; // Valid VN word in dictionary
             }
 
@@ -5565,8 +7657,8 @@ impl Engine {
             // Words like "choose" (oo), "see" (ee), "add" (dd) should restore.
             let raw_str = self.get_raw_input_string();
             let has_telex_double = self.raw_input.windows(2).any(|pair| {
-                let (k1, _, _) = pair[0];
-                let (k2, _, _) = pair[1];
+                let k1 = pair[0].key;
+                let k2 = pair[1].key;
                 k1 == k2
                     && matches!(
                         k1,
@@ -5602,10 +7694,10 @@ impl Engine {
         let buffer_marks: Vec<u8> = self.buf.iter().map(|c| c.mark).collect();
 
         // Check 1: Basic structural validation (with foreign consonants support)
-        if !is_valid_with_tones_and_foreign(
+        if !is_valid_with_tones_and_profile(
             &buffer_keys,
             &buffer_tones,
-            self.allow_foreign_consonants,
+            self.active_validation_profile(),
         ) {
             return true;
         }
@@ -5625,6 +7717,19 @@ impl Engine {
             }
         }
 
+        // Check 2b: Tone × coda matrix - a final typed after the mark can still
+        // leave a checked syllable with huyền/hỏi/ngã ("bef" + 't' → "bèt")
+        {
+            let syllable = syllable::parse(&buffer_keys);
+            let final_keys: Vec<u16> = syllable.final_c.iter().map(|&i| buffer_keys[i]).collect();
+            if buffer_marks
+                .iter()
+                .any(|&m| !constants::is_tone_allowed(&final_keys, m))
+            {
+                return true;
+            }
+        }
+
         // Check 3: Single vowel validation
         // ALL single vowels with tone marks are valid Vietnamese words
         // Vietnamese-first logic: valid VN → keep VN
@@ -5840,7 +7945,7 @@ impl Engine {
         }
 
         // All keys must be ASCII letters (A-Z)
-        let all_ascii_letters = self.raw_input.iter().all(|(k, _, _)| {
+        let all_ascii_letters = self.raw_input.iter().all(|RawKeystroke { key: k, .. }| {
             // Keys are in range A-Z (from keys.rs)
             // Consonants and vowels are valid English letters
             keys::is_consonant(*k) || keys::is_vowel(*k)
@@ -5852,7 +7957,7 @@ impl Engine {
 
         // Check raw_input is structurally valid (can be parsed as English word)
         // Simplified check: must have at least one vowel (except for short abbreviations)
-        let has_vowel = self.raw_input.iter().any(|(k, _, _)| keys::is_vowel(*k));
+        let has_vowel = self.raw_input.iter().any(|r| keys::is_vowel(r.key));
 
         // Short words (1-2 chars) without vowels might be abbreviations
         if self.raw_input.len() <= 2 {
@@ -5882,18 +7987,15 @@ impl Engine {
                 // raw_input has been shortened further (e.g. backspace) after
                 // telex_double_raw was stored.
                 let subsequent_start = subsequent_start.min(self.raw_input.len());
-                for &(key, caps, shift) in &self.raw_input[subsequent_start..] {
-                    if let Some(ch) = utils::key_to_char_ext(key, caps, shift) {
-                        result.push(ch);
-                    }
-                }
+                result.extend(
+                    self.raw_input[subsequent_start..]
+                        .iter()
+                        .filter_map(|r| r.ch),
+                );
                 return result;
             }
         }
-        self.raw_input
-            .iter()
-            .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
-            .collect()
+        self.raw_input.iter().filter_map(|r| r.ch).collect()
     }
 
     /// Build raw chars from raw_input EXACTLY as typed (no collapsing).
@@ -6254,10 +8356,8 @@ impl Engine {
         // Optimization: If raw_chars equals current buffer, no restore needed
         // This happens when user manually reverted (e.g., "usser" → "user")
         // Avoids unnecessary backspace + retype of the same content
-        // NOTE: Use to_full_string() to include diacritics for proper comparison
-        let buffer_str: String = self.buf.to_full_string();
-        let raw_str: String = raw_chars.iter().collect();
-        if buffer_str == raw_str {
+        // NOTE: Compare displayed chars to include diacritics
+        if self.buf.eq_chars(&raw_chars) {
             return None;
         }
 
@@ -6309,8 +8409,8 @@ impl Engine {
         // - "masson" (ss at pos 2-3, then on) → use buffer "mason" (open diphthong case)
         let raw_len = self.raw_input.len();
         for i in 0..raw_len.saturating_sub(1) {
-            let (k1, _, _) = self.raw_input[i];
-            let (k2, _, _) = self.raw_input[i + 1];
+            let k1 = self.raw_input[i].key;
+            let k2 = self.raw_input[i + 1].key;
             if (k1 == keys::S && k2 == keys::S) || (k1 == keys::F && k2 == keys::F) {
                 // Found double at position i, i+1
                 // Check how many chars follow after the double
@@ -6373,15 +8473,11 @@ impl Engine {
                 if buf_str.ends_with(suffix) {
                     // Only check for double 's' at position 1,2 (0-indexed)
                     // Pattern: V-SS-V-C like "usser" → "user"
-                    let (key_1, _, _) = self.raw_input[1];
-                    let (key_2, _, _) = self.raw_input[2];
+                    let key_1 = self.raw_input[1].key;
+                    let key_2 = self.raw_input[2].key;
                     if key_1 == keys::S && key_2 == keys::S {
                         // Check 's' appears exactly twice
-                        let s_count = self
-                            .raw_input
-                            .iter()
-                            .filter(|(k, _, _)| *k == keys::S)
-                            .count();
+                        let s_count = self.raw_input.iter().filter(|r| r.key == keys::S).count();
                         if s_count == 2 {
                             return true;
                         }
@@ -6397,9 +8493,9 @@ impl Engine {
         // - Double 's'/'r' + vowel has many valid words (worry, sorry, carry, etc.)
         if self.raw_input.len() >= 4 {
             let len = self.raw_input.len();
-            let (last_key, _, _) = self.raw_input[len - 1];
-            let (second_last_key, _, _) = self.raw_input[len - 2];
-            let (third_last_key, _, _) = self.raw_input[len - 3];
+            let last_key = self.raw_input[len - 1].key;
+            let second_last_key = self.raw_input[len - 2].key;
+            let third_last_key = self.raw_input[len - 3].key;
 
             // Only for double 'f' + single vowel at end
             if keys::is_vowel(last_key) && second_last_key == keys::F && third_last_key == keys::F {
@@ -6483,15 +8579,15 @@ impl Engine {
             }
 
             // Constraint 2: Raw must end with consonant
-            let (last_key, _, _) = self.raw_input[self.raw_input.len() - 1];
+            let last_key = self.raw_input[self.raw_input.len() - 1].key;
             if !keys::is_consonant(last_key) {
                 return false;
             }
 
             // Constraint 6: Exclude common English suffixes after double consonant
             // Get last 2 key codes
-            let (last_key_1, _, _) = self.raw_input[self.raw_input.len() - 1];
-            let (last_key_2, _, _) = self.raw_input[self.raw_input.len() - 2];
+            let last_key_1 = self.raw_input[self.raw_input.len() - 1].key;
+            let last_key_2 = self.raw_input[self.raw_input.len() - 2].key;
 
             // Common English suffixes that appear after double consonants:
             // - "ow" (borrow, sorrow), "or" (error, mirror), "ry" (carry, sorry, worry)
@@ -6513,8 +8609,8 @@ impl Engine {
 
             // Find double modifier with exactly 2 chars after (V+C or C+C pattern)
             for i in 0..self.raw_input.len().saturating_sub(2) {
-                let (key_i, _, _) = self.raw_input[i];
-                let (key_next, _, _) = self.raw_input[i + 1];
+                let key_i = self.raw_input[i].key;
+                let key_next = self.raw_input[i + 1].key;
 
                 if RARE_DOUBLE_MODIFIERS.contains(&key_i) && key_i == key_next {
                     // Double modifier found at position i, i+1
@@ -6524,11 +8620,8 @@ impl Engine {
                     // This excludes longer suffixes like "ent" (current), "ect" (effect)
                     if chars_after_double == 2 {
                         // Count total occurrences of this modifier
-                        let occurrence_count = self
-                            .raw_input
-                            .iter()
-                            .filter(|(k, _, _)| *k == key_i)
-                            .count();
+                        let occurrence_count =
+                            self.raw_input.iter().filter(|r| r.key == key_i).count();
 
                         // Constraint 4: Only 2 occurrences
                         if occurrence_count == 2 {
@@ -6549,18 +8642,14 @@ impl Engine {
         // If buffer is invalid (like "gues" ending with 's'), use raw input "guess" instead.
         if self.raw_input.len() >= 4 && buf_str.len() >= 4 && buf_str.len() <= 6 {
             let len = self.raw_input.len();
-            let (last_key, _, _) = self.raw_input[len - 1];
-            let (second_last_key, _, _) = self.raw_input[len - 2];
+            let last_key = self.raw_input[len - 1].key;
+            let second_last_key = self.raw_input[len - 2].key;
 
             // Check for double modifier at end (ss, rr, ff, xx, jj)
             let tone_modifiers = [keys::S, keys::F, keys::R, keys::X, keys::J];
             if tone_modifiers.contains(&last_key) && last_key == second_last_key {
                 // Count occurrences of this modifier key in raw_input
-                let occurrence_count = self
-                    .raw_input
-                    .iter()
-                    .filter(|(k, _, _)| *k == last_key)
-                    .count();
+                let occurrence_count = self.raw_input.iter().filter(|r| r.key == last_key).count();
                 // Only apply if the double at end is the only occurrence (exactly 2)
                 if occurrence_count == 2 {
                     // Buffer should end with that consonant (after revert)
@@ -6604,17 +8693,14 @@ impl Engine {
         // BUT: if raw_input is in English dictionary (like "cursor"), restore to English
         if is_word_complete && !self.buf.is_empty() {
             // Find position of first vowel
-            let first_vowel_pos = self
-                .raw_input
-                .iter()
-                .position(|(k, _, _)| keys::is_vowel(*k));
+            let first_vowel_pos = self.raw_input.iter().position(|r| keys::is_vowel(r.key));
 
             if let Some(vowel_pos) = first_vowel_pos {
                 // Check for pattern: vowel + mod1 + mod2 + vowel at positions vowel_pos..vowel_pos+4
                 if vowel_pos + 3 < self.raw_input.len() {
-                    let (k1, _, _) = self.raw_input[vowel_pos + 1];
-                    let (k2, _, _) = self.raw_input[vowel_pos + 2];
-                    let (k3, _, _) = self.raw_input[vowel_pos + 3];
+                    let k1 = self.raw_input[vowel_pos + 1].key;
+                    let k2 = self.raw_input[vowel_pos + 2].key;
+                    let k3 = self.raw_input[vowel_pos + 3].key;
 
                     let has_tone_override = tone_modifiers.contains(&k1)
                         && tone_modifiers.contains(&k2)
@@ -6623,11 +8709,7 @@ impl Engine {
 
                     if has_tone_override {
                         // Check if raw is in English dictionary
-                        let raw_str: String = self
-                            .raw_input
-                            .iter()
-                            .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
-                            .collect();
+                        let raw_str: String = self.raw_input.iter().filter_map(|r| r.ch).collect();
                         let raw_in_dict = english_dict::is_english_word(&raw_str);
 
                         // If raw is NOT in English dict AND buffer is valid Vietnamese, keep it
@@ -6644,9 +8726,9 @@ impl Engine {
         // Examples: "ira" → "ỉa", "ofa" → "òa", "ore" → "ỏe", "iru" → "ỉu"
         // Only match VALID Vietnamese diphthongs (not all vowel pairs are valid)
         if self.raw_input.len() == 3 {
-            let (k0, _, _) = self.raw_input[0];
-            let (k1, _, _) = self.raw_input[1];
-            let (k2, _, _) = self.raw_input[2];
+            let k0 = self.raw_input[0].key;
+            let k1 = self.raw_input[1].key;
+            let k2 = self.raw_input[2].key;
             if keys::is_vowel(k0) && tone_modifiers.contains(&k1) && keys::is_vowel(k2) {
                 // Check if this vowel pair is a valid Vietnamese diphthong
                 // Valid: ia, iu, ua, uo, oa, oe, oi, ai, ao, au, ay, ei, eo, eu
@@ -6679,11 +8761,11 @@ impl Engine {
         // Examples: "as" → "á", "es" → "é", "is" → "í", "or" → "ỏ", "us" → "ú"
         // Vietnamese-first logic: valid VN → keep VN (don't check if raw looks English)
         if self.raw_input.len() >= 2 {
-            let (first, _, _) = self.raw_input[0];
+            let first = self.raw_input[0].key;
             if keys::is_vowel(first) && first != keys::W {
                 let all_after_are_modifiers = self.raw_input[1..]
                     .iter()
-                    .all(|(k, _, _)| tone_modifiers.contains(k));
+                    .all(|r| tone_modifiers.contains(&r.key));
                 if all_after_are_modifiers {
                     // Vowel + mark modifiers only → valid Vietnamese, not English
                     return false;
@@ -6695,10 +8777,10 @@ impl Engine {
         // Words like "wow", "window", "water" start with W
         // Exception: standalone "w" → "ư" is valid Vietnamese
         if self.raw_input.len() >= 2 {
-            let (first, _, _) = self.raw_input[0];
+            let first = self.raw_input[0].key;
             if first == keys::W {
                 // Check if there's another W later (non-adjacent) → English pattern like "wow"
-                let has_later_w = self.raw_input[2..].iter().any(|(k, _, _)| *k == keys::W);
+                let has_later_w = self.raw_input[2..].iter().any(|r| r.key == keys::W);
                 if has_later_w {
                     return true;
                 }
@@ -6712,7 +8794,7 @@ impl Engine {
                 // This handles standalone W with tone marks like "wf " → "ừ "
                 let all_are_modifiers = self.raw_input[1..]
                     .iter()
-                    .all(|(k, _, _)| tone_modifiers.contains(k));
+                    .all(|r| tone_modifiers.contains(&r.key));
                 if all_are_modifiers && !self.raw_input[1..].is_empty() {
                     // W + mark modifiers only → valid Vietnamese, not English
                     return false;
@@ -6726,21 +8808,21 @@ impl Engine {
                     // First check if there are any other vowels after W
                     let has_other_vowels = self.raw_input[1..]
                         .iter()
-                        .any(|(k, _, _)| keys::is_vowel(*k) && *k != keys::W);
+                        .any(|r| keys::is_vowel(r.key) && r.key != keys::W);
 
                     // Only apply W+consonant+mark pattern if there are NO other vowels
                     if !has_other_vowels {
                         let non_modifier_consonants: Vec<u16> = self.raw_input[1..]
                             .iter()
-                            .filter(|(k, _, _)| {
+                            .filter(|RawKeystroke { key: k, .. }| {
                                 keys::is_consonant(*k) && !tone_modifiers.contains(k)
                             })
-                            .map(|(k, _, _)| *k)
+                            .map(|r| r.key)
                             .collect();
 
                         let has_mark_modifier = self.raw_input[1..]
                             .iter()
-                            .any(|(k, _, _)| tone_modifiers.contains(k));
+                            .any(|r| tone_modifiers.contains(&r.key));
 
                         // W + valid_final + mark → valid Vietnamese (ừm, ứng, etc.)
                         if !non_modifier_consonants.is_empty() && has_mark_modifier {
@@ -6748,11 +8830,10 @@ impl Engine {
                                 1 => {
                                     constants::VALID_FINALS_1.contains(&non_modifier_consonants[0])
                                 }
-                                2 => {
-                                    let pair =
-                                        [non_modifier_consonants[0], non_modifier_consonants[1]];
-                                    constants::VALID_FINALS_2.contains(&pair)
-                                }
+                                2 => constants::FINAL_PAIRS.contains(
+                                    non_modifier_consonants[0],
+                                    non_modifier_consonants[1],
+                                ),
                                 _ => false,
                             };
                             if is_valid_final {
@@ -6766,12 +8847,12 @@ impl Engine {
                 // Find position of first vowel to distinguish consonants from modifiers
                 let first_vowel_pos = self.raw_input[1..]
                     .iter()
-                    .position(|(k, _, _)| keys::is_vowel(*k) && *k != keys::W);
+                    .position(|r| keys::is_vowel(r.key) && r.key != keys::W);
 
                 let vowels_after: Vec<u16> = self.raw_input[1..]
                     .iter()
-                    .filter(|(k, _, _)| keys::is_vowel(*k) && *k != keys::W)
-                    .map(|(k, _, _)| *k)
+                    .filter(|r| keys::is_vowel(r.key) && r.key != keys::W)
+                    .map(|r| r.key)
                     .collect();
 
                 // Only exclude Telex mark modifiers (s, f, r, x, j) when they come AFTER a vowel
@@ -6780,7 +8861,7 @@ impl Engine {
                 let consonants_after: Vec<u16> = self.raw_input[1..]
                     .iter()
                     .enumerate()
-                    .filter(|(i, (k, _, _))| {
+                    .filter(|(i, RawKeystroke { key: k, .. })| {
                         if !keys::is_consonant(*k) || *k == keys::W {
                             return false;
                         }
@@ -6798,7 +8879,7 @@ impl Engine {
                         }
                         true
                     })
-                    .map(|(_, (k, _, _))| *k)
+                    .map(|(_, r)| r.key)
                     .collect();
 
                 // W + vowel + consonant → likely English like "win", "water"
@@ -6850,10 +8931,8 @@ impl Engine {
                 if !consonants_after.is_empty() && vowels_after.is_empty() {
                     let is_valid_final = match consonants_after.len() {
                         1 => constants::VALID_FINALS_1.contains(&consonants_after[0]),
-                        2 => {
-                            let pair = [consonants_after[0], consonants_after[1]];
-                            constants::VALID_FINALS_2.contains(&pair)
-                        }
+                        2 => constants::FINAL_PAIRS
+                            .contains(consonants_after[0], consonants_after[1]),
                         _ => false, // 3+ consonants is invalid
                     };
 
@@ -6869,18 +8948,18 @@ impl Engine {
             // - "nwoc" during typing → might become "nwocj" → "nược" (Vietnamese)
             // - "swim" on space → no tone modifiers → restore to English
             if is_word_complete {
-                let (second, _, _) = self.raw_input[1];
+                let second = self.raw_input[1].key;
                 if second == keys::W && keys::is_consonant(first) && first != keys::Q {
                     // Q+W is valid Vietnamese (qu-), but other consonant+W may be English
                     if self.raw_input.len() >= 3 {
-                        let (third, _, _) = self.raw_input[2];
+                        let third = self.raw_input[2].key;
                         // Check if third char is a vowel (not a tone modifier like j)
                         if keys::is_vowel(third) {
                             // Exception: C+W+O+NG pattern is Vietnamese "ương" (tương, sương, etc.)
                             // Pattern: consonant + W + O + N + G → valid Vietnamese diphthong
                             if third == keys::O && self.raw_input.len() >= 5 {
-                                let (fourth, _, _) = self.raw_input[3];
-                                let (fifth, _, _) = self.raw_input[4];
+                                let fourth = self.raw_input[3].key;
+                                let fifth = self.raw_input[4].key;
                                 if fourth == keys::N && fifth == keys::G {
                                     // This is Vietnamese "ương" pattern, don't restore
                                     return false;
@@ -6906,7 +8985,7 @@ impl Engine {
                             let tone_modifiers = [keys::S, keys::F, keys::R, keys::X, keys::J];
                             let has_tone_modifier = self.raw_input[2..]
                                 .iter()
-                                .any(|(k, _, _)| tone_modifiers.contains(k));
+                                .any(|r| tone_modifiers.contains(&r.key));
 
                             // No tone modifier + consonant+W+vowel → likely English like "swim"
                             if !has_tone_modifier {
@@ -6926,9 +9005,9 @@ impl Engine {
         // Counter-example: "đướng" typed as dduowfsng → "fs" followed by consonant 'n' → Vietnamese
         // Vietnamese allows consecutive modifiers for tone adjustment (f→s changes huyền to sắc)
         for i in 0..self.raw_input.len().saturating_sub(2) {
-            let (key, _, _) = self.raw_input[i];
-            let (next_key, _, _) = self.raw_input[i + 1];
-            let (after_key, _, _) = self.raw_input[i + 2];
+            let key = self.raw_input[i].key;
+            let next_key = self.raw_input[i + 1].key;
+            let after_key = self.raw_input[i + 2].key;
             // Two DIFFERENT consecutive modifiers followed by vowel → English
             // Example: "cursor" = c-u-r-s-o-r → "rs" (r≠s) followed by vowel 'o' → English
             // Same modifier doubled (rr, ss, ff) is Telex revert pattern, NOT English
@@ -6944,7 +9023,7 @@ impl Engine {
 
         // Find positions of modifiers in raw_input
         for i in 0..self.raw_input.len() {
-            let (key, _, _) = self.raw_input[i];
+            let key = self.raw_input[i].key;
 
             if !tone_modifiers.contains(&key) {
                 continue;
@@ -6957,7 +9036,7 @@ impl Engine {
             // Counter-example: "muwowjt" has J followed by T (Vietnamese - multiple vowels)
             // Counter-example: "dojdc" = D+O+J+D+C (Vietnamese "đọc" - j + consonants is valid)
             if i + 1 < self.raw_input.len() {
-                let (next_key, _, _) = self.raw_input[i + 1];
+                let next_key = self.raw_input[i + 1].key;
                 // W is a vowel modifier in Telex, not a true consonant for this check
                 // Also exclude tone modifier keys (S, F, R, X, J) - these are mark keys, not consonants
                 // when they appear after a vowel. Example: "dduowfs" has 'f' then 's', both are modifiers.
@@ -6990,8 +9069,8 @@ impl Engine {
                     // Sonorants: M, N, or G/H when following N (part of ng, nh finals)
                     let is_sonorant_or_part_of_final = next_key == keys::M
                         || next_key == keys::N
-                        || (next_key == keys::G && i >= 1 && self.raw_input[i - 1].0 == keys::N)
-                        || (next_key == keys::H && i >= 1 && self.raw_input[i - 1].0 == keys::N);
+                        || (next_key == keys::G && i >= 1 && self.raw_input[i - 1].key == keys::N)
+                        || (next_key == keys::H && i >= 1 && self.raw_input[i - 1].key == keys::N);
 
                     // Always skip for J and S - these are very common in Vietnamese
                     if is_common_viet_mark {
@@ -7009,11 +9088,7 @@ impl Engine {
                     // Example: "daafdm" = d + aa + f + D + m → "đầm"
                     // The two D's form the stroke pattern for đ
                     if next_key == keys::D {
-                        let d_count = self
-                            .raw_input
-                            .iter()
-                            .filter(|(k, _, _)| *k == keys::D)
-                            .count();
+                        let d_count = self.raw_input.iter().filter(|r| r.key == keys::D).count();
                         if d_count >= 2 {
                             continue; // Vietnamese stroke pattern
                         }
@@ -7028,7 +9103,7 @@ impl Engine {
                     // Case 1b: Final consonant but only 1 vowel before modifier → likely English
                     // Example: "text" = T+E+X+T (only 1 vowel E before X)
                     let vowels_before: usize = (0..i)
-                        .filter(|&j| keys::is_vowel(self.raw_input[j].0))
+                        .filter(|&j| keys::is_vowel(self.raw_input[j].key))
                         .count();
                     if vowels_before == 1 {
                         return true;
@@ -7043,14 +9118,14 @@ impl Engine {
             // Counter-example: "chiuj" → c-h-i-u-j, "iu" → valid Vietnamese diphthong
             // Counter-example: "hoaij" → h-o-a-i-j, "oai" (3 vowels) → valid Vietnamese
             if i + 1 == self.raw_input.len() && i >= 2 {
-                let (v1, _, _) = self.raw_input[i - 2];
-                let (v2, _, _) = self.raw_input[i - 1];
+                let v1 = self.raw_input[i - 2].key;
+                let v2 = self.raw_input[i - 1].key;
                 // Check for suspicious English vowel patterns before modifier
                 // Same vowel doubling (oo, aa, ee) is Telex pattern, not suspicious
                 if keys::is_vowel(v1) && keys::is_vowel(v2) && v1 != v2 {
                     // Count total vowels before modifier
                     let total_vowels: usize = (0..i)
-                        .filter(|&j| keys::is_vowel(self.raw_input[j].0))
+                        .filter(|&j| keys::is_vowel(self.raw_input[j].key))
                         .count();
 
                     // EI before modifier is very English (their, weird, vein)
@@ -7063,9 +9138,10 @@ impl Engine {
                     // This catches "pair" but not "mái", "cái", "xài" (common Vietnamese)
                     if v1 == keys::A && v2 == keys::I && total_vowels == 2 {
                         // Check if initial is just P (rare in native Vietnamese)
-                        if !self.raw_input.is_empty() && self.raw_input[0].0 == keys::P {
+                        if !self.raw_input.is_empty() && self.raw_input[0].key == keys::P {
                             // Make sure it's not PH (PH is common Vietnamese)
-                            let is_ph = self.raw_input.len() >= 2 && self.raw_input[1].0 == keys::H;
+                            let is_ph =
+                                self.raw_input.len() >= 2 && self.raw_input[1].key == keys::H;
                             if !is_ph {
                                 return true;
                             }
@@ -7084,8 +9160,8 @@ impl Engine {
                         // Check for Vietnamese-specific initials (both digraphs and single consonants)
                         // Vietnamese OE words from dictionary: hòe, loè, tóe, xòe, khoẻ, ngoé...
                         let is_vietnamese_oe_initial = if self.raw_input.len() >= 2 {
-                            let (c1, _, _) = self.raw_input[0];
-                            let (c2, _, _) = self.raw_input[1];
+                            let c1 = self.raw_input[0].key;
+                            let c2 = self.raw_input[1].key;
 
                             // Vietnamese digraphs: kh, gh, ph, ch, th, nh (end with H), tr, ng
                             let ends_with_h = c2 == keys::H
@@ -7122,7 +9198,7 @@ impl Engine {
                         // Only return true if there's an initial consonant (goes, does, foes, woes)
                         // Words without initial like "oes" → "oé" should stay Vietnamese
                         let has_initial =
-                            !self.raw_input.is_empty() && keys::is_consonant(self.raw_input[0].0);
+                            !self.raw_input.is_empty() && keys::is_consonant(self.raw_input[0].key);
                         if has_initial {
                             return true;
                         }
@@ -7132,12 +9208,12 @@ impl Engine {
                 // Pattern 2b: P + single vowel + modifier at end → English
                 // P alone (not PH) is rare in native Vietnamese
                 // Example: "per" = P + E + R → pẻ (but "per" is English preposition)
-                if self.raw_input.len() >= 2 && self.raw_input[0].0 == keys::P {
-                    let is_ph = self.raw_input.len() >= 2 && self.raw_input[1].0 == keys::H;
+                if self.raw_input.len() >= 2 && self.raw_input[0].key == keys::P {
+                    let is_ph = self.raw_input.len() >= 2 && self.raw_input[1].key == keys::H;
                     if !is_ph {
                         // Count vowels before modifier
                         let vowels_before: usize = (0..i)
-                            .filter(|&j| keys::is_vowel(self.raw_input[j].0))
+                            .filter(|&j| keys::is_vowel(self.raw_input[j].key))
                             .count();
                         // P + single vowel + modifier at end (no more chars after modifier)
                         if vowels_before == 1 && i + 1 == self.raw_input.len() {
@@ -7152,20 +9228,20 @@ impl Engine {
             // Example: "use" → U (vowel) + S (modifier) + E (vowel) = starts with vowel → English
             // Counter-example: "cura" → C + U + R + A = starts with consonant → Vietnamese "của"
             let vowels_before: usize = (0..i)
-                .filter(|&j| keys::is_vowel(self.raw_input[j].0))
+                .filter(|&j| keys::is_vowel(self.raw_input[j].key))
                 .count();
 
             // If only 1 vowel before modifier AND vowel after AND no initial consonant → English
             if vowels_before == 1 && i + 1 < self.raw_input.len() {
-                let (next_key, _, _) = self.raw_input[i + 1];
+                let next_key = self.raw_input[i + 1].key;
                 if keys::is_vowel(next_key) {
                     // Find first vowel position
                     let first_vowel_pos = (0..i)
-                        .find(|&j| keys::is_vowel(self.raw_input[j].0))
+                        .find(|&j| keys::is_vowel(self.raw_input[j].key))
                         .unwrap_or(0);
                     // Check if there's a consonant before the first vowel
                     let has_initial_consonant = first_vowel_pos > 0
-                        && keys::is_consonant(self.raw_input[first_vowel_pos - 1].0);
+                        && keys::is_consonant(self.raw_input[first_vowel_pos - 1].key);
                     // Only restore if NO initial consonant (pure vowel-start like "use")
                     // EXCEPT: Vietnamese diphthongs without initial consonant
                     // U + modifier + A: ủa, ùa, úa, ũa, ụa (interjections)
@@ -7176,9 +9252,9 @@ impl Engine {
                     // This distinguishes intentional Vietnamese (vowel-consonant-modifier-vowel)
                     // from accidental English (vowel-modifier-vowel without consonant)
                     let has_consonant_between = (first_vowel_pos + 1 < i)
-                        && keys::is_consonant(self.raw_input[first_vowel_pos + 1].0);
+                        && keys::is_consonant(self.raw_input[first_vowel_pos + 1].key);
                     if !has_initial_consonant && !has_consonant_between {
-                        let first_vowel = self.raw_input[first_vowel_pos].0;
+                        let first_vowel = self.raw_input[first_vowel_pos].key;
                         // Vietnamese no-initial patterns:
                         // - Same vowel doubling: OFO → ồ, EFE → ề, AFA → ầ (circumflex + tone)
                         // - U + modifier + A: ủa, ùa, úa (interjections)
@@ -7200,11 +9276,8 @@ impl Engine {
                         // "ore" is common English word, should restore
                         // "oer", "oje" are not English, keep Vietnamese (oẻ, oẹ)
                         if first_vowel == keys::O && next_key == keys::E {
-                            let raw_str: String = self
-                                .raw_input
-                                .iter()
-                                .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
-                                .collect();
+                            let raw_str: String =
+                                self.raw_input.iter().filter_map(|r| r.ch).collect();
                             if english_dict::is_english_word(&raw_str) {
                                 return true; // Restore to English
                             }
@@ -7227,7 +9300,7 @@ impl Engine {
                     // Example: "gasi" = g + a + s + i → a+s+i IS Vietnamese (gái)
                     // Example: "nafo" = n + a + f + o → a+f+o IS Vietnamese (nào)
                     if has_initial_consonant {
-                        let (prev_char, _, _) = self.raw_input[i - 1];
+                        let prev_char = self.raw_input[i - 1].key;
                         // Skip if prev char is not a vowel (e.g., "ddense" has n before s)
                         // Pattern requires vowel + modifier + vowel
                         if !keys::is_vowel(prev_char) {
@@ -7242,7 +9315,7 @@ impl Engine {
                         if prev_vowel == next_key {
                             // Check if there are more chars after the second vowel
                             if i + 2 < self.raw_input.len() {
-                                let (char_after, _, _) = self.raw_input[i + 2];
+                                let char_after = self.raw_input[i + 2].key;
                                 // Only English if followed by CONSONANT (param has 'm')
                                 // If followed by vowel (loxoi has 'i'), it's Vietnamese diphthong
                                 //
@@ -7269,7 +9342,7 @@ impl Engine {
                                         let d_count = self
                                             .raw_input
                                             .iter()
-                                            .filter(|(k, _, _)| *k == keys::D)
+                                            .filter(|r| r.key == keys::D)
                                             .count();
                                         if d_count >= 2 {
                                             continue; // Vietnamese stroke pattern
@@ -7286,13 +9359,8 @@ impl Engine {
                                     if is_circumflex_vowel && is_valid_final {
                                         // Could be Vietnamese delayed circumflex (vận, hận)
                                         // But if raw input is in English dict → restore to English
-                                        let raw_str: String = self
-                                            .raw_input
-                                            .iter()
-                                            .filter_map(|&(k, c, s)| {
-                                                utils::key_to_char_ext(k, c, s)
-                                            })
-                                            .collect();
+                                        let raw_str: String =
+                                            self.raw_input.iter().filter_map(|r| r.ch).collect();
                                         if english_dict::is_english_word(&raw_str) {
                                             return true; // English word (param, etc.)
                                         }
@@ -7348,8 +9416,8 @@ impl Engine {
                                 // Check for Vietnamese digraph initials (CH, KH, GH, TH, PH, NH, NG, TR)
                                 // These are Vietnamese-specific, so keep Vietnamese for OE words
                                 let has_vn_digraph = if self.raw_input.len() >= 2 {
-                                    let (c1, _, _) = self.raw_input[0];
-                                    let (c2, _, _) = self.raw_input[1];
+                                    let c1 = self.raw_input[0].key;
+                                    let c2 = self.raw_input[1].key;
                                     // Digraphs ending with H: CH, KH, GH, TH, PH, NH
                                     let ends_with_h = c2 == keys::H
                                         && matches!(
@@ -7375,11 +9443,8 @@ impl Engine {
                                 }
 
                                 // For single initial + OE: only restore if raw is English word
-                                let raw_str: String = self
-                                    .raw_input
-                                    .iter()
-                                    .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
-                                    .collect();
+                                let raw_str: String =
+                                    self.raw_input.iter().filter_map(|r| r.ch).collect();
                                 if !english_dict::is_english_word(&raw_str) {
                                     // Not a common English word, keep Vietnamese
                                     continue;
@@ -7394,8 +9459,8 @@ impl Engine {
                             if prev_vowel == keys::U && next_key == keys::E {
                                 // Check for Vietnamese digraph initials (QU is special for UE)
                                 let has_vn_digraph = if self.raw_input.len() >= 2 {
-                                    let (c1, _, _) = self.raw_input[0];
-                                    let (c2, _, _) = self.raw_input[1];
+                                    let c1 = self.raw_input[0].key;
+                                    let c2 = self.raw_input[1].key;
                                     // QU + E pattern: quế, qué (valid Vietnamese)
                                     let is_qu = c1 == keys::Q && c2 == keys::U;
                                     // Digraphs ending with H: CH, KH, GH, TH, PH, NH
@@ -7423,11 +9488,8 @@ impl Engine {
                                 }
 
                                 // For single initial + UE: only restore if raw is English word
-                                let raw_str: String = self
-                                    .raw_input
-                                    .iter()
-                                    .filter_map(|&(k, c, s)| utils::key_to_char_ext(k, c, s))
-                                    .collect();
+                                let raw_str: String =
+                                    self.raw_input.iter().filter_map(|r| r.ch).collect();
                                 if !english_dict::is_english_word(&raw_str) {
                                     // Not a common English word, keep Vietnamese
                                     continue;
@@ -7446,9 +9508,9 @@ impl Engine {
         // Exception: "ow" ending is Vietnamese (cow → cơ)
         // Exception: W modified a diphthong (oiw → ơi where OI is diphthong, W adds horn to O)
         if self.raw_input.len() >= 2 {
-            let (last, _, _) = self.raw_input[self.raw_input.len() - 1];
+            let last = self.raw_input[self.raw_input.len() - 1].key;
             if last == keys::W {
-                let (second_last, _, _) = self.raw_input[self.raw_input.len() - 2];
+                let second_last = self.raw_input[self.raw_input.len() - 2].key;
                 // W after vowel (not U or O) at end is English: raw, law, saw
                 // W after U is Vietnamese: tuw → tư
                 // W after O is Vietnamese: cow → cơ
@@ -7461,7 +9523,7 @@ impl Engine {
                     // Count vowels before W in raw_input
                     let vowel_count = self.raw_input[..self.raw_input.len() - 1]
                         .iter()
-                        .filter(|(k, _, _)| keys::is_vowel(*k))
+                        .filter(|r| keys::is_vowel(r.key))
                         .count();
 
                     // Only skip restore if BOTH conditions are true:
@@ -7481,9 +9543,9 @@ impl Engine {
         // This distinguishes "looks" (English) from "đắk" (Vietnamese)
         if self.raw_input.len() >= 3 {
             for i in 0..self.raw_input.len() - 2 {
-                let (v1, _, _) = self.raw_input[i];
-                let (v2, _, _) = self.raw_input[i + 1];
-                let (next, _, _) = self.raw_input[i + 2];
+                let v1 = self.raw_input[i].key;
+                let v2 = self.raw_input[i + 1].key;
+                let next = self.raw_input[i + 2].key;
 
                 // Check for double vowel (same vowel twice) followed by K
                 if keys::is_vowel(v1) && v1 == v2 && next == keys::K {
@@ -7492,34 +9554,14 @@ impl Engine {
             }
         }
 
-        // Pattern 6a: Double E (ee) followed by P at END → English (keep, deep, sleep, seep)
-        // Only EE+P, not AA+P or OO+P which can be valid Vietnamese (cấp = caaps)
+        // Pattern 6a: Doubled circumflex vowel + final at END → English
+        // (keep, deep, sleep, seep). Pairs and their Vietnamese exceptions
+        // (nghiệp, xếp) live in constants::CIRCUMFLEX_CLOSED_ENGLISH.
         // ONLY check at word boundary - mid-word "kêp" could still become valid Vietnamese
-        // Exceptions:
-        //   - I+EE+P is Vietnamese "iệp" pattern (nghiệp, hiệp, kiệp, v.v.)
-        //   - X+EE+P is Vietnamese "xếp" pattern (xếp = to arrange)
-        if is_word_complete && self.raw_input.len() >= 3 {
-            let len = self.raw_input.len();
-            let (last, _, _) = self.raw_input[len - 1];
-            if last == keys::P {
-                let (v1, _, _) = self.raw_input[len - 3];
-                let (v2, _, _) = self.raw_input[len - 2];
-                // Only match EE (not AA or OO)
-                if v1 == keys::E && v2 == keys::E {
-                    // Exception: I+EE+P or X+EE+P are Vietnamese patterns
-                    // Check if there's an I or X before the double E
-                    if len >= 4 {
-                        let (before_ee, _, _) = self.raw_input[len - 4];
-                        if before_ee == keys::I || before_ee == keys::X {
-                            // This is Vietnamese "iêp" or "xêp" pattern, don't restore
-                            // Continue to check other patterns
-                        } else {
-                            return true;
-                        }
-                    } else {
-                        return true;
-                    }
-                }
+        if is_word_complete {
+            let raw_keys: Vec<u16> = self.raw_input.iter().map(|r| r.key).collect();
+            if is_circumflex_closed_english(&raw_keys) {
+                return true;
             }
         }
 
@@ -7533,14 +9575,14 @@ impl Engine {
         // Counter-example: "seef" = s + ee + f → "sề" (valid Vietnamese word)
         let tone_modifiers = [keys::S, keys::F, keys::R, keys::X, keys::J];
         if self.raw_input.len() >= 4 {
-            let (first, _, _) = self.raw_input[0];
-            let (last, _, _) = self.raw_input[self.raw_input.len() - 1];
+            let first = self.raw_input[0].key;
+            let last = self.raw_input[self.raw_input.len() - 1].key;
             // Only match if initial is S or F (rare alone in Vietnamese)
             // S alone (not SH) and F are English patterns
             if (first == keys::S || first == keys::F) && tone_modifiers.contains(&last) {
                 // Check for double vowel just before the last key
-                let (v1, _, _) = self.raw_input[self.raw_input.len() - 3];
-                let (v2, _, _) = self.raw_input[self.raw_input.len() - 2];
+                let v1 = self.raw_input[self.raw_input.len() - 3].key;
+                let v2 = self.raw_input[self.raw_input.len() - 2].key;
                 if keys::is_vowel(v1) && v1 == v2 {
                     // Exception: S/F + OO/EE + modifier → Vietnamese
                     // - số, sở, sỗ, sổ (number-related words)
@@ -7558,9 +9600,9 @@ impl Engine {
         // "sax" = s + a + x → "sã" but should restore to "sax"
         // "six" = s + i + x → "sĩ" (valid Vietnamese: soldier, scholar)
         if self.raw_input.len() == 3 {
-            let (first, _, _) = self.raw_input[0];
-            let (second, _, _) = self.raw_input[1];
-            let (third, _, _) = self.raw_input[2];
+            let first = self.raw_input[0].key;
+            let second = self.raw_input[1].key;
+            let third = self.raw_input[2].key;
             // Only S + A + X (not other vowels)
             if first == keys::S && second == keys::A && third == keys::X {
                 return true;
@@ -7572,11 +9614,11 @@ impl Engine {
         // Example: "mufaa" = m + u + f + a + a → restore to "mùaa"
         // This pattern detects when someone types like "tattoo" with Vietnamese tone
         if self.raw_input.len() == 5 {
-            let (c0, _, _) = self.raw_input[0];
-            let (c1, _, _) = self.raw_input[1];
-            let (c2, _, _) = self.raw_input[2];
-            let (c3, _, _) = self.raw_input[3];
-            let (c4, _, _) = self.raw_input[4];
+            let c0 = self.raw_input[0].key;
+            let c1 = self.raw_input[1].key;
+            let c2 = self.raw_input[2].key;
+            let c3 = self.raw_input[3].key;
+            let c4 = self.raw_input[4].key;
 
             let is_consonant_0 = keys::is_consonant(c0);
             let is_vowel_1 = keys::is_vowel(c1);
@@ -7595,9 +9637,9 @@ impl Engine {
         // Counter-example: "đắk" = dd + aw + k → "đắk" (breve 'ắ', valid Vietnamese)
         // Counter-example: "Busk" = B + u + s + k → "Búk" (s consumed as sắc, valid Vietnamese)
         if self.raw_input.len() >= 4 {
-            let (last, _, _) = self.raw_input[self.raw_input.len() - 1];
+            let last = self.raw_input[self.raw_input.len() - 1].key;
             if last == keys::K {
-                let (second_last, _, _) = self.raw_input[self.raw_input.len() - 2];
+                let second_last = self.raw_input[self.raw_input.len() - 2].key;
                 let tone_modifiers = [keys::S, keys::F, keys::R, keys::X, keys::J];
                 // Check if second_last is a tone modifier (s, f, r, x, j)
                 if tone_modifiers.contains(&second_last) {
@@ -7613,13 +9655,13 @@ impl Engine {
                     // Check if there's a 'w' in raw_input before the modifier (indicating breve)
                     let has_breve_marker = self.raw_input[..self.raw_input.len() - 2]
                         .iter()
-                        .any(|(k, _, _)| *k == keys::W);
+                        .any(|r| r.key == keys::W);
 
                     // Also check for common English -Vsk patterns where V is i, a, e, o, u
                     // but NOT ethnic minority patterns
                     // The key difference: ethnic minority words are usually short (3-4 letters)
                     // and have specific structures. English -sk words often have more consonants.
-                    let (third_last, _, _) = self.raw_input[self.raw_input.len() - 3];
+                    let third_last = self.raw_input[self.raw_input.len() - 3].key;
                     let is_isk_ask_pattern = keys::is_vowel(third_last)
                         && second_last == keys::S
                         && !has_breve_marker
@@ -7631,7 +9673,7 @@ impl Engine {
                     if is_isk_ask_pattern {
                         // Check if there's a consonant initial before the vowel
                         let has_consonant_before_vowel =
-                            self.raw_input.len() >= 4 && keys::is_consonant(self.raw_input[0].0);
+                            self.raw_input.len() >= 4 && keys::is_consonant(self.raw_input[0].key);
 
                         // For short words (4 chars like "risk", "disk", "task"),
                         // only restore if initial is a common English consonant pattern
@@ -7640,7 +9682,7 @@ impl Engine {
                             // B, L are common in Vietnamese ethnic minority words (Búk, Lắk)
                             // Note: Đắk uses DD (double D) for Đ, not single D
                             // So D initial (disk, desk, dusk) should restore as English
-                            let (first, _, _) = self.raw_input[0];
+                            let first = self.raw_input[0].key;
                             let is_ethnic_initial = first == keys::B || first == keys::L;
 
                             if !is_ethnic_initial {
@@ -7663,10 +9705,10 @@ impl Engine {
         // modifier (sắc), not as English plural suffix. Keep Vietnamese in this case.
         // Words that DON'T get tone mark applied are true English plurals.
         if self.raw_input.len() == 4 {
-            let (c0, _, _) = self.raw_input[0];
-            let (c1, _, _) = self.raw_input[1];
-            let (c2, _, _) = self.raw_input[2];
-            let (c3, _, _) = self.raw_input[3];
+            let c0 = self.raw_input[0].key;
+            let c1 = self.raw_input[1].key;
+            let c2 = self.raw_input[2].key;
+            let c3 = self.raw_input[3].key;
 
             // Pattern: single consonant + i/e + m + s (tone modifier)
             if keys::is_consonant(c0)
@@ -7773,7 +9815,7 @@ impl Engine {
         }
 
         // Track raw input for ESC restore
-        self.raw_input.push((key, caps, false));
+        self.raw_input.push(RawKeystroke::new(key, caps, false));
 
         // Mark transform
         self.last_transform = Some(Transform::BracketAsVowel);
@@ -7795,8 +9837,8 @@ impl Engine {
             // Add space at the end
             raw_chars.push(' ');
             // Backspace count = current buffer length (displayed chars)
-            let backspace = self.buf.len() as u8;
-            Result::send(backspace, &raw_chars)
+            let backspace = backspaces(self.buf.len());
+            Result::restore(backspace, &raw_chars)
         } else {
             Result::none()
         }
@@ -7811,8 +9853,8 @@ impl Engine {
     fn try_auto_restore_on_break(&self) -> Result {
         if let Some(raw_chars) = self.should_auto_restore(true) {
             // Backspace count = current buffer length (displayed chars)
-            let backspace = self.buf.len() as u8;
-            Result::send(backspace, &raw_chars)
+            let backspace = backspaces(self.buf.len());
+            Result::restore(backspace, &raw_chars)
         } else {
             Result::none()
         }
@@ -7829,55 +9871,66 @@ impl Engine {
             return Result::none();
         }
 
-        // Build raw ASCII output from raw_input history
-        // If telex_double_raw is set (revert happened), use it as base and append subsequent chars
-        // This ensures "aww" → ESC → "aww" (not "aw"), "a66" → ESC → "a66" (not "a6")
-        let raw_chars: Vec<char> = if let Some(ref base_raw) = self.telex_double_raw {
-            // Start with the original raw string before revert modification
-            let mut chars: Vec<char> = base_raw.chars().collect();
-            // Append any characters typed after the revert
-            for &(key, caps, shift) in self.raw_input.iter().skip(self.telex_double_raw_len) {
-                if let Some(ch) = utils::key_to_char_ext(key, caps, shift) {
-                    chars.push(ch);
-                }
-            }
-            chars
-        } else {
-            // Normal case: use raw_input directly
-            self.raw_input
-                .iter()
-                .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
-                .collect()
-        };
+        let raw_chars = self.typed_chars();
 
         if raw_chars.is_empty() {
             return Result::none();
         }
 
-        // Get current buffer content for comparison
-        let buffer_str = self.buf.to_full_string();
-        let raw_str: String = raw_chars.iter().collect();
-
         // Only restore if:
         // 1. Any transform was ever applied (even if later reverted), OR
         // 2. Buffer differs from raw input (handles edge cases)
-        if !self.had_any_transform && buffer_str == raw_str {
+        if !self.had_any_transform && self.buf.eq_chars(&raw_chars) {
             return Result::none();
         }
 
         // Backspace count = current buffer length (displayed chars)
-        let backspace = self.buf.len() as u8;
+        let backspace = backspaces(self.buf.len());
 
-        Result::send(backspace, &raw_chars)
+        Result::restore(backspace, &raw_chars)
     }
 
-    /// Restore raw_input from buffer (for ESC restore to work after backspace-restore)
-    fn restore_raw_input_from_buffer(&mut self, buf: &Buffer) {
-        self.raw_input.clear();
-        for c in buf.iter() {
-            self.raw_input.push((c.key, c.caps, false));
+    /// Keystrokes of the current word as typed, including keys a double-key
+    /// revert dropped from raw_input
+    fn typed_chars(&self) -> Vec<char> {
+        // Build raw ASCII output from raw_input history
+        // If telex_double_raw is set (revert happened), use it as base and append subsequent chars
+        // This ensures "aww" → ESC → "aww" (not "aw"), "a66" → ESC → "a66" (not "a6")
+        if let Some(ref base_raw) = self.telex_double_raw {
+            // Start with the original raw string before revert modification
+            let mut chars: Vec<char> = base_raw.chars().collect();
+            // Append any characters typed after the revert
+            chars.extend(
+                self.raw_input
+                    .iter()
+                    .skip(self.telex_double_raw_len)
+                    .filter_map(|r| r.ch),
+            );
+            chars
+        } else {
+            // Normal case: use raw_input directly
+            self.raw_input.iter().filter_map(|r| r.ch).collect()
         }
     }
+
+    /// Canonical keystrokes of `word` in the current method, for words the
+    /// engine didn't see typed (see `text::tutor`)
+    fn raw_for_word(&self, word: &str) -> Option<Vec<RawKeystroke>> {
+        text::tutor::keystrokes(word, self.current_input_method())?
+            .into_iter()
+            .map(|c| {
+                let key = utils::char_to_key(c.to_ascii_lowercase());
+                (key != 255).then(|| RawKeystroke::new(key, c.is_uppercase(), false))
+            })
+            .collect()
+    }
+
+    /// One raw keystroke per char of a buffer whose chars carry no diacritics
+    fn raw_from_buffer(buf: &Buffer) -> Vec<RawKeystroke> {
+        buf.iter()
+            .map(|c| RawKeystroke::new(c.key, c.caps, false))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -7980,6 +10033,9 @@ mod tests {
         ("a44\x1b", "a44"), // ã → a → a44 (ngã reverted)
         ("a55\x1b", "a55"), // ạ → a → a55 (nặng reverted)
         ("a66\x1b", "a66"), // â → a → a66 (circumflex reverted)
+        // Restores output the recorded chars, caps included
+        ("VIE65T\x1b", "VIE65T"),
+        ("D9u7o7c5\x1b", "D9u7o7c5"),
     ];
 
     // Normal Vietnamese transforms apply
@@ -8286,6 +10342,7 @@ mod tests {
     /// - "booo" → "boo" (revert), then "s" → "boos" (not "boós")
     /// - "seee" → "see" (revert), then "m" → "seem" (not "seém")
     /// - "booo" + "k" → "book" (consonant also literal)
+    ///
     /// Note: Only works with valid Vietnamese initials (b, c, d, h, l, m, n, p, s, t, etc.)
     #[test]
     fn test_literal_after_circumflex_revert() {
@@ -8374,4 +10431,325 @@ mod tests {
             );
         }
     }
+
+    fn apply(screen: &mut String, r: &super::Result) {
+        for _ in 0..r.backspace {
+            screen.pop();
+        }
+        for i in 0..r.count as usize {
+            screen.push(char::from_u32(r.chars[i]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_strip_last_word_current() {
+        let mut e = Engine::new();
+        let mut screen = type_word(&mut e, "dduwowngf");
+        assert_eq!(screen, "đường");
+        let r = e.strip_last_word();
+        apply(&mut screen, &r);
+        assert_eq!(screen, "duong");
+        // Buffer is plain now: further marks apply to the ASCII word
+        assert_eq!(e.get_buffer_string(), "duong");
+    }
+
+    #[test]
+    fn test_strip_last_word_committed() {
+        let mut e = Engine::new();
+        let mut screen = type_word(&mut e, "Vieetj, ");
+        assert_eq!(screen, "Việt, ");
+        let r = e.strip_last_word();
+        apply(&mut screen, &r);
+        assert_eq!(screen, "Viet, ");
+
+        // Backspace into the word restores the stripped form
+        let mut e = Engine::new();
+        let mut screen = type_word(&mut e, "tieengs ");
+        apply(&mut screen, &e.strip_last_word());
+        assert_eq!(screen, "tieng ");
+        e.on_key(crate::data::keys::DELETE, false, false);
+        assert_eq!(e.get_buffer_string(), "tieng");
+    }
+
+    #[test]
+    fn test_strip_last_word_noop() {
+        let mut e = Engine::new();
+        type_word(&mut e, "hello ");
+        assert_eq!(e.strip_last_word().action, 0);
+        let mut e = Engine::new();
+        assert_eq!(e.strip_last_word().action, 0);
+    }
+
+    #[test]
+    fn test_spell_check_on_commit() {
+        let mut e = Engine::new();
+        type_word(&mut e, "vieetj ");
+        assert_eq!(e.take_spell_flag(), None); // Disabled by default
+
+        e.set_spell_check(true);
+        type_word(&mut e, "vieetj ");
+        assert_eq!(e.take_spell_flag(), None);
+
+        type_word(&mut e, "vieejt. ");
+        assert_eq!(e.take_spell_flag(), None); // Same word, other typing order
+
+        type_word(&mut e, "gheeps ");
+        let flag = e.take_spell_flag().unwrap();
+        assert_eq!(flag.word, "ghếp");
+        assert_eq!(e.take_spell_flag(), None); // Taken once
+
+        e.add_user_word("Ghếp");
+        type_word(&mut e, "gheeps ");
+        assert_eq!(e.take_spell_flag(), None);
+    }
+
+    #[test]
+    fn test_status_snapshot() {
+        use super::status::InputMode;
+
+        let mut e = Engine::new();
+        type_word(&mut e, "vieetj");
+        let st = e.status();
+        assert_eq!(st.current_word, "việt");
+        assert_eq!(st.raw_word, "vieetj");
+        assert_eq!(st.mode, InputMode::Vietnamese);
+        assert_eq!(st.method, 0);
+        assert!(!st.pending_capitalize);
+
+        e.clear();
+        type_word(&mut e, "str");
+        assert_eq!(e.status().mode, InputMode::Foreign);
+
+        e.set_enabled(false);
+        assert_eq!(e.status().mode, InputMode::English);
+        assert_eq!(e.status().current_word, "");
+    }
+
+    #[test]
+    fn test_status_pending_capitalize_and_prefix() {
+        let mut e = Engine::new();
+        e.set_auto_capitalize(true);
+        type_word(&mut e, "ok. ");
+        assert!(e.status().pending_capitalize);
+
+        let mut e = Engine::new();
+        type_word(&mut e, "#");
+        assert_eq!(e.status().shortcut_prefix, "#");
+    }
+
+    #[test]
+    fn test_result_flags() {
+        use super::Action;
+        use crate::data::keys;
+
+        // Second 'w' after a complete ươ is swallowed: consumed, no output
+        let mut e = Engine::new();
+        type_word(&mut e, "dduwow");
+        let r = e.on_key(keys::W, false, false);
+        assert_eq!(r.action, Action::Send as u8);
+        assert_eq!(r.count, 0);
+        assert!(r.key_consumed());
+        assert!(!r.is_restore());
+
+        // ESC restore is flagged as a restore
+        let mut e = Engine::new();
+        e.set_esc_restore(true);
+        type_word(&mut e, "tesst");
+        let r = e.on_key(keys::ESC, false, false);
+        assert!(r.is_restore());
+        assert!(!r.key_consumed());
+
+        // Auto-restore on space too
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "use");
+        let r = e.on_key(keys::R, false, false);
+        assert!(!r.is_restore());
+        let r = e.on_key(keys::SPACE, false, false);
+        assert!(r.is_restore());
+    }
+
+    #[test]
+    fn test_last_restore_details() {
+        use super::restore::RestoreReason;
+
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "user ");
+        let info = e.last_restore().unwrap();
+        assert_eq!(info.transformed, "uẻ");
+        assert_eq!(info.raw, "user");
+        assert_eq!(info.reason, RestoreReason::EnglishWord);
+
+        type_word(&mut e, "a");
+        assert_eq!(e.last_restore(), None);
+    }
+
+    #[test]
+    fn test_edit_op() {
+        use super::edit::EditOp;
+        use crate::data::keys;
+
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "tie");
+        let r = e.on_key(keys::E, false, false);
+        assert_eq!(
+            e.edit_op(&r),
+            Some(EditOp::ReplaceTail {
+                n_chars: 1,
+                text: "ê".into()
+            })
+        );
+
+        type_word(&mut e, "<<<<te");
+        let r = e.on_key(keys::X, false, false);
+        assert_eq!(
+            e.edit_op(&r),
+            Some(EditOp::ReplaceTail {
+                n_chars: 1,
+                text: "ẽ".into()
+            })
+        );
+        // Restored as soon as the word can't be Vietnamese
+        let r = e.on_key(keys::T, false, false);
+        assert_eq!(
+            e.edit_op(&r),
+            Some(EditOp::ReplaceWord {
+                old: "tẽ".into(),
+                new: "text".into()
+            })
+        );
+
+        e.clear_all();
+        type_word(&mut e, "user");
+        let r = e.on_key(keys::SPACE, false, false);
+        assert_eq!(
+            e.edit_op(&r),
+            Some(EditOp::ReplaceWord {
+                old: "uẻ".into(),
+                new: "user ".into()
+            })
+        );
+        let r = e.on_key(keys::A, false, false);
+        assert_eq!(e.edit_op(&r), None, "plain letter: the app types it");
+    }
+
+    #[test]
+    fn test_history_capacity_and_peek() {
+        let mut e = Engine::new();
+        type_word(&mut e, "mootj hai ba ");
+        assert_eq!(e.history_len(), 3);
+        assert_eq!(e.peek_history(0).as_deref(), Some("ba"));
+        assert_eq!(e.peek_history(2).as_deref(), Some("một"));
+        assert_eq!(e.peek_history(3), None);
+
+        // Shrinking drops the oldest words
+        e.set_history_capacity(2);
+        assert_eq!(e.history_len(), 2);
+        assert_eq!(e.peek_history(1).as_deref(), Some("hai"));
+        type_word(&mut e, "boosn ");
+        assert_eq!(e.peek_history(0).as_deref(), Some("bốn"));
+        assert_eq!(e.peek_history(1).as_deref(), Some("ba"));
+
+        // Disabled: backspace after space no longer restores
+        let mut e = Engine::new();
+        e.set_history_capacity(0);
+        assert_eq!(type_word(&mut e, "chaof <s"), "chàos");
+    }
+
+    #[test]
+    fn test_secure_mode_keeps_nothing() {
+        use super::context::ContextSync;
+        use super::status::DisableReason;
+        use crate::data::keys;
+
+        let mut e = Engine::new();
+        e.shortcuts_mut()
+            .add(super::shortcut::Shortcut::new("vn", "Việt Nam"));
+        type_word(&mut e, "mootj hai vieet");
+        e.set_secure_mode(true);
+        assert_eq!(e.disable_reason(), Some(DisableReason::SecureField));
+        assert_eq!(e.history_len(), 0);
+        assert_eq!(e.status().current_word, "");
+
+        // Keys pass through untouched and leave no trace
+        assert_eq!(type_word(&mut e, "vn paass "), "vn paass ");
+        e.on_key_with_char(keys::V, false, false, false, Some('√'));
+        e.on_key_at(keys::A, false, false, false, 1000);
+        e.push_composed('ế');
+        e.restore_word("mật");
+        assert_eq!(e.sync_context("mật"), ContextSync::InSync);
+        assert_eq!(e.debug_raw_input_len(), 0);
+        assert_eq!(e.debug_buffer_len(), 0);
+        assert_eq!(e.history_len(), 0);
+        let status = e.status();
+        assert_eq!(status.current_word, "");
+        assert_eq!(status.shortcut_prefix, "");
+
+        // The user can't turn input back on inside the field
+        e.set_enabled(true);
+        assert_eq!(type_word(&mut e, "aa"), "aa");
+        assert_eq!(e.debug_raw_input_len(), 0);
+
+        e.set_secure_mode(false);
+        assert_eq!(e.disable_reason(), None);
+        assert_eq!(type_word(&mut e, "vieetj <"), "việt");
+        assert_eq!(type_word(&mut e, " vn "), " Việt Nam ");
+    }
+
+    #[test]
+    fn test_wipe_keeps_settings() {
+        let mut e = Engine::new();
+        e.set_method(1);
+        e.shortcuts_mut()
+            .add(super::shortcut::Shortcut::new("vn", "Việt Nam"));
+        type_word(&mut e, "mo65t hai ba");
+        e.wipe();
+        assert_eq!(e.history_len(), 0);
+        assert_eq!(e.debug_raw_input_len(), 0);
+        assert_eq!(e.status().current_word, "");
+        // Backspace has no word to bring back
+        assert_eq!(type_word(&mut e, "<"), "");
+        assert_eq!(type_word(&mut e, "vie65t vn "), "việt Việt Nam ");
+    }
+
+    #[test]
+    fn test_clear_overwrites_typed_keys() {
+        let mut e = Engine::new();
+        type_word(&mut e, "mootj");
+        e.clear();
+        let raw = &e.raw_input;
+        // SAFETY: clear() wrote the whole capacity
+        let all = unsafe { core::slice::from_raw_parts(raw.as_ptr(), raw.capacity()) };
+        assert!(all.iter().all(|k| k.key == 0));
+        assert_eq!(e.buf.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_undo_group_hints() {
+        use crate::data::keys;
+
+        let mut e = Engine::new();
+        // New word opens a group, following keys join it
+        assert!(e.on_key(keys::V, false, false).new_undo_group());
+        assert!(!e.on_key(keys::I, false, false).new_undo_group());
+        assert!(!e.on_key(keys::E, false, false).new_undo_group());
+        assert!(!e.on_key(keys::E, false, false).new_undo_group());
+        assert!(!e.on_key(keys::SPACE, false, false).new_undo_group());
+        assert!(e.on_key(keys::B, false, false).new_undo_group());
+
+        // Restores open a group
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "user");
+        assert!(e.on_key(keys::SPACE, false, false).new_undo_group());
+
+        // Shortcut expansions open a group
+        let mut e = Engine::new();
+        e.shortcuts_mut()
+            .add(super::shortcut::Shortcut::new("vn", "Việt Nam"));
+        type_word(&mut e, "vn");
+        assert!(e.on_key(keys::SPACE, false, false).new_undo_group());
+    }
 }
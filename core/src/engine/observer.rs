@@ -0,0 +1,187 @@
+//! Engine Observer
+//!
+//! Lifecycle callbacks for host UIs (menu bar state, statistics, tutors) that
+//! need to react to what the engine did without polling or parsing `Result`s.
+//!
+//! All methods have empty default implementations; implement only the events
+//! you need. Callbacks run synchronously inside the key handler, so they must
+//! be cheap and must not call back into the engine.
+
+use super::spell::SpellFlag;
+use super::status::DisableReason;
+
+/// Which diacritic a double-key revert removed ("ass" → "as", "aaa" → "aa")
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevertKind {
+    /// Tone mark (sắc, huyền, hỏi, ngã, nặng)
+    Mark,
+    /// Vowel modifier (circumflex, horn, breve)
+    Tone,
+    /// đ stroke
+    Stroke,
+}
+
+/// Receiver for engine lifecycle events
+pub trait EngineObserver: Send {
+    /// A word was committed by space or punctuation (final on-screen form)
+    fn on_word_committed(&mut self, _word: &str) {}
+
+    /// Same commit with the keystrokes that produced it ("vieetj", "việt"),
+    /// for learning, statistics or dictionary sync. After an auto-restore
+    /// both are the raw form.
+    fn on_word_committed_pair(&mut self, _raw: &str, _word: &str) {}
+
+    /// Transformed text was restored to raw keystrokes (auto-restore or ESC)
+    fn on_restore(&mut self, _from: &str, _to: &str) {}
+
+    /// A repeated modifier key undid a transform; `word` is the result
+    fn on_revert(&mut self, _kind: RevertKind, _word: &str) {}
+
+    /// A shortcut trigger was replaced by its expansion
+    fn on_shortcut_expanded(&mut self, _trigger: &str, _replacement: &str) {}
+
+    /// Vietnamese input was enabled/disabled or the input method changed
+    fn on_mode_changed(&mut self, _enabled: bool, _method: u8) {}
+
+    /// The reason input is off changed (`None`: input is on again), for
+    /// logs and menu bar text explaining why keys aren't transformed
+    fn on_disable_reason_changed(&mut self, _reason: Option<DisableReason>) {}
+
+    /// A committed word failed spell check (see `Engine::set_spell_check`)
+    fn on_spell_flag(&mut self, _flag: &SpellFlag) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::shortcut::Shortcut;
+    use crate::engine::Engine;
+    use crate::utils::type_word;
+    use std::sync::{Arc, Mutex};
+
+    /// Records every event as a line of text
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl EngineObserver for Recorder {
+        fn on_word_committed(&mut self, word: &str) {
+            self.0.lock().unwrap().push(format!("commit {word}"));
+        }
+        fn on_restore(&mut self, from: &str, to: &str) {
+            self.0.lock().unwrap().push(format!("restore {from}->{to}"));
+        }
+        fn on_revert(&mut self, kind: RevertKind, word: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("revert {kind:?} {word}"));
+        }
+        fn on_shortcut_expanded(&mut self, trigger: &str, replacement: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("shortcut {trigger}->{replacement}"));
+        }
+        fn on_mode_changed(&mut self, enabled: bool, method: u8) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("mode {enabled} {method}"));
+        }
+        fn on_disable_reason_changed(&mut self, reason: Option<DisableReason>) {
+            self.0.lock().unwrap().push(format!("reason {reason:?}"));
+        }
+    }
+
+    fn engine_with_recorder() -> (Engine, Arc<Mutex<Vec<String>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut e = Engine::new();
+        e.set_observer(Box::new(Recorder(events.clone())));
+        (e, events)
+    }
+
+    fn take(events: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+        std::mem::take(&mut *events.lock().unwrap())
+    }
+
+    #[test]
+    fn test_commit_events() {
+        let (mut e, events) = engine_with_recorder();
+        type_word(&mut e, "vieetj namw, ");
+        assert_eq!(take(&events), vec!["commit việt", "commit năm"]);
+    }
+
+    struct PairRecorder(Arc<Mutex<Vec<(String, String)>>>);
+
+    impl EngineObserver for PairRecorder {
+        fn on_word_committed_pair(&mut self, raw: &str, word: &str) {
+            self.0.lock().unwrap().push((raw.into(), word.into()));
+        }
+    }
+
+    #[test]
+    fn test_commit_pairs() {
+        let pairs = Arc::new(Mutex::new(Vec::new()));
+        let mut e = Engine::new();
+        e.set_observer(Box::new(PairRecorder(pairs.clone())));
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "vieetj aww user. ");
+        let pairs = std::mem::take(&mut *pairs.lock().unwrap());
+        let pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|(r, w)| (r.as_str(), w.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("vieetj", "việt"), ("aww", "aw"), ("user", "user")]
+        );
+    }
+
+    #[test]
+    fn test_restore_events() {
+        let (mut e, events) = engine_with_recorder();
+        e.set_english_auto_restore(true);
+        type_word(&mut e, "user ");
+        assert_eq!(take(&events), vec!["restore uẻ->user", "commit user"]);
+
+        e.set_esc_restore(true);
+        type_word(&mut e, "vieetj\x1b");
+        assert_eq!(take(&events), vec!["restore việt->vieetj"]);
+    }
+
+    #[test]
+    fn test_revert_events() {
+        let (mut e, events) = engine_with_recorder();
+        type_word(&mut e, "ass");
+        assert_eq!(take(&events), vec!["revert Mark as"]);
+        e.clear();
+        type_word(&mut e, "aaa");
+        assert_eq!(take(&events), vec!["revert Tone aa"]);
+        e.clear();
+        type_word(&mut e, "ddd");
+        assert_eq!(take(&events), vec!["revert Stroke dd"]);
+    }
+
+    #[test]
+    fn test_shortcut_and_mode_events() {
+        let (mut e, events) = engine_with_recorder();
+        e.shortcuts_mut().add(Shortcut::new("vn", "Việt Nam"));
+        type_word(&mut e, "vn ");
+        assert_eq!(take(&events), vec!["shortcut vn->Việt Nam"]);
+
+        e.set_method(1);
+        e.set_method(1); // Unchanged: no event
+        e.set_enabled(false);
+        assert_eq!(
+            take(&events),
+            vec!["mode true 1", "mode false 1", "reason Some(User)"]
+        );
+
+        e.set_enabled_for(false, DisableReason::SecureField);
+        e.set_enabled(true); // Still off: the secure field holds
+        e.set_enabled_for(true, DisableReason::SecureField);
+        assert_eq!(
+            take(&events),
+            vec!["reason Some(SecureField)", "mode true 1", "reason None"]
+        );
+    }
+}
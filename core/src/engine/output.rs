@@ -0,0 +1,431 @@
+//! Per-App Output Strategy
+//!
+//! How a host should deliver a `Result` depends on the app receiving it:
+//! terminals and Electron editors drop keystrokes sent too fast, browser
+//! address bars autocomplete over backspaces, remote-desktop clients only
+//! forward physical keys. This is the shared table of those workarounds,
+//! keyed by app id (macOS bundle id, Windows exe name, Linux WM class) and
+//! the focused element's accessibility role, plus user overrides per app that
+//! hosts persist as text (`OutputRegistry::overrides_to_text`).
+
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+
+/// How the host injects an edit
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OutputMethod {
+    /// Backspaces then text, minimal delays
+    Fast = 0,
+    /// Backspaces then text with longer delays (terminals, Electron)
+    Slow = 1,
+    /// Text one character at a time (Safari content, Google Docs)
+    CharByChar = 2,
+    /// Shift+Left to select, then type the replacement (combo boxes)
+    Selection = 3,
+    /// Empty char first to break autocomplete, one extra backspace
+    EmptyCharPrefix = 4,
+    /// Accessibility API sets the text directly (Spotlight)
+    AxDirect = 5,
+    /// Synchronous injection through the event tap (games)
+    SyncProxy = 6,
+    /// Don't transform: the keys go through as typed (remote desktops)
+    Passthrough = 7,
+}
+
+impl OutputMethod {
+    const ALL: [Self; 8] = [
+        Self::Fast,
+        Self::Slow,
+        Self::CharByChar,
+        Self::Selection,
+        Self::EmptyCharPrefix,
+        Self::AxDirect,
+        Self::SyncProxy,
+        Self::Passthrough,
+    ];
+
+    /// Parse FFI value
+    pub fn from_u8(v: u8) -> Option<Self> {
+        Self::ALL.get(v as usize).copied()
+    }
+
+    /// Name used in the overrides text
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Slow => "slow",
+            Self::CharByChar => "charByChar",
+            Self::Selection => "selection",
+            Self::EmptyCharPrefix => "emptyCharPrefix",
+            Self::AxDirect => "axDirect",
+            Self::SyncProxy => "syncProxy",
+            Self::Passthrough => "passthrough",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+}
+
+/// Method and timing to deliver edits to one app
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutputStrategy {
+    pub method: OutputMethod,
+    /// Delays in µs: after each backspace, between backspaces and text,
+    /// after each text chunk
+    pub delays: [u32; 3],
+    /// Rule that chose it, for logs ("slow:code", "override")
+    pub label: &'static str,
+}
+
+impl OutputStrategy {
+    const fn new(method: OutputMethod, delays: [u32; 3], label: &'static str) -> Self {
+        Self {
+            method,
+            delays,
+            label,
+        }
+    }
+}
+
+/// Focus without an app id (nothing focused yet)
+const UNKNOWN_APP: OutputStrategy =
+    OutputStrategy::new(OutputMethod::Fast, [200, 800, 500], "fast");
+const DEFAULT: OutputStrategy =
+    OutputStrategy::new(OutputMethod::Fast, [1000, 3000, 1500], "default");
+
+const BROWSER_DELAYS: [u32; 3] = [3000, 8000, 3000];
+const EDITOR_DELAYS: [u32; 3] = [8000, 25000, 8000];
+
+const REMOTE_DESKTOPS: &[&str] = &[
+    "com.carriez.rustdesk",
+    "com.philandro.anydesk",
+    "com.teamviewer.TeamViewer",
+];
+
+const BROWSERS: &[&str] = &[
+    // The Browser Company
+    "company.thebrowser.Browser",
+    "company.thebrowser.Arc",
+    "company.thebrowser.dia",
+    // Firefox-based
+    "org.mozilla.firefox",
+    "org.mozilla.firefoxdeveloperedition",
+    "org.mozilla.nightly",
+    "org.waterfoxproject.waterfox",
+    "io.gitlab.librewolf-community.librewolf",
+    "one.ablaze.floorp",
+    "org.torproject.torbrowser",
+    "net.mullvad.mullvadbrowser",
+    "app.zen-browser.zen",
+    // Chromium-based
+    "com.google.Chrome",
+    "com.google.Chrome.canary",
+    "com.google.Chrome.beta",
+    "org.chromium.Chromium",
+    "com.brave.Browser",
+    "com.brave.Browser.beta",
+    "com.brave.Browser.nightly",
+    "com.microsoft.edgemac",
+    "com.microsoft.edgemac.Beta",
+    "com.microsoft.edgemac.Dev",
+    "com.microsoft.edgemac.Canary",
+    "com.vivaldi.Vivaldi",
+    "com.vivaldi.Vivaldi.snapshot",
+    "ru.yandex.desktop.yandex-browser",
+    // Opera
+    "com.opera.Opera",
+    "com.operasoftware.Opera",
+    "com.operasoftware.OperaGX",
+    "com.operasoftware.OperaAir",
+    "com.opera.OperaNext",
+    // WebKit-based
+    "com.kagi.kagimacOS",
+    // Others
+    "com.sigmaos.sigmaos.macos",
+    "com.pushplaylabs.sidekick",
+    "com.firstversionist.polypane",
+    "ai.perplexity.comet",
+    "com.duckduckgo.macos.browser",
+    "com.openai.atlas",
+];
+
+/// VSCode-based editors, terminals and other code editors (Monaco/Electron)
+const CODE_APPS: &[&str] = &[
+    "com.microsoft.VSCode",
+    "com.google.antigravity-ide",
+    "com.todesktop.cursor",
+    "com.visualstudio.code.oss",
+    "com.vscodium",
+    "dev.warp.Warp-Stable",
+    "com.mitchellh.ghostty",
+    "net.kovidgoyal.kitty",
+    "com.apple.Terminal",
+    "com.googlecode.iterm2",
+    "io.alacritty",
+    "com.github.wez.wezterm",
+    "co.zeit.hyper",
+    "org.tabby",
+    "com.raphaelamorim.rio",
+    "com.termius-dmg.mac",
+    "dev.zed.Zed",
+    "com.sublimetext.4",
+    "com.sublimetext.3",
+    "com.panic.Nova",
+];
+
+/// Built-in strategy for an app and focused role, first matching rule wins
+fn detect(app_id: &str, role: Option<&str>) -> OutputStrategy {
+    use OutputMethod::*;
+    if app_id.is_empty() {
+        return UNKNOWN_APP;
+    }
+    let is = |ids: &[&str]| ids.contains(&app_id);
+    let jetbrains = app_id.starts_with("com.jetbrains");
+
+    // iPhone Mirroring and remote desktops forward physical keys only:
+    // Vietnamese composition happens on the remote side
+    if app_id == "com.apple.ScreenContinuity" {
+        return OutputStrategy::new(Passthrough, [0; 3], "pass:iphone");
+    }
+    if is(REMOTE_DESKTOPS) {
+        return OutputStrategy::new(Passthrough, [0; 3], "pass:remote");
+    }
+    // Autocomplete UI elements in any app
+    match role {
+        Some("AXComboBox") => return OutputStrategy::new(Selection, [0; 3], "sel:combo"),
+        Some("AXSearchField") => return OutputStrategy::new(Selection, [0; 3], "sel:search"),
+        _ => {}
+    }
+    if is(&["com.apple.Spotlight", "com.apple.systemuiserver"]) {
+        return OutputStrategy::new(AxDirect, [0; 3], "ax:spotlight");
+    }
+    // Safari: address bar breaks autocomplete, content (Google Docs) goes
+    // char by char. Before the other browsers.
+    if is(&["com.apple.Safari", "com.apple.SafariTechnologyPreview"]) {
+        if role == Some("AXTextField") {
+            return OutputStrategy::new(EmptyCharPrefix, BROWSER_DELAYS, "emptyChar:safari");
+        }
+        return OutputStrategy::new(CharByChar, BROWSER_DELAYS, "char:safari");
+    }
+    if is(BROWSERS) {
+        return OutputStrategy::new(EmptyCharPrefix, BROWSER_DELAYS, "emptyChar:browser");
+    }
+    if jetbrains && role == Some("AXTextField") {
+        return OutputStrategy::new(Selection, [0; 3], "sel:jb");
+    }
+    // Microsoft Office: selection conflicts with its autocomplete
+    match app_id {
+        "com.microsoft.Excel" => {
+            return OutputStrategy::new(Slow, [3000, 8000, 3000], "slow:excel")
+        }
+        "com.microsoft.Word" => return OutputStrategy::new(Slow, [3000, 8000, 3000], "slow:word"),
+        "com.microsoft.Outlook" => {
+            return OutputStrategy::new(Slow, [8000, 15000, 8000], "slow:outlook")
+        }
+        // Electron apps with Monaco editors
+        "com.todesktop.230313mzl4w4u92" => {
+            return OutputStrategy::new(Slow, [8000, 15000, 8000], "slow:claude")
+        }
+        "notion.id" => return OutputStrategy::new(Slow, [12000, 25000, 12000], "slow:notion"),
+        _ => {}
+    }
+    if is(CODE_APPS) {
+        return OutputStrategy::new(Slow, EDITOR_DELAYS, "slow:code");
+    }
+    // Qt-based LaTeX editor needs char by char for Unicode input
+    if app_id == "texstudio" {
+        return OutputStrategy::new(CharByChar, BROWSER_DELAYS, "char:texstudio");
+    }
+    if jetbrains {
+        return OutputStrategy::new(Slow, EDITOR_DELAYS, "slow:jb");
+    }
+    match app_id {
+        "com.caudex.dev" => {
+            return OutputStrategy::new(CharByChar, [5000, 15000, 5000], "char:caudex")
+        }
+        "com.foxit-software.Foxit.PDF.Reader" => {
+            return OutputStrategy::new(CharByChar, [0; 3], "char:foxit")
+        }
+        _ => {}
+    }
+    // Games (Issue #264: League of Legends)
+    if app_id.starts_with("com.riotgames") {
+        return OutputStrategy::new(SyncProxy, [0; 3], "sync:game");
+    }
+    DEFAULT
+}
+
+/// Line of the overrides text that couldn't be read (1-based)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BadOverrideLine(pub usize);
+
+/// Built-in rules plus per-app user overrides
+#[derive(Clone, Debug, Default)]
+pub struct OutputRegistry {
+    overrides: BTreeMap<String, OutputStrategy>,
+}
+
+impl OutputRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Strategy for an app and its focused element's role
+    ///
+    /// A user override for the app wins over the built-in rules.
+    pub fn lookup(&self, app_id: &str, role: Option<&str>) -> OutputStrategy {
+        self.overrides
+            .get(app_id)
+            .copied()
+            .unwrap_or_else(|| detect(app_id, role))
+    }
+
+    /// Built-in strategy, ignoring overrides (the "detected" hint in settings)
+    pub fn detected(&self, app_id: &str, role: Option<&str>) -> OutputStrategy {
+        detect(app_id, role)
+    }
+
+    /// Use `method` and `delays` for every element of an app
+    pub fn set_override(&mut self, app_id: &str, method: OutputMethod, delays: [u32; 3]) {
+        self.overrides.insert(
+            app_id.to_string(),
+            OutputStrategy::new(method, delays, "override"),
+        );
+    }
+
+    pub fn remove_override(&mut self, app_id: &str) -> Option<OutputStrategy> {
+        self.overrides.remove(app_id)
+    }
+
+    /// Overridden apps with their strategies, sorted by app id
+    pub fn overrides(&self) -> impl Iterator<Item = (&str, &OutputStrategy)> {
+        self.overrides.iter().map(|(id, s)| (id.as_str(), s))
+    }
+
+    /// Overrides as text, one `app_id<TAB>method<TAB>d0,d1,d2` line each
+    pub fn overrides_to_text(&self) -> String {
+        self.overrides
+            .iter()
+            .map(|(id, s)| {
+                let [a, b, c] = s.delays;
+                format!("{id}\t{}\t{a},{b},{c}\n", s.method.name())
+            })
+            .collect()
+    }
+
+    /// Replace overrides with those in `text` (see `overrides_to_text`)
+    ///
+    /// Blank lines and `#` comments are skipped. Nothing changes if a line
+    /// can't be read.
+    pub fn load_overrides(&mut self, text: &str) -> core::result::Result<(), BadOverrideLine> {
+        let mut overrides = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, method, delays) = parse_override(line).ok_or(BadOverrideLine(i + 1))?;
+            overrides.insert(
+                id.to_string(),
+                OutputStrategy::new(method, delays, "override"),
+            );
+        }
+        self.overrides = overrides;
+        Ok(())
+    }
+}
+
+fn parse_override(line: &str) -> Option<(&str, OutputMethod, [u32; 3])> {
+    let mut fields = line.split('\t');
+    let id = fields.next().filter(|id| !id.is_empty())?;
+    let method = OutputMethod::from_name(fields.next()?)?;
+    let mut delays = [0; 3];
+    let mut parts = fields.next()?.split(',');
+    for d in delays.iter_mut() {
+        *d = parts.next()?.trim().parse().ok()?;
+    }
+    if parts.next().is_some() || fields.next().is_some() {
+        return None;
+    }
+    Some((id, method, delays))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules() {
+        let r = OutputRegistry::new();
+        let label = |app: &str, role: Option<&str>| r.lookup(app, role).label;
+        assert_eq!(label("com.apple.Terminal", None), "slow:code");
+        assert_eq!(
+            label("com.apple.Safari", Some("AXTextField")),
+            "emptyChar:safari"
+        );
+        assert_eq!(label("com.apple.Safari", Some("AXWebArea")), "char:safari");
+        assert_eq!(label("com.google.Chrome", None), "emptyChar:browser");
+        // Roles come before the app's own rule
+        assert_eq!(label("com.google.Chrome", Some("AXComboBox")), "sel:combo");
+        assert_eq!(label("com.jetbrains.rider", Some("AXTextField")), "sel:jb");
+        assert_eq!(label("com.jetbrains.rider", None), "slow:jb");
+        assert_eq!(label("com.riotgames.LeagueofLegends", None), "sync:game");
+        assert_eq!(label("com.carriez.rustdesk", None), "pass:remote");
+        assert_eq!(r.lookup("com.example.Notes", None), DEFAULT);
+        assert_eq!(r.lookup("", None), UNKNOWN_APP);
+        assert_eq!(r.lookup("notion.id", None).delays, [12000, 25000, 12000]);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut r = OutputRegistry::new();
+        r.set_override("com.apple.Terminal", OutputMethod::CharByChar, [1, 2, 3]);
+        let s = r.lookup("com.apple.Terminal", Some("AXTextArea"));
+        assert_eq!(s.method, OutputMethod::CharByChar);
+        assert_eq!(s.label, "override");
+        assert_eq!(r.detected("com.apple.Terminal", None).label, "slow:code");
+
+        assert!(r.remove_override("com.apple.Terminal").is_some());
+        assert_eq!(r.lookup("com.apple.Terminal", None).label, "slow:code");
+    }
+
+    #[test]
+    fn test_overrides_text_round_trip() {
+        let mut r = OutputRegistry::new();
+        r.set_override("notion.id", OutputMethod::Fast, [0, 0, 0]);
+        r.set_override("com.example.Game", OutputMethod::SyncProxy, [10, 20, 30]);
+        let text = r.overrides_to_text();
+        assert_eq!(
+            text,
+            "com.example.Game\tsyncProxy\t10,20,30\nnotion.id\tfast\t0,0,0\n"
+        );
+
+        let mut loaded = OutputRegistry::new();
+        assert_eq!(loaded.load_overrides(&format!("# saved\n\n{text}")), Ok(()));
+        assert_eq!(loaded.overrides().count(), 2);
+        assert_eq!(loaded.lookup("notion.id", None).method, OutputMethod::Fast);
+
+        // A bad line keeps the previous overrides
+        assert_eq!(
+            loaded.load_overrides("a\tfast\t1,2,3\nb\tturbo\t1,2,3"),
+            Err(BadOverrideLine(2))
+        );
+        assert_eq!(loaded.overrides().count(), 2);
+        assert_eq!(
+            loaded.load_overrides("a\tfast\t1,2"),
+            Err(BadOverrideLine(1))
+        );
+    }
+
+    #[test]
+    fn test_method_codes() {
+        for m in OutputMethod::ALL {
+            assert_eq!(OutputMethod::from_u8(m as u8), Some(m));
+            assert_eq!(OutputMethod::from_name(m.name()), Some(m));
+        }
+        assert_eq!(OutputMethod::from_u8(8), None);
+    }
+}
@@ -0,0 +1,89 @@
+//! Named Profiles
+//!
+//! A profile bundles the per-user state a host would otherwise have to keep
+//! twice: settings, shortcut table and user dictionary. Users with separate
+//! work and personal accounts switch snippet sets and restore behavior with
+//! one `Engine::set_profile` call. Enabled/disabled and the observer are
+//! global and not part of a profile.
+
+use super::shortcut::ShortcutTable;
+use super::timing::KeyTiming;
+use super::transform::ToneKeyPolicy;
+use super::validation::ValidationProfile;
+use crate::prelude::*;
+use crate::utils::ForeignFinalTone;
+use alloc::collections::BTreeSet;
+
+/// Name of the profile a new engine starts in
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Engine settings carried by a profile (see the matching `Engine::set_*`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Input method (0=Telex, 1=VNI)
+    pub method: u8,
+    pub skip_w_shortcut: bool,
+    pub auto_uo_horn: bool,
+    pub tag_composition: bool,
+    pub bracket_shortcut: bool,
+    pub esc_restore: bool,
+    pub free_tone: bool,
+    pub modern_tone: bool,
+    pub english_auto_restore: bool,
+    /// Fewest keystrokes of an auto-restored word (0 = any)
+    pub restore_min_length: u8,
+    pub auto_capitalize: bool,
+    pub spell_check: bool,
+    pub validation_profile: ValidationProfile,
+    pub tone_key_policy: ToneKeyPolicy,
+    pub foreign_final_tone: ForeignFinalTone,
+    /// Committed words backspace can restore (0 disables)
+    pub history_capacity: usize,
+    pub mid_word_editing: bool,
+    pub key_timing: KeyTiming,
+}
+
+impl Default for Config {
+    /// Settings of `Engine::new()`
+    fn default() -> Self {
+        Self {
+            method: 0,
+            skip_w_shortcut: false,
+            auto_uo_horn: true,
+            tag_composition: true,
+            bracket_shortcut: false,
+            esc_restore: false,
+            free_tone: false,
+            modern_tone: true,
+            english_auto_restore: false,
+            restore_min_length: 0,
+            auto_capitalize: false,
+            spell_check: false,
+            validation_profile: ValidationProfile::Standard,
+            tone_key_policy: ToneKeyPolicy::Smart,
+            foreign_final_tone: ForeignFinalTone::Reject,
+            history_capacity: super::HISTORY_CAPACITY,
+            mid_word_editing: false,
+            key_timing: KeyTiming::default(),
+        }
+    }
+}
+
+/// Settings, shortcuts and user dictionary of one profile
+#[derive(Debug)]
+pub struct Profile {
+    pub config: Config,
+    pub shortcuts: ShortcutTable,
+    /// User dictionary words (lowercase)
+    pub user_words: BTreeSet<String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            config: Config::default(),
+            shortcuts: ShortcutTable::with_defaults(),
+            user_words: BTreeSet::new(),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Restore Details
+//!
+//! When the engine puts the raw keystrokes back (auto-restore on a word
+//! boundary, ESC), the `Result` only carries the replacement text. This keeps
+//! both candidates and the reason, so hosts can offer a one-tap "undo restore"
+//! and logs show what was decided.
+
+use super::english::{ConfidenceTier, EnglishConfidence, NEUTRAL};
+use super::wipe;
+use crate::data::{chars, dictionary, english_dict};
+use crate::prelude::*;
+
+/// Why a word was restored to its raw keystrokes
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestoreReason {
+    /// User pressed ESC
+    Escape = 0,
+    /// Raw keystrokes form a known English word
+    EnglishWord = 1,
+    /// Transformed word isn't valid Vietnamese
+    InvalidVietnamese = 2,
+}
+
+/// Both forms of a restored word
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestoreInfo {
+    /// Word as displayed before the restore ("tẽt")
+    pub transformed: String,
+    /// Word after the restore, without the trailing space ("text")
+    pub raw: String,
+    pub reason: RestoreReason,
+}
+
+/// Words auto-restore leaves as typed
+///
+/// Below `min_length` keystrokes a finished word is never auto-restored:
+/// short tokens are as often Vietnamese ("per" → "pẻ", "her" → "hẻ") as English, and
+/// flipping them is churn. Words in the exception list are exempt from the
+/// minimum and keep the usual decision ("per" with a minimum of 4).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestoreFilter {
+    /// Fewest keystrokes a restored word has (0 = no minimum)
+    pub min_length: u8,
+    /// Raw words exempt from the minimum, lowercase
+    exceptions: Vec<String>,
+}
+
+impl RestoreFilter {
+    /// Exempt `word` (its keystrokes, "per") from the minimum length
+    pub fn add_exception(&mut self, word: &str) {
+        let word = word.to_lowercase();
+        if !self.exceptions.contains(&word) {
+            self.exceptions.push(word);
+        }
+    }
+
+    pub fn clear_exceptions(&mut self) {
+        self.exceptions.clear();
+    }
+
+    pub fn exceptions(&self) -> &[String] {
+        &self.exceptions
+    }
+
+    /// Whether a word typed as `raw` (lowercase keystrokes) may be auto-restored
+    pub fn allows(&self, raw: &str) -> bool {
+        raw.chars().count() >= self.min_length as usize || self.exceptions.iter().any(|w| w == raw)
+    }
+}
+
+/// Language a word was committed in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordLanguage {
+    Vietnamese,
+    English,
+}
+
+impl WordLanguage {
+    /// Language a word of text reads as: Vietnamese with any Vietnamese
+    /// letter, English when it is an English word and not a Vietnamese one
+    /// ("love", not "an"). None when it reads as both or neither.
+    pub fn of_word(word: &str) -> Option<Self> {
+        if word
+            .chars()
+            .any(|c| !c.is_ascii() && chars::parse_char(c).is_some())
+        {
+            return Some(Self::Vietnamese);
+        }
+        let word = word.to_lowercase();
+        (english_dict::is_english_word(&word) && !dictionary::is_vietnamese(&word, true))
+            .then_some(Self::English)
+    }
+}
+
+/// Most keystroke words `WordChoices` remembers
+const CHOICE_CAPACITY: usize = 16;
+
+/// How the user last committed recent keystroke words ("her" → English
+/// after an ESC restore, Vietnamese when "hẻ" was kept)
+#[derive(Clone, Debug, Default)]
+pub struct WordChoices {
+    /// Lowercase keystrokes and language, oldest first
+    entries: Vec<(String, WordLanguage)>,
+}
+
+impl WordChoices {
+    /// Remember the latest choice for `raw`, dropping the oldest when full
+    pub fn record(&mut self, raw: &str, language: WordLanguage) {
+        self.entries.retain(|(word, _)| word != raw);
+        if self.entries.len() == CHOICE_CAPACITY {
+            self.entries.remove(0);
+        }
+        self.entries.push((raw.to_string(), language));
+    }
+
+    pub fn get(&self, raw: &str) -> Option<WordLanguage> {
+        self.entries
+            .iter()
+            .find(|(word, _)| word == raw)
+            .map(|(_, language)| *language)
+    }
+
+    /// Forget every choice, overwriting the remembered words
+    pub fn zeroize(&mut self) {
+        for (word, _) in &mut self.entries {
+            wipe::zero_string(word);
+        }
+        self.entries.clear();
+    }
+}
+
+/// Weight of the user's last commit of the same keystrokes
+const HISTORY_WEIGHT: i32 = 70;
+/// Weight of each of the last two committed words
+const CONTEXT_WEIGHT: i32 = 30;
+/// Total above which a valid Vietnamese word is restored
+const RESTORE_MARGIN: i32 = 50;
+
+/// Tie-break for a finished word with a valid Vietnamese reading whose
+/// keystrokes are also plausible English ("bars" → "bá", "her" → "hẻ")
+///
+/// The English score counts from neutral, so on its own it never
+/// passes the margin and such words stay Vietnamese as before. The user's
+/// own last choice for the same keystrokes tips it either way, and so do the
+/// words around it in either language. Low-tier
+/// words always stay Vietnamese. The dictionary has no frequencies, so the
+/// Vietnamese side is its membership, already part of the score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TieBreak {
+    pub confidence: EnglishConfidence,
+    /// How the same keystrokes were last committed, if still in history
+    pub history: Option<WordLanguage>,
+    /// Language of two neighbouring words: the last two committed while
+    /// typing, the one before and the one after in `correct_sentence`
+    pub context: [Option<WordLanguage>; 2],
+}
+
+impl TieBreak {
+    /// Evidence for English (positive) or Vietnamese (negative)
+    pub fn weight(&self) -> i32 {
+        let lean = |language: Option<WordLanguage>, weight: i32| match language {
+            Some(WordLanguage::English) => weight,
+            Some(WordLanguage::Vietnamese) => -weight,
+            None => 0,
+        };
+        let context: i32 = self.context.iter().map(|l| lean(*l, CONTEXT_WEIGHT)).sum();
+        self.confidence.score as i32 - NEUTRAL + lean(self.history, HISTORY_WEIGHT) + context
+    }
+
+    /// Whether the word goes back to its keystrokes
+    pub fn restores(&self) -> bool {
+        self.confidence.tier != ConfidenceTier::Low && self.weight() > RESTORE_MARGIN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_minimum_allows_everything() {
+        let filter = RestoreFilter::default();
+        assert!(filter.allows("a"));
+        assert!(filter.allows("text"));
+    }
+
+    #[test]
+    fn test_minimum_with_exceptions() {
+        let mut filter = RestoreFilter {
+            min_length: 4,
+            ..Default::default()
+        };
+        filter.add_exception("PER");
+        filter.add_exception("per");
+        assert_eq!(filter.exceptions(), ["per"]);
+        assert!(!filter.allows("as"));
+        assert!(filter.allows("per"));
+        assert!(filter.allows("text"));
+        filter.clear_exceptions();
+        assert!(!filter.allows("per"));
+    }
+
+    fn tie_break(score: u8, history: Option<WordLanguage>) -> TieBreak {
+        TieBreak {
+            confidence: EnglishConfidence {
+                score,
+                tier: ConfidenceTier::from_score(score),
+            },
+            history,
+            context: [None; 2],
+        }
+    }
+
+    #[test]
+    fn test_word_language() {
+        assert_eq!(WordLanguage::of_word("hẻ"), Some(WordLanguage::Vietnamese));
+        assert_eq!(WordLanguage::of_word("Love"), Some(WordLanguage::English));
+        assert_eq!(WordLanguage::of_word("an"), None);
+        assert_eq!(WordLanguage::of_word("x2"), None);
+    }
+
+    #[test]
+    fn test_word_choices_keep_latest() {
+        let mut choices = WordChoices::default();
+        choices.record("her", WordLanguage::Vietnamese);
+        choices.record("her", WordLanguage::English);
+        assert_eq!(choices.get("her"), Some(WordLanguage::English));
+        for i in 0..CHOICE_CAPACITY {
+            choices.record(&format!("w{i}"), WordLanguage::English);
+        }
+        assert_eq!(choices.get("her"), None);
+        choices.zeroize();
+        assert_eq!(choices.get("w1"), None);
+    }
+
+    #[test]
+    fn test_tie_break_needs_history() {
+        assert!(!tie_break(100, None).restores());
+        assert!(tie_break(47, Some(WordLanguage::English)).restores());
+        assert!(!tie_break(100, Some(WordLanguage::Vietnamese)).restores());
+        assert!(!tie_break(20, Some(WordLanguage::English)).restores());
+    }
+
+    #[test]
+    fn test_tie_break_context() {
+        use WordLanguage::{English, Vietnamese};
+        let with = |score, context| TieBreak {
+            context,
+            ..tie_break(score, None)
+        };
+        assert!(with(97, [Some(English), None]).restores());
+        assert!(!with(47, [Some(English), None]).restores());
+        assert!(with(47, [Some(English), Some(English)]).restores());
+        assert!(!with(97, [Some(English), Some(Vietnamese)]).restores());
+        let vietnamese_run = TieBreak {
+            history: Some(English),
+            ..with(47, [Some(Vietnamese); 2])
+        };
+        assert!(!vietnamese_run.restores());
+    }
+}
@@ -4,12 +4,22 @@
 //! Shortcuts can be specific to input methods (Telex/VNI) or apply to all.
 
 use super::buffer::MAX;
-use std::collections::HashMap;
-
-/// Maximum replacement length in UTF-32 codepoints (matches Result.chars array size)
-/// This limit ensures replacement fits in the FFI result buffer.
+use super::shortcut_pack::{self, PackedShortcuts};
+use crate::prelude::*;
+use crate::text::{grapheme, remove_diacritics};
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+
+/// Maximum replacement length in UTF-32 codepoints
+/// `Result.chars` only carries the first 255; hosts using `ime_key_into` get the full text.
 /// Note: Vietnamese characters with diacritics (ồ, ế, ẫ) count as 1 codepoint each.
-pub const MAX_REPLACEMENT_LEN: usize = MAX - 1; // -1 to leave room for trailing space
+pub const MAX_REPLACEMENT_LEN: usize = 16 * MAX - 1; // -1 to leave room for trailing space
+
+/// Matching form of a trigger: composed (NFC) and lowercase, so "a\u{306}n"
+/// from a decomposing text field matches "ăn" typed with Telex
+fn normalize_trigger(trigger: &str) -> String {
+    grapheme::compose_text(trigger).to_lowercase()
+}
 
 /// Input method that shortcut applies to
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -73,10 +83,10 @@ impl Shortcut {
 
     /// Create a new shortcut with word boundary trigger (applies to all input methods)
     /// Issue #86: Case-insensitive matching, smart case output (ko→không, KO→KHÔNG, Ko→Không)
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
+    /// Replacement is truncated to MAX_REPLACEMENT_LEN (4095) codepoints if too long.
     pub fn new(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger), // Lowercase for case-insensitive matching
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::OnWordBoundary,
             case_mode: CaseMode::MatchCase, // Smart case transformation
@@ -87,10 +97,10 @@ impl Shortcut {
 
     /// Create an immediate trigger shortcut (applies to all input methods).
     /// Issue #86: Case-insensitive matching, smart case output
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
+    /// Replacement is truncated to MAX_REPLACEMENT_LEN (4095) codepoints if too long.
     pub fn immediate(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger), // Lowercase for case-insensitive matching
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
@@ -101,10 +111,10 @@ impl Shortcut {
 
     /// Create a Telex-specific shortcut with immediate trigger.
     /// Issue #86: Case-insensitive matching, smart case output
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
+    /// Replacement is truncated to MAX_REPLACEMENT_LEN (4095) codepoints if too long.
     pub fn telex(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger), // Lowercase for case-insensitive matching
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
@@ -115,10 +125,10 @@ impl Shortcut {
 
     /// Create a VNI-specific shortcut with immediate trigger.
     /// Issue #86: Case-insensitive matching, smart case output
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (255) codepoints if too long.
+    /// Replacement is truncated to MAX_REPLACEMENT_LEN (4095) codepoints if too long.
     pub fn vni(trigger: &str, replacement: &str) -> Self {
         Self {
-            trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
+            trigger: normalize_trigger(trigger), // Lowercase for case-insensitive matching
             replacement: Self::validate_replacement(replacement),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
@@ -168,16 +178,22 @@ pub struct ShortcutMatch {
 #[derive(Debug, Default)]
 pub struct ShortcutTable {
     /// Shortcuts indexed by trigger (lowercase)
-    shortcuts: HashMap<String, Shortcut>,
+    shortcuts: BTreeMap<String, Shortcut>,
     /// Sorted triggers by length (longest first) for matching
     sorted_triggers: Vec<String>,
+    /// Also match triggers with diacritics stripped ("dc" → trigger "đc")
+    ignore_diacritics: bool,
+    /// Imported library, searched after the shortcuts added here
+    library: Option<PackedShortcuts>,
 }
 
 impl ShortcutTable {
     pub fn new() -> Self {
         Self {
-            shortcuts: HashMap::new(),
+            shortcuts: BTreeMap::new(),
             sorted_triggers: vec![],
+            ignore_diacritics: false,
+            library: None,
         }
     }
 
@@ -250,6 +266,52 @@ impl ShortcutTable {
         self.lookup_for_method(buffer, InputMethod::All)
     }
 
+    /// Use a packed shortcut library (replaces any previous one)
+    ///
+    /// Entries stay packed and are decoded only when they match. Shortcuts
+    /// added with `add` take precedence over library entries with the same
+    /// trigger. Library triggers match exactly (case-insensitive), not with
+    /// diacritics stripped.
+    pub fn set_library(&mut self, library: PackedShortcuts) {
+        self.library = Some(library);
+    }
+
+    /// Remove the packed library, returning it
+    pub fn take_library(&mut self) -> Option<PackedShortcuts> {
+        self.library.take()
+    }
+
+    /// The packed library, if one is set
+    pub fn library(&self) -> Option<&PackedShortcuts> {
+        self.library.as_ref()
+    }
+
+    /// Encode the added shortcuts (not the library) into the packed format
+    pub fn to_packed(&self) -> Vec<u8> {
+        shortcut_pack::pack(self.shortcuts.values())
+    }
+
+    /// Library entry matching the whole buffer
+    fn library_lookup(&self, buffer: &str, method: InputMethod) -> Option<Shortcut> {
+        self.library
+            .as_ref()?
+            .get(&normalize_trigger(buffer))
+            .filter(|s| s.enabled && s.applies_to(method))
+    }
+
+    /// Set whether triggers also match with diacritics stripped
+    ///
+    /// With it on, "dc" typed plainly matches trigger "đc"; a trigger
+    /// that matches exactly still wins over one that only matches stripped.
+    pub fn set_ignore_diacritics(&mut self, enabled: bool) {
+        self.ignore_diacritics = enabled;
+    }
+
+    /// Get whether triggers also match with diacritics stripped
+    pub fn ignores_diacritics(&self) -> bool {
+        self.ignore_diacritics
+    }
+
     /// Check if buffer matches any shortcut for specific input method
     ///
     /// Issue #86: Case-insensitive matching - "ko", "Ko", "KO" all match trigger "ko"
@@ -259,10 +321,26 @@ impl ShortcutTable {
         buffer: &str,
         method: InputMethod,
     ) -> Option<(&str, &Shortcut)> {
-        let buffer_lower = buffer.to_lowercase();
+        let buffer_lower = normalize_trigger(buffer);
         // Longest-match-first, case-insensitive match
+        self.find_trigger(method, |trigger| buffer_lower == trigger)
+            .or_else(|| {
+                if !self.ignore_diacritics {
+                    return None;
+                }
+                let stripped = remove_diacritics(&buffer_lower);
+                self.find_trigger(method, |trigger| remove_diacritics(trigger) == stripped)
+            })
+    }
+
+    /// First enabled trigger (longest first) for `method` accepted by `matches`
+    fn find_trigger(
+        &self,
+        method: InputMethod,
+        matches: impl Fn(&str) -> bool,
+    ) -> Option<(&str, &Shortcut)> {
         for trigger in &self.sorted_triggers {
-            if buffer_lower == *trigger {
+            if matches(trigger) {
                 if let Some(shortcut) = self.shortcuts.get(trigger) {
                     if shortcut.enabled && shortcut.applies_to(method) {
                         return Some((trigger, shortcut));
@@ -308,7 +386,15 @@ impl ShortcutTable {
         is_word_boundary: bool,
         method: InputMethod,
     ) -> Option<ShortcutMatch> {
-        let (trigger, shortcut) = self.lookup_for_method(buffer, method)?;
+        let shortcut = match self.lookup_for_method(buffer, method) {
+            Some((_, shortcut)) => Cow::Borrowed(shortcut),
+            None => Cow::Owned(self.library_lookup(buffer, method)?),
+        };
+        let trigger = shortcut.trigger.as_str();
+        // Results can't delete a longer trigger (u8 backspace count)
+        if trigger.chars().count() > u8::MAX as usize {
+            return None;
+        }
 
         match shortcut.condition {
             TriggerCondition::Immediate => {
@@ -373,7 +459,7 @@ impl ShortcutTable {
     fn rebuild_sorted_triggers(&mut self) {
         self.sorted_triggers = self.shortcuts.keys().cloned().collect();
         self.sorted_triggers
-            .sort_by_key(|s| std::cmp::Reverse(s.len()));
+            .sort_by_key(|s| core::cmp::Reverse(s.len()));
     }
 
     /// Check if shortcut table is empty
@@ -381,12 +467,12 @@ impl ShortcutTable {
         self.shortcuts.is_empty()
     }
 
-    /// Get number of shortcuts
+    /// Get number of added shortcuts (library entries not counted)
     pub fn len(&self) -> usize {
         self.shortcuts.len()
     }
 
-    /// Clear all shortcuts
+    /// Clear all added shortcuts (a packed library stays, see `take_library`)
     pub fn clear(&mut self) {
         self.shortcuts.clear();
         self.sorted_triggers.clear();
@@ -683,9 +769,9 @@ mod tests {
 
     #[test]
     fn test_replacement_validation_truncation() {
-        // Create a very long replacement (>255 characters with Vietnamese)
-        // MAX_REPLACEMENT_LEN is 255, so we need more than that
-        let long_text = "Đây là một đoạn văn bản rất dài để kiểm tra việc cắt ngắn. Nó có nhiều ký tự tiếng Việt có dấu như ồ, ế, ẫ, ơ, ư. Tiếp tục thêm nhiều nội dung để vượt quá giới hạn 255 ký tự. Đây là một câu rất dài với nhiều từ tiếng Việt phức tạp để đảm bảo rằng chúng ta vượt quá giới hạn cho phép của hệ thống.";
+        // Create a very long replacement (>4095 characters with Vietnamese)
+        // MAX_REPLACEMENT_LEN is 4095, so we need more than that
+        let long_text = &"Đây là một đoạn văn bản rất dài để kiểm tra việc cắt ngắn. Nó có nhiều ký tự tiếng Việt có dấu như ồ, ế, ẫ, ơ, ư. Tiếp tục thêm nhiều nội dung để vượt quá giới hạn 4095 ký tự. Đây là một câu rất dài với nhiều từ tiếng Việt phức tạp để đảm bảo rằng chúng ta vượt quá giới hạn cho phép của hệ thống. ".repeat(16);
         let char_count = long_text.chars().count();
         assert!(
             char_count > MAX_REPLACEMENT_LEN,
@@ -949,6 +1035,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trigger_normalized_to_nfc() {
+        let table = table_with_shortcut("A\u{0306}n", "ăn cơm");
+        assert_eq!(table.lookup("ăn").map(|(t, _)| t), Some("ăn"));
+        assert_shortcut_match(
+            &table,
+            "ăn",
+            Some(' '),
+            true,
+            "ăn cơm ",
+            2,
+            InputMethod::All,
+        );
+    }
+
+    #[test]
+    fn test_trigger_length_limit() {
+        // A result deletes at most 255 chars
+        let longest = "x".repeat(255);
+        let table = table_with_immediate(&longest, "y");
+        assert_shortcut_match(&table, &longest, None, false, "y", 255, InputMethod::All);
+        let too_long = "x".repeat(256);
+        let table = table_with_immediate(&too_long, "y");
+        assert_no_match(&table, &too_long, None, false, InputMethod::All);
+    }
+
+    #[test]
+    fn test_ignore_diacritics() {
+        let mut table = table_with_shortcut("đc", "được");
+        assert!(table.lookup("dc").is_none());
+        table.set_ignore_diacritics(true);
+        assert!(table.ignores_diacritics());
+        assert_eq!(table.lookup("dc").map(|(t, _)| t), Some("đc"));
+        assert_eq!(table.lookup("DC").map(|(t, _)| t), Some("đc"));
+        assert!(table.lookup("dcx").is_none());
+    }
+
     #[test]
     fn issue343_multiline_immediate_shortcut() {
         let table = table_with_immediate("---", "━━━━━━━━━━\n");
@@ -0,0 +1,348 @@
+//! Packed Shortcut Library
+//!
+//! Binary format for large snippet sets imported from text expanders (10k+
+//! entries). The file is a sorted index followed by a string pool, so a host
+//! can map it and hand the bytes over as-is: loading only checks the header
+//! and index bounds, lookups binary-search the index in place, and only the
+//! matched entry is decoded into a `Shortcut`.
+//!
+//! ## Layout (little-endian)
+//! ```text
+//! header  "GNSP" | version u16 | reserved u16 | count u32 | pool_len u32
+//! index   count × 16-byte record, sorted by trigger bytes:
+//!         trigger_off u32 | replacement_off u32 | replacement_len u32
+//!         | trigger_len u16 | flags u8 | method u8
+//! pool    UTF-8 triggers and replacements (offsets are pool-relative)
+//! ```
+//! Flags: bit 0 immediate trigger, bit 1 exact case, bit 2 disabled.
+//! Method: 0 all, 1 Telex, 2 VNI.
+
+use super::shortcut::{CaseMode, InputMethod, Shortcut, TriggerCondition};
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+use core::cmp::Ordering;
+
+/// File signature
+pub const MAGIC: [u8; 4] = *b"GNSP";
+
+/// Format version written by `pack`
+pub const VERSION: u16 = 1;
+
+const HEADER_LEN: usize = 16;
+const RECORD_LEN: usize = 16;
+
+const FLAG_IMMEDIATE: u8 = 1 << 0;
+const FLAG_EXACT_CASE: u8 = 1 << 1;
+const FLAG_DISABLED: u8 = 1 << 2;
+
+/// Why packed bytes were rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackError {
+    /// Not a packed shortcut file
+    BadMagic,
+    /// Written by a newer format version
+    UnsupportedVersion(u16),
+    /// Shorter than its header or index says
+    Truncated,
+    /// Record points outside the string pool
+    InvalidRecord(u32),
+}
+
+/// Encode shortcuts into the packed format
+///
+/// Later shortcuts replace earlier ones with the same trigger, as
+/// `ShortcutTable::add` does.
+pub fn pack<'a>(shortcuts: impl IntoIterator<Item = &'a Shortcut>) -> Vec<u8> {
+    // str order is byte order for UTF-8, the order lookups search in
+    let mut by_trigger: BTreeMap<&str, &Shortcut> = BTreeMap::new();
+    for s in shortcuts {
+        by_trigger.insert(&s.trigger, s);
+    }
+    let entries: Vec<&Shortcut> = by_trigger.into_values().collect();
+
+    let mut index = Vec::with_capacity(entries.len() * RECORD_LEN);
+    let mut pool = Vec::new();
+    for s in &entries {
+        let trigger_off = pool.len() as u32;
+        pool.extend_from_slice(s.trigger.as_bytes());
+        let replacement_off = pool.len() as u32;
+        pool.extend_from_slice(s.replacement.as_bytes());
+
+        let mut flags = 0;
+        if s.condition == TriggerCondition::Immediate {
+            flags |= FLAG_IMMEDIATE;
+        }
+        if s.case_mode == CaseMode::Exact {
+            flags |= FLAG_EXACT_CASE;
+        }
+        if !s.enabled {
+            flags |= FLAG_DISABLED;
+        }
+        let method = match s.input_method {
+            InputMethod::All => 0,
+            InputMethod::Telex => 1,
+            InputMethod::Vni => 2,
+        };
+        index.extend_from_slice(&trigger_off.to_le_bytes());
+        index.extend_from_slice(&replacement_off.to_le_bytes());
+        index.extend_from_slice(&(s.replacement.len() as u32).to_le_bytes());
+        index.extend_from_slice(&(s.trigger.len() as u16).to_le_bytes());
+        index.push(flags);
+        index.push(method);
+    }
+
+    let mut out = Vec::with_capacity(HEADER_LEN + index.len() + pool.len());
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    out.extend_from_slice(&(pool.len() as u32).to_le_bytes());
+    out.extend_from_slice(&index);
+    out.extend_from_slice(&pool);
+    out
+}
+
+fn read_u16(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+fn read_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+/// Packed bytes, owned or borrowed for the program's lifetime
+enum Bytes {
+    Owned(Vec<u8>),
+    Static(&'static [u8]),
+}
+
+/// Read-only shortcut library over packed bytes
+pub struct PackedShortcuts {
+    bytes: Bytes,
+    count: usize,
+}
+
+impl core::fmt::Debug for PackedShortcuts {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PackedShortcuts")
+            .field("count", &self.count)
+            .field("bytes", &self.data().len())
+            .finish()
+    }
+}
+
+impl PackedShortcuts {
+    /// Load from owned bytes (a file read into memory)
+    pub fn from_vec(data: Vec<u8>) -> core::result::Result<Self, PackError> {
+        let count = Self::check(&data)?;
+        Ok(Self {
+            bytes: Bytes::Owned(data),
+            count,
+        })
+    }
+
+    /// Load from bytes that outlive the library: embedded with
+    /// `include_bytes!` or mapped for the rest of the process
+    pub fn from_static(data: &'static [u8]) -> core::result::Result<Self, PackError> {
+        let count = Self::check(data)?;
+        Ok(Self {
+            bytes: Bytes::Static(data),
+            count,
+        })
+    }
+
+    /// Validate header and record bounds without decoding any entry
+    fn check(data: &[u8]) -> core::result::Result<usize, PackError> {
+        if data.len() < HEADER_LEN {
+            return Err(if data.starts_with(&MAGIC[..data.len().min(4)]) {
+                PackError::Truncated
+            } else {
+                PackError::BadMagic
+            });
+        }
+        if data[..4] != MAGIC {
+            return Err(PackError::BadMagic);
+        }
+        let version = read_u16(data, 4);
+        if version > VERSION {
+            return Err(PackError::UnsupportedVersion(version));
+        }
+        let count = read_u32(data, 8) as usize;
+        let pool_len = read_u32(data, 12) as usize;
+        let total = count
+            .checked_mul(RECORD_LEN)
+            .and_then(|index| index.checked_add(pool_len))
+            .and_then(|n| n.checked_add(HEADER_LEN));
+        if total.is_none_or(|total| data.len() < total) {
+            return Err(PackError::Truncated);
+        }
+        let in_pool = |off: u32, len: usize| {
+            (off as usize)
+                .checked_add(len)
+                .is_some_and(|end| end <= pool_len)
+        };
+        for i in 0..count {
+            let at = HEADER_LEN + i * RECORD_LEN;
+            if !in_pool(read_u32(data, at), read_u16(data, at + 12) as usize)
+                || !in_pool(read_u32(data, at + 4), read_u32(data, at + 8) as usize)
+            {
+                return Err(PackError::InvalidRecord(i as u32));
+            }
+        }
+        Ok(count)
+    }
+
+    fn data(&self) -> &[u8] {
+        match &self.bytes {
+            Bytes::Owned(v) => v,
+            Bytes::Static(s) => s,
+        }
+    }
+
+    /// Number of shortcuts in the library
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    fn pool(&self) -> &[u8] {
+        &self.data()[HEADER_LEN + self.count * RECORD_LEN..]
+    }
+
+    fn record(&self, i: usize) -> &[u8] {
+        let at = HEADER_LEN + i * RECORD_LEN;
+        &self.data()[at..at + RECORD_LEN]
+    }
+
+    fn trigger_bytes(&self, i: usize) -> &[u8] {
+        let r = self.record(i);
+        let off = read_u32(r, 0) as usize;
+        &self.pool()[off..off + read_u16(r, 12) as usize]
+    }
+
+    /// Decode entry `i`; None if its text isn't valid UTF-8
+    fn decode(&self, i: usize) -> Option<Shortcut> {
+        let r = self.record(i);
+        let off = read_u32(r, 4) as usize;
+        let replacement =
+            core::str::from_utf8(&self.pool()[off..off + read_u32(r, 8) as usize]).ok()?;
+        let trigger = core::str::from_utf8(self.trigger_bytes(i)).ok()?;
+        let flags = r[14];
+        Some(Shortcut {
+            trigger: trigger.to_string(),
+            replacement: replacement.to_string(),
+            condition: if flags & FLAG_IMMEDIATE != 0 {
+                TriggerCondition::Immediate
+            } else {
+                TriggerCondition::OnWordBoundary
+            },
+            case_mode: if flags & FLAG_EXACT_CASE != 0 {
+                CaseMode::Exact
+            } else {
+                CaseMode::MatchCase
+            },
+            enabled: flags & FLAG_DISABLED == 0,
+            input_method: match r[15] {
+                1 => InputMethod::Telex,
+                2 => InputMethod::Vni,
+                _ => InputMethod::All,
+            },
+        })
+    }
+
+    /// Shortcut with exactly this (normalized, lowercase) trigger
+    pub fn get(&self, trigger: &str) -> Option<Shortcut> {
+        let (mut lo, mut hi) = (0, self.count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match self.trigger_bytes(mid).cmp(trigger.as_bytes()) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return self.decode(mid),
+            }
+        }
+        None
+    }
+
+    /// Decode every entry, in trigger order
+    pub fn iter(&self) -> impl Iterator<Item = Shortcut> + '_ {
+        (0..self.count).filter_map(|i| self.decode(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Shortcut> {
+        vec![
+            Shortcut::new("vn", "Việt Nam"),
+            Shortcut::immediate("->", "→"),
+            Shortcut::telex("ko", "không"),
+            Shortcut::new("hn", "Hà Nội"),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let lib = PackedShortcuts::from_vec(pack(&sample())).unwrap();
+        assert_eq!(lib.len(), 4);
+        let ko = lib.get("ko").unwrap();
+        assert_eq!(ko.replacement, "không");
+        assert_eq!(ko.condition, TriggerCondition::Immediate);
+        assert_eq!(ko.input_method, InputMethod::Telex);
+        assert_eq!(lib.get("->").unwrap().replacement, "→");
+        assert_eq!(
+            lib.get("vn").unwrap().condition,
+            TriggerCondition::OnWordBoundary
+        );
+        assert!(lib.get("v").is_none());
+        assert!(lib.get("zz").is_none());
+        let triggers: Vec<String> = lib.iter().map(|s| s.trigger).collect();
+        assert_eq!(triggers, ["->", "hn", "ko", "vn"]);
+    }
+
+    #[test]
+    fn test_later_duplicate_wins() {
+        let shortcuts = [Shortcut::new("vn", "VN"), Shortcut::new("vn", "Việt Nam")];
+        let lib = PackedShortcuts::from_vec(pack(&shortcuts)).unwrap();
+        assert_eq!(lib.len(), 1);
+        assert_eq!(lib.get("vn").unwrap().replacement, "Việt Nam");
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let bytes = pack(&sample());
+        assert_eq!(
+            PackedShortcuts::from_vec(b"nope".to_vec()).unwrap_err(),
+            PackError::BadMagic
+        );
+        assert_eq!(
+            PackedShortcuts::from_vec(bytes[..bytes.len() - 1].to_vec()).unwrap_err(),
+            PackError::Truncated
+        );
+        let mut newer = bytes.clone();
+        newer[4] = 9;
+        assert_eq!(
+            PackedShortcuts::from_vec(newer).unwrap_err(),
+            PackError::UnsupportedVersion(9)
+        );
+        let mut bad = bytes;
+        bad[HEADER_LEN..HEADER_LEN + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            PackedShortcuts::from_vec(bad).unwrap_err(),
+            PackError::InvalidRecord(0)
+        );
+    }
+
+    #[test]
+    fn test_static_bytes() {
+        static EMPTY: [u8; 16] = *b"GNSP\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+        let lib = PackedShortcuts::from_static(&EMPTY).unwrap();
+        assert!(lib.is_empty());
+        assert!(lib.get("vn").is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! Commit-time Spell Check
+//!
+//! Optional pass run when a word is committed (space/punctuation). A word is
+//! flagged when it is neither a Vietnamese dictionary syllable, an English
+//! dictionary word, nor a user word. Flags carry suggestions so hosts can
+//! underline the word and offer a fix.
+//!
+//! ## Suggestions
+//! - Vietnamese syllables with the same letters but other diacritics
+//!   ("bóm" → "bom", "bỏm", ...), sorted in dictionary order
+//! - The ASCII form when it is an English word ("màp" → "map")
+
+use crate::data::{dictionary, english_dict};
+use crate::prelude::*;
+use crate::text::{collate, remove_diacritics};
+use alloc::collections::BTreeSet;
+
+/// Maximum suggestions per flagged word
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// A committed word that failed spell check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpellFlag {
+    /// The committed word as it appears on screen
+    pub word: String,
+    /// Replacement candidates, best first (may be empty)
+    pub suggestions: Vec<String>,
+}
+
+/// Check one committed word
+///
+/// Returns `None` for known words and for tokens that aren't plain words
+/// (digits, symbols), which spell check doesn't judge.
+pub fn check(word: &str, user_words: &BTreeSet<String>) -> Option<SpellFlag> {
+    if word.is_empty() || !word.chars().all(char::is_alphabetic) {
+        return None;
+    }
+    let lower = word.to_lowercase();
+    if dictionary::is_vietnamese(&lower, true)
+        || english_dict::is_english_word(&lower)
+        || user_words.contains(&lower)
+    {
+        return None;
+    }
+    Some(SpellFlag {
+        word: word.to_string(),
+        suggestions: suggest(&lower),
+    })
+}
+
+/// Suggestions for an unknown lowercase word
+fn suggest(lower: &str) -> Vec<String> {
+    let skeleton = remove_diacritics(lower);
+    let mut vietnamese: Vec<&str> = dictionary::words()
+        .filter(|w| *w != lower && remove_diacritics(w) == skeleton)
+        .collect();
+    collate::sort(&mut vietnamese);
+
+    let mut out: Vec<String> = Vec::new();
+    if skeleton != lower && english_dict::is_english_word(&skeleton) {
+        out.push(skeleton);
+    }
+    out.extend(vietnamese.into_iter().map(str::to_string));
+    out.truncate(MAX_SUGGESTIONS);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_words_pass() {
+        let user = BTreeSet::new();
+        assert_eq!(check("việt", &user), None);
+        assert_eq!(check("Tiếng", &user), None);
+        assert_eq!(check("hello", &user), None);
+        assert_eq!(check("123", &user), None);
+    }
+
+    #[test]
+    fn test_unknown_word_flagged_with_suggestions() {
+        let user = BTreeSet::new();
+        let flag = check("viẹt", &user).unwrap();
+        assert_eq!(flag.word, "viẹt");
+        assert!(flag.suggestions.contains(&"việt".to_string()));
+        assert!(flag.suggestions.len() <= MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn test_user_words() {
+        let mut user = BTreeSet::new();
+        assert!(check("gonhanh", &user).is_some());
+        user.insert("gonhanh".to_string());
+        assert_eq!(check("GoNhanh", &user), None);
+    }
+}
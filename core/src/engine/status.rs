@@ -0,0 +1,69 @@
+//! Engine Status Snapshot
+//!
+//! Read-only view of the composing state for status items and floating hints,
+//! so hosts don't have to reconstruct it from `Result`s.
+
+use crate::prelude::*;
+
+/// What the engine is doing with the current input
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputMode {
+    /// Vietnamese input active, current word (if any) is valid Vietnamese
+    Vietnamese = 0,
+    /// Vietnamese input disabled (keys pass through, shortcuts still work)
+    English = 1,
+    /// Vietnamese input active but the current word isn't Vietnamese
+    /// (will be left as typed or auto-restored on commit)
+    Foreign = 2,
+}
+
+/// Why Vietnamese input is off (see `Engine::set_enabled_for`)
+///
+/// Each reason is tracked on its own: input is on only when none holds, so
+/// leaving a password field doesn't undo the user's own toggle.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DisableReason {
+    /// The user turned input off (menu, hotkey, double Shift)
+    User = 1,
+    /// The host's per-app rules turn input off in the focused app
+    AppPolicy = 2,
+    /// Focus is in a password or other secure field
+    SecureField = 3,
+    /// The focused field takes an address or URL (see `FieldKind`)
+    Field = 4,
+}
+
+impl DisableReason {
+    /// From least to most specific
+    pub const ALL: [Self; 4] = [Self::User, Self::AppPolicy, Self::Field, Self::SecureField];
+
+    /// Reason for an FFI code (1..=4)
+    pub fn from_u8(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| *r as u8 == code)
+    }
+
+    pub(crate) fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+/// Snapshot returned by `Engine::status()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineStatus {
+    /// Composing word as displayed ("việt")
+    pub current_word: String,
+    /// Keystrokes of the composing word ("vieetj")
+    pub raw_word: String,
+    /// Current input mode
+    pub mode: InputMode,
+    /// Why input is off, if it is (see `Engine::disable_reason`)
+    pub disable_reason: Option<DisableReason>,
+    /// Input method (0=Telex, 1=VNI)
+    pub method: u8,
+    /// Next letter will be auto-capitalized
+    pub pending_capitalize: bool,
+    /// Accumulated symbol prefix for shortcuts ("#", "->")
+    pub shortcut_prefix: String,
+}
@@ -8,6 +8,7 @@
 
 use crate::data::constants;
 use crate::data::keys;
+use crate::prelude::*;
 
 /// Parsed syllable structure
 #[derive(Debug, Clone, Default)]
@@ -0,0 +1,62 @@
+//! Key Timing
+//!
+//! A keycode alone can't say whether a key was held down or pressed twice,
+//! or whether two spaces came in a quick double tap. `Engine::on_key_at`
+//! takes the event's timestamp so the engine can tell:
+//!
+//! - auto-repeat: a held letter types itself again instead of toggling a
+//!   transform ("aaaa", not "â" flipping back and forth)
+//! - double space: two quick spaces after a word type ". " (opt-in)
+//! - double Shift: two quick Shift taps toggle Vietnamese input (opt-in)
+//! - idle commit: a word left alone for a while is finished, so a stray
+//!   tone key minutes later starts a new word instead of editing it (opt-in)
+
+/// Time windows and opt-in rules of `Engine::on_key_at`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyTiming {
+    /// The same letter again within this many ms is an auto-repeat
+    /// (macOS repeats every 30-120 ms; deliberate double presses are slower)
+    pub repeat_ms: u32,
+    /// Window for the second space of a double space
+    pub double_space_ms: u32,
+    /// Window for the second Shift tap of a double Shift
+    pub double_shift_ms: u32,
+    /// Two quick spaces after a word type ". "
+    pub double_space_period: bool,
+    /// Two quick Shift taps toggle Vietnamese input
+    pub double_shift_toggle: bool,
+    /// Finish the word being typed when the next key comes more than this
+    /// many ms later (0 = never)
+    pub idle_commit_ms: u32,
+}
+
+impl Default for KeyTiming {
+    fn default() -> Self {
+        Self {
+            repeat_ms: 50,
+            double_space_ms: 400,
+            double_shift_ms: 300,
+            double_space_period: false,
+            double_shift_toggle: false,
+            idle_commit_ms: 0,
+        }
+    }
+}
+
+/// `now` is at most `window_ms` after `earlier` (clocks going backwards never match)
+pub(crate) fn within(earlier: u64, now: u64, window_ms: u32) -> bool {
+    now >= earlier && now - earlier <= window_ms as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within() {
+        assert!(within(100, 100, 0));
+        assert!(within(100, 150, 50));
+        assert!(!within(100, 151, 50));
+        assert!(!within(200, 100, 50));
+    }
+}
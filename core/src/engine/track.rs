@@ -0,0 +1,100 @@
+//! Transform Track
+//!
+//! What the engine changed in the composing word and which key did it:
+//! "việt" is a circumflex on 'e' from the second `e` and a nặng from `j`.
+//! Tutors explain it, trace exporters log it, and tests assert on it instead
+//! of comparing rendered strings.
+
+/// Diacritic added to a letter of the composing word
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransformKind {
+    /// Circumflex: â, ê, ô
+    Circumflex,
+    /// Horn: ơ, ư
+    Horn,
+    /// Breve: ă
+    Breve,
+    /// Tone mark, a `chars::mark` value (1=sắc … 5=nặng)
+    Mark(u8),
+    /// Stroke: đ
+    Stroke,
+}
+
+/// One transform on the composing word (see `Engine::transforms`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AppliedTransform {
+    /// Letter index in the word
+    pub pos: usize,
+    pub kind: TransformKind,
+    /// Key of the current input method that applies it (`keys::*`)
+    pub key: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{chars::mark, keys};
+    use crate::engine::Engine;
+    use crate::utils::type_word;
+
+    fn track(method: u8, input: &str) -> Vec<(usize, TransformKind, u16)> {
+        let mut e = Engine::new();
+        e.set_method(method);
+        type_word(&mut e, input);
+        e.transforms()
+            .into_iter()
+            .map(|t| (t.pos, t.kind, t.key))
+            .collect()
+    }
+
+    #[test]
+    fn test_telex_track() {
+        assert_eq!(
+            track(0, "vieetj"),
+            [
+                (2, TransformKind::Circumflex, keys::E),
+                (2, TransformKind::Mark(mark::NANG), keys::J),
+            ]
+        );
+        assert_eq!(
+            track(0, "dduowcj"),
+            [
+                (0, TransformKind::Stroke, keys::D),
+                (1, TransformKind::Horn, keys::W),
+                (2, TransformKind::Horn, keys::W),
+                (2, TransformKind::Mark(mark::NANG), keys::J),
+            ]
+        );
+        assert_eq!(track(0, "awn"), [(0, TransformKind::Breve, keys::W)]);
+        assert!(track(0, "xin").is_empty());
+    }
+
+    #[test]
+    fn test_vni_track() {
+        assert_eq!(
+            track(1, "vie65t"),
+            [
+                (2, TransformKind::Circumflex, keys::N6),
+                (2, TransformKind::Mark(mark::NANG), keys::N5),
+            ]
+        );
+        assert_eq!(
+            track(1, "d9a8n"),
+            [
+                (0, TransformKind::Stroke, keys::N9),
+                (1, TransformKind::Breve, keys::N8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_track_follows_reverts() {
+        // "ass" reverts the sắc: nothing left to report
+        assert!(track(0, "ass").is_empty());
+        let mut e = Engine::new();
+        type_word(&mut e, "as");
+        assert_eq!(e.transforms().len(), 1);
+        e.clear();
+        assert!(e.transforms().is_empty());
+    }
+}
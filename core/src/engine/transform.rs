@@ -9,6 +9,7 @@ use crate::data::{
     keys,
     vowel::Phonology,
 };
+use crate::prelude::*;
 use crate::utils;
 
 /// Modifier type detected from key
@@ -24,6 +25,35 @@ pub enum ModifierType {
     Remove,
 }
 
+/// What a tone or mark key does when it changes nothing in the word
+///
+/// Either the diacritic is already there ("ươ" + w, "u7o7" + 7) or the key
+/// has nothing to act on ("việt" + w, VNI "ba" + 6).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneKeyPolicy {
+    /// Swallow a key that repeats a diacritic already there; type any other
+    /// as-is
+    #[default]
+    Smart = 0,
+    /// Always type the key as-is: nothing the user types disappears
+    Literal = 1,
+    /// Also swallow modifier and mark keys with nothing to act on once the
+    /// word has a vowel (Telex vowel keys a/e/o are still typed)
+    Consume = 2,
+}
+
+impl ToneKeyPolicy {
+    /// Policy from its FFI value (unknown values fall back to Smart)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Literal,
+            2 => Self::Consume,
+            _ => Self::Smart,
+        }
+    }
+}
+
 /// Transformation result
 #[derive(Debug)]
 pub struct TransformResult {
@@ -7,6 +7,7 @@ use super::syllable::{parse, Syllable};
 use crate::data::chars::tone;
 use crate::data::constants;
 use crate::data::keys;
+use crate::prelude::*;
 
 /// Validation result
 #[derive(Debug, Clone, PartialEq)]
@@ -25,6 +26,40 @@ impl ValidationResult {
     }
 }
 
+/// How much non-standard spelling validation accepts as Vietnamese
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationProfile {
+    /// Standard Vietnamese only: no foreign initials, no minority clusters (Krông, Đắk)
+    Strict = 0,
+    /// Standard Vietnamese plus ethnic minority place names
+    #[default]
+    Standard = 1,
+    /// Also accept foreign initials (z, w, j, f) for loanwords and names
+    Tolerant = 2,
+}
+
+impl ValidationProfile {
+    /// Profile from its FFI value (unknown values fall back to Standard)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Strict,
+            2 => Self::Tolerant,
+            _ => Self::Standard,
+        }
+    }
+
+    /// Foreign consonants (z, w, j, f) are valid initials
+    pub fn allows_foreign_initials(self) -> bool {
+        self == Self::Tolerant
+    }
+
+    /// Ethnic minority clusters (kr-, -k) are valid
+    pub fn allows_minority_clusters(self) -> bool {
+        self != Self::Strict
+    }
+}
+
 // =============================================================================
 // BUFFER SNAPSHOT - Keys + Modifiers for validation
 // =============================================================================
@@ -37,8 +72,8 @@ pub struct BufferSnapshot {
     /// True when tones were explicitly provided (validate modifier requirements)
     /// False when created from keys-only (legacy, skip modifier checks)
     pub has_tone_info: bool,
-    /// True when foreign consonants (z, w, j, f) are allowed as valid initials
-    pub allow_foreign_consonants: bool,
+    /// Which non-standard spellings are accepted
+    pub profile: ValidationProfile,
 }
 
 impl BufferSnapshot {
@@ -50,22 +85,36 @@ impl BufferSnapshot {
             keys,
             tones: vec![0; len],
             has_tone_info: false,
-            allow_foreign_consonants: false,
+            profile: ValidationProfile::Standard,
         }
     }
 
     /// Create from keys with foreign consonants setting
     pub fn from_keys_with_foreign(keys: Vec<u16>, allow_foreign_consonants: bool) -> Self {
+        Self::from_keys_with_profile(keys, foreign_profile(allow_foreign_consonants))
+    }
+
+    /// Create from keys with a validation profile
+    pub fn from_keys_with_profile(keys: Vec<u16>, profile: ValidationProfile) -> Self {
         let len = keys.len();
         Self {
             keys,
             tones: vec![0; len],
             has_tone_info: false,
-            allow_foreign_consonants,
+            profile,
         }
     }
 }
 
+/// Profile equivalent to the legacy foreign consonants flag
+fn foreign_profile(allow_foreign_consonants: bool) -> ValidationProfile {
+    if allow_foreign_consonants {
+        ValidationProfile::Tolerant
+    } else {
+        ValidationProfile::Standard
+    }
+}
+
 // =============================================================================
 // VALIDATION RULES
 // =============================================================================
@@ -81,6 +130,7 @@ const RULES: &[Rule] = &[
     rule_spelling,
     rule_valid_final,
     rule_valid_vowel_pattern,
+    rule_breve_final,
 ];
 
 /// Rule 1: Must have at least one vowel
@@ -102,12 +152,14 @@ fn rule_valid_initial(snap: &BufferSnapshot, syllable: &Syllable) -> Option<Vali
     let is_valid = match initial.len() {
         1 => {
             constants::VALID_INITIALS_1.contains(&initial[0])
-                || (snap.allow_foreign_consonants
+                || (snap.profile.allows_foreign_initials()
                     && constants::FOREIGN_INITIALS.contains(&initial[0]))
         }
-        2 => constants::VALID_INITIALS_2
-            .iter()
-            .any(|p| p[0] == initial[0] && p[1] == initial[1]),
+        2 => {
+            constants::INITIAL_PAIRS.contains(initial[0], initial[1])
+                && (snap.profile.allows_minority_clusters()
+                    || !constants::MINORITY_INITIALS_2.contains(&[initial[0], initial[1]]))
+        }
         3 => initial[0] == keys::N && initial[1] == keys::G && initial[2] == keys::H,
         _ => false,
     };
@@ -158,10 +210,12 @@ fn rule_valid_final(snap: &BufferSnapshot, syllable: &Syllable) -> Option<Valida
     let final_c: Vec<u16> = syllable.final_c.iter().map(|&i| snap.keys[i]).collect();
 
     let is_valid = match final_c.len() {
-        1 => constants::VALID_FINALS_1.contains(&final_c[0]),
-        2 => constants::VALID_FINALS_2
-            .iter()
-            .any(|p| p[0] == final_c[0] && p[1] == final_c[1]),
+        1 => {
+            constants::VALID_FINALS_1.contains(&final_c[0])
+                && (snap.profile.allows_minority_clusters()
+                    || !constants::MINORITY_FINALS_1.contains(&final_c[0]))
+        }
+        2 => constants::FINAL_PAIRS.contains(final_c[0], final_c[1]),
         _ => false,
     };
 
@@ -272,6 +326,27 @@ fn rule_valid_vowel_pattern(
     None
 }
 
+/// Rule 7: Breve (ă) only closes with c, m, n, ng, p, t ("ănh", "ăch" invalid)
+///
+/// Only enforced with tone info, since the breve is a modifier.
+fn rule_breve_final(snap: &BufferSnapshot, syllable: &Syllable) -> Option<ValidationResult> {
+    if !snap.has_tone_info || syllable.final_c.len() != 2 {
+        return None;
+    }
+    let has_breve = syllable
+        .vowel
+        .iter()
+        .any(|&i| snap.keys[i] == keys::A && snap.tones[i] == tone::HORN);
+    let final_c = [
+        snap.keys[syllable.final_c[0]],
+        snap.keys[syllable.final_c[1]],
+    ];
+    if has_breve && constants::BREVE_INVALID_FINALS.contains(&final_c) {
+        return Some(ValidationResult::InvalidFinal);
+    }
+    None
+}
+
 // =============================================================================
 // PUBLIC API
 // =============================================================================
@@ -293,6 +368,42 @@ pub fn validate(snap: &BufferSnapshot) -> ValidationResult {
     ValidationResult::Valid
 }
 
+/// One tone mark per syllable: a mark may go on `pos` only if no other char
+/// already carries one (a mark already on `pos` is replaced, not stacked)
+///
+/// Blocks "gíi" + 's' → "gíí"; the key is typed as a letter instead.
+pub fn is_valid_mark_placement(marks: &[u8], pos: usize) -> bool {
+    marks.iter().enumerate().all(|(i, &m)| i == pos || m == 0)
+}
+
+/// Check if raw keystrokes end in a doubled circumflex vowel + final listed
+/// in `CIRCUMFLEX_CLOSED_ENGLISH` ("keep" → "kêp" reads as English)
+pub fn is_circumflex_closed_english(raw_keys: &[u16]) -> bool {
+    let [.., v1, v2, last] = raw_keys else {
+        return false;
+    };
+    if v1 != v2 {
+        return false;
+    }
+    let before = raw_keys.len().checked_sub(4).map(|i| raw_keys[i]);
+    constants::CIRCUMFLEX_CLOSED_ENGLISH
+        .iter()
+        .any(|&(nucleus, final_key, exceptions)| {
+            *v1 == nucleus && *last == final_key && !before.is_some_and(|k| exceptions.contains(&k))
+        })
+}
+
+/// Check if a breve (ă) sits before a final it cannot take ("ănh", "ăch")
+pub fn has_invalid_breve_final(keys: &[u16], tones: &[u8]) -> bool {
+    let snap = BufferSnapshot {
+        keys: keys.to_vec(),
+        tones: tones.to_vec(),
+        has_tone_info: true,
+        profile: ValidationProfile::Standard,
+    };
+    rule_breve_final(&snap, &parse(keys)).is_some()
+}
+
 /// Quick check if buffer could be valid Vietnamese (with modifier info)
 /// This will fully validate modifier requirements (e.g., E+U requires circumflex)
 pub fn is_valid_with_tones(keys: &[u16], tones: &[u8]) -> bool {
@@ -300,7 +411,7 @@ pub fn is_valid_with_tones(keys: &[u16], tones: &[u8]) -> bool {
         keys: keys.to_vec(),
         tones: tones.to_vec(),
         has_tone_info: true, // Enforce modifier requirements
-        allow_foreign_consonants: false,
+        profile: ValidationProfile::Standard,
     };
     validate(&snap).is_valid()
 }
@@ -310,12 +421,21 @@ pub fn is_valid_with_tones_and_foreign(
     keys: &[u16],
     tones: &[u8],
     allow_foreign_consonants: bool,
+) -> bool {
+    is_valid_with_tones_and_profile(keys, tones, foreign_profile(allow_foreign_consonants))
+}
+
+/// Quick check if buffer could be valid Vietnamese (with modifier info) under a profile
+pub fn is_valid_with_tones_and_profile(
+    keys: &[u16],
+    tones: &[u8],
+    profile: ValidationProfile,
 ) -> bool {
     let snap = BufferSnapshot {
         keys: keys.to_vec(),
         tones: tones.to_vec(),
         has_tone_info: true,
-        allow_foreign_consonants,
+        profile,
     };
     validate(&snap).is_valid()
 }
@@ -331,8 +451,12 @@ pub fn is_valid(buffer_keys: &[u16]) -> bool {
 
 /// Quick check if buffer could be valid Vietnamese with foreign consonants option
 pub fn is_valid_with_foreign(buffer_keys: &[u16], allow_foreign_consonants: bool) -> bool {
-    let snap =
-        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants);
+    is_valid_with_profile(buffer_keys, foreign_profile(allow_foreign_consonants))
+}
+
+/// Quick check if buffer could be valid Vietnamese under a validation profile
+pub fn is_valid_with_profile(buffer_keys: &[u16], profile: ValidationProfile) -> bool {
+    let snap = BufferSnapshot::from_keys_with_profile(buffer_keys.to_vec(), profile);
     validate(&snap).is_valid()
 }
 
@@ -360,13 +484,20 @@ pub fn is_valid_for_transform(buffer_keys: &[u16]) -> bool {
 pub fn is_valid_for_transform_with_foreign(
     buffer_keys: &[u16],
     allow_foreign_consonants: bool,
+) -> bool {
+    is_valid_for_transform_with_profile(buffer_keys, foreign_profile(allow_foreign_consonants))
+}
+
+/// Pre-transformation validation under a validation profile
+pub fn is_valid_for_transform_with_profile(
+    buffer_keys: &[u16],
+    profile: ValidationProfile,
 ) -> bool {
     if buffer_keys.is_empty() {
         return false;
     }
 
-    let snap =
-        BufferSnapshot::from_keys_with_foreign(buffer_keys.to_vec(), allow_foreign_consonants);
+    let snap = BufferSnapshot::from_keys_with_profile(buffer_keys.to_vec(), profile);
     let syllable = parse(&snap.keys);
 
     for rule in RULES_FOR_TRANSFORM {
@@ -378,6 +509,30 @@ pub fn is_valid_for_transform_with_foreign(
     true
 }
 
+/// Whether the buffer is a Vietnamese syllable but for its final ("gel",
+/// "mail"): initial and vowels are valid, the consonants after the last
+/// vowel aren't a Vietnamese final
+///
+/// The g/gh and c/k spelling rules are not applied: loanwords keep their
+/// own spelling.
+pub fn has_foreign_final(buffer_keys: &[u16], profile: ValidationProfile) -> bool {
+    let Some(last_vowel) = buffer_keys.iter().rposition(|&k| keys::is_vowel(k)) else {
+        return false;
+    };
+    if last_vowel + 1 == buffer_keys.len() {
+        return false;
+    }
+    let passes = |part: &[u16], rules: &[Rule]| {
+        let snap = BufferSnapshot::from_keys_with_profile(part.to_vec(), profile);
+        let syllable = parse(&snap.keys);
+        rules.iter().all(|rule| rule(&snap, &syllable).is_none())
+    };
+    passes(
+        &buffer_keys[..=last_vowel],
+        &[rule_has_vowel, rule_valid_initial, rule_all_chars_parsed],
+    ) && !passes(buffer_keys, &[rule_all_chars_parsed, rule_valid_final])
+}
+
 /// Check if the buffer shows patterns that suggest foreign word input.
 ///
 /// This is a heuristic to detect when the user is likely typing a foreign word
@@ -508,8 +663,7 @@ pub fn is_foreign_word_pattern(
             }
             2 => {
                 // Valid double finals: CH, NG, NH
-                let pair = [finals[0], finals[1]];
-                !constants::VALID_FINALS_2.contains(&pair)
+                !constants::FINAL_PAIRS.contains(finals[0], finals[1])
             }
             _ => true, // 3+ consonants after vowel is always invalid Vietnamese
         };
@@ -673,4 +827,68 @@ mod tests {
             "'ăi' should be invalid"
         );
     }
+
+    #[test]
+    fn test_mark_placement_one_per_syllable() {
+        use crate::data::chars::mark;
+        assert!(is_valid_mark_placement(&[0, 0, 0], 2));
+        // Replacing the mark on the same vowel
+        assert!(is_valid_mark_placement(&[0, mark::SAC, 0], 1));
+        // "gíi": a second mark on the last 'i' would stack
+        assert!(!is_valid_mark_placement(&[0, mark::SAC, 0], 2));
+    }
+
+    #[test]
+    fn test_breve_invalid_final() {
+        let breve = |word: &str| {
+            let keys = keys_from_str(word);
+            let tones: Vec<u8> = keys
+                .iter()
+                .map(|&k| if k == keys::A { tone::HORN } else { 0 })
+                .collect();
+            (keys, tones)
+        };
+        for word in ["anh", "tach", "banh"] {
+            let (keys, tones) = breve(word);
+            assert!(has_invalid_breve_final(&keys, &tones), "{word}");
+            assert!(!is_valid_with_tones(&keys, &tones), "{word}");
+        }
+        for word in ["an", "ang", "tac", "bam"] {
+            let (keys, tones) = breve(word);
+            assert!(!has_invalid_breve_final(&keys, &tones), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_circumflex_closed_english() {
+        for raw in ["keep", "deep", "sleep", "eep"] {
+            assert!(is_circumflex_closed_english(&keys_from_str(raw)), "{raw}");
+        }
+        // Vietnamese: iêp, xêp; other nuclei; tone after the final
+        for raw in ["hieep", "xeep", "caap", "hoop", "keeps", "kep", "ep"] {
+            assert!(!is_circumflex_closed_english(&keys_from_str(raw)), "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_validation_profiles() {
+        use ValidationProfile::*;
+        // (word, strict, standard, tolerant)
+        let cases = [
+            ("ba", true, true, true),
+            ("krong", false, true, true),
+            ("dak", false, true, true),
+            ("za", false, false, true),
+            ("fan", false, false, true),
+            ("john", false, false, false),
+        ];
+        for (word, strict, standard, tolerant) in cases {
+            let keys = keys_from_str(word);
+            assert_eq!(is_valid_with_profile(&keys, Strict), strict, "{word}");
+            assert_eq!(is_valid_with_profile(&keys, Standard), standard, "{word}");
+            assert_eq!(is_valid_with_profile(&keys, Tolerant), tolerant, "{word}");
+        }
+        assert_eq!(ValidationProfile::from_u8(0), Strict);
+        assert_eq!(ValidationProfile::from_u8(9), Standard);
+    }
 }
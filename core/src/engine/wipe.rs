@@ -0,0 +1,61 @@
+//! Zeroizing Wipe
+//!
+//! Clearing a `Vec` or `String` only resets its length: the typed text stays
+//! in the allocation until something overwrites it, and a plain overwrite
+//! right before a free is the kind of store the optimizer removes. These
+//! helpers overwrite the whole allocation with volatile writes, so
+//! `Engine::wipe` leaves no typed keys behind in the engine's memory.
+
+use crate::prelude::*;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// Overwrite every item with `value`
+pub(crate) fn zero_slice<T: Copy>(items: &mut [T], value: T) {
+    for item in items.iter_mut() {
+        // SAFETY: `item` is a valid, aligned, exclusive reference
+        unsafe { core::ptr::write_volatile(item, value) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Overwrite the whole allocation, spare capacity included, then clear
+pub(crate) fn zero_vec<T: Copy>(items: &mut Vec<T>, value: T) {
+    let ptr = items.as_mut_ptr();
+    for i in 0..items.capacity() {
+        // SAFETY: within the allocation; `T: Copy` has no drop to skip
+        unsafe { core::ptr::write_volatile(ptr.add(i), value) };
+    }
+    compiler_fence(Ordering::SeqCst);
+    items.clear();
+}
+
+/// Overwrite the whole allocation with NULs, then clear
+pub(crate) fn zero_string(text: &mut String) {
+    // SAFETY: NUL bytes keep the string valid UTF-8
+    zero_vec(unsafe { text.as_mut_vec() }, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_vec_covers_capacity() {
+        let mut v = vec![7u16; 8];
+        v.truncate(2);
+        zero_vec(&mut v, 0);
+        assert!(v.is_empty());
+        // SAFETY: the whole capacity was written above
+        let all = unsafe { core::slice::from_raw_parts(v.as_ptr(), v.capacity()) };
+        assert!(all.iter().all(|&x| x == 0));
+    }
+
+    #[test]
+    fn test_zero_string() {
+        let mut s = String::from("mật khẩu");
+        zero_string(&mut s);
+        assert!(s.is_empty());
+        let all = unsafe { core::slice::from_raw_parts(s.as_ptr(), s.capacity()) };
+        assert!(all.iter().all(|&b| b == 0));
+    }
+}
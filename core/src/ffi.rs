@@ -0,0 +1,3207 @@
+//! C FFI Layer
+//!
+//! `ime_*` functions used by the platform apps (Swift, C#, C). Holds the
+//! global engine instance behind a mutex, so this layer requires `std`.
+//!
+//! ## Panic safety
+//! Every entry point runs inside `catch_unwind`: an engine bug returns the
+//! function's failure value (null / -1 / 0) instead of aborting the host
+//! process. The panic message is kept for `ime_last_error_message`, and the
+//! word state is reset so the next keystroke starts clean.
+
+use crate::engine::context::ResetPolicy;
+use crate::engine::field::FieldKind;
+use crate::engine::log::{LogLevel, LogSink};
+use crate::engine::status::DisableReason;
+use crate::engine::transform::ToneKeyPolicy;
+use crate::engine::validation::ValidationProfile;
+use crate::engine::{Engine, Result};
+use crate::utils::ForeignFinalTone;
+use crate::{engine, text, utils};
+use std::sync::Mutex;
+
+// Global engine instance (thread-safe via Mutex)
+static ENGINE: Mutex<Option<Engine>> = Mutex::new(None);
+
+/// Lock the engine mutex, recovering from poisoned state if needed (for tests)
+fn lock_engine() -> std::sync::MutexGuard<'static, Option<Engine>> {
+    ENGINE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Error codes returned by `ime_last_error`
+pub const IME_OK: i32 = 0;
+pub const IME_ERR_PANIC: i32 = 1;
+
+// Error of the most recent `ime_*` call (code, message)
+static LAST_ERROR: Mutex<(i32, String)> = Mutex::new((IME_OK, String::new()));
+
+fn set_last_error(code: i32, message: String) {
+    *LAST_ERROR.lock().unwrap_or_else(|e| e.into_inner()) = (code, message);
+}
+
+/// Run an FFI body, turning a panic into `fallback` + last error
+fn guarded<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    set_last_error(IME_OK, String::new());
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(IME_ERR_PANIC, message);
+            // Buffer may be half-updated: drop the word, keep settings
+            if let Some(ref mut e) = *lock_engine() {
+                e.clear_all();
+            }
+            fallback
+        }
+    }
+}
+
+// ============================================================
+// FFI Interface
+// ============================================================
+
+/// Initialize the IME engine.
+///
+/// Must be called exactly once before any other `ime_*` functions.
+/// Thread-safe: uses internal mutex.
+#[no_mangle]
+pub extern "C" fn ime_init() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        *guard = Some(Engine::new());
+    })
+}
+
+/// Process a key event and return the result.
+///
+/// # Arguments
+/// * `key` - macOS virtual keycode (0-127 for standard keys)
+/// * `caps` - true if CapsLock is pressed (for uppercase letters)
+/// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+///
+/// # Result struct
+/// * `action`: 0=None (pass through), 1=Send (replace text), 2=Restore
+/// * `backspace`: number of characters to delete
+/// * `chars`: UTF-32 codepoints to insert
+/// * `count`: number of valid chars
+///
+/// # Note
+/// For VNI mode with Shift+number keys (to type @, #, $ etc.),
+/// use `ime_key_ext` with the shift parameter.
+/// Shortcut expansions longer than 255 chars are truncated; use `ime_key_into`.
+#[no_mangle]
+pub extern "C" fn ime_key(key: u16, caps: bool, ctrl: bool) -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let r = e.on_key(key, caps, ctrl);
+            Box::into_raw(Box::new(r))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Process a key event with extended parameters.
+///
+/// # Arguments
+/// * `key` - macOS virtual keycode (0-127 for standard keys)
+/// * `caps` - true if CapsLock is pressed (for uppercase letters)
+/// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
+/// * `shift` - true if Shift key is pressed (for symbols like @, #, $)
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+///
+/// # VNI Shift+number behavior
+/// In VNI mode, when `shift=true` and key is a number (0-9), the engine
+/// will NOT apply VNI marks/tones. This allows typing symbols:
+/// - Shift+2 → @ (not huyền mark)
+/// - Shift+3 → # (not hỏi mark)
+/// - etc.
+#[no_mangle]
+pub extern "C" fn ime_key_ext(key: u16, caps: bool, ctrl: bool, shift: bool) -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let r = e.on_key_ext(key, caps, ctrl, shift);
+            Box::into_raw(Box::new(r))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Process a key event with the actual Unicode character.
+///
+/// Used for Option-modified keys on macOS where the keycode doesn't change
+/// but the actual character is different (e.g., Option+V produces √).
+///
+/// # Arguments
+/// * `key` - macOS virtual keycode (0-127 for standard keys)
+/// * `caps` - true if CapsLock is pressed (for uppercase letters)
+/// * `ctrl` - true if Cmd/Ctrl is pressed (bypasses IME)
+/// * `shift` - true if Shift key is pressed
+/// * `char_code` - The actual Unicode character code (UTF-32). If > 0, uses this
+///   for shortcut matching instead of deriving from keycode.
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+///
+/// # Example
+/// When Option+V is pressed on macOS:
+/// - keycode is still V (9)
+/// - char_code is √ (0x221A)
+/// - Engine uses √ for shortcut matching, allowing shortcuts like √√ → ✅
+#[no_mangle]
+pub extern "C" fn ime_key_with_char(
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+    char_code: u32,
+) -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let ch = if char_code > 0 {
+                char::from_u32(char_code)
+            } else {
+                None
+            };
+            let r = e.on_key_with_char(key, caps, ctrl, shift, ch);
+            Box::into_raw(Box::new(r))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Process a key event, recording the character the keyboard layout produced.
+///
+/// Same as `ime_key_ext`; `char_code` is kept with the keystroke so ESC and
+/// auto-restore put back what was typed on non-US layouts (AZERTY "é" on the
+/// 2 key) instead of the US character of the keycode.
+///
+/// # Arguments
+/// * `char_code` - Unicode character produced by the key (UTF-32). 0 or an
+///   invalid value behaves like `ime_key_ext`.
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_key_typed(
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+    char_code: u32,
+) -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let r = match char::from_u32(char_code).filter(|&c| c != '\0') {
+                Some(ch) => e.on_key_typed(key, caps, ctrl, shift, ch),
+                None => e.on_key_ext(key, caps, ctrl, shift),
+            };
+            Box::into_raw(Box::new(r))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Process a key event with its timestamp.
+///
+/// Same as `ime_key_ext`, plus the timing rules (held-key repeat, double
+/// space, double Shift). Send Shift taps as their own events with
+/// `keys::LSHIFT` (56) / `keys::RSHIFT` (60).
+///
+/// # Arguments
+/// * `time_ms` - event time in milliseconds, from any monotonic clock
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_key_at(
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+    time_ms: u64,
+) -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let r = e.on_key_at(key, caps, ctrl, shift, time_ms);
+            Box::into_raw(Box::new(r))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Header written by `ime_key_into`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct KeyOutput {
+    /// 0=None (pass through), 1=Send (replace text), 2=Restore
+    pub action: u8,
+    /// Number of characters to delete
+    pub backspace: u8,
+    /// Same bits as `Result::flags`
+    pub flags: u8,
+    /// Total codepoints in the output, may exceed what fit in `out`
+    pub count: u32,
+}
+
+/// Process a key event, writing the output into caller-owned memory.
+///
+/// Alternative to `ime_key_with_char` with no heap `Result` box to free.
+/// The engine still builds its fixed-size `Result` for the key; only the
+/// `count` codepoints in use are copied into `out`. Shortcut expansions are
+/// not capped at 255 chars: they are copied from the full expansion text.
+/// If `header.count` exceeds the return value, fetch the rest with
+/// `ime_last_expansion`.
+///
+/// # Arguments
+/// * `key`, `caps`, `ctrl`, `shift`, `char_code` - as in `ime_key_with_char`
+/// * `header` - Receives action, backspace, flags and total count
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+///
+/// # Returns
+/// Number of codepoints written to `out`, or -1 if engine not initialized
+/// or `header` is null.
+///
+/// # Safety
+/// * `header` must point to a valid `KeyOutput`
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes, or be null with `max_len` 0
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn ime_key_into(
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+    char_code: u32,
+    header: *mut KeyOutput,
+    out: *mut u32,
+    max_len: i64,
+) -> i64 {
+    guarded(-1, || {
+        if header.is_null() {
+            return -1;
+        }
+        let mut guard = lock_engine();
+        let Some(ref mut e) = *guard else {
+            return -1;
+        };
+        let ch = if char_code > 0 {
+            char::from_u32(char_code)
+        } else {
+            None
+        };
+        let r = e.on_key_with_char(key, caps, ctrl, shift, ch);
+        let expansion = e.last_expansion();
+        *header = KeyOutput {
+            action: r.action,
+            backspace: r.backspace,
+            flags: r.flags,
+            count: expansion.map_or(r.count as usize, <[char]>::len) as u32,
+        };
+        if out.is_null() || max_len <= 0 {
+            return 0;
+        }
+        match expansion {
+            Some(text) => write_chars(text, out, max_len),
+            None => {
+                let chars = &r.chars[..r.count as usize];
+                let len = chars.len().min(max_len as usize);
+                std::ptr::copy_nonoverlapping(chars.as_ptr(), out, len);
+                len as i64
+            }
+        }
+    })
+}
+
+/// Copy `text` as UTF-32 into `out`, at most `max_len` codepoints
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+unsafe fn write_chars(text: &[char], out: *mut u32, max_len: i64) -> i64 {
+    let len = text.len().min(max_len.max(0) as usize);
+    for (i, &c) in text[..len].iter().enumerate() {
+        *out.add(i) = c as u32;
+    }
+    len as i64
+}
+
+/// Get the full text of the shortcut expanded by the last key.
+///
+/// # Returns
+/// Number of codepoints written to `out`, or 0 if the last key expanded nothing.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_last_expansion(out: *mut u32, max_len: i64) -> i64 {
+    guarded(0, || {
+        if out.is_null() || max_len <= 0 {
+            return 0;
+        }
+        let guard = lock_engine();
+        let Some(text) = guard.as_ref().and_then(|e| e.last_expansion()) else {
+            return 0;
+        };
+        write_chars(text, out, max_len)
+    })
+}
+
+/// Set the input method.
+///
+/// # Arguments
+/// * `method` - 0 for Telex, 1 for VNI
+///
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_method(method: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_method(method);
+        }
+    })
+}
+
+/// Enable or disable the engine.
+///
+/// When disabled, `ime_key` returns action=0 (pass through).
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_enabled(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_enabled(enabled);
+        }
+    })
+}
+
+/// Enable or disable the engine for a reason.
+///
+/// `reason`: 1=user, 2=per-app policy, 3=secure field, 4=field kind (left
+/// to `ime_field_kind`). Input is on only when no reason holds it off;
+/// `ime_enabled` is reason 1.
+/// No-op if engine not initialized or `reason` is unknown.
+#[no_mangle]
+pub extern "C" fn ime_enabled_for(enabled: bool, reason: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let (Some(ref mut e), Some(reason)) = (&mut *guard, DisableReason::from_u8(reason)) {
+            e.set_enabled_for(enabled, reason);
+        }
+    })
+}
+
+/// Adjust input to the kind of the focused field.
+///
+/// `kind`: 0=normal, 1=email, 2=URL, 3=code, 4=password. Password fields
+/// enter secure mode, email and URL fields turn input off (reason 4), code
+/// fields accept only standard Vietnamese spellings; only normal fields
+/// auto-capitalize. The user's settings apply again in a normal field.
+/// No-op if engine not initialized or `kind` is unknown.
+#[no_mangle]
+pub extern "C" fn ime_field_kind(kind: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let (Some(ref mut e), Some(kind)) = (&mut *guard, FieldKind::from_u8(kind)) {
+            e.set_field_kind(kind);
+        }
+    })
+}
+
+/// Enter or leave secure mode (password and other secure fields).
+///
+/// While on, input is disabled for reason 3 and the engine holds no typed
+/// text: buffers, word history and shortcut prefix are wiped on entry and
+/// keys are not read or recorded until secure mode is left. Leaving is
+/// ignored while the field kind is password (see `ime_field_kind`).
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_secure_mode(secure: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_secure_mode(secure);
+        }
+    })
+}
+
+/// Follow left/right arrows inside the word being typed.
+///
+/// When on, arrows keep the word and later keys edit it at the caret;
+/// when off (default), arrows end the word. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_mid_word_editing(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_mid_word_editing(enabled);
+        }
+    })
+}
+
+/// Forget all typed text the engine holds, overwriting its memory.
+///
+/// Buffers, word history and shortcut prefix are zeroed; settings,
+/// shortcuts and the user dictionary stay. `ime_secure_mode(true)` does this
+/// on entry. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_wipe() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.wipe();
+        }
+    })
+}
+
+/// Diagnostic log callback: level (0=debug, 1=info), then target and
+/// message as NUL-terminated UTF-8, valid only during the call.
+pub type ImeLogCallback = extern "C" fn(
+    level: u8,
+    target: *const std::os::raw::c_char,
+    message: *const std::os::raw::c_char,
+);
+
+/// Forwards engine log records to the host's callback
+struct CallbackSink(ImeLogCallback);
+
+impl LogSink for CallbackSink {
+    fn log(&mut self, level: LogLevel, target: &'static str, message: &str) {
+        let (Ok(target), Ok(message)) = (
+            std::ffi::CString::new(target),
+            std::ffi::CString::new(message),
+        ) else {
+            return;
+        };
+        (self.0)(level as u8, target.as_ptr(), message.as_ptr());
+    }
+}
+
+/// Install (or with null, remove) the diagnostic log callback.
+///
+/// Records describe engine decisions (restores, expansions, mode changes)
+/// and never contain typed text. The callback runs inside `ime_key*` with
+/// the engine locked: it must be cheap and must not call `ime_*` functions.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_set_log_callback(callback: Option<ImeLogCallback>) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            match callback {
+                Some(cb) => e.set_log_sink(Box::new(CallbackSink(cb))),
+                None => drop(e.take_log_sink()),
+            }
+        }
+    })
+}
+
+/// Why the engine is disabled.
+///
+/// # Returns
+/// 0 if enabled (or engine not initialized), else the most specific reason
+/// holding it off: 3=secure field, 4=email or URL field, 2=per-app policy,
+/// 1=user.
+#[no_mangle]
+pub extern "C" fn ime_disable_reason() -> u8 {
+    guarded(0, || {
+        let guard = lock_engine();
+        guard
+            .as_ref()
+            .and_then(|e| e.disable_reason())
+            .map_or(0, |r| r as u8)
+    })
+}
+
+/// Set whether to skip w→ư shortcut in Telex mode.
+///
+/// When `skip` is true, typing 'w' stays as 'w' instead of
+/// converting to 'ư'. Horn modifier still works: "ow" → "ơ", "uw" → "ư".
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_skip_w_shortcut(skip: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_skip_w_shortcut(skip);
+        }
+    })
+}
+
+/// Set whether 'o' after 'ư' gets the horn automatically.
+///
+/// When `enabled` is true (default), "uwo" → "ươ" and the 'ơ' goes back to
+/// 'o' if the word turns out not to be Vietnamese ("uwoman" → "ưoman").
+/// When false, the 'o' needs its own horn key: "uwow" → "ươ".
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_auto_uo_horn(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_auto_uo_horn(enabled);
+        }
+    })
+}
+
+/// Set whether the word after '#' or '@' is composed.
+///
+/// When `enabled` is true (default), "#chaof" → "#chào" for
+/// hashtags in social apps. When false, the word after '#' or '@' is typed
+/// as is: "#tieengs" → "#tieengs", "user@domain" stays intact.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_tag_composition(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_tag_composition(enabled);
+        }
+    })
+}
+
+/// Set whether bracket shortcuts are enabled: ] → ư, [ → ơ (Issue #159)
+///
+/// When `enabled` is true (default), ] types ư and [ types ơ in Telex mode.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_bracket_shortcut(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_bracket_shortcut(enabled);
+        }
+    })
+}
+
+/// Set whether ESC key restores raw ASCII input.
+///
+/// When `enabled` is true (default), pressing ESC restores original keystrokes.
+/// When `enabled` is false, ESC key is passed through without restoration.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_esc_restore(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_esc_restore(enabled);
+        }
+    })
+}
+
+/// Set how many committed words backspace can restore.
+///
+/// Default 10; 0 disables backspace-after-space restore. Capped at 64.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_history_capacity(capacity: u32) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_history_capacity(capacity as usize);
+        }
+    })
+}
+
+/// Get a committed word from the backspace history as UTF-32 codepoints.
+///
+/// # Arguments
+/// * `depth` - 0 for the most recent word, 1 for the one before, ...
+///
+/// # Returns
+/// Number of codepoints written to `out`, or -1 if there is no word at `depth`.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_peek_history(depth: u32, out: *mut u32, max_len: i64) -> i64 {
+    guarded(-1, || {
+        if out.is_null() || max_len <= 0 {
+            return -1;
+        }
+        let guard = lock_engine();
+        let Some(word) = guard.as_ref().and_then(|e| e.peek_history(depth as usize)) else {
+            return -1;
+        };
+        let utf32: Vec<u32> = word.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    })
+}
+
+/// Set whether to enable free tone placement (skip validation).
+///
+/// When `enabled` is true, allows placing diacritics anywhere without
+/// spelling validation (e.g., "Zìa" is allowed).
+/// When `enabled` is false (default), validates Vietnamese spelling rules.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_free_tone(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_free_tone(enabled);
+        }
+    })
+}
+
+/// Set whether to use modern orthography for tone placement.
+///
+/// When `modern` is true: hoà, thuý (tone on second vowel - new style)
+/// When `modern` is false (default): hòa, thúy (tone on first vowel - traditional)
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_modern(modern: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_modern_tone(modern);
+        }
+    })
+}
+
+/// Enable/disable English auto-restore (experimental feature).
+///
+/// When `enabled` is true, automatically restores English words that were
+/// accidentally transformed (e.g., "tẽt" → "text", "ễpct" → "expect").
+/// When `enabled` is false (default), no auto-restore happens.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_english_auto_restore(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_english_auto_restore(enabled);
+        }
+    })
+}
+
+/// Enable/disable auto-capitalize after sentence-ending punctuation.
+///
+/// When `enabled` is true, automatically capitalizes the first letter
+/// after sentence-ending punctuation (. ! ? Enter).
+/// When `enabled` is false (default), no auto-capitalize happens.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_auto_capitalize(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_auto_capitalize(enabled);
+        }
+    })
+}
+
+/// Enable/disable typing ". " with two quick spaces after a word.
+///
+/// Needs timestamped keys (`ime_key_at`). Default false.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_double_space_period(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let mut timing = e.key_timing();
+            timing.double_space_period = enabled;
+            e.set_key_timing(timing);
+        }
+    })
+}
+
+/// Enable/disable toggling Vietnamese input with two quick Shift taps.
+///
+/// Needs timestamped keys (`ime_key_at`). Default false.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_double_shift_toggle(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let mut timing = e.key_timing();
+            timing.double_shift_toggle = enabled;
+            e.set_key_timing(timing);
+        }
+    })
+}
+
+/// Set how long a word may sit untouched before the next key finishes it.
+///
+/// With `ms` > 0, a key arriving more than `ms` after the previous one first
+/// finalizes the word being typed (auto-restore included), so a stray tone
+/// key doesn't edit a word typed minutes ago. 0 (default) turns it off.
+/// Needs timestamped keys (`ime_key_at`). No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_idle_commit(ms: u32) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            let mut timing = e.key_timing();
+            timing.idle_commit_ms = ms;
+            e.set_key_timing(timing);
+        }
+    })
+}
+
+/// Enable/disable foreign consonants (z, w, j, f) as valid initial consonants.
+///
+/// When `enabled` is true, allows z, w, j, f as valid Vietnamese consonants
+/// for typing loanwords while still getting Vietnamese diacritics.
+/// When `enabled` is false (default), these letters are treated as invalid initials.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_allow_foreign_consonants(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_allow_foreign_consonants(enabled);
+        }
+    })
+}
+
+/// Set the validation profile.
+///
+/// # Arguments
+/// * `profile` - 0 for Strict, 1 for Standard (default), 2 for Tolerant
+///
+/// Strict rejects ethnic minority clusters (Krông, Đắk); Tolerant also accepts
+/// foreign initials (z, w, j, f), like `ime_allow_foreign_consonants(true)`.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_validation_profile(profile: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_validation_profile(ValidationProfile::from_u8(profile));
+        }
+    })
+}
+
+/// Set what tone and mark keys that change nothing do.
+///
+/// # Arguments
+/// * `policy` - 0 for Smart (default), 1 for Literal, 2 for Consume
+///
+/// Smart swallows a key repeating a diacritic the word already has ("ươ" +
+/// w); Literal types it instead, so no keystroke disappears; Consume also
+/// swallows modifier and mark keys with nothing to act on.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_tone_key_policy(policy: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_tone_key_policy(ToneKeyPolicy::from_u8(policy));
+        }
+    })
+}
+
+/// Set where tone keys on syllables with a non-Vietnamese final go.
+///
+/// # Arguments
+/// * `policy` - 0 for Reject (default), 1 for LastVowel
+///
+/// Reject types the key as a letter ("gels"); LastVowel places the mark on
+/// the last vowel before the final ("gels" → "gél", "mails" → "maíl").
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_foreign_final_tone(policy: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_foreign_final_tone(ForeignFinalTone::from_u8(policy));
+        }
+    })
+}
+
+/// Enable/disable spell check of committed words.
+///
+/// When enabled, words that are neither Vietnamese, English nor user words
+/// raise a flag the host can fetch with `ime_take_spell_flag`.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_spell_check(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_spell_check(enabled);
+        }
+    })
+}
+
+/// Set the fewest keystrokes a word needs to be auto-restored.
+///
+/// Shorter words stay as typed on a word boundary ("per" → "pẻ" with a
+/// minimum of 4). 0 (default) restores words of any length.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_restore_min_length(len: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_restore_min_length(len);
+        }
+    })
+}
+
+/// Exempt a word from the minimum auto-restore length.
+///
+/// `word` is the typed keystrokes ("per"); it is restored as usual even
+/// below the minimum.
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_restore_exception(word: *const std::os::raw::c_char) {
+    guarded((), || {
+        if word.is_null() {
+            return;
+        }
+        let Ok(word_str) = std::ffi::CStr::from_ptr(word).to_str() else {
+            return;
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.add_restore_exception(word_str);
+        }
+    })
+}
+
+/// Remove all words exempt from the minimum auto-restore length.
+///
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_restore_exceptions() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.clear_restore_exceptions();
+        }
+    })
+}
+
+/// Add a word to the spell check user dictionary.
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_user_word(word: *const std::os::raw::c_char) {
+    guarded((), || {
+        if word.is_null() {
+            return;
+        }
+        let Ok(word_str) = std::ffi::CStr::from_ptr(word).to_str() else {
+            return;
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.add_user_word(word_str);
+        }
+    })
+}
+
+/// Take the spell check flag raised by the last commit.
+///
+/// Writes the flagged word followed by its suggestions, separated by '\n'.
+///
+/// # Returns
+/// Number of codepoints written to `out`, or 0 if nothing was flagged.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_take_spell_flag(out: *mut u32, max_len: i64) -> i64 {
+    guarded(0, || {
+        if out.is_null() || max_len <= 0 {
+            return 0;
+        }
+        let mut guard = lock_engine();
+        let Some(flag) = guard.as_mut().and_then(|e| e.take_spell_flag()) else {
+            return 0;
+        };
+        let text = std::iter::once(flag.word)
+            .chain(flag.suggestions)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let utf32: Vec<u32> = text.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    })
+}
+
+/// Clear the input buffer.
+///
+/// Call on word boundaries (space, punctuation).
+/// Preserves word history for backspace-after-space feature.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.clear();
+        }
+    })
+}
+
+/// Notify the engine of a host "delete word" (Option+Backspace, Ctrl+Backspace).
+///
+/// Call after (or instead of forwarding) the word deletion so the engine
+/// drops the deleted word from its buffer and backspace history.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_delete_word() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.delete_word();
+        }
+    })
+}
+
+/// Clear everything including word history.
+///
+/// Call when cursor position changes (mouse click, arrow keys, focus change).
+/// This prevents accidental restore from stale history.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_all() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.clear_all();
+        }
+    })
+}
+
+/// Get the full composed buffer as UTF-32 codepoints.
+///
+/// Used for "Select All + Replace" injection method where the entire
+/// buffer content is needed instead of incremental backspace + chars.
+///
+/// # Arguments
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+///
+/// # Returns
+/// Number of codepoints written to `out`.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
+    guarded(0, || {
+        if out.is_null() || max_len <= 0 {
+            return 0;
+        }
+
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            let full = e.get_buffer_string();
+            let utf32: Vec<u32> = full.chars().map(|c| c as u32).collect();
+            let len = utf32.len().min(max_len as usize);
+            std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+            len as i64
+        } else {
+            0
+        }
+    })
+}
+
+/// Get the raw keystrokes of the current word as UTF-32 codepoints.
+///
+/// Companion to `ime_get_buffer` for status UIs ("vieetj" for "việt").
+///
+/// # Returns
+/// Number of codepoints written to `out`.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_get_raw_word(out: *mut u32, max_len: i64) -> i64 {
+    guarded(0, || {
+        if out.is_null() || max_len <= 0 {
+            return 0;
+        }
+
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            let raw = e.status().raw_word;
+            let utf32: Vec<u32> = raw.chars().map(|c| c as u32).collect();
+            let len = utf32.len().min(max_len as usize);
+            std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+            len as i64
+        } else {
+            0
+        }
+    })
+}
+
+/// Get the current input mode.
+///
+/// # Returns
+/// 0=Vietnamese, 1=English (disabled), 2=Foreign word, 255 if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_mode() -> u8 {
+    guarded(255, || {
+        let guard = lock_engine();
+        match *guard {
+            Some(ref e) => e.mode() as u8,
+            None => 255,
+        }
+    })
+}
+
+/// Get both forms of the word restored by the last key (auto-restore, ESC).
+///
+/// Writes the transformed word and the raw word, separated by '\n', so the
+/// host can offer "undo restore".
+///
+/// # Arguments
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+/// * `reason` - Receives 0=ESC, 1=English word, 2=invalid Vietnamese (may be null)
+///
+/// # Returns
+/// Number of codepoints written to `out`, or 0 if the last key restored nothing.
+///
+/// # Safety
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes
+/// * `reason` must be null or point to a valid `u8`
+#[no_mangle]
+pub unsafe extern "C" fn ime_last_restore(out: *mut u32, max_len: i64, reason: *mut u8) -> i64 {
+    guarded(0, || {
+        if out.is_null() || max_len <= 0 {
+            return 0;
+        }
+        let guard = lock_engine();
+        let Some(info) = guard.as_ref().and_then(|e| e.last_restore()) else {
+            return 0;
+        };
+        if !reason.is_null() {
+            *reason = info.reason as u8;
+        }
+        let text = format!("{}\n{}", info.transformed, info.raw);
+        let utf32: Vec<u32> = text.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    })
+}
+
+/// Get what the last key's result does to the text.
+///
+/// For hosts that edit through higher-level APIs (accessibility value
+/// replacement, marked text) instead of replaying backspaces. The plain
+/// `backspace` + `chars` form stays valid either way.
+///
+/// # Arguments
+/// * `r` - Result of the last `ime_key*` call
+/// * `old` - Receives the replaced word for kind 3 (UTF-32, `r.backspace`
+///   codepoints), may be null
+/// * `max_len` - Maximum number of codepoints to write to `old`
+///
+/// # Returns
+/// 0 = no edit, 1 = insert `chars` at the cursor, 2 = replace the last
+/// `backspace` chars, 3 = replace the engine's whole word (written to `old`).
+///
+/// # Safety
+/// * `r` must be a pointer returned by `ime_key*` and not yet freed, or null
+/// * `old` must be null or point to valid memory of at least `max_len * sizeof(u32)` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_edit_op(r: *const Result, old: *mut u32, max_len: i64) -> u8 {
+    guarded(0, || {
+        if r.is_null() {
+            return 0;
+        }
+        let guard = lock_engine();
+        let Some(op) = guard.as_ref().and_then(|e| e.edit_op(&*r)) else {
+            return 0;
+        };
+        if let engine::edit::EditOp::ReplaceWord { old: word, .. } = &op {
+            if !old.is_null() && max_len > 0 {
+                let utf32: Vec<u32> = word.chars().map(|c| c as u32).collect();
+                let len = utf32.len().min(max_len as usize);
+                std::ptr::copy_nonoverlapping(utf32.as_ptr(), old, len);
+            }
+        }
+        op.kind()
+    })
+}
+
+/// Check the last result against the app's text before applying it.
+///
+/// Pass the text before the cursor (at least the chars the result deletes)
+/// for apps that autocomplete or rewrite text as it is typed. `r` is
+/// updated in place: resized to the separators on screen, or emptied when
+/// the screen doesn't show what it deletes (the key then types itself).
+///
+/// # Returns
+/// 0 = confirmed, 1 = adjusted, 2 = aborted (see `ScreenCheck`).
+/// 0 if an argument is invalid or the engine isn't initialized.
+///
+/// # Safety
+/// * `r` must be a pointer returned by `ime_key*` and not yet freed, or null
+/// * `before` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_verify_result(
+    r: *mut Result,
+    before: *const std::os::raw::c_char,
+) -> u8 {
+    guarded(0, || {
+        if r.is_null() || before.is_null() {
+            return 0;
+        }
+        let Ok(before_str) = std::ffi::CStr::from_ptr(before).to_str() else {
+            return 0;
+        };
+        let mut guard = lock_engine();
+        match *guard {
+            Some(ref mut e) => e.verify_result(&mut *r, before_str) as u8,
+            None => 0,
+        }
+    })
+}
+
+/// Free a result pointer returned by `ime_key`.
+///
+/// # Safety
+/// * `r` must be a pointer returned by `ime_key`, or null
+/// * Must be called exactly once per non-null `ime_key` return
+/// * Do not use `r` after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn ime_free(r: *mut Result) {
+    guarded((), || {
+        if !r.is_null() {
+            drop(Box::from_raw(r));
+        }
+    })
+}
+
+// ============================================================
+// Profile FFI
+// ============================================================
+
+/// Switch to a named profile (settings, shortcuts, user dictionary).
+///
+/// An unknown name creates the profile with default settings; configure it
+/// with the usual `ime_*` setters while it is active. The active profile is
+/// kept for switching back. The word being typed is dropped.
+///
+/// # Returns
+/// true if the profile already existed.
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_profile(name: *const std::os::raw::c_char) -> bool {
+    guarded(false, || {
+        if name.is_null() {
+            return false;
+        }
+        let Ok(name_str) = std::ffi::CStr::from_ptr(name).to_str() else {
+            return false;
+        };
+        let mut guard = lock_engine();
+        match *guard {
+            Some(ref mut e) => e.set_profile(name_str),
+            None => false,
+        }
+    })
+}
+
+// ============================================================
+// Output Strategy FFI
+// ============================================================
+
+/// Record the focused app and element role for `ime_output_strategy`.
+///
+/// # Arguments
+/// * `app_id` - Bundle id (macOS), exe name (Windows) or WM class (Linux)
+/// * `role` - Accessibility role of the focused element (e.g. "AXComboBox"), may be null
+///
+/// # Safety
+/// `app_id` must be a valid null-terminated UTF-8 string; `role` must be
+/// null or one.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_focus(
+    app_id: *const std::os::raw::c_char,
+    role: *const std::os::raw::c_char,
+) {
+    guarded((), || {
+        if app_id.is_null() {
+            return;
+        }
+        let Ok(app_str) = std::ffi::CStr::from_ptr(app_id).to_str() else {
+            return;
+        };
+        let role_str = if role.is_null() {
+            None
+        } else {
+            std::ffi::CStr::from_ptr(role).to_str().ok()
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.set_focus(app_str, role_str);
+        }
+    })
+}
+
+/// Get how to deliver results to the focused app.
+///
+/// # Arguments
+/// * `delays` - Receives 3 delays in µs (after each backspace, before the
+///   text, after each text chunk), may be null
+///
+/// # Returns
+/// Output method: 0=fast, 1=slow, 2=char by char, 3=selection, 4=empty char
+/// prefix, 5=AX direct, 6=sync proxy, 7=passthrough.
+///
+/// # Safety
+/// `delays` must be null or point to at least 3 `u32`s.
+#[no_mangle]
+pub unsafe extern "C" fn ime_output_strategy(delays: *mut u32) -> u8 {
+    guarded(0, || {
+        let guard = lock_engine();
+        let Some(e) = guard.as_ref() else {
+            return 0;
+        };
+        let strategy = e.output_strategy();
+        if !delays.is_null() {
+            std::ptr::copy_nonoverlapping(strategy.delays.as_ptr(), delays, 3);
+        }
+        strategy.method as u8
+    })
+}
+
+/// Override the output method and delays for an app.
+///
+/// # Arguments
+/// * `app_id` - App to override
+/// * `method` - Output method (see `ime_output_strategy`); unknown values are ignored
+/// * `d0`, `d1`, `d2` - Delays in µs
+///
+/// # Safety
+/// `app_id` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_output_override(
+    app_id: *const std::os::raw::c_char,
+    method: u8,
+    d0: u32,
+    d1: u32,
+    d2: u32,
+) {
+    guarded((), || {
+        if app_id.is_null() {
+            return;
+        }
+        let Ok(app_str) = std::ffi::CStr::from_ptr(app_id).to_str() else {
+            return;
+        };
+        let Some(method) = engine::output::OutputMethod::from_u8(method) else {
+            return;
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.output_registry_mut()
+                .set_override(app_str, method, [d0, d1, d2]);
+        }
+    })
+}
+
+/// Remove an app's output override, back to the built-in rules.
+///
+/// # Safety
+/// `app_id` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_remove_output_override(app_id: *const std::os::raw::c_char) {
+    guarded((), || {
+        if app_id.is_null() {
+            return;
+        }
+        let Ok(app_str) = std::ffi::CStr::from_ptr(app_id).to_str() else {
+            return;
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.output_registry_mut().remove_override(app_str);
+        }
+    })
+}
+
+// ============================================================
+// Shortcut FFI
+// ============================================================
+
+/// Add a shortcut to the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger (e.g., "vn")
+/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+///
+/// # Safety
+/// Both pointers must be valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_shortcut(
+    trigger: *const std::os::raw::c_char,
+    replacement: *const std::os::raw::c_char,
+) {
+    guarded((), || {
+        if trigger.is_null() || replacement.is_null() {
+            return;
+        }
+
+        let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+        let replacement_str = match std::ffi::CStr::from_ptr(replacement).to_str() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            // Auto-detect shortcut type:
+            // - If trigger contains only non-letter chars (like "->", "=>"), use immediate trigger
+            // - Otherwise use word boundary trigger (traditional abbreviations like "vn" → "Việt Nam")
+            let is_symbol_trigger = trigger_str.chars().all(|c| !c.is_alphabetic());
+            let shortcut = if is_symbol_trigger {
+                engine::shortcut::Shortcut::immediate(trigger_str, replacement_str)
+            } else {
+                engine::shortcut::Shortcut::new(trigger_str, replacement_str)
+            };
+            e.shortcuts_mut().add(shortcut);
+        }
+    })
+}
+
+/// Remove a shortcut from the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger to remove
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const std::os::raw::c_char) {
+    guarded((), || {
+        if trigger.is_null() {
+            return;
+        }
+
+        let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.shortcuts_mut().remove(trigger_str);
+        }
+    })
+}
+
+/// Clear all shortcuts from the engine.
+#[no_mangle]
+pub extern "C" fn ime_clear_shortcuts() {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.shortcuts_mut().clear();
+        }
+    })
+}
+
+/// Load a packed shortcut library (see `engine::shortcut_pack`).
+///
+/// The bytes are copied; entries stay packed and are decoded only when
+/// typed. Replaces any previous library. Added shortcuts take precedence.
+///
+/// # Returns
+/// true if the data was a valid library and the engine is initialized.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_load_shortcut_pack(data: *const u8, len: usize) -> bool {
+    guarded(false, || {
+        if data.is_null() {
+            return false;
+        }
+        let bytes = std::slice::from_raw_parts(data, len).to_vec();
+        let Ok(library) = engine::shortcut_pack::PackedShortcuts::from_vec(bytes) else {
+            return false;
+        };
+        let mut guard = lock_engine();
+        match *guard {
+            Some(ref mut e) => {
+                e.shortcuts_mut().set_library(library);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Encode the added shortcuts into the packed library format.
+///
+/// Call with `max_len` 0 to get the size, then again with a buffer that big.
+///
+/// # Returns
+/// Size of the packed data in bytes (written only if it fits `max_len`),
+/// or 0 if the engine isn't initialized.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len` bytes (may be null if 0).
+#[no_mangle]
+pub unsafe extern "C" fn ime_pack_shortcuts(out: *mut u8, max_len: usize) -> usize {
+    guarded(0, || {
+        let guard = lock_engine();
+        let Some(ref e) = *guard else {
+            return 0;
+        };
+        let packed = e.shortcuts().to_packed();
+        if !out.is_null() && packed.len() <= max_len {
+            std::ptr::copy_nonoverlapping(packed.as_ptr(), out, packed.len());
+        }
+        packed.len()
+    })
+}
+
+/// Set whether shortcut triggers also match with diacritics stripped.
+///
+/// When enabled, "dc" typed plainly expands a shortcut defined as "đc".
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_shortcuts_ignore_diacritics(enabled: bool) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.shortcuts_mut().set_ignore_diacritics(enabled);
+        }
+    })
+}
+
+// ============================================================
+// Word Restore FFI
+// ============================================================
+
+/// Restore buffer from a Vietnamese word string.
+///
+/// Used when native app detects cursor at word boundary and user
+/// wants to continue editing (e.g., backspace into previous word).
+/// Parses Vietnamese characters back to buffer components.
+///
+/// # Arguments
+/// * `word` - C string containing the Vietnamese word to restore
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
+    guarded((), || {
+        if word.is_null() {
+            return;
+        }
+        let word_str = match std::ffi::CStr::from_ptr(word).to_str() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.restore_word(word_str);
+        }
+    })
+}
+
+/// Append a pre-composed character the app inserted itself.
+///
+/// For text entered outside the engine (another layout, a character picker):
+/// the engine decomposes it and continues the word from there, so the next
+/// keys can add a tone or finish the syllable. Nothing is sent back.
+///
+/// # Arguments
+/// * `ch` - Unicode scalar value of the inserted character (invalid values are ignored)
+#[no_mangle]
+pub extern "C" fn ime_push_composed(ch: u32) {
+    guarded((), || {
+        let Some(ch) = char::from_u32(ch) else {
+            return;
+        };
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.push_composed(ch);
+        }
+    })
+}
+
+/// Check the engine's view of the text before the cursor against the app's.
+///
+/// Pass the text preceding the cursor (at least the current or last word and
+/// the separators after it) when the context may have changed behind the
+/// engine, e.g. before `ime_restore_word`. Separators are counted in
+/// graphemes, so a CRLF takes one backspace.
+///
+/// # Returns
+/// 0 = in sync, 1 = separator count adjusted, 2 = composing state reset
+/// (see `ContextSync`). 0 if `before` is invalid or the engine isn't initialized.
+///
+/// # Safety
+/// `before` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_sync_context(before: *const std::os::raw::c_char) -> u8 {
+    guarded(0, || {
+        if before.is_null() {
+            return 0;
+        }
+        let Ok(before_str) = std::ffi::CStr::from_ptr(before).to_str() else {
+            return 0;
+        };
+        let mut guard = lock_engine();
+        match *guard {
+            Some(ref mut e) => e.sync_context(before_str) as u8,
+            None => 0,
+        }
+    })
+}
+
+/// Reset after an edit the engine can't follow (multi-cursor typing, a
+/// remote collaborator's change).
+///
+/// Commits the word being typed as it is on screen and forgets the text
+/// before the cursor. `policy`: 0 = commit only, 1 = also report
+/// `ime_needs_context()` until `ime_sync_context` is called (see
+/// `ResetPolicy`); other values act as 0. No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_reset_external(policy: u8) {
+    guarded((), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            e.reset_external(ResetPolicy::from_u8(policy).unwrap_or_default());
+        }
+    })
+}
+
+/// Whether the engine asked for the text before the cursor
+/// (`ime_sync_context`) after `ime_reset_external(1)`.
+///
+/// false if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_needs_context() -> bool {
+    guarded(false, || {
+        let guard = lock_engine();
+        guard.as_ref().is_some_and(|e| e.needs_context())
+    })
+}
+
+/// Remove diacritics from the current or last committed word.
+///
+/// Bindable command: "đường " → "duong ". The result carries the backspace
+/// count and replacement text (including any separators after the word).
+///
+/// # Returns
+/// Pointer to Result (caller must free with `ime_free`), or null if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_strip_last_word() -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            Box::into_raw(Box::new(e.strip_last_word()))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Finalize the word being typed, for focus changes and app switches.
+///
+/// Call before the focus leaves the field: the result carries any final edit
+/// (auto-restore of an English word) to apply there. Composing state is cleared.
+///
+/// # Returns
+/// Pointer to Result (caller must free with `ime_free`), or null if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_flush() -> *mut Result {
+    guarded(std::ptr::null_mut(), || {
+        let mut guard = lock_engine();
+        if let Some(ref mut e) = *guard {
+            Box::into_raw(Box::new(e.flush()))
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+// ============================================================
+// Phonology Query FFI
+// ============================================================
+
+/// Find the character index where the tone mark belongs in a syllable.
+///
+/// Stateless query; does not require `ime_init()`.
+///
+/// # Arguments
+/// * `word` - C string containing the syllable (with or without diacritics)
+/// * `modern` - true for modern placement (hoà), false for traditional (hòa)
+///
+/// # Returns
+/// Character index of the tone vowel, or -1 if the word has no Vietnamese vowel.
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_tone_position(word: *const std::os::raw::c_char, modern: bool) -> i32 {
+    guarded(-1, || {
+        if word.is_null() {
+            return -1;
+        }
+        let word_str = match std::ffi::CStr::from_ptr(word).to_str() {
+            Ok(s) => s,
+            Err(_) => return -1,
+        };
+        utils::tone_position(word_str, modern)
+            .map(|pos| pos as i32)
+            .unwrap_or(-1)
+    })
+}
+
+// ============================================================
+// Text Conversion FFI
+// ============================================================
+
+/// Convert a whole text for "Paste and convert".
+///
+/// Stateless; does not touch the global engine.
+///
+/// # Arguments
+/// * `text` - C string to convert
+/// * `direction` - 0=Telex→VN, 1=VNI→VN, 2=strip diacritics, 3=legacy (TCVN3/VNI, auto-detected)→Unicode
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+///
+/// # Returns
+/// Number of codepoints written to `out` (output is truncated to `max_len`),
+/// or -1 if `text` is invalid or `direction` is unknown.
+///
+/// # Safety
+/// * `text` must be a valid null-terminated UTF-8 string
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_transform_text(
+    text: *const std::os::raw::c_char,
+    direction: u8,
+    out: *mut u32,
+    max_len: i64,
+) -> i64 {
+    guarded(-1, || {
+        if text.is_null() || out.is_null() || max_len <= 0 {
+            return -1;
+        }
+        let Ok(text_str) = std::ffi::CStr::from_ptr(text).to_str() else {
+            return -1;
+        };
+        let Some(direction) = text::Direction::from_u8(direction) else {
+            return -1;
+        };
+        let converted = text::transform_text(text_str, direction);
+        let utf32: Vec<u32> = converted.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    })
+}
+
+/// Re-derive diacritics in selected text typed with the IME off.
+///
+/// Uses the global engine's input method and tone settings; words that
+/// already contain non-ASCII letters, English words and punctuation are kept.
+/// Output is UTF-32 like `ime_transform_text`.
+///
+/// # Returns
+/// Number of codepoints written to `out` (output is truncated to `max_len`),
+/// or -1 if `text` is invalid or the engine is not initialized.
+///
+/// # Safety
+/// * `text` must be a valid null-terminated UTF-8 string
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_transform_selection(
+    text: *const std::os::raw::c_char,
+    out: *mut u32,
+    max_len: i64,
+) -> i64 {
+    guarded(-1, || {
+        if text.is_null() || out.is_null() || max_len <= 0 {
+            return -1;
+        }
+        let Ok(text_str) = std::ffi::CStr::from_ptr(text).to_str() else {
+            return -1;
+        };
+        let guard = lock_engine();
+        let Some(e) = guard.as_ref() else {
+            return -1;
+        };
+        let converted = e.transform_selection(text_str);
+        let utf32: Vec<u32> = converted.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    })
+}
+
+/// Re-decide English/Vietnamese words of a committed sentence, for a
+/// "fix this line" command ("tell hẻ" → "tell her").
+///
+/// Stateless; does not touch the global engine. Output is UTF-32 like
+/// `ime_transform_text`.
+///
+/// # Returns
+/// Number of codepoints written to `out` (output is truncated to `max_len`),
+/// or -1 if `text` is invalid.
+///
+/// # Safety
+/// * `text` must be a valid null-terminated UTF-8 string
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_correct_sentence(
+    text: *const std::os::raw::c_char,
+    out: *mut u32,
+    max_len: i64,
+) -> i64 {
+    guarded(-1, || {
+        if text.is_null() || out.is_null() || max_len <= 0 {
+            return -1;
+        }
+        let Ok(text_str) = std::ffi::CStr::from_ptr(text).to_str() else {
+            return -1;
+        };
+        let corrected = text::correct_sentence(text_str);
+        let utf32: Vec<u32> = corrected.chars().map(|c| c as u32).collect();
+        let len = utf32.len().min(max_len as usize);
+        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+        len as i64
+    })
+}
+
+/// Score how likely a raw Telex keystroke word is English.
+///
+/// Stateless; does not touch the global engine. Tiers: below 35 is likely
+/// Vietnamese, 35-69 is ambiguous, 70 and above is likely English.
+///
+/// # Returns
+/// Score from 0 (Vietnamese) to 100 (English), or 50 if `word` is invalid.
+///
+/// # Safety
+/// `word` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_english_confidence(word: *const std::os::raw::c_char) -> u8 {
+    guarded(50, || {
+        if word.is_null() {
+            return 50;
+        }
+        let Ok(word_str) = std::ffi::CStr::from_ptr(word).to_str() else {
+            return 50;
+        };
+        engine::english::english_confidence(word_str).score
+    })
+}
+
+// ============================================================
+// ABI / Capability FFI
+// ============================================================
+
+/// FFI ABI version; hosts should refuse a mismatched version.
+///
+/// Bumped when an existing `ime_*` signature, a `#[repr(C)]` layout
+/// (`Result`, `KeyOutput`, ...) or the meaning of a capability bit changes.
+/// New entry points don't bump it: each gets a new capability bit instead,
+/// and bits are never reused, so one dropped later stays reserved.
+/// Version 2 widened `ime_capabilities` from u32 to u64.
+pub const IME_ABI_VERSION: u32 = 2;
+
+/// Capability bits reported by `ime_capabilities` (bits 31-63 free)
+pub const IME_CAP_KEY_CONSUMED: u64 = 1 << 0;
+pub const IME_CAP_MARKED_TEXT: u64 = 1 << 1;
+pub const IME_CAP_RESTORE_REASON: u64 = 1 << 2;
+pub const IME_CAP_CHUNK_HINTS: u64 = 1 << 3;
+pub const IME_CAP_SPELL_CHECK: u64 = 1 << 4;
+pub const IME_CAP_TRANSFORM_TEXT: u64 = 1 << 5;
+pub const IME_CAP_LAST_ERROR: u64 = 1 << 6;
+pub const IME_CAP_KEY_INTO: u64 = 1 << 7;
+pub const IME_CAP_UNDO_GROUPS: u64 = 1 << 8;
+pub const IME_CAP_ENGLISH_CONFIDENCE: u64 = 1 << 9;
+pub const IME_CAP_PUSH_COMPOSED: u64 = 1 << 10;
+pub const IME_CAP_SYNC_CONTEXT: u64 = 1 << 11;
+pub const IME_CAP_FLUSH: u64 = 1 << 12;
+pub const IME_CAP_PROFILES: u64 = 1 << 13;
+pub const IME_CAP_SHORTCUT_DIACRITICS: u64 = 1 << 14;
+pub const IME_CAP_SHORTCUT_PACK: u64 = 1 << 15;
+pub const IME_CAP_KEY_TYPED: u64 = 1 << 16;
+pub const IME_CAP_TRANSFORM_SELECTION: u64 = 1 << 17;
+pub const IME_CAP_EDIT_OPS: u64 = 1 << 18;
+pub const IME_CAP_OUTPUT_STRATEGY: u64 = 1 << 19;
+pub const IME_CAP_KEY_TIMING: u64 = 1 << 20;
+pub const IME_CAP_DISABLE_REASON: u64 = 1 << 21;
+pub const IME_CAP_SECURE_MODE: u64 = 1 << 22;
+pub const IME_CAP_WIPE: u64 = 1 << 23;
+pub const IME_CAP_LOG: u64 = 1 << 24;
+pub const IME_CAP_MID_WORD_EDITING: u64 = 1 << 25;
+pub const IME_CAP_RESET_EXTERNAL: u64 = 1 << 26;
+pub const IME_CAP_VERIFY_RESULT: u64 = 1 << 27;
+pub const IME_CAP_BEHAVIOR_VERSION: u64 = 1 << 28;
+pub const IME_CAP_FIELD_KIND: u64 = 1 << 29;
+pub const IME_CAP_CORRECT_SENTENCE: u64 = 1 << 30;
+
+/// Log records are only emitted with the `diagnostics` feature
+const LOG_CAPABILITY: u64 = if cfg!(feature = "diagnostics") {
+    IME_CAP_LOG
+} else {
+    0
+};
+
+/// Capabilities of this build
+const CAPABILITIES: u64 = IME_CAP_KEY_CONSUMED
+    | IME_CAP_RESTORE_REASON
+    | IME_CAP_SPELL_CHECK
+    | IME_CAP_TRANSFORM_TEXT
+    | IME_CAP_LAST_ERROR
+    | IME_CAP_KEY_INTO
+    | IME_CAP_UNDO_GROUPS
+    | IME_CAP_ENGLISH_CONFIDENCE
+    | IME_CAP_PUSH_COMPOSED
+    | IME_CAP_SYNC_CONTEXT
+    | IME_CAP_FLUSH
+    | IME_CAP_PROFILES
+    | IME_CAP_SHORTCUT_DIACRITICS
+    | IME_CAP_SHORTCUT_PACK
+    | IME_CAP_KEY_TYPED
+    | IME_CAP_TRANSFORM_SELECTION
+    | IME_CAP_EDIT_OPS
+    | IME_CAP_OUTPUT_STRATEGY
+    | IME_CAP_KEY_TIMING
+    | IME_CAP_DISABLE_REASON
+    | IME_CAP_SECURE_MODE
+    | IME_CAP_WIPE
+    | LOG_CAPABILITY
+    | IME_CAP_MID_WORD_EDITING
+    | IME_CAP_RESET_EXTERNAL
+    | IME_CAP_VERIFY_RESULT
+    | IME_CAP_BEHAVIOR_VERSION
+    | IME_CAP_FIELD_KIND
+    | IME_CAP_CORRECT_SENTENCE;
+
+/// Get the FFI ABI version of this library.
+///
+/// Stateless; does not require `ime_init()`. Lets the host detect a dylib
+/// left over from a different app version during updates.
+#[no_mangle]
+pub extern "C" fn ime_abi_version() -> u32 {
+    IME_ABI_VERSION
+}
+
+/// Get the capability bitmask of this library (`IME_CAP_*`).
+///
+/// Stateless; does not require `ime_init()`. Hosts should only use a
+/// feature whose bit is set and degrade gracefully otherwise.
+#[no_mangle]
+pub extern "C" fn ime_capabilities() -> u64 {
+    CAPABILITIES
+}
+
+/// Get the behavior version of this library as UTF-32 codepoints.
+///
+/// Stateless; does not require `ime_init()`. Unlike the app version, it
+/// changes only when typing rules do; include it in debug logs and issues.
+///
+/// # Returns
+/// Number of codepoints written to `out` (output is truncated to `max_len`).
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_behavior_version(out: *mut u32, max_len: i64) -> i64 {
+    if out.is_null() {
+        return 0;
+    }
+    let version: Vec<char> = engine::behavior::engine_behavior_version()
+        .chars()
+        .collect();
+    write_chars(&version, out, max_len)
+}
+
+/// Get the behavior changelog as UTF-32 codepoints.
+///
+/// Stateless; does not require `ime_init()`. One `version rule_id` line per
+/// behavior-affecting change, oldest first, each ending in '\n'.
+///
+/// # Returns
+/// Number of codepoints written to `out` (output is truncated to `max_len`).
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_behavior_changelog(out: *mut u32, max_len: i64) -> i64 {
+    if out.is_null() {
+        return 0;
+    }
+    let changelog: Vec<char> = engine::behavior::behavior_changelog().chars().collect();
+    write_chars(&changelog, out, max_len)
+}
+
+// ============================================================
+// Error FFI
+// ============================================================
+
+/// Get the error code of the most recent `ime_*` call.
+///
+/// # Returns
+/// `IME_OK` (0) on success, `IME_ERR_PANIC` (1) if the engine panicked.
+#[no_mangle]
+pub extern "C" fn ime_last_error() -> i32 {
+    LAST_ERROR.lock().unwrap_or_else(|e| e.into_inner()).0
+}
+
+/// Get the message of the most recent `ime_*` error as UTF-32 codepoints.
+///
+/// # Returns
+/// Number of codepoints written to `out`, or 0 if the last call succeeded.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_last_error_message(out: *mut u32, max_len: i64) -> i64 {
+    if out.is_null() || max_len <= 0 {
+        return 0;
+    }
+    let last = LAST_ERROR.lock().unwrap_or_else(|e| e.into_inner());
+    let utf32: Vec<u32> = last.1.chars().map(|c| c as u32).collect();
+    let len = utf32.len().min(max_len as usize);
+    std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+    len as i64
+}
+
+// ============================================================
+// Tests
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::keys;
+    use serial_test::serial;
+    use std::ffi::CString;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    #[serial]
+    fn test_ffi_flow() {
+        ime_init();
+        ime_method(0); // Telex
+
+        // Type 'a' + 's' -> á
+        let r1 = ime_key(keys::A, false, false);
+        assert!(!r1.is_null());
+        unsafe { ime_free(r1) };
+
+        let r2 = ime_key(keys::S, false, false);
+        assert!(!r2.is_null());
+        unsafe {
+            assert_eq!((*r2).chars[0], 'á' as u32);
+            ime_free(r2);
+        }
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_add_and_clear() {
+        ime_init();
+        ime_clear_shortcuts(); // Clear any existing shortcuts
+        ime_method(0); // Telex
+
+        // Add a shortcut via FFI
+        let trigger = CString::new("vn").unwrap();
+        let replacement = CString::new("Việt Nam").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Verify shortcut was added by checking engine state
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+        }
+        drop(guard);
+
+        // Clear all shortcuts
+        ime_clear_shortcuts();
+
+        // Verify shortcuts cleared
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 0);
+        }
+        drop(guard);
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_ignore_diacritics() {
+        ime_init();
+        ime_shortcuts_ignore_diacritics(true);
+
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert!(e.shortcuts().ignores_diacritics());
+        }
+        drop(guard);
+        assert_ne!(ime_capabilities() & IME_CAP_SHORTCUT_DIACRITICS, 0);
+
+        ime_init();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_pack_round_trip() {
+        ime_init();
+        let trigger = CString::new("vn").unwrap();
+        let replacement = CString::new("Việt Nam").unwrap();
+        unsafe { ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr()) };
+
+        let size = unsafe { ime_pack_shortcuts(std::ptr::null_mut(), 0) };
+        let mut packed = vec![0u8; size];
+        assert_eq!(
+            unsafe { ime_pack_shortcuts(packed.as_mut_ptr(), packed.len()) },
+            size
+        );
+
+        ime_init();
+        unsafe {
+            assert!(ime_load_shortcut_pack(packed.as_ptr(), packed.len()));
+            assert!(!ime_load_shortcut_pack(packed.as_ptr(), 3));
+            assert!(!ime_load_shortcut_pack(std::ptr::null(), 0));
+        }
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 0);
+            assert_eq!(e.shortcuts().library().map(|l| l.len()), Some(1));
+        }
+        drop(guard);
+        assert_ne!(ime_capabilities() & IME_CAP_SHORTCUT_PACK, 0);
+
+        ime_init();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_remove() {
+        ime_init();
+        ime_clear_shortcuts(); // Clear any existing shortcuts
+        ime_method(0); // Telex
+
+        // Add two shortcuts
+        let trigger1 = CString::new("hn").unwrap();
+        let replacement1 = CString::new("Hà Nội").unwrap();
+        let trigger2 = CString::new("hcm").unwrap();
+        let replacement2 = CString::new("Hồ Chí Minh").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger1.as_ptr(), replacement1.as_ptr());
+            ime_add_shortcut(trigger2.as_ptr(), replacement2.as_ptr());
+        }
+
+        // Verify both added
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 2);
+        }
+        drop(guard);
+
+        // Remove one shortcut
+        unsafe {
+            ime_remove_shortcut(trigger1.as_ptr());
+        }
+
+        // Verify only one remains
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+        }
+        drop(guard);
+
+        // Clean up
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_null_safety() {
+        ime_init();
+
+        // Should not crash with null pointers
+        unsafe {
+            ime_add_shortcut(std::ptr::null(), std::ptr::null());
+            ime_remove_shortcut(std::ptr::null());
+        }
+
+        // Engine should still work
+        let r = ime_key(keys::A, false, false);
+        assert!(!r.is_null());
+        unsafe { ime_free(r) };
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_unicode() {
+        ime_init();
+        ime_clear_shortcuts(); // Clear any existing shortcuts
+        ime_method(0);
+
+        // Test with Unicode in both trigger and replacement
+        let trigger = CString::new("tphcm").unwrap();
+        let replacement = CString::new("Thành phố Hồ Chí Minh").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Verify shortcut added with proper UTF-8 handling
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+        }
+        drop(guard);
+
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_symbol_trigger_immediate() {
+        // Test that symbol-only triggers (like "->") are created as immediate shortcuts
+        ime_init();
+        ime_clear_shortcuts();
+        ime_method(0); // Telex
+
+        // Add arrow shortcut via FFI - should auto-detect as immediate
+        let trigger = CString::new("->").unwrap();
+        let replacement = CString::new("→").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Verify shortcut was added with immediate trigger
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+            let shortcut = e.shortcuts().lookup("->").unwrap().1;
+            assert_eq!(
+                shortcut.condition,
+                engine::shortcut::TriggerCondition::Immediate,
+                "Symbol-only trigger should be immediate"
+            );
+        }
+        drop(guard);
+
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_letter_trigger_word_boundary() {
+        // Test that letter triggers (like "vn") are created as word boundary shortcuts
+        ime_init();
+        ime_clear_shortcuts();
+        ime_method(0); // Telex
+
+        // Add abbreviation shortcut via FFI - should be word boundary
+        let trigger = CString::new("vn").unwrap();
+        let replacement = CString::new("Việt Nam").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Verify shortcut was added with word boundary trigger
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+            let shortcut = e.shortcuts().lookup("vn").unwrap().1;
+            assert_eq!(
+                shortcut.condition,
+                engine::shortcut::TriggerCondition::OnWordBoundary,
+                "Letter trigger should be word boundary"
+            );
+        }
+        drop(guard);
+
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    /// Issue #161: Test that shortcuts containing numbers work correctly via FFI
+    #[test]
+    #[serial]
+    fn test_shortcut_ffi_with_numbers() {
+        ime_init();
+        ime_clear_shortcuts();
+        ime_method(0); // Telex
+
+        // Add shortcut with number via FFI
+        let trigger = CString::new("f1").unwrap();
+        let replacement = CString::new("formula one").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Verify shortcut was added
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+            let shortcut = e.shortcuts().lookup("f1").unwrap().1;
+            assert_eq!(
+                shortcut.condition,
+                engine::shortcut::TriggerCondition::OnWordBoundary,
+                "Mixed letter+number trigger should be word boundary"
+            );
+        }
+        drop(guard);
+
+        // Type "f1" + space and verify shortcut triggers
+        let _ = ime_key(keys::F, false, false);
+        let _ = ime_key(keys::N1, false, false);
+        let r = ime_key(keys::SPACE, false, false);
+
+        assert!(!r.is_null());
+        let result = unsafe { &*r };
+        assert_eq!(
+            result.action,
+            engine::Action::Send as u8,
+            "Shortcut should trigger"
+        );
+        assert_eq!(result.backspace, 2, "Should backspace 2 chars (f1)");
+
+        // Verify output
+        let output: String = (0..result.count as usize)
+            .filter_map(|i| char::from_u32(result.chars[i]))
+            .collect();
+        assert_eq!(output, "formula one ", "Should output replacement + space");
+
+        unsafe { ime_free(r) };
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_restore_word_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+
+        // Restore a Vietnamese word
+        let word = CString::new("việt").unwrap();
+        unsafe {
+            ime_restore_word(word.as_ptr());
+        }
+
+        // Type 's' to add sắc mark - should change ệ to ế
+        // Engine returns replacement for changed portion
+        let r = ime_key(keys::S, false, false);
+        assert!(!r.is_null());
+        unsafe {
+            assert_eq!((*r).action, 1, "Should send replacement");
+            // Engine outputs the modified result
+            assert!((*r).count > 0, "Should have output chars");
+            ime_free(r);
+        }
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_push_composed_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+        ime_clear();
+
+        ime_push_composed('ê' as u32);
+        ime_push_composed(0xD800); // surrogate: ignored
+
+        // 's' adds sắc to the pushed ê
+        let r = ime_key(keys::S, false, false);
+        assert!(!r.is_null());
+        unsafe {
+            assert_eq!((*r).action, 1, "Should send replacement");
+            assert_eq!((*r).backspace, 1);
+            assert_eq!(char::from_u32((*r).chars[0]), Some('ế'));
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_PUSH_COMPOSED, 0);
+
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_edit_op_ffi() {
+        ime_init();
+        ime_method(0);
+        ime_english_auto_restore(true);
+        ime_clear();
+
+        let mut old = [0u32; 8];
+        for key in [keys::U, keys::S, keys::E, keys::R] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let r = ime_key(keys::SPACE, false, false);
+        unsafe {
+            assert_eq!(ime_edit_op(r, old.as_mut_ptr(), 8), 3);
+            let word: String = old[..(*r).backspace as usize]
+                .iter()
+                .filter_map(|&c| char::from_u32(c))
+                .collect();
+            assert_eq!(word, "uẻ");
+            ime_free(r);
+            assert_eq!(ime_edit_op(std::ptr::null(), old.as_mut_ptr(), 8), 0);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_EDIT_OPS, 0);
+
+        ime_english_auto_restore(false);
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_output_strategy_ffi() {
+        ime_init();
+        let terminal = CString::new("com.apple.Terminal").unwrap();
+        let combo = CString::new("AXComboBox").unwrap();
+        let mut delays = [0u32; 3];
+        unsafe {
+            ime_set_focus(terminal.as_ptr(), std::ptr::null());
+            assert_eq!(ime_output_strategy(delays.as_mut_ptr()), 1);
+            assert_eq!(delays, [8000, 25000, 8000]);
+
+            ime_set_focus(terminal.as_ptr(), combo.as_ptr());
+            assert_eq!(ime_output_strategy(std::ptr::null_mut()), 3);
+
+            ime_set_output_override(terminal.as_ptr(), 2, 1, 2, 3);
+            assert_eq!(ime_output_strategy(delays.as_mut_ptr()), 2);
+            assert_eq!(delays, [1, 2, 3]);
+            ime_remove_output_override(terminal.as_ptr());
+            assert_eq!(ime_output_strategy(std::ptr::null_mut()), 3);
+
+            ime_set_focus(std::ptr::null(), std::ptr::null());
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_OUTPUT_STRATEGY, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_disable_reason_ffi() {
+        ime_init();
+        ime_enabled(true);
+        assert_eq!(ime_disable_reason(), 0);
+        ime_enabled_for(false, 2);
+        ime_enabled(false);
+        assert_eq!(ime_disable_reason(), 2);
+        ime_enabled_for(true, 2);
+        assert_eq!(ime_disable_reason(), 1);
+        ime_enabled_for(true, 9); // Unknown reason: ignored
+        ime_enabled(true);
+        assert_eq!(ime_disable_reason(), 0);
+        assert_ne!(ime_capabilities() & IME_CAP_DISABLE_REASON, 0);
+    }
+
+    static LOGGED: AtomicUsize = AtomicUsize::new(0);
+
+    extern "C" fn count_mode_records(
+        _level: u8,
+        target: *const std::os::raw::c_char,
+        _message: *const std::os::raw::c_char,
+    ) {
+        if unsafe { std::ffi::CStr::from_ptr(target) }.to_bytes() == b"mode" {
+            LOGGED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "diagnostics")]
+    fn test_log_callback_ffi() {
+        ime_init();
+        ime_enabled(true);
+        LOGGED.store(0, Ordering::SeqCst);
+        ime_set_log_callback(Some(count_mode_records));
+        ime_enabled(false);
+        ime_enabled(true);
+        ime_set_log_callback(None);
+        ime_enabled(false);
+        ime_enabled(true);
+        // enabled=..., disabled by / enabled: two records per toggle
+        assert_eq!(LOGGED.load(Ordering::SeqCst), 4);
+        assert_ne!(ime_capabilities() & IME_CAP_LOG, 0);
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(not(feature = "diagnostics"))]
+    fn test_log_callback_compiled_out() {
+        ime_init();
+        LOGGED.store(0, Ordering::SeqCst);
+        ime_set_log_callback(Some(count_mode_records));
+        ime_enabled(false);
+        ime_enabled(true);
+        ime_set_log_callback(None);
+        assert_eq!(LOGGED.load(Ordering::SeqCst), 0);
+        assert_eq!(ime_capabilities() & IME_CAP_LOG, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_secure_mode_ffi() {
+        ime_init();
+        ime_method(0);
+        ime_enabled(true);
+        ime_clear();
+        ime_secure_mode(true);
+        assert_eq!(ime_disable_reason(), 3);
+        for key in [keys::A, keys::A, keys::S] {
+            let r = ime_key(key, false, false);
+            unsafe {
+                assert_eq!((*r).action, 0);
+                ime_free(r);
+            }
+        }
+        ime_secure_mode(false);
+        assert_eq!(ime_disable_reason(), 0);
+        // Nothing typed in the field carries over
+        let r = ime_key(keys::S, false, false);
+        unsafe {
+            assert_eq!((*r).action, 0);
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_SECURE_MODE, 0);
+
+        // Wiped: backspace after space has no word to restore
+        for key in [keys::A, keys::A, keys::SPACE] {
+            unsafe { ime_free(ime_key(key, false, false)) };
+        }
+        ime_wipe();
+        let r = ime_key(keys::DELETE, false, false);
+        unsafe {
+            assert_eq!((*r).action, 0);
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_WIPE, 0);
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_key_at_ffi() {
+        ime_init();
+        ime_method(0);
+        ime_clear();
+        ime_double_space_period(true);
+
+        for (i, key) in [keys::O, keys::K, keys::SPACE].into_iter().enumerate() {
+            let r = ime_key_at(key, false, false, false, i as u64 * 100);
+            assert!(!r.is_null());
+            unsafe { ime_free(r) };
+        }
+        let r = ime_key_at(keys::SPACE, false, false, false, 350);
+        unsafe {
+            assert_eq!((*r).backspace, 1);
+            assert_eq!((*r).count, 2);
+            assert!((*r).key_consumed());
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_KEY_TIMING, 0);
+        ime_double_space_period(false);
+
+        // Idle commit: the late 's' no longer marks "ca"
+        ime_idle_commit(5000);
+        for (key, time) in [(keys::C, 1000), (keys::A, 1100), (keys::S, 9000)] {
+            let r = ime_key_at(key, false, false, false, time);
+            unsafe {
+                assert_eq!((*r).action, 0);
+                ime_free(r);
+            }
+        }
+        ime_idle_commit(0);
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_key_typed_ffi() {
+        ime_init();
+        ime_method(1); // VNI
+        ime_esc_restore(true);
+        ime_clear();
+
+        // AZERTY: the 2 key types "é" but is still the VNI huyền key
+        for (key, ch) in [(keys::A, 'a'), (keys::N2, 'é')] {
+            let r = ime_key_typed(key, false, false, false, ch as u32);
+            assert!(!r.is_null());
+            unsafe { ime_free(r) };
+        }
+        let r = ime_key(keys::ESC, false, false);
+        assert!(!r.is_null());
+        unsafe {
+            let restored: String = (0..(*r).count as usize)
+                .filter_map(|i| char::from_u32((*r).chars[i]))
+                .collect();
+            assert_eq!(restored, "aé");
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_KEY_TYPED, 0);
+
+        ime_esc_restore(false);
+        ime_method(0);
+        ime_clear();
+    }
+
+    #[test]
+    #[serial]
+    fn test_flush_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+        ime_english_auto_restore(true);
+        ime_clear_all();
+
+        for key in [keys::C, keys::A, keys::S, keys::E] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let r = ime_flush();
+        assert!(!r.is_null());
+        unsafe {
+            assert_eq!((*r).action, engine::Action::Send as u8);
+            assert_eq!((*r).backspace, 3); // "cáe"
+            let output: String = (0..(*r).count as usize)
+                .filter_map(|i| char::from_u32((*r).chars[i]))
+                .collect();
+            assert_eq!(output, "case");
+            ime_free(r);
+        }
+        // Nothing left to finalize
+        let r = ime_flush();
+        unsafe {
+            assert_eq!((*r).action, engine::Action::None as u8);
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_FLUSH, 0);
+
+        ime_english_auto_restore(false);
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_profile_ffi() {
+        ime_init();
+        ime_clear_all();
+        let work = CString::new("work").unwrap();
+        let default = CString::new("default").unwrap();
+
+        unsafe {
+            assert!(!ime_set_profile(work.as_ptr()));
+        }
+        ime_method(1); // VNI in "work"
+        unsafe {
+            assert!(ime_set_profile(default.as_ptr()));
+        }
+        // Back in "default": Telex
+        let r = ime_key(keys::A, false, false);
+        unsafe { ime_free(r) };
+        let r = ime_key(keys::S, false, false);
+        unsafe {
+            assert_eq!((*r).action, 1);
+            assert_eq!(char::from_u32((*r).chars[0]), Some('á'));
+            ime_free(r);
+            assert!(!ime_set_profile(std::ptr::null()));
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_PROFILES, 0);
+
+        ime_init();
+    }
+
+    #[test]
+    #[serial]
+    fn test_sync_context_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+        ime_clear_all();
+
+        for key in [keys::A, keys::N, keys::SPACE] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let crlf = CString::new("an\r\n").unwrap();
+        let moved = CString::new("xyz ").unwrap();
+        unsafe {
+            assert_eq!(ime_sync_context(crlf.as_ptr()), 1);
+            assert_eq!(ime_sync_context(crlf.as_ptr()), 0);
+            assert_eq!(ime_sync_context(moved.as_ptr()), 2);
+            assert_eq!(ime_sync_context(std::ptr::null()), 0);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_SYNC_CONTEXT, 0);
+
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_reset_external_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+        ime_clear_all();
+
+        ime_reset_external(1);
+        assert!(ime_needs_context());
+        let before = CString::new("x ").unwrap();
+        unsafe { ime_sync_context(before.as_ptr()) };
+        assert!(!ime_needs_context());
+        ime_reset_external(7);
+        assert!(!ime_needs_context());
+        assert_ne!(ime_capabilities() & IME_CAP_RESET_EXTERNAL, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_verify_result_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+        ime_clear_all();
+
+        for key in [keys::T, keys::I, keys::E, keys::E, keys::N, keys::G] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let r = ime_key(keys::S, false, false);
+        let screen = CString::new("tiêng").unwrap();
+        let rewritten = CString::new("tiêngx").unwrap();
+        unsafe {
+            assert_eq!(ime_verify_result(r, screen.as_ptr()), 0);
+            assert_eq!((*r).action, 1);
+            assert_eq!(ime_verify_result(r, rewritten.as_ptr()), 2);
+            assert_eq!((*r).action, 0);
+            assert_eq!(ime_verify_result(std::ptr::null_mut(), screen.as_ptr()), 0);
+            assert_eq!(ime_verify_result(r, std::ptr::null()), 0);
+            ime_free(r);
+        }
+        assert_ne!(ime_capabilities() & IME_CAP_VERIFY_RESULT, 0);
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_restore_word_ffi_null_safety() {
+        ime_init();
+
+        // Should not crash with null pointer
+        unsafe {
+            ime_restore_word(std::ptr::null());
+        }
+
+        // Engine should still work
+        let r = ime_key(keys::A, false, false);
+        assert!(!r.is_null());
+        unsafe { ime_free(r) };
+
+        ime_clear();
+    }
+
+    /// Issue #275: Test that special character shortcuts work via ime_key_with_char
+    /// Example: √√ → ✅ (Option+V twice produces checkmark)
+    #[test]
+    #[serial]
+    fn test_special_char_shortcut_ffi() {
+        ime_init();
+        ime_clear_shortcuts();
+        ime_method(0); // Telex
+
+        // Add special character shortcut: √√ → ✅
+        let trigger = CString::new("√√").unwrap();
+        let replacement = CString::new("✅").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Verify shortcut was added as immediate (symbol-only trigger)
+        let guard = lock_engine();
+        if let Some(ref e) = *guard {
+            assert_eq!(e.shortcuts().len(), 1);
+            let shortcut = e.shortcuts().lookup("√√").unwrap().1;
+            assert_eq!(
+                shortcut.condition,
+                engine::shortcut::TriggerCondition::Immediate,
+                "Symbol-only trigger should be immediate"
+            );
+        }
+        drop(guard);
+
+        // Simulate typing √ (Option+V) twice using ime_key_with_char
+        // First √ - should accumulate in shortcut_prefix
+        let r1 = ime_key_with_char(keys::V, false, false, false, '√' as u32);
+        assert!(!r1.is_null());
+        let result1 = unsafe { &*r1 };
+        assert_eq!(result1.action, 0, "First √ should not trigger yet");
+        unsafe { ime_free(r1) };
+
+        // Second √ - should match and trigger shortcut
+        let r2 = ime_key_with_char(keys::V, false, false, false, '√' as u32);
+        assert!(!r2.is_null());
+        let result2 = unsafe { &*r2 };
+        assert_eq!(
+            result2.action,
+            engine::Action::Send as u8,
+            "Second √ should trigger shortcut"
+        );
+        assert_eq!(result2.backspace, 1, "Should backspace 1 char (first √)");
+
+        // Verify output is ✅
+        let output: String = (0..result2.count as usize)
+            .filter_map(|i| char::from_u32(result2.chars[i]))
+            .collect();
+        assert_eq!(output, "✅", "Should output ✅");
+
+        // Verify key_consumed flag is set
+        assert!(
+            (result2.flags & engine::FLAG_KEY_CONSUMED) != 0,
+            "Key should be consumed"
+        );
+
+        unsafe { ime_free(r2) };
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    /// Issue #275: Test suffix matching - typing ≈ç√√ should still match √√
+    /// When other special chars precede the shortcut, it should still trigger
+    #[test]
+    #[serial]
+    fn test_special_char_shortcut_with_prefix() {
+        ime_init();
+        ime_clear_shortcuts();
+        ime_method(0); // Telex
+
+        // Add special character shortcut: √√ → ✅
+        let trigger = CString::new("√√").unwrap();
+        let replacement = CString::new("✅").unwrap();
+
+        unsafe {
+            ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr());
+        }
+
+        // Type ≈ (Option+X) - should pass through
+        let r1 = ime_key_with_char(keys::X, false, false, false, '≈' as u32);
+        assert!(!r1.is_null());
+        let result1 = unsafe { &*r1 };
+        assert_eq!(result1.action, 0, "≈ should not trigger anything");
+        unsafe { ime_free(r1) };
+
+        // Type ç (Option+C) - should pass through
+        let r2 = ime_key_with_char(keys::C, false, false, false, 'ç' as u32);
+        assert!(!r2.is_null());
+        let result2 = unsafe { &*r2 };
+        assert_eq!(result2.action, 0, "ç should not trigger anything");
+        unsafe { ime_free(r2) };
+
+        // Type √ (Option+V) - first √, should accumulate
+        let r3 = ime_key_with_char(keys::V, false, false, false, '√' as u32);
+        assert!(!r3.is_null());
+        let result3 = unsafe { &*r3 };
+        assert_eq!(result3.action, 0, "First √ should not trigger yet");
+        unsafe { ime_free(r3) };
+
+        // Type √ (Option+V) - second √, should trigger √√ → ✅
+        let r4 = ime_key_with_char(keys::V, false, false, false, '√' as u32);
+        assert!(!r4.is_null());
+        let result4 = unsafe { &*r4 };
+        assert_eq!(
+            result4.action,
+            engine::Action::Send as u8,
+            "Second √ should trigger shortcut (suffix match)"
+        );
+        assert_eq!(result4.backspace, 1, "Should backspace 1 char (first √)");
+
+        // Verify output is ✅
+        let output: String = (0..result4.count as usize)
+            .filter_map(|i| char::from_u32(result4.chars[i]))
+            .collect();
+        assert_eq!(output, "✅", "Should output ✅");
+
+        unsafe { ime_free(r4) };
+        ime_clear_shortcuts();
+        ime_clear();
+    }
+
+    #[test]
+    fn test_ffi_tone_position() {
+        let word = CString::new("hoa").unwrap();
+        unsafe {
+            assert_eq!(ime_tone_position(word.as_ptr(), true), 2);
+            assert_eq!(ime_tone_position(word.as_ptr(), false), 1);
+            assert_eq!(ime_tone_position(std::ptr::null(), true), -1);
+        }
+        let word = CString::new("xyz!").unwrap();
+        unsafe { assert_eq!(ime_tone_position(word.as_ptr(), true), -1) };
+    }
+
+    #[test]
+    fn test_ffi_transform_text() {
+        let text = CString::new("Tieengs Vieetj").unwrap();
+        let mut out = [0u32; 32];
+        let len = unsafe { ime_transform_text(text.as_ptr(), 0, out.as_mut_ptr(), 32) };
+        let result: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(result, "Tiếng Việt");
+
+        let len = unsafe { ime_transform_text(text.as_ptr(), 9, out.as_mut_ptr(), 32) };
+        assert_eq!(len, -1);
+        let len = unsafe { ime_transform_text(text.as_ptr(), 2, out.as_mut_ptr(), 4) };
+        assert_eq!(len, 4);
+    }
+
+    #[test]
+    fn test_ffi_correct_sentence() {
+        let text = CString::new("tell hẻ").unwrap();
+        let mut out = [0u32; 16];
+        let len = unsafe { ime_correct_sentence(text.as_ptr(), out.as_mut_ptr(), 16) };
+        let result: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(result, "tell her");
+        assert_eq!(
+            unsafe { ime_correct_sentence(std::ptr::null(), out.as_mut_ptr(), 16) },
+            -1
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_transform_selection() {
+        ime_init();
+        ime_method(1);
+        let text = CString::new("Vie65t Nam, hello").unwrap();
+        let mut out = [0u32; 32];
+        let len = unsafe { ime_transform_selection(text.as_ptr(), out.as_mut_ptr(), 32) };
+        let result: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(result, "Việt Nam, hello");
+        ime_method(0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_spell_flag() {
+        ime_init();
+        ime_method(0);
+        ime_spell_check(true);
+        for key in [
+            keys::G,
+            keys::H,
+            keys::E,
+            keys::E,
+            keys::P,
+            keys::S,
+            keys::SPACE,
+        ] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let mut out = [0u32; 64];
+        let len = unsafe { ime_take_spell_flag(out.as_mut_ptr(), 64) };
+        let text: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert!(text.starts_with("ghếp"));
+        assert_eq!(unsafe { ime_take_spell_flag(out.as_mut_ptr(), 64) }, 0);
+        ime_spell_check(false);
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_status() {
+        ime_init();
+        ime_method(0);
+        ime_enabled(true);
+        ime_clear_all();
+        for key in [keys::V, keys::I, keys::E, keys::E] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let mut out = [0u32; 16];
+        let len = unsafe { ime_get_raw_word(out.as_mut_ptr(), 16) };
+        let raw: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(raw, "viee");
+        assert_eq!(ime_mode(), 0);
+        ime_enabled(false);
+        assert_eq!(ime_mode(), 1);
+        ime_enabled(true);
+        ime_clear_all();
+    }
+
+    #[test]
+    fn test_ffi_english_confidence() {
+        let english = CString::new("text").unwrap();
+        let vietnamese = CString::new("vieetj").unwrap();
+        assert!(unsafe { ime_english_confidence(english.as_ptr()) } >= 70);
+        assert!(unsafe { ime_english_confidence(vietnamese.as_ptr()) } < 35);
+        assert_eq!(unsafe { ime_english_confidence(std::ptr::null()) }, 50);
+        assert_ne!(ime_capabilities() & IME_CAP_ENGLISH_CONFIDENCE, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_panic_is_caught() {
+        ime_init();
+        ime_method(0);
+        for key in [keys::V, keys::I, keys::E] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+
+        let value = guarded(-1, || -> i32 { panic!("boom") });
+        assert_eq!(value, -1);
+        assert_eq!(ime_last_error(), IME_ERR_PANIC);
+        let mut out = [0u32; 16];
+        let len = unsafe { ime_last_error_message(out.as_mut_ptr(), 16) };
+        let message: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(message, "boom");
+
+        // Word state was reset; next call succeeds and clears the error
+        let len = unsafe { ime_get_buffer(out.as_mut_ptr(), 16) };
+        assert_eq!(len, 0);
+        assert_eq!(ime_last_error(), IME_OK);
+    }
+
+    #[test]
+    fn test_ffi_abi_and_capabilities() {
+        assert_eq!(ime_abi_version(), IME_ABI_VERSION);
+        let caps = ime_capabilities();
+        assert_ne!(caps & IME_CAP_KEY_CONSUMED, 0);
+        assert_ne!(caps & IME_CAP_LAST_ERROR, 0);
+        assert_eq!(caps & IME_CAP_MARKED_TEXT, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_field_kind() {
+        ime_init();
+        ime_field_kind(FieldKind::Url as u8);
+        assert_eq!(ime_disable_reason(), DisableReason::Field as u8);
+        ime_field_kind(9); // unknown: ignored
+        assert_eq!(ime_disable_reason(), DisableReason::Field as u8);
+        ime_field_kind(FieldKind::Normal as u8);
+        assert_eq!(ime_disable_reason(), 0);
+    }
+
+    #[test]
+    fn test_ffi_behavior_version() {
+        let read = |f: unsafe extern "C" fn(*mut u32, i64) -> i64, max_len: i64| {
+            let mut out = vec![0u32; 4096];
+            let n = unsafe { f(out.as_mut_ptr(), max_len) };
+            out[..n as usize]
+                .iter()
+                .filter_map(|&c| char::from_u32(c))
+                .collect::<String>()
+        };
+        assert_eq!(
+            read(ime_behavior_version, 4096),
+            engine::behavior::BEHAVIOR_VERSION
+        );
+        let changelog = read(ime_behavior_changelog, 4096);
+        assert_eq!(changelog, engine::behavior::behavior_changelog());
+        assert_eq!(read(ime_behavior_changelog, 2), changelog[..2]);
+        assert_ne!(ime_capabilities() & IME_CAP_BEHAVIOR_VERSION, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_key_into_long_expansion() {
+        ime_init();
+        ime_method(0);
+        ime_clear_shortcuts();
+        let long = "Cộng hoà xã hội chủ nghĩa Việt Nam. ".repeat(10);
+        let trigger = CString::new("chxh").unwrap();
+        let replacement = CString::new(long.clone()).unwrap();
+        unsafe { ime_add_shortcut(trigger.as_ptr(), replacement.as_ptr()) };
+
+        let mut header = KeyOutput::default();
+        let mut out = vec![0u32; 512];
+        for key in [keys::C, keys::H, keys::X, keys::H] {
+            let n = unsafe {
+                ime_key_into(
+                    key,
+                    false,
+                    false,
+                    false,
+                    0,
+                    &mut header,
+                    out.as_mut_ptr(),
+                    512,
+                )
+            };
+            assert!(n >= 0);
+        }
+        let n = unsafe {
+            ime_key_into(
+                keys::SPACE,
+                false,
+                false,
+                false,
+                0,
+                &mut header,
+                out.as_mut_ptr(),
+                512,
+            )
+        };
+        assert_eq!(header.action, 1);
+        assert_eq!(header.count as i64, n);
+        let text: String = out[..n as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(text, format!("{long} "));
+        assert!(text.chars().count() > 255);
+
+        // Short buffer: count reports the total, full text stays fetchable
+        let mut small = [0u32; 4];
+        ime_clear_all();
+        for key in [keys::C, keys::H, keys::X, keys::H, keys::SPACE] {
+            unsafe {
+                ime_key_into(
+                    key,
+                    false,
+                    false,
+                    false,
+                    0,
+                    &mut header,
+                    small.as_mut_ptr(),
+                    4,
+                )
+            };
+        }
+        assert_eq!(header.count as usize, long.chars().count() + 1);
+        assert_eq!(
+            small.map(|c| char::from_u32(c).unwrap()),
+            ['C', 'ộ', 'n', 'g']
+        );
+        let n = unsafe { ime_last_expansion(out.as_mut_ptr(), 512) };
+        assert_eq!(n, header.count as i64);
+
+        ime_clear_shortcuts();
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_last_restore() {
+        ime_init();
+        ime_method(0);
+        ime_esc_restore(true);
+        for key in [keys::T, keys::E, keys::S, keys::ESC] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let mut out = [0u32; 32];
+        let mut reason = 255u8;
+        let len = unsafe { ime_last_restore(out.as_mut_ptr(), 32, &mut reason) };
+        let text: String = out[..len as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+            .collect();
+        assert_eq!(text, "té\ntes");
+        assert_eq!(reason, 0);
+
+        // Cleared by the next key
+        let r = ime_key(keys::A, false, false);
+        unsafe { ime_free(r) };
+        assert_eq!(
+            unsafe { ime_last_restore(out.as_mut_ptr(), 32, &mut reason) },
+            0
+        );
+        ime_esc_restore(false);
+        ime_clear_all();
+    }
+
+    #[test]
+    #[serial]
+    fn test_ffi_history() {
+        ime_init();
+        ime_method(0);
+        for key in [keys::A, keys::S, keys::SPACE, keys::B, keys::SPACE] {
+            let r = ime_key(key, false, false);
+            unsafe { ime_free(r) };
+        }
+        let mut out = [0u32; 8];
+        assert_eq!(unsafe { ime_peek_history(0, out.as_mut_ptr(), 8) }, 1);
+        assert_eq!(out[0], 'b' as u32);
+        assert_eq!(unsafe { ime_peek_history(1, out.as_mut_ptr(), 8) }, 1);
+        assert_eq!(out[0], 'á' as u32);
+        assert_eq!(unsafe { ime_peek_history(2, out.as_mut_ptr(), 8) }, -1);
+
+        ime_history_capacity(1);
+        assert_eq!(unsafe { ime_peek_history(1, out.as_mut_ptr(), 8) }, -1);
+        ime_history_capacity(10);
+        ime_clear_all();
+    }
+}
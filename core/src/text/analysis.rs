@@ -0,0 +1,226 @@
+//! Syllable Analysis
+//!
+//! Phonology queries over a single written syllable, for tools built around
+//! Vietnamese verse (lục bát checkers, lyric tools). Syllables go through the
+//! engine's own parser and validator, so "hoà" and "hòa" agree and words the
+//! engine wouldn't accept as Vietnamese get no answer.
+//!
+//! `analyze_text` applies the same checks to every word of a text, so hosts
+//! can find words that need re-checking in text that was already typed.
+
+use core::ops::Range;
+
+use super::grapheme::compose_text;
+use crate::data::chars::{self, mark};
+use crate::engine::syllable::{self, Syllable};
+use crate::engine::validation;
+use crate::prelude::*;
+use crate::utils::key_to_char;
+
+/// Tone class used by Vietnamese metre (luật bằng trắc)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ToneClass {
+    /// Level tones: ngang (no mark) and huyền
+    Bang,
+    /// Oblique tones: sắc, hỏi, ngã, nặng
+    Trac,
+}
+
+/// Letters of a syllable split into keys, vowel modifiers and its tone mark
+struct Parsed {
+    keys: Vec<u16>,
+    tones: Vec<u8>,
+    mark: u8,
+    syllable: Syllable,
+}
+
+/// Parse and validate one syllable; None for anything else
+fn parse(word: &str) -> Option<Parsed> {
+    let mut keys = Vec::new();
+    let mut tones = Vec::new();
+    let mut word_mark = mark::NONE;
+    for c in word.chars() {
+        let parsed = chars::parse_char(c)?;
+        if parsed.mark != mark::NONE {
+            // One tone mark per syllable
+            if word_mark != mark::NONE {
+                return None;
+            }
+            word_mark = parsed.mark;
+        }
+        keys.push(parsed.key);
+        tones.push(parsed.tone);
+    }
+    if !validation::is_valid_with_tones(&keys, &tones) {
+        return None;
+    }
+    let syllable = syllable::parse(&keys);
+    Some(Parsed {
+        keys,
+        tones,
+        mark: word_mark,
+        syllable,
+    })
+}
+
+/// Tone class of a syllable ("hoa", "hoà" → Bằng; "hoá", "hoạ" → Trắc)
+///
+/// Case-insensitive. `None` when `syllable` isn't one Vietnamese syllable.
+pub fn tone_class(syllable: &str) -> Option<ToneClass> {
+    let parsed = parse(syllable)?;
+    Some(match parsed.mark {
+        mark::NONE | mark::HUYEN => ToneClass::Bang,
+        _ => ToneClass::Trac,
+    })
+}
+
+/// Rhyme (vần) of a syllable: glide, vowel and final consonant, lowercase and
+/// without the tone mark ("Hoàng" → "oang", "thuyền" → "uyên", "quê" → "ê")
+///
+/// Two syllables rhyme in verse when their rhymes match and they share a
+/// `tone_class`. The "qu" and "gi" initials are not part of the rhyme.
+/// `None` when `syllable` isn't one Vietnamese syllable.
+pub fn rhyme(syllable: &str) -> Option<String> {
+    let parsed = parse(syllable)?;
+    let s = &parsed.syllable;
+    s.glide
+        .iter()
+        .chain(&s.vowel)
+        .chain(&s.final_c)
+        .map(|&i| {
+            let key = parsed.keys[i];
+            chars::to_char(key, false, parsed.tones[i], mark::NONE)
+                .or_else(|| key_to_char(key, false))
+        })
+        .collect()
+}
+
+/// What a word of text is (see `analyze_text`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpanKind {
+    /// A valid Vietnamese syllable ("việt", "hoa", also "an")
+    ValidVietnamese,
+    /// Not Vietnamese and without Vietnamese letters ("hello", "x2")
+    Foreign,
+    /// Vietnamese letters in a word that isn't a valid syllable ("viêtnam",
+    /// "hellô"): typically typed with the IME in the wrong state
+    Mixed,
+}
+
+/// A word of text and its kind
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// Byte range in the analyzed text
+    pub range: Range<usize>,
+    pub kind: SpanKind,
+}
+
+/// Word character: letters, digits and combining marks (NFD input)
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || chars::is_combining(c)
+}
+
+/// Classify each word of `text`
+///
+/// Words are runs of letters and digits; everything between them
+/// (whitespace, punctuation) gets no span. Decomposed letters are composed
+/// before checking, so NFD text classifies like NFC.
+pub fn analyze_text(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (start, is_word_char(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                spans.push(Span {
+                    range: s..i,
+                    kind: classify(&text[s..i]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+fn classify(word: &str) -> SpanKind {
+    let word = compose_text(word);
+    if parse(&word).is_some() {
+        SpanKind::ValidVietnamese
+    } else if word
+        .chars()
+        .any(|c| !c.is_ascii() && chars::parse_char(c).is_some())
+    {
+        SpanKind::Mixed
+    } else {
+        SpanKind::Foreign
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_class() {
+        assert_eq!(tone_class("hoa"), Some(ToneClass::Bang));
+        assert_eq!(tone_class("Hoà"), Some(ToneClass::Bang));
+        assert_eq!(tone_class("hòa"), Some(ToneClass::Bang));
+        assert_eq!(tone_class("nước"), Some(ToneClass::Trac));
+        assert_eq!(tone_class("mỏi"), Some(ToneClass::Trac));
+        assert_eq!(tone_class("ngã"), Some(ToneClass::Trac));
+        assert_eq!(tone_class("Việt"), Some(ToneClass::Trac));
+    }
+
+    #[test]
+    fn test_rhyme() {
+        assert_eq!(rhyme("Hoàng").as_deref(), Some("oang"));
+        assert_eq!(rhyme("thuyền").as_deref(), Some("uyên"));
+        assert_eq!(rhyme("quê").as_deref(), Some("ê"));
+        assert_eq!(rhyme("gì").as_deref(), Some("i"));
+        assert_eq!(rhyme("giàu").as_deref(), Some("au"));
+        assert_eq!(rhyme("đường").as_deref(), Some("ương"));
+        assert_eq!(rhyme("a").as_deref(), Some("a"));
+        // Lục bát: "cây" rhymes with "dây"
+        assert_eq!(rhyme("cây"), rhyme("dây"));
+    }
+
+    #[test]
+    fn test_not_a_syllable() {
+        for word in ["", "bcd", "hello", "việt nam", "áà", "x2"] {
+            assert_eq!(tone_class(word), None, "{word}");
+            assert_eq!(rhyme(word), None, "{word}");
+        }
+    }
+
+    #[test]
+    fn test_analyze_text() {
+        let text = "Tiếng Việt, hello viêtnam x2 hellô!";
+        let spans: Vec<(&str, SpanKind)> = analyze_text(text)
+            .into_iter()
+            .map(|s| (&text[s.range], s.kind))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("Tiếng", SpanKind::ValidVietnamese),
+                ("Việt", SpanKind::ValidVietnamese),
+                ("hello", SpanKind::Foreign),
+                ("viêtnam", SpanKind::Mixed),
+                ("x2", SpanKind::Foreign),
+                ("hellô", SpanKind::Mixed),
+            ]
+        );
+        assert!(analyze_text("").is_empty());
+        assert!(analyze_text(" ... ").is_empty());
+    }
+
+    #[test]
+    fn test_analyze_decomposed_text() {
+        let spans = analyze_text("Vie\u{0302}\u{0323}t Nam");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].range, 0..8);
+        assert_eq!(spans[0].kind, SpanKind::ValidVietnamese);
+    }
+}
@@ -0,0 +1,169 @@
+//! Vietnamese Collation
+//!
+//! Sorts strings by Vietnamese dictionary order instead of Unicode code point.
+//! Code point order puts "ă" after "z" and interleaves tones with unrelated
+//! letters; dictionary order compares in three levels:
+//!
+//! 1. **Letters**: a ă â b c d đ e ê (f) g h i (j) k l m n o ô ơ p q r s t u ư v (w) x y (z)
+//! 2. **Tones**: ngang, huyền, hỏi, ngã, sắc, nặng (a à ả ã á ạ)
+//! 3. **Case**: lowercase before uppercase
+//!
+//! A later level only breaks ties of the earlier one: "ca" < "cà" < "cả" < "cã"
+//! < "cá" < "cạ" < "cb", and "mai" < "mái" < "mau" (letters decide before tones).
+//!
+//! Non-letters (digits, punctuation) sort before letters by code point;
+//! characters outside the Vietnamese/Latin alphabet sort after by code point.
+
+use crate::data::chars::{self, mark};
+use crate::prelude::*;
+use crate::utils::key_to_char;
+use core::cmp::Ordering;
+
+/// Vietnamese alphabet with foreign letters (f, j, w, z) in their Latin positions
+const ALPHABET: [&str; 33] = [
+    "a", "ă", "â", "b", "c", "d", "đ", "e", "ê", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o",
+    "ô", "ơ", "p", "q", "r", "s", "t", "u", "ư", "v", "w", "x", "y", "z",
+];
+
+/// Letters start after every ASCII non-letter
+const LETTER_BASE: u32 = 0x80;
+
+/// Unknown characters sort after every letter
+const OTHER_BASE: u32 = LETTER_BASE + ALPHABET.len() as u32;
+
+/// Collation weights of one character: (letter, tone, case)
+fn weights(c: char) -> (u32, u8, u8) {
+    let Some(parsed) = chars::parse_char(c) else {
+        return if c.is_ascii() {
+            (c as u32, 0, 0)
+        } else {
+            (OTHER_BASE + c as u32, 0, 0)
+        };
+    };
+
+    let letter = if parsed.stroke {
+        Some(chars::get_d(false))
+    } else {
+        chars::base_char(parsed.key, parsed.tone).or_else(|| key_to_char(parsed.key, false))
+    };
+    let Some(letter) = letter else {
+        return (OTHER_BASE + c as u32, 0, 0);
+    };
+    let index = ALPHABET.iter().position(|l| l.starts_with(letter));
+
+    // Dictionary tone order differs from the mark constants (sắc=1, huyền=2, ...)
+    let tone_weight = match parsed.mark {
+        mark::HUYEN => 1,
+        mark::HOI => 2,
+        mark::NGA => 3,
+        mark::SAC => 4,
+        mark::NANG => 5,
+        _ => 0,
+    };
+
+    match index {
+        Some(i) => (LETTER_BASE + i as u32, tone_weight, parsed.caps as u8),
+        None => (OTHER_BASE + c as u32, 0, 0),
+    }
+}
+
+/// Build a sort key: all letter weights, then all tone weights, then all case weights
+///
+/// Keys compare with plain `Ord`, so they can be cached for large lists
+/// (`sort_by_cached_key`).
+pub fn sort_key(s: &str) -> Vec<u32> {
+    let w: Vec<(u32, u8, u8)> = s.chars().map(weights).collect();
+    let mut key = Vec::with_capacity(w.len() * 3 + 2);
+    key.extend(w.iter().map(|&(l, _, _)| l + 1));
+    key.push(0);
+    key.extend(w.iter().map(|&(_, t, _)| t as u32));
+    key.push(0);
+    key.extend(w.iter().map(|&(_, _, c)| c as u32));
+    key
+}
+
+/// Compare two strings in Vietnamese dictionary order
+pub fn compare(a: &str, b: &str) -> Ordering {
+    for level in 0..3 {
+        let pick = |c: char| {
+            let (l, t, cs) = weights(c);
+            match level {
+                0 => l,
+                1 => t as u32,
+                _ => cs as u32,
+            }
+        };
+        let ord = a.chars().map(pick).cmp(b.chars().map(pick));
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Sort a slice of strings in Vietnamese dictionary order
+pub fn sort<S: AsRef<str>>(items: &mut [S]) {
+    items.sort_by_cached_key(|s| sort_key(s.as_ref()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(words: &[&str]) -> Vec<String> {
+        let mut v: Vec<String> = words.iter().map(|s| s.to_string()).collect();
+        sort(&mut v);
+        v
+    }
+
+    #[test]
+    fn test_alphabet_order() {
+        assert_eq!(
+            sorted(&["đ", "d", "â", "ă", "a", "ơ", "ô", "o", "ư", "u", "ê", "e"]),
+            vec!["a", "ă", "â", "d", "đ", "e", "ê", "o", "ô", "ơ", "u", "ư"]
+        );
+        assert_eq!(sorted(&["ăn", "anh", "ba"]), vec!["anh", "ăn", "ba"]);
+    }
+
+    #[test]
+    fn test_tone_order() {
+        assert_eq!(
+            sorted(&["cạ", "cá", "cã", "cả", "cà", "ca"]),
+            vec!["ca", "cà", "cả", "cã", "cá", "cạ"]
+        );
+    }
+
+    #[test]
+    fn test_letters_before_tones() {
+        // Tone is secondary: "cá" < "cb" even though 'á' > 'b' by code point
+        assert_eq!(compare("cá", "cb"), Ordering::Less);
+        assert_eq!(
+            sorted(&["mái", "mai", "màu", "mau"]),
+            vec!["mai", "mái", "mau", "màu"]
+        );
+    }
+
+    #[test]
+    fn test_case_is_tertiary() {
+        assert_eq!(compare("an", "An"), Ordering::Less);
+        assert_eq!(compare("An", "ăn"), Ordering::Less);
+        assert_eq!(compare("Đà", "đà"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_non_letters() {
+        assert_eq!(sorted(&["b", "1a", "a"]), vec!["1a", "a", "b"]);
+        assert_eq!(compare("a b", "ab"), Ordering::Less);
+        assert_eq!(compare("z", "ñ"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_sort_key_matches_compare() {
+        let words = ["người", "nguội", "ngươi", "Người", "ngưởi", "ngoài", "ngô"];
+        for a in words {
+            for b in words {
+                assert_eq!(sort_key(a).cmp(&sort_key(b)), compare(a, b), "{a} vs {b}");
+            }
+        }
+    }
+}
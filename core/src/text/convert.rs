@@ -0,0 +1,240 @@
+//! Batch Text Conversion
+//!
+//! Converts whole strings for "Paste and convert" style features, reusing the
+//! engine rules instead of reimplementing them on the host:
+//! - `Telex` / `Vni`: raw keystrokes text → Vietnamese ("Tieengs Vieetj" → "Tiếng Việt")
+//! - `StripDiacritics`: Vietnamese → ASCII ("Tiếng Việt" → "Tieng Viet")
+//! - `Legacy`: TCVN3/VNI font text → Unicode, encoding auto-detected ("TiÕng ViÖt" → "Tiếng Việt")
+//!
+//! `transform_selection` is the word-by-word variant for "convert selection"
+//! commands: text typed while the IME was off, mixed with text that wasn't.
+
+use super::diacritics::remove_diacritics;
+use crate::data::{keys, legacy};
+use crate::engine::profile::Config;
+use crate::engine::{Action, Engine, FLAG_KEY_CONSUMED};
+use crate::prelude::*;
+use crate::utils::char_to_key_shift;
+
+/// Conversion direction for `transform_text`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Raw Telex keystrokes → Vietnamese
+    Telex = 0,
+    /// Raw VNI keystrokes → Vietnamese
+    Vni = 1,
+    /// Vietnamese → ASCII
+    StripDiacritics = 2,
+    /// Legacy font encoding → Unicode
+    Legacy = 3,
+}
+
+impl Direction {
+    /// Parse FFI value (0=Telex, 1=VNI, 2=Strip, 3=Legacy)
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Telex),
+            1 => Some(Self::Vni),
+            2 => Some(Self::StripDiacritics),
+            3 => Some(Self::Legacy),
+            _ => None,
+        }
+    }
+}
+
+/// Convert text in the given direction
+pub fn transform_text(text: &str, direction: Direction) -> String {
+    match direction {
+        Direction::Telex | Direction::Vni => {
+            let mut e = Engine::new();
+            e.set_method(if direction == Direction::Vni { 1 } else { 0 });
+            compose(&mut e, text)
+        }
+        Direction::StripDiacritics => remove_diacritics(text),
+        Direction::Legacy => legacy::auto_to_unicode(text),
+    }
+}
+
+/// Re-derive diacritics in selected text typed with the IME off
+///
+/// Each word of ASCII letters and digits is typed through an engine with
+/// `config`'s method and tone settings ("Tieengs Vieetj" → "Tiếng Việt").
+/// Words that already contain other characters ("Việt"), whitespace and
+/// punctuation are kept as they are. English auto-restore is always on so
+/// English words in the selection survive; auto-capitalize is off so case
+/// is never changed.
+pub fn transform_selection(raw_text: &str, config: &Config) -> String {
+    let mut e = Engine::new();
+    e.set_config(&Config {
+        english_auto_restore: true,
+        auto_capitalize: false,
+        spell_check: false,
+        ..config.clone()
+    });
+    let mut out = String::with_capacity(raw_text.len());
+    let mut start = None;
+    for (i, c) in raw_text.char_indices().chain([(raw_text.len(), ' ')]) {
+        match (start, c.is_alphanumeric()) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                let word = &raw_text[s..i];
+                if word.is_ascii() {
+                    out.push_str(&compose(&mut e, word));
+                } else {
+                    out.push_str(word);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+        if start.is_none() && i < raw_text.len() {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Map a text char to (key, shift) for keys the engine understands
+///
+/// Letters, digits, space and word-ending punctuation. Other chars have no
+/// key here: brackets would type ư/ơ in Telex, and `char_to_key` maps '<'
+/// to DELETE.
+fn text_char_to_key(c: char) -> Option<(u16, bool)> {
+    (c.is_ascii_alphanumeric() || " .,;'-/!?:\"()".contains(c)).then(|| char_to_key_shift(c))
+}
+
+/// Type text through a configured engine and return the resulting text
+///
+/// Each char is fed as a keystroke and the returned `Result`s are applied to
+/// a virtual screen, so output matches what the user would see typing it.
+/// Chars without a key (newlines, symbols, non-ASCII) pass through and end
+/// the current word.
+pub fn compose(engine: &mut Engine, text: &str) -> String {
+    let mut screen: Vec<char> = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let Some((key, shift)) = text_char_to_key(c) else {
+            // Commit pending word as if a space was typed, without emitting it
+            apply(
+                &mut screen,
+                &engine.on_key_ext(keys::SPACE, false, false, false),
+            );
+            engine.clear_all();
+            screen.push(c);
+            continue;
+        };
+        let r = engine.on_key_ext(key, c.is_uppercase(), false, shift);
+        apply(&mut screen, &r);
+        // Handled letters/digits include the key in the output, and so does
+        // space (restore/shortcut output ends with it); punctuation still
+        // types itself unless consumed
+        let handled = r.action == Action::Send as u8;
+        let consumed = r.flags & FLAG_KEY_CONSUMED != 0;
+        let types_itself = key != keys::SPACE && keys::is_break_ext(key, shift);
+        if !handled || (types_itself && !consumed) {
+            screen.push(c);
+        }
+    }
+    // Finish the last word (auto-restore, shortcuts) without emitting a space
+    apply(
+        &mut screen,
+        &engine.on_key_ext(keys::SPACE, false, false, false),
+    );
+    engine.clear_all();
+    screen.into_iter().collect()
+}
+
+/// Apply backspaces and output chars of one result
+fn apply(screen: &mut Vec<char>, r: &crate::engine::Result) {
+    if r.action != Action::Send as u8 {
+        return;
+    }
+    for _ in 0..r.backspace {
+        screen.pop();
+    }
+    screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_telex() {
+        assert_eq!(
+            transform_text("Tieengs Vieetj", Direction::Telex),
+            "Tiếng Việt"
+        );
+        assert_eq!(
+            transform_text("dduwowngf phoos, xin chaof!", Direction::Telex),
+            "đường phố, xin chào!"
+        );
+        assert_eq!(transform_text("mootj\nhai", Direction::Telex), "một\nhai");
+    }
+
+    #[test]
+    fn test_compose_restore_keeps_single_space() {
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        assert_eq!(compose(&mut e, "Google burnout nhes"), "Google burnout nhé");
+    }
+
+    #[test]
+    fn test_compose_circumflex_closed_english() {
+        let mut e = Engine::new();
+        e.set_english_auto_restore(true);
+        assert_eq!(
+            compose(&mut e, "keep sleep nghieepj xeeps"),
+            "keep sleep nghiệp xếp"
+        );
+    }
+
+    #[test]
+    fn test_transform_vni() {
+        assert_eq!(
+            transform_text("Tie61ng Vie65t", Direction::Vni),
+            "Tiếng Việt"
+        );
+    }
+
+    #[test]
+    fn test_transform_strip_and_legacy() {
+        assert_eq!(
+            transform_text("Tiếng Việt", Direction::StripDiacritics),
+            "Tieng Viet"
+        );
+        assert_eq!(
+            transform_text("TiÕng ViÖt", Direction::Legacy),
+            "Tiếng Việt"
+        );
+    }
+
+    #[test]
+    fn test_transform_selection() {
+        let telex = Config::default();
+        assert_eq!(
+            transform_selection("Tieengs Vieetj raats hay!", &telex),
+            "Tiếng Việt rất hay!"
+        );
+        // Already-converted words, English words and punctuation are kept
+        assert_eq!(
+            transform_selection("Việt Nam, hello (nuwowcs)", &telex),
+            "Việt Nam, hello (nước)"
+        );
+        assert_eq!(transform_selection("mootj\n\thai", &telex), "một\n\thai");
+        assert_eq!(transform_selection("", &telex), "");
+
+        let vni = Config {
+            method: 1,
+            ..Config::default()
+        };
+        assert_eq!(transform_selection("Tie61ng Vie65t", &vni), "Tiếng Việt");
+    }
+
+    #[test]
+    fn test_direction_from_u8() {
+        assert_eq!(Direction::from_u8(0), Some(Direction::Telex));
+        assert_eq!(Direction::from_u8(3), Some(Direction::Legacy));
+        assert_eq!(Direction::from_u8(9), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Sentence Correction
+//!
+//! Auto-restore decides each word when it is committed, knowing only the
+//! words typed before it. `correct_sentence` goes over a committed sentence
+//! again for a "fix this line" command: words valid in both readings ("hẻ" /
+//! "her") are decided by the same tie-break as auto-restore, now with the
+//! words on both sides known ("tell hẻ" → "tell her", "em yêu her" →
+//! "em yêu hẻ"). Other words are left as they are.
+
+use super::analysis::{analyze_text, SpanKind};
+use super::convert::{transform_text, Direction};
+use super::tutor::keystrokes;
+use crate::engine::english::{english_confidence, ConfidenceTier, EnglishConfidence};
+use crate::engine::restore::{TieBreak, WordLanguage};
+use crate::engine::shortcut::InputMethod;
+use crate::prelude::*;
+use core::ops::Range;
+
+/// A word of the sentence
+struct Word {
+    range: Range<usize>,
+    /// Both readings, when the word is valid in both
+    readings: Option<Readings>,
+    /// Language of the word as it stands (None while undecided)
+    language: Option<WordLanguage>,
+}
+
+/// Raw Telex keystrokes ("her") and their Vietnamese reading ("hẻ")
+struct Readings {
+    raw: String,
+    vietnamese: String,
+    confidence: EnglishConfidence,
+}
+
+/// Both readings of `word` when the Vietnamese one is a valid syllable and
+/// the English one is plausible
+fn readings(word: &str) -> Option<Readings> {
+    let (raw, vietnamese) = if word.is_ascii() {
+        (word.to_string(), transform_text(word, Direction::Telex))
+    } else {
+        let raw: String = keystrokes(word, InputMethod::Telex)?.into_iter().collect();
+        (raw, word.to_string())
+    };
+    let valid = matches!(
+        analyze_text(&vietnamese).as_slice(),
+        [span] if span.kind == SpanKind::ValidVietnamese && span.range.len() == vietnamese.len()
+    );
+    let confidence = english_confidence(&raw);
+    (valid && vietnamese != raw && confidence.tier != ConfidenceTier::Low).then_some(Readings {
+        raw,
+        vietnamese,
+        confidence,
+    })
+}
+
+/// Re-decide the words of a committed sentence that are valid in both
+/// readings, with the words before and after as context
+///
+/// Words are read as Telex. Whitespace, punctuation and words with a single
+/// reading are kept as they are.
+pub fn correct_sentence(text: &str) -> String {
+    let mut words: Vec<Word> = analyze_text(text)
+        .into_iter()
+        .map(|span| {
+            let word = &text[span.range.clone()];
+            let readings = readings(word);
+            Word {
+                language: match readings {
+                    Some(_) => None,
+                    None => WordLanguage::of_word(word),
+                },
+                range: span.range,
+                readings,
+            }
+        })
+        .collect();
+
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    for i in 0..words.len() {
+        let Some(readings) = &words[i].readings else {
+            continue;
+        };
+        let before = words[..i].iter().rev().find_map(|w| w.language);
+        let after = words[i + 1..].iter().find_map(|w| w.language);
+        let tie_break = TieBreak {
+            confidence: readings.confidence,
+            history: None,
+            context: [before, after],
+        };
+        let (language, replacement) = if tie_break.restores() {
+            (WordLanguage::English, &readings.raw)
+        } else {
+            (WordLanguage::Vietnamese, &readings.vietnamese)
+        };
+        out.push_str(&text[copied..words[i].range.start]);
+        out.push_str(replacement);
+        copied = words[i].range.end;
+        words[i].language = Some(language);
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_on_both_sides() {
+        assert_eq!(correct_sentence("tell hẻ"), "tell her");
+        assert_eq!(correct_sentence("hẻ name"), "her name");
+        assert_eq!(correct_sentence("em yêu her"), "em yêu hẻ");
+        assert_eq!(correct_sentence("her"), "hẻ");
+    }
+
+    #[test]
+    fn test_single_reading_words_kept() {
+        let text = "Tiếng Việt, hello world! x2";
+        assert_eq!(correct_sentence(text), text);
+        assert_eq!(correct_sentence(""), "");
+    }
+}
@@ -0,0 +1,115 @@
+//! Diacritic Removal
+//!
+//! Converts Vietnamese text to its ASCII skeleton: "Đường phố" → "Duong pho".
+//! Handles both precomposed (NFC) and decomposed (NFD) input; characters
+//! outside the Vietnamese alphabet are kept as-is.
+//!
+//! Per-character tone helpers (`mark_of`, `with_mark`, `without_mark`) read
+//! and replace the tone mark of any precomposed vowel, upper or lower case,
+//! keeping its vowel modifier: `with_mark('à', NANG)` is 'ạ'.
+
+use crate::data::chars::{self, mark};
+use crate::prelude::*;
+use crate::utils::key_to_char;
+
+/// Strip one character to its base Latin letter (á → a, Đ → D, ư → u)
+pub fn strip_char(c: char) -> char {
+    chars::parse_char(c)
+        .and_then(|p| key_to_char(p.key, p.caps))
+        .unwrap_or(c)
+}
+
+/// Remove all Vietnamese diacritics (tones, marks and đ stroke) from text
+pub fn remove_diacritics(text: &str) -> String {
+    text.chars()
+        .filter(|&c| !chars::is_combining(c))
+        .map(strip_char)
+        .collect()
+}
+
+/// Tone mark of a vowel (`mark::NONE` when unmarked); None for non-vowels
+pub fn mark_of(c: char) -> Option<u8> {
+    let p = chars::parse_char(c)?;
+    chars::base_char(p.key, p.tone).map(|_| p.mark)
+}
+
+/// `c` with its tone mark replaced by `m` ('à' + nặng → 'ạ', 'Ơ' + sắc → 'Ớ')
+///
+/// None for non-vowels and for marks past `mark::NANG`.
+pub fn with_mark(c: char, m: u8) -> Option<char> {
+    if m > mark::NANG {
+        return None;
+    }
+    let p = chars::parse_char(c)?;
+    chars::base_char(p.key, p.tone)?;
+    chars::to_char(p.key, p.caps, p.tone, m)
+}
+
+/// `c` without its tone mark ('ệ' → 'ê'); anything else unchanged
+pub fn without_mark(c: char) -> char {
+    with_mark(c, mark::NONE).unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_diacritics() {
+        assert_eq!(remove_diacritics("Tiếng Việt"), "Tieng Viet");
+        assert_eq!(remove_diacritics("Đường phố"), "Duong pho");
+        assert_eq!(remove_diacritics("ĂÂÊÔƠƯĐ ăâêôơưđ"), "AAEOOUD aaeooud");
+        assert_eq!(
+            remove_diacritics("khuyến_mãi-2024.txt"),
+            "khuyen_mai-2024.txt"
+        );
+    }
+
+    #[test]
+    fn test_remove_diacritics_decomposed() {
+        // "Việt" in NFD: e + circumflex + dot below
+        assert_eq!(remove_diacritics("Vie\u{0302}\u{0323}t"), "Viet");
+    }
+
+    #[test]
+    fn test_remove_diacritics_ascii_unchanged() {
+        assert_eq!(remove_diacritics("hello world!"), "hello world!");
+        assert_eq!(remove_diacritics(""), "");
+    }
+
+    #[test]
+    fn test_mark_helpers() {
+        assert_eq!(with_mark('à', mark::NANG), Some('ạ'));
+        assert_eq!(with_mark('Ơ', mark::SAC), Some('Ớ'));
+        assert_eq!(with_mark('Ư', mark::NGA), Some('Ữ'));
+        assert_eq!(with_mark('Ấ', mark::HUYEN), Some('Ầ'));
+        assert_eq!(with_mark('d', mark::SAC), None);
+        assert_eq!(with_mark('a', 6), None);
+        assert_eq!(mark_of('Ậ'), Some(mark::NANG));
+        assert_eq!(mark_of('ư'), Some(mark::NONE));
+        assert_eq!(mark_of('đ'), None);
+        assert_eq!(without_mark('ệ'), 'ê');
+        assert_eq!(without_mark('Đ'), 'Đ');
+        assert_eq!(without_mark('!'), '!');
+    }
+
+    #[test]
+    fn test_mark_round_trips() {
+        let vowels: Vec<char> = (0..0x2000)
+            .filter_map(char::from_u32)
+            .filter(|&c| mark_of(c).is_some())
+            .collect();
+        // 12 bases × 6 marks × 2 cases
+        assert_eq!(vowels.len(), 144);
+        for c in vowels {
+            let bare = without_mark(c);
+            assert_eq!(with_mark(bare, mark_of(c).unwrap()), Some(c), "{c}");
+            for m in mark::NONE..=mark::NANG {
+                let marked = with_mark(c, m).unwrap();
+                assert_eq!(mark_of(marked), Some(m), "{c} + {m}");
+                assert_eq!(without_mark(marked), bare, "{c} + {m}");
+                assert_eq!(marked.is_uppercase(), c.is_uppercase(), "{c} + {m}");
+            }
+        }
+    }
+}
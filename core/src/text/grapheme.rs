@@ -0,0 +1,172 @@
+//! Grapheme Clusters
+//!
+//! Apps delete one user-perceived character per backspace: "e\u{301}", "\r\n"
+//! and a flag emoji each go at once. Splitting the text around the cursor the
+//! same way lets the engine count backspaces the way the app applies them.
+//!
+//! Covers the parts of UAX #29 that show up next to typed words, without
+//! Unicode tables: CR LF, combining marks, variation selectors, emoji
+//! modifiers and tags, ZWJ sequences and regional indicator pairs.
+
+use crate::data::chars::{self, Combining};
+use crate::prelude::*;
+
+const ZWJ: char = '\u{200D}';
+
+/// Characters that attach to the previous one (Grapheme_Extend subset)
+fn is_extend(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'     // Combining diacritical marks
+        | '\u{1AB0}'..='\u{1AFF}'   // ... extended
+        | '\u{1DC0}'..='\u{1DFF}'   // ... supplement
+        | '\u{20D0}'..='\u{20FF}'   // ... for symbols (keycaps)
+        | '\u{FE00}'..='\u{FE0F}'   // Variation selectors
+        | '\u{FE20}'..='\u{FE2F}'   // Combining half marks
+        | '\u{200C}'                // ZWNJ
+        | '\u{1F3FB}'..='\u{1F3FF}' // Emoji skin tone modifiers
+        | '\u{E0020}'..='\u{E007F}' // Tags (subdivision flags)
+        | '\u{E0100}'..='\u{E01EF}' // Variation selectors supplement
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Split text into grapheme clusters
+pub fn graphemes(text: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    // Regional indicators in the current cluster: they join in pairs
+    let mut regional = 0;
+    for (i, c) in text.char_indices() {
+        let joins = match prev {
+            None => false,
+            Some('\r') => c == '\n',
+            Some(p) if p.is_control() || c.is_control() => false,
+            Some(p) => {
+                is_extend(c)
+                    || c == ZWJ
+                    || p == ZWJ
+                    || (is_regional_indicator(p) && is_regional_indicator(c) && regional % 2 == 1)
+            }
+        };
+        if !joins && i > 0 {
+            clusters.push(&text[start..i]);
+            start = i;
+            regional = 0;
+        }
+        if is_regional_indicator(c) {
+            regional += 1;
+        }
+        prev = Some(c);
+    }
+    if start < text.len() {
+        clusters.push(&text[start..]);
+    }
+    clusters
+}
+
+/// Precomposed character of a cluster ("e\u{302}\u{301}" → 'ế')
+///
+/// Single characters are returned as-is. Decomposed Vietnamese letters are
+/// composed; any other multi-character cluster has no single-char form.
+pub fn compose(cluster: &str) -> Option<char> {
+    let mut it = cluster.chars();
+    let base = it.next()?;
+    let rest = it.as_str();
+    if rest.is_empty() {
+        return Some(base);
+    }
+    let parsed = chars::parse_char(base)?;
+    let (mut tone_val, mut mark_val) = (parsed.tone, parsed.mark);
+    for c in rest.chars() {
+        let (slot, value) = match chars::combining(c) {
+            Some(Combining::Tone(t)) => (&mut tone_val, t),
+            Some(Combining::Mark(m)) => (&mut mark_val, m),
+            None => return None,
+        };
+        if *slot != 0 {
+            return None;
+        }
+        *slot = value;
+    }
+    if parsed.stroke {
+        return None;
+    }
+    let composed = chars::to_char(parsed.key, parsed.caps, tone_val, mark_val)?;
+    // to_char ignores modifiers a letter can't take (breve on 'e')
+    let round_trip = chars::parse_char(composed)?;
+    (round_trip.tone == tone_val && round_trip.mark == mark_val).then_some(composed)
+}
+
+/// Compose decomposed Vietnamese letters in text ("a\u{306}n" → "ăn")
+///
+/// The NFC subset the engine produces; clusters `compose` can't reduce to
+/// one char are kept as they are.
+pub fn compose_text(text: &str) -> String {
+    graphemes(text)
+        .into_iter()
+        .fold(String::with_capacity(text.len()), |mut out, cluster| {
+            match compose(cluster) {
+                Some(c) => out.push(c),
+                None => out.push_str(cluster),
+            }
+            out
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphemes() {
+        assert_eq!(graphemes("abc"), ["a", "b", "c"]);
+        assert_eq!(graphemes("a\r\nb"), ["a", "\r\n", "b"]);
+        assert_eq!(graphemes("\n\n"), ["\n", "\n"]);
+        assert_eq!(
+            graphemes("Vie\u{0302}\u{0323}t"),
+            ["V", "i", "e\u{0302}\u{0323}", "t"]
+        );
+        // 👍🏽, 👨‍👩‍👧, 🇻🇳🇺🇸, ❤️
+        assert_eq!(
+            graphemes("\u{1F44D}\u{1F3FD}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"),
+            [
+                "\u{1F44D}\u{1F3FD}",
+                "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"
+            ]
+        );
+        assert_eq!(
+            graphemes("\u{1F1FB}\u{1F1F3}\u{1F1FA}\u{1F1F8}"),
+            ["\u{1F1FB}\u{1F1F3}", "\u{1F1FA}\u{1F1F8}"]
+        );
+        assert_eq!(graphemes("\u{2764}\u{FE0F}a"), ["\u{2764}\u{FE0F}", "a"]);
+        assert!(graphemes("").is_empty());
+    }
+
+    #[test]
+    fn test_compose() {
+        assert_eq!(compose("a"), Some('a'));
+        assert_eq!(compose("\u{1F600}"), Some('\u{1F600}'));
+        assert_eq!(compose("e\u{0302}\u{0301}"), Some('ế'));
+        assert_eq!(compose("U\u{031B}\u{0300}"), Some('Ừ'));
+        assert_eq!(compose("a\u{0306}\u{0323}"), Some('ặ'));
+        // Precomposed base plus a tone mark
+        assert_eq!(compose("ê\u{0301}"), Some('ế'));
+        assert_eq!(compose("e\u{0306}"), None);
+        assert_eq!(compose("a\u{0301}\u{0300}"), None);
+        assert_eq!(compose("\u{1F44D}\u{1F3FD}"), None);
+    }
+
+    #[test]
+    fn test_compose_text() {
+        assert_eq!(compose_text("Vie\u{0302}\u{0323}t Nam"), "Việt Nam");
+        assert_eq!(
+            compose_text("a\u{0306}n \u{1F44D}\u{1F3FD}"),
+            "ăn \u{1F44D}\u{1F3FD}"
+        );
+        assert_eq!(compose_text("đc"), "đc");
+    }
+}
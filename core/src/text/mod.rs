@@ -0,0 +1,23 @@
+//! Vietnamese Text Utilities
+//!
+//! Stateless helpers that operate on whole strings rather than keystrokes:
+//! - `analysis`: Tone class and rhyme of a syllable; Vietnamese vs foreign words
+//! - `collate`: Vietnamese alphabet + tone ordering for sorting
+//! - `diacritics`: Accent stripping (Tiếng Việt → Tieng Viet), per-char tone marks
+//! - `convert`: Whole-text conversion (raw Telex/VNI, strip, legacy → Unicode)
+//! - `correct`: Re-deciding English/Vietnamese words of a committed sentence
+//! - `grapheme`: Grapheme clusters, the unit apps delete per backspace
+//! - `tutor`: Keystrokes that type a word, for typing-practice UIs
+
+pub mod analysis;
+pub mod collate;
+pub mod convert;
+pub mod correct;
+pub mod diacritics;
+pub mod grapheme;
+pub mod tutor;
+
+pub use collate::{compare, sort};
+pub use convert::{transform_text, Direction};
+pub use correct::correct_sentence;
+pub use diacritics::remove_diacritics;
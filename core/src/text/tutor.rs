@@ -0,0 +1,182 @@
+//! Typing Tutor
+//!
+//! Keystrokes that type a Vietnamese word, for typing-practice UIs that
+//! highlight the next key and check the learner's input. Every sequence is
+//! verified by typing it through the engine, so hints follow the engine's own
+//! rules rather than a separate table.
+
+use super::convert::compose;
+use crate::data::chars::{self, mark, tone};
+use crate::engine::shortcut::InputMethod;
+use crate::engine::Engine;
+use crate::prelude::*;
+
+/// One keystroke of a word (see `keys_for`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyHint {
+    /// Character the key types; uppercase letters need Shift or Caps Lock
+    pub ch: char,
+    /// Keys that may be pressed at this step instead. Each is a later key of
+    /// the sequence that the engine also accepts now (a tone mark typed right
+    /// after its vowel: "vieejt" as well as "vieetj")
+    pub alternatives: Vec<char>,
+}
+
+/// Canonical keystrokes that type `word` with `method`
+///
+/// Letters come with their vowel/stroke keys right after them and the tone
+/// mark at the end of the word ("việt" → "vieetj", "vie6t5"). `All` uses
+/// Telex. Empty when the engine can't produce the word (not a single
+/// Vietnamese word, or characters without a key).
+pub fn keys_for(word: &str, method: InputMethod) -> Vec<KeyHint> {
+    let vni = method == InputMethod::Vni;
+    let Some(sequence) = keystrokes(word, method) else {
+        return Vec::new();
+    };
+    (0..sequence.len())
+        .map(|i| KeyHint {
+            ch: sequence[i],
+            alternatives: alternatives(&sequence, i, word, vni),
+        })
+        .collect()
+}
+
+/// Canonical keystrokes of `word` without the alternatives of `keys_for`
+///
+/// For rebuilding the raw input of a word the engine didn't see typed
+/// (restored from the screen). None when the engine can't produce the word.
+pub(crate) fn keystrokes(word: &str, method: InputMethod) -> Option<Vec<char>> {
+    let vni = method == InputMethod::Vni;
+    let sequence = canonical(word, vni)?;
+    types_word(&sequence, word, vni).then_some(sequence)
+}
+
+/// Letter keys with vowel/stroke modifiers inline, tone mark last
+///
+/// In an all-caps word the Telex modifier and mark keys are uppercase too,
+/// as typed with Caps Lock on ("ĐƯỜNG" → "DDUWOWNGF").
+fn canonical(word: &str, vni: bool) -> Option<Vec<char>> {
+    let mut sequence = Vec::new();
+    let mut word_mark = None;
+    let all_caps = word.chars().any(char::is_uppercase) && !word.chars().any(char::is_lowercase);
+    let case = |c: char| {
+        if all_caps {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        }
+    };
+    for c in word.chars() {
+        if c.is_ascii_alphanumeric() {
+            sequence.push(c);
+            continue;
+        }
+        let parsed = chars::parse_char(c)?;
+        let base = crate::utils::key_to_char(parsed.key, parsed.caps)?;
+        sequence.push(base);
+        if parsed.stroke {
+            sequence.push(if vni { '9' } else { base });
+        }
+        match parsed.tone {
+            tone::CIRCUMFLEX => sequence.push(if vni { '6' } else { base }),
+            tone::HORN if vni => sequence.push(if base.eq_ignore_ascii_case(&'a') {
+                '8'
+            } else {
+                '7'
+            }),
+            tone::HORN => sequence.push(case('w')),
+            _ => {}
+        }
+        if parsed.mark != mark::NONE {
+            // One tone mark per syllable
+            if word_mark.replace(parsed.mark).is_some() {
+                return None;
+            }
+        }
+    }
+    if let Some(m) = word_mark {
+        sequence.push(case(mark_char(m, vni)));
+    }
+    (!sequence.is_empty()).then_some(sequence)
+}
+
+fn mark_char(mark_val: u8, vni: bool) -> char {
+    let (telex, vni_key) = match mark_val {
+        mark::SAC => ('s', '1'),
+        mark::HUYEN => ('f', '2'),
+        mark::HOI => ('r', '3'),
+        mark::NGA => ('x', '4'),
+        _ => ('j', '5'),
+    };
+    if vni {
+        vni_key
+    } else {
+        telex
+    }
+}
+
+/// Later keys of the sequence the engine also accepts at step `i`
+fn alternatives(sequence: &[char], i: usize, word: &str, vni: bool) -> Vec<char> {
+    let mut found = Vec::new();
+    for j in i + 1..sequence.len() {
+        let ch = sequence[j];
+        if ch == sequence[i] || found.contains(&ch) {
+            continue;
+        }
+        let mut reordered = sequence.to_vec();
+        reordered.remove(j);
+        reordered.insert(i, ch);
+        if types_word(&reordered, word, vni) {
+            found.push(ch);
+        }
+    }
+    found
+}
+
+/// Does typing `sequence` produce `word`?
+fn types_word(sequence: &[char], word: &str, vni: bool) -> bool {
+    let mut e = Engine::new();
+    e.set_method(if vni { 1 } else { 0 });
+    let text: String = sequence.iter().collect();
+    compose(&mut e, &text) == word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(word: &str, method: InputMethod) -> String {
+        keys_for(word, method).iter().map(|h| h.ch).collect()
+    }
+
+    #[test]
+    fn test_canonical_keys() {
+        assert_eq!(keys("việt", InputMethod::Telex), "vieetj");
+        assert_eq!(keys("Việt", InputMethod::Vni), "Vie6t5");
+        assert_eq!(keys("đường", InputMethod::Telex), "dduwowngf");
+        assert_eq!(keys("Đường", InputMethod::Vni), "D9u7o7ng2");
+        assert_eq!(keys("ăn", InputMethod::All), "awn");
+        assert_eq!(keys("ÂM", InputMethod::Telex), "AAM");
+        assert_eq!(keys("ĐƯỜNG", InputMethod::Telex), "DDUWOWNGF");
+        assert_eq!(keys("ĐƯỜNG", InputMethod::Vni), "D9U7O7NG2");
+        assert_eq!(keys("xin", InputMethod::Telex), "xin");
+    }
+
+    #[test]
+    fn test_untypeable_words() {
+        assert!(keys_for("", InputMethod::Telex).is_empty());
+        assert!(keys_for("xin chào", InputMethod::Telex).is_empty());
+        assert!(keys_for("ü", InputMethod::Telex).is_empty());
+        // Two tone marks can't be one syllable
+        assert!(keys_for("áà", InputMethod::Telex).is_empty());
+    }
+
+    #[test]
+    fn test_mark_alternatives() {
+        let hints = keys_for("việt", InputMethod::Telex);
+        // After "viee" the mark may come before the final consonant
+        assert_eq!(hints[4].ch, 't');
+        assert_eq!(hints[4].alternatives, ['j']);
+        assert!(hints[0].alternatives.is_empty());
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides version comparison utilities that can be used across all platforms.
 //! HTTP calls are handled by the platform layer (Swift/C#/GTK) for flexibility.
 
+use crate::prelude::*;
+
 /// Semantic version representation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Version {
@@ -68,8 +70,8 @@ impl Version {
     }
 }
 
-impl std::fmt::Display for Version {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Version {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
         if let Some(pre) = &self.pre {
             write!(f, "-{}", pre)?;
@@ -94,7 +96,7 @@ pub extern "C" fn version_compare(v1: *const i8, v2: *const i8) -> i32 {
         if v1.is_null() {
             return -99;
         }
-        match std::ffi::CStr::from_ptr(v1).to_str() {
+        match core::ffi::CStr::from_ptr(v1).to_str() {
             Ok(s) => s,
             Err(_) => return -99,
         }
@@ -104,7 +106,7 @@ pub extern "C" fn version_compare(v1: *const i8, v2: *const i8) -> i32 {
         if v2.is_null() {
             return -99;
         }
-        match std::ffi::CStr::from_ptr(v2).to_str() {
+        match core::ffi::CStr::from_ptr(v2).to_str() {
             Ok(s) => s,
             Err(_) => return -99,
         }
@@ -4,11 +4,13 @@
 //! Also includes test utilities under #[cfg(test)].
 
 use crate::data::{
-    chars::tone,
+    chars::{self, tone},
     keys,
-    vowel::{Modifier, Vowel},
+    vowel::{Modifier, Phonology, Vowel},
 };
-use crate::engine::buffer::Buffer;
+use crate::engine::buffer::{Buffer, Char};
+use crate::engine::validation::{self, ValidationProfile};
+use crate::prelude::*;
 
 /// Convert key code to character
 pub fn key_to_char(key: u16, caps: bool) -> Option<char> {
@@ -139,6 +141,85 @@ pub fn has_gi_initial(buf: &Buffer) -> bool {
         && third.map(keys::is_vowel).unwrap_or(false)
 }
 
+/// Find the character index where the tone mark belongs in a syllable
+///
+/// Public query over `Phonology::find_tone_position` for hosts and tools that
+/// only have a plain string (spell checkers, tutors, tests). Accepts both
+/// unmarked ("hoa") and marked ("hoà") input; existing marks are ignored.
+///
+/// * `modern` - true for modern placement (hoà, thuỷ), false for traditional (hòa, thủy)
+///
+/// Returns `None` if the word contains non-Vietnamese letters or no vowel.
+pub fn tone_position(word: &str, modern: bool) -> Option<usize> {
+    let mut buf = Buffer::new();
+    for c in word.chars() {
+        let parsed = chars::parse_char(c)?;
+        let mut ch = Char::new(parsed.key, parsed.caps);
+        ch.tone = parsed.tone;
+        ch.stroke = parsed.stroke;
+        buf.push(ch);
+    }
+
+    let vowels = collect_vowels(&buf);
+    let last_vowel_pos = vowels.last()?.pos;
+    Some(Phonology::find_tone_position(
+        &vowels,
+        has_final_consonant(&buf, last_vowel_pos),
+        modern,
+        has_qu_initial(&buf),
+        has_gi_initial(&buf),
+    ))
+}
+
+/// Where a tone goes in a syllable whose final isn't Vietnamese ("gel", "mail")
+///
+/// Loanwords written into Vietnamese text keep their own finals ("xà phòng
+/// gel"); a tone key on them is otherwise typed as a letter, or with free
+/// tone placed by the Vietnamese rules for that vowel pair ("máil").
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForeignFinalTone {
+    /// Type the key as a letter ("gels"); free tone places the mark by the
+    /// usual rules
+    #[default]
+    Reject = 0,
+    /// Place the mark on the last vowel before the final, with or without
+    /// free tone ("gels" → "gél", "mails" → "maíl")
+    LastVowel = 1,
+}
+
+impl ForeignFinalTone {
+    /// Policy from its FFI value (unknown values fall back to Reject)
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::LastVowel,
+            _ => Self::Reject,
+        }
+    }
+}
+
+/// Index of the last vowel of a syllable with a non-Vietnamese final
+/// (see `validation::has_foreign_final`)
+pub fn foreign_final_vowel(buffer_keys: &[u16], profile: ValidationProfile) -> Option<usize> {
+    if !validation::has_foreign_final(buffer_keys, profile) {
+        return None;
+    }
+    buffer_keys.iter().rposition(|&k| keys::is_vowel(k))
+}
+
+/// Character index of the tone mark in a syllable with a non-Vietnamese
+/// final under `ForeignFinalTone::LastVowel` ("gel" → 1, "mail" → 2)
+///
+/// Returns `None` when the final is Vietnamese (see `tone_position`), or the
+/// word has non-Vietnamese letters or no vowel.
+pub fn foreign_final_tone_position(word: &str) -> Option<usize> {
+    let keys = word
+        .chars()
+        .map(|c| chars::parse_char(c).map(|p| p.key))
+        .collect::<Option<Vec<u16>>>()?;
+    foreign_final_vowel(&keys, ValidationProfile::Standard)
+}
+
 mod test_utils {
     //! Shared test utilities for inline tests
     //!
@@ -146,7 +227,8 @@ mod test_utils {
     //! Used by `#[cfg(test)]` modules throughout the crate.
 
     use crate::data::keys;
-    use crate::engine::{Action, Engine};
+    use crate::engine::{Action, Engine, Result};
+    use crate::prelude::*;
 
     // ============================================================
     // KEY MAPPING
@@ -224,6 +306,36 @@ mod test_utils {
         }
     }
 
+    /// Convert character to (key code, shift) for shifted symbols typed with
+    /// Shift ('@' → Shift+2, '?' → Shift+/); other chars as `char_to_key`
+    ///
+    /// '<' is NOT shifted here: it maps to DELETE in test utilities.
+    pub fn char_to_key_shift(c: char) -> (u16, bool) {
+        match c {
+            '@' => (keys::N2, true),
+            '!' => (keys::N1, true),
+            '#' => (keys::N3, true),
+            '$' => (keys::N4, true),
+            '%' => (keys::N5, true),
+            '^' => (keys::N6, true),
+            '&' => (keys::N7, true),
+            '*' => (keys::N8, true),
+            '(' => (keys::N9, true),
+            ')' => (keys::N0, true),
+            '_' => (keys::MINUS, true),
+            '+' => (keys::EQUAL, true),
+            ':' => (keys::SEMICOLON, true),
+            '"' => (keys::QUOTE, true),
+            '>' => (keys::DOT, true),
+            '?' => (keys::SLASH, true),
+            '|' => (keys::BACKSLASH, true),
+            '{' => (keys::LBRACKET, true),
+            '}' => (keys::RBRACKET, true),
+            '~' => (keys::BACKQUOTE, true),
+            _ => (char_to_key(c), false),
+        }
+    }
+
     /// Convert string to key codes
     pub fn keys_from_str(s: &str) -> Vec<u16> {
         s.chars().map(char_to_key).filter(|&k| k != 255).collect()
@@ -233,112 +345,311 @@ mod test_utils {
     // TYPING SIMULATION
     // ============================================================
 
-    /// Simulate typing, returns screen output
-    pub fn type_word(e: &mut Engine, input: &str) -> String {
-        let mut screen = String::new();
-        for c in input.chars() {
-            // Detect shifted symbols and get proper (key, shift) pair
-            // NOTE: '<' is NOT included here - it maps to DELETE in test utilities
-            let (key, shift) = match c {
-                '@' => (keys::N2, true),
-                '!' => (keys::N1, true),
-                '#' => (keys::N3, true),
-                '$' => (keys::N4, true),
-                '%' => (keys::N5, true),
-                '^' => (keys::N6, true),
-                '&' => (keys::N7, true),
-                '*' => (keys::N8, true),
-                '(' => (keys::N9, true),
-                ')' => (keys::N0, true),
-                '_' => (keys::MINUS, true),
-                '+' => (keys::EQUAL, true),
-                ':' => (keys::SEMICOLON, true),
-                '"' => (keys::QUOTE, true),
-                '>' => (keys::DOT, true),
-                '?' => (keys::SLASH, true),
-                '|' => (keys::BACKSLASH, true),
-                '{' => (keys::LBRACKET, true),
-                '}' => (keys::RBRACKET, true),
-                '~' => (keys::BACKQUOTE, true),
-                _ => (char_to_key(c), false),
-            };
-            let is_caps = c.is_uppercase();
+    /// App text as a host leaves it after each key's `Result`
+    ///
+    /// Tests assert on this rather than on the engine's buffer, so edits
+    /// sized wrong (too many backspaces, a dropped char) show up as the user
+    /// would see them. The caret follows Left/Right; edits and typed chars
+    /// go in at the caret.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct VirtualScreen {
+        text: Vec<char>,
+        caret: usize,
+        overrun: usize,
+    }
 
-            if key == keys::DELETE {
-                let r = e.on_key_ext(key, false, false, false);
-                if r.action == Action::Send as u8 {
-                    // Restore from history - apply backspaces and replacement
-                    for _ in 0..r.backspace {
-                        screen.pop();
-                    }
-                    for i in 0..r.count as usize {
-                        if let Some(ch) = char::from_u32(r.chars[i]) {
-                            screen.push(ch);
-                        }
-                    }
-                } else {
-                    // Normal backspace - just remove last char
-                    screen.pop();
-                }
-                continue;
+    impl VirtualScreen {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Screen already showing `text`, caret at the end
+        pub fn with_text(text: &str) -> Self {
+            let text: Vec<char> = text.chars().collect();
+            Self {
+                caret: text.len(),
+                text,
+                overrun: 0,
             }
+        }
 
-            // ESC key: restore to raw ASCII
-            if key == keys::ESC {
-                let r = e.on_key_ext(key, false, false, false);
-                if r.action == Action::Send as u8 {
-                    for _ in 0..r.backspace {
-                        screen.pop();
-                    }
-                    for i in 0..r.count as usize {
-                        if let Some(ch) = char::from_u32(r.chars[i]) {
-                            screen.push(ch);
-                        }
-                    }
+        pub fn text(&self) -> String {
+            self.text.iter().collect()
+        }
+
+        /// Caret position in chars
+        pub fn caret(&self) -> usize {
+            self.caret
+        }
+
+        /// Backspaces that found nothing left to delete: edits sized larger
+        /// than the text the engine typed (0 unless the engine miscounts)
+        pub fn overrun(&self) -> usize {
+            self.overrun
+        }
+
+        /// Apply the `Result` of a key the way a host does
+        ///
+        /// An edit deletes `backspace` chars before the caret and inserts
+        /// `chars`; a break key is still typed after it unless the result
+        /// consumed it. A key the engine left alone does what it does in an
+        /// app: Backspace deletes, Left/Right move the caret, ESC does
+        /// nothing, anything else types `ch` (the layout's char, if any).
+        pub fn apply(&mut self, r: &Result, key: u16, shift: bool, ch: Option<char>) {
+            if r.action == Action::Send as u8 {
+                let n = (r.backspace as usize).min(self.caret);
+                self.overrun += r.backspace as usize - n;
+                self.text.drain(self.caret - n..self.caret);
+                self.caret -= n;
+                for i in 0..r.count as usize {
+                    self.insert(char::from_u32(r.chars[i]));
                 }
-                continue;
+                let types_itself = !matches!(key, keys::SPACE | keys::DELETE | keys::ESC)
+                    && keys::is_break_ext(key, shift);
+                if types_itself && !r.key_consumed() {
+                    self.insert(ch);
+                }
+                return;
+            }
+            match key {
+                keys::DELETE if self.caret > 0 => {
+                    self.caret -= 1;
+                    self.text.remove(self.caret);
+                }
+                keys::DELETE | keys::ESC => {}
+                keys::LEFT => self.caret = self.caret.saturating_sub(1),
+                keys::RIGHT => self.caret = (self.caret + 1).min(self.text.len()),
+                _ => self.insert(ch),
             }
+        }
 
-            if key == keys::SPACE {
-                // Space can trigger shortcuts - process result
-                let r = e.on_key_ext(key, false, false, false);
-                if r.action == Action::Send as u8 {
-                    // Shortcut triggered - apply backspaces and replacement
-                    for _ in 0..r.backspace {
-                        screen.pop();
-                    }
-                    for i in 0..r.count as usize {
-                        if let Some(ch) = char::from_u32(r.chars[i]) {
-                            screen.push(ch);
-                        }
-                    }
+        /// Press `key` in `e` and apply its result
+        pub fn key(&mut self, e: &mut Engine, key: u16, caps: bool, shift: bool) {
+            let r = e.on_key_ext(key, caps, false, shift);
+            let ch = match key {
+                keys::SPACE => Some(' '),
+                _ => super::key_to_char_ext(key, caps, shift),
+            };
+            self.apply(&r, key, shift, ch);
+        }
+
+        /// Type `input` in the `type_word` notation into `e`
+        pub fn type_str(&mut self, e: &mut Engine, input: &str) {
+            for c in input.chars() {
+                let (key, shift) = char_to_key_shift(c);
+                let is_caps = c.is_uppercase();
+
+                // Non-ASCII letter from another layout (dead key, AltGr): no keycode
+                let r = if !c.is_ascii() && c.is_alphabetic() {
+                    e.on_key_with_char(key, is_caps, false, false, Some(c))
                 } else {
-                    // No shortcut - just add space
-                    screen.push(' ');
+                    e.on_key_ext(key, is_caps, false, shift)
+                };
+                self.apply(&r, key, shift, Some(c));
+            }
+        }
+
+        fn insert(&mut self, ch: Option<char>) {
+            if let Some(ch) = ch {
+                self.text.insert(self.caret, ch);
+                self.caret += 1;
+            }
+        }
+    }
+
+    /// Reproducible pseudo-random typing for stress tests
+    ///
+    /// The same seed always gives the same input, so a failure found with
+    /// one seed can be replayed exactly. Input uses the `type_word` notation
+    /// (`<` is Backspace) and mixes Vietnamese syllables in the chosen
+    /// method, English words, digits, punctuation, spaces and backspaces.
+    #[derive(Clone, Debug)]
+    pub struct InputGenerator {
+        state: u64,
+        vni: bool,
+    }
+
+    const TELEX_SYLLABLES: &[&str] = &[
+        "vieetj",
+        "nam",
+        "dduwowngf",
+        "tieengs",
+        "khoong",
+        "nguwowif",
+        "chaof",
+        "xin",
+        "camr",
+        "own",
+        "hoaf",
+        "thuys",
+        "quar",
+        "gias",
+        "dduocwj",
+        "trowif",
+        "muaf",
+        "laf",
+        "nhaf",
+        "cuar",
+        "aw",
+        "uwa",
+        "ooo",
+        "ddd",
+        "tuaan",
+    ];
+    const VNI_SYLLABLES: &[&str] = &[
+        "vie65t",
+        "nam",
+        "d9u7o7ng2",
+        "tie6ng1",
+        "kho6ng",
+        "ngu7o7i2",
+        "chao2",
+        "xin",
+        "cam3",
+        "o7n",
+        "hoa2",
+        "thuy1",
+        "qua3",
+        "gia1",
+        "d9uoc75",
+        "tro7i2",
+        "mua2",
+        "la2",
+        "nha2",
+        "cua3",
+        "a8",
+        "u7a",
+        "o66",
+        "d99",
+        "tua6n",
+    ];
+    const ENGLISH_WORDS: &[&str] = &[
+        "the", "text", "respect", "window", "issue", "sessions", "coffee", "express", "class",
+        "would", "saw", "pass", "user", "world", "test", "roof", "fix", "data", "java", "wow",
+    ];
+    const PUNCTUATION: &[&str] = &[".", ",", ";", "'", "-", "/", "!", "?", ":"];
+
+    impl InputGenerator {
+        pub fn new(seed: u64, vni: bool) -> Self {
+            Self { state: seed, vni }
+        }
+
+        /// splitmix64
+        pub fn next_u64(&mut self) -> u64 {
+            self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        /// Number in `0..n`
+        pub fn below(&mut self, n: usize) -> usize {
+            (self.next_u64() % n as u64) as usize
+        }
+
+        fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+            items[self.below(items.len())]
+        }
+
+        /// One token: a word, a number, punctuation or backspaces, with
+        /// the separator after it
+        pub fn token(&mut self) -> String {
+            let mut token = match self.below(10) {
+                0..=4 => {
+                    let syllables = if self.vni {
+                        VNI_SYLLABLES
+                    } else {
+                        TELEX_SYLLABLES
+                    };
+                    self.pick(syllables).to_string()
                 }
-                continue;
+                5 | 6 => self.pick(ENGLISH_WORDS).to_string(),
+                7 => (0..1 + self.below(4))
+                    .map(|_| char::from(b'0' + self.below(10) as u8))
+                    .collect(),
+                8 => self.pick(PUNCTUATION).to_string(),
+                _ => "<".repeat(1 + self.below(4)),
+            };
+            // Capitalized now and then
+            if self.below(8) == 0 {
+                token = token
+                    .char_indices()
+                    .map(|(i, c)| if i == 0 { c.to_ascii_uppercase() } else { c })
+                    .collect();
             }
+            match self.below(6) {
+                0 => {}
+                1 => token.push_str("  "),
+                _ => token.push(' '),
+            }
+            token
+        }
 
-            let r = e.on_key_ext(key, is_caps, false, shift);
+        /// `tokens` tokens in a row
+        pub fn input(&mut self, tokens: usize) -> String {
+            (0..tokens).map(|_| self.token()).collect()
+        }
+    }
+
+    /// Simulate typing, returns screen output
+    pub fn type_word(e: &mut Engine, input: &str) -> String {
+        let mut screen = String::new();
+        type_onto(e, &mut screen, input);
+        screen
+    }
+
+    /// Simulate typing after the text already on `screen`, as `type_word` does
+    ///
+    /// Backspaces in results reach into the earlier text, so typing in steps
+    /// ends with the same screen as typing the whole input at once.
+    pub fn type_onto(e: &mut Engine, screen: &mut String, input: &str) {
+        let mut view = VirtualScreen::with_text(screen);
+        view.type_str(e, input);
+        *screen = view.text();
+    }
+
+    /// Type in steps and check the screen after each (see `test_typing!`)
+    ///
+    /// Each step is `"keys|screen"`: `keys` are typed as with `type_word`,
+    /// then the whole screen must read `screen`. The first `|` splits the
+    /// step, so `keys` can't contain one.
+    #[track_caller]
+    pub fn assert_steps(e: &mut Engine, steps: &[&str]) {
+        let mut screen = String::new();
+        let mut typed = String::new();
+        for step in steps {
+            let Some((input, expected)) = step.split_once('|') else {
+                panic!("step {step:?} has no '|' between keys and screen");
+            };
+            type_onto(e, &mut screen, input);
+            typed.push_str(input);
+            assert_eq!(screen, expected, "after typing {typed:?}");
+        }
+    }
+
+    /// Feed already-composed text back into the engine and end the word
+    ///
+    /// Chars go in through `push_composed` (as the app's own text), spaces
+    /// and the closing space are typed. Processing is idempotent: for the
+    /// engine's own output the screen is `text` plus that space.
+    pub fn reprocess(e: &mut Engine, text: &str) -> String {
+        e.clear_all();
+        let mut screen = String::new();
+        for c in text.chars().chain([' ']) {
+            if c != ' ' {
+                e.push_composed(c);
+                screen.push(c);
+                continue;
+            }
+            let r = e.on_key_ext(keys::SPACE, false, false, false);
             if r.action == Action::Send as u8 {
                 for _ in 0..r.backspace {
                     screen.pop();
                 }
-                for i in 0..r.count as usize {
-                    if let Some(ch) = char::from_u32(r.chars[i]) {
-                        screen.push(ch);
-                    }
-                }
-                // For break keys (punctuation), add the character after auto-restore
-                // The restored text doesn't include the break character
-                // Use is_break_ext to handle shifted symbols like @, !, #, etc.
-                // BUT: if key_consumed flag is set (shortcut match), don't add the char
-                if keys::is_break_ext(key, shift) && !r.key_consumed() {
-                    screen.push(c);
-                }
+                screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
             } else {
-                // Pass through if not handled (mimic editor receiving char)
-                screen.push(c);
+                screen.push(' ');
             }
         }
         screen
@@ -520,3 +831,121 @@ mod test_utils {
 
 // Re-export test utilities for use in other test modules
 pub use test_utils::*;
+
+/// Check the screen after every step of typing
+///
+/// Steps are `"keys|screen"` string literals: the keys typed since the last
+/// step, then the whole screen expected after them. Catches words that are
+/// only wrong mid-way ("respect" briefly shown as "rếp").
+///
+/// ```
+/// use gonhanh_core::test_typing;
+///
+/// test_typing!("b|b", "a|ba", "n|ban", "f|bàn");
+/// let mut e = gonhanh_core::engine::Engine::new();
+/// e.set_method(1);
+/// test_typing!(e; "ba|ba", "n2|bàn");
+/// ```
+#[macro_export]
+macro_rules! test_typing {
+    ($engine:expr; $($step:literal),+ $(,)?) => {
+        $crate::utils::assert_steps(&mut $engine, &[$($step),+])
+    };
+    ($($step:literal),+ $(,)?) => {
+        $crate::utils::assert_steps(&mut $crate::engine::Engine::new(), &[$($step),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_onto_continues_screen() {
+        let mut e = crate::engine::Engine::new();
+        let mut screen = String::new();
+        type_onto(&mut e, &mut screen, "vieet");
+        type_onto(&mut e, &mut screen, "j");
+        assert_eq!(screen, "việt");
+    }
+
+    #[test]
+    fn test_virtual_screen() {
+        use crate::data::keys;
+        use crate::engine::{Engine, Result};
+
+        let mut screen = VirtualScreen::with_text("ab");
+        screen.apply(&Result::none(), keys::LEFT, false, None);
+        screen.apply(&Result::send(1, &['x', 'y']), keys::J, false, Some('j'));
+        assert_eq!((screen.text().as_str(), screen.caret()), ("xyb", 2));
+        // A break key types itself after the edit unless consumed
+        screen.apply(&Result::send(0, &['z']), keys::DOT, false, Some('.'));
+        screen.apply(
+            &Result::send_consumed(0, &['!']),
+            keys::DOT,
+            false,
+            Some('.'),
+        );
+        assert_eq!(screen.text(), "xyz.!b");
+        screen.apply(&Result::none(), keys::DELETE, false, None);
+        assert_eq!(screen.text(), "xyz.b");
+
+        let mut e = Engine::new();
+        let mut screen = VirtualScreen::new();
+        for key in [keys::D, keys::D, keys::A, keys::S, keys::SPACE] {
+            screen.key(&mut e, key, false, false);
+        }
+        assert_eq!(screen.text(), "đá ");
+    }
+
+    #[test]
+    #[should_panic(expected = "after typing \"vieet\"")]
+    fn test_assert_steps_reports_step() {
+        test_typing!("vie|vie", "et|viet");
+    }
+
+    #[test]
+    fn test_tone_position_modern_vs_traditional() {
+        assert_eq!(tone_position("hoa", true), Some(2));
+        assert_eq!(tone_position("hoa", false), Some(1));
+        assert_eq!(tone_position("thuy", true), Some(3));
+        assert_eq!(tone_position("thuy", false), Some(2));
+    }
+
+    #[test]
+    fn test_tone_position_finals_and_diacritics() {
+        assert_eq!(tone_position("toan", true), Some(2));
+        assert_eq!(tone_position("khuya", true), Some(3));
+        assert_eq!(tone_position("tieng", true), Some(2));
+        assert_eq!(tone_position("nguoi", true), Some(3));
+        assert_eq!(tone_position("người", true), Some(3));
+        assert_eq!(tone_position("HOÀ", false), Some(1));
+    }
+
+    #[test]
+    fn test_tone_position_qu_gi_initials() {
+        assert_eq!(tone_position("qua", true), Some(2));
+        assert_eq!(tone_position("gia", true), Some(2));
+        assert_eq!(tone_position("giau", true), Some(2));
+    }
+
+    #[test]
+    fn test_tone_position_invalid_input() {
+        assert_eq!(tone_position("", true), None);
+        assert_eq!(tone_position("str", true), None);
+        assert_eq!(tone_position("café!", true), None);
+    }
+
+    #[test]
+    fn test_foreign_final_tone_position() {
+        assert_eq!(foreign_final_tone_position("gel"), Some(1));
+        assert_eq!(foreign_final_tone_position("mail"), Some(2));
+        assert_eq!(foreign_final_tone_position("Goal"), Some(2));
+        // Vietnamese finals and open syllables: the usual rules apply
+        assert_eq!(foreign_final_tone_position("toan"), None);
+        assert_eq!(foreign_final_tone_position("hoa"), None);
+        // Not Vietnamese before the final either
+        assert_eq!(foreign_final_tone_position("str"), None);
+        assert_eq!(foreign_final_tone_position("blog"), None);
+    }
+}
@@ -0,0 +1,331 @@
+//! Key Event Test Vectors
+//!
+//! One JSON format for recorded key events, so the macOS, Windows and Linux
+//! hosts and the core crate check the engine against the same cases. Each
+//! vector lists key events with the engine settings they were typed with and
+//! the text the app should end up with:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "vectors": [
+//!     {
+//!       "name": "telex tone after final",
+//!       "config": { "method": 0, "english_auto_restore": true },
+//!       "events": [
+//!         { "code": 17, "char": "t", "time": 0 },
+//!         { "code": 14, "caps": false, "shift": false, "time": 95 }
+//!       ],
+//!       "expect": "te"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Event fields: `code` (macOS virtual keycode, required), `char` (text the
+//! layout produced, see `Engine::on_key_typed`), `caps` (letter is
+//! uppercase), `shift`, `ctrl`, `time` (ms since the first event, for the
+//! timing rules of `Engine::on_key_at`).
+//! `config` takes the fields of `Config` except `validation_profile`,
+//! `tone_key_policy` and `key_timing`; `foreign_final_tone` is its FFI value.
+//! Unknown fields are ignored.
+
+mod json;
+
+use crate::data::keys;
+use crate::engine::profile::Config;
+use crate::engine::{Engine, MAX_HISTORY_CAPACITY};
+use crate::prelude::*;
+use crate::utils::{key_to_char_ext, ForeignFinalTone, VirtualScreen};
+use json::Json;
+
+/// Schema version this reader understands
+pub const VECTORS_VERSION: u32 = 1;
+
+/// One recorded key event
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub code: u16,
+    /// Text the layout produced for the key, if the recorder knew it
+    pub ch: Option<char>,
+    pub caps: bool,
+    pub shift: bool,
+    pub ctrl: bool,
+    /// Milliseconds since the first event
+    pub time: Option<u64>,
+}
+
+/// Key events, the settings they were typed with and the expected text
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestVector {
+    pub name: String,
+    pub config: Config,
+    pub events: Vec<KeyEvent>,
+    pub expect: String,
+}
+
+impl TestVector {
+    /// Type the events into a new engine with the vector's settings
+    pub fn run(&self) -> String {
+        let mut e = Engine::new();
+        e.set_config(&self.config);
+        replay(&mut e, &self.events)
+    }
+}
+
+/// Why a vectors file couldn't be read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VectorError {
+    /// Not valid JSON: byte offset of the error
+    Syntax(usize),
+    /// Valid JSON that doesn't follow the schema: where and what
+    Schema(String),
+}
+
+/// Read a vectors file
+pub fn parse_vectors(text: &str) -> Result<Vec<TestVector>, VectorError> {
+    let doc = json::parse(text).map_err(VectorError::Syntax)?;
+    if let Some(version) = doc.get("version") {
+        if number(version) != Some(VECTORS_VERSION as f64) {
+            return Err(schema("version", "unsupported"));
+        }
+    }
+    let Some(Json::Array(items)) = doc.get("vectors") else {
+        return Err(schema("vectors", "expected an array"));
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| parse_vector(item, &format!("vectors[{i}]")))
+        .collect()
+}
+
+fn schema(path: &str, problem: &str) -> VectorError {
+    VectorError::Schema(format!("{path}: {problem}"))
+}
+
+fn number(value: &Json) -> Option<f64> {
+    match value {
+        Json::Number(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn string<'a>(item: &'a Json, key: &str, path: &str) -> Result<&'a str, VectorError> {
+    match item.get(key) {
+        Some(Json::String(s)) => Ok(s),
+        _ => Err(schema(&format!("{path}.{key}"), "expected a string")),
+    }
+}
+
+fn flag(item: &Json, key: &str, path: &str) -> Result<bool, VectorError> {
+    match item.get(key) {
+        None => Ok(false),
+        Some(Json::Bool(b)) => Ok(*b),
+        Some(_) => Err(schema(&format!("{path}.{key}"), "expected a boolean")),
+    }
+}
+
+/// Whole number in `0..=max`
+fn integer(item: &Json, key: &str, path: &str, max: u64) -> Result<Option<u64>, VectorError> {
+    match item.get(key) {
+        None => Ok(None),
+        Some(Json::Number(n)) if *n % 1.0 == 0.0 && *n >= 0.0 && *n <= max as f64 => {
+            Ok(Some(*n as u64))
+        }
+        Some(_) => Err(schema(&format!("{path}.{key}"), "expected a whole number")),
+    }
+}
+
+fn parse_vector(item: &Json, path: &str) -> Result<TestVector, VectorError> {
+    let Some(Json::Array(events)) = item.get("events") else {
+        return Err(schema(&format!("{path}.events"), "expected an array"));
+    };
+    Ok(TestVector {
+        name: string(item, "name", path)?.to_string(),
+        config: match item.get("config") {
+            None => Config::default(),
+            Some(config) => parse_config(config, &format!("{path}.config"))?,
+        },
+        events: events
+            .iter()
+            .enumerate()
+            .map(|(i, ev)| parse_event(ev, &format!("{path}.events[{i}]")))
+            .collect::<Result<_, _>>()?,
+        expect: string(item, "expect", path)?.to_string(),
+    })
+}
+
+fn parse_config(item: &Json, path: &str) -> Result<Config, VectorError> {
+    let defaults = Config::default();
+    let flag_or = |key: &str, default: bool| match item.get(key) {
+        None => Ok(default),
+        Some(_) => flag(item, key, path),
+    };
+    Ok(Config {
+        method: integer(item, "method", path, 1)?.map_or(defaults.method, |m| m as u8),
+        skip_w_shortcut: flag_or("skip_w_shortcut", defaults.skip_w_shortcut)?,
+        auto_uo_horn: flag_or("auto_uo_horn", defaults.auto_uo_horn)?,
+        tag_composition: flag_or("tag_composition", defaults.tag_composition)?,
+        bracket_shortcut: flag_or("bracket_shortcut", defaults.bracket_shortcut)?,
+        esc_restore: flag_or("esc_restore", defaults.esc_restore)?,
+        free_tone: flag_or("free_tone", defaults.free_tone)?,
+        modern_tone: flag_or("modern_tone", defaults.modern_tone)?,
+        english_auto_restore: flag_or("english_auto_restore", defaults.english_auto_restore)?,
+        restore_min_length: integer(item, "restore_min_length", path, u8::MAX as u64)?
+            .map_or(defaults.restore_min_length, |n| n as u8),
+        auto_capitalize: flag_or("auto_capitalize", defaults.auto_capitalize)?,
+        spell_check: flag_or("spell_check", defaults.spell_check)?,
+        validation_profile: defaults.validation_profile,
+        tone_key_policy: defaults.tone_key_policy,
+        foreign_final_tone: integer(item, "foreign_final_tone", path, 1)?
+            .map_or(defaults.foreign_final_tone, |n| {
+                ForeignFinalTone::from_u8(n as u8)
+            }),
+        history_capacity: integer(item, "history_capacity", path, MAX_HISTORY_CAPACITY as u64)?
+            .map_or(defaults.history_capacity, |n| n as usize),
+        mid_word_editing: flag_or("mid_word_editing", defaults.mid_word_editing)?,
+        key_timing: defaults.key_timing,
+    })
+}
+
+fn parse_event(item: &Json, path: &str) -> Result<KeyEvent, VectorError> {
+    let code = integer(item, "code", path, u16::MAX as u64)?
+        .ok_or_else(|| schema(&format!("{path}.code"), "missing"))?;
+    let ch = match item.get("char") {
+        None => None,
+        Some(Json::String(s)) if s.chars().count() == 1 => s.chars().next(),
+        Some(_) => return Err(schema(&format!("{path}.char"), "expected one character")),
+    };
+    Ok(KeyEvent {
+        code: code as u16,
+        ch,
+        caps: flag(item, "caps", path)?,
+        shift: flag(item, "shift", path)?,
+        ctrl: flag(item, "ctrl", path)?,
+        time: integer(item, "time", path, u64::MAX)?,
+    })
+}
+
+/// Text an unhandled key types in the app
+fn typed_char(ev: &KeyEvent) -> Option<char> {
+    if let Some(ch) = ev.ch {
+        return Some(ch);
+    }
+    key_to_char_ext(ev.code, ev.caps, ev.shift).or(match ev.code {
+        keys::SPACE => Some(' '),
+        keys::RETURN | keys::ENTER => Some('\n'),
+        keys::TAB => Some('\t'),
+        keys::DOT => Some('.'),
+        keys::COMMA => Some(','),
+        keys::SLASH => Some('/'),
+        keys::SEMICOLON => Some(';'),
+        keys::QUOTE => Some('\''),
+        keys::LBRACKET => Some('['),
+        keys::RBRACKET => Some(']'),
+        keys::BACKSLASH => Some('\\'),
+        keys::MINUS => Some('-'),
+        keys::EQUAL => Some('='),
+        keys::BACKQUOTE => Some('`'),
+        _ => None,
+    })
+}
+
+/// Type key events into an engine and return the app's text
+///
+/// Events with a `time` and no `char` go through `Engine::on_key_at`.
+/// Results are applied to a `VirtualScreen` the way a host applies them;
+/// Ctrl combinations type nothing.
+pub fn replay(engine: &mut Engine, events: &[KeyEvent]) -> String {
+    let mut screen = VirtualScreen::new();
+    for ev in events {
+        let r = match (ev.ch, ev.time) {
+            (Some(ch), _) => engine.on_key_typed(ev.code, ev.caps, ev.ctrl, ev.shift, ch),
+            (None, Some(time)) => engine.on_key_at(ev.code, ev.caps, ev.ctrl, ev.shift, time),
+            (None, None) => engine.on_key_ext(ev.code, ev.caps, ev.ctrl, ev.shift),
+        };
+        if !ev.ctrl {
+            screen.apply(&r, ev.code, ev.shift, typed_char(ev));
+        }
+    }
+    screen.text()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(text: &str) -> Vec<KeyEvent> {
+        crate::utils::keys_from_str(text)
+            .into_iter()
+            .map(|code| KeyEvent {
+                code,
+                ..KeyEvent::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut e = Engine::new();
+        assert_eq!(replay(&mut e, &events("vieetj nam")), "việt nam");
+        e.clear_all();
+        let mut typed = events("ab");
+        typed.push(KeyEvent {
+            code: keys::DELETE,
+            ..KeyEvent::default()
+        });
+        typed.push(KeyEvent {
+            code: keys::S,
+            ch: Some('s'),
+            ..KeyEvent::default()
+        });
+        assert_eq!(replay(&mut e, &typed), "á");
+    }
+
+    #[test]
+    fn test_parse_vectors() {
+        let text = r#"{
+            "version": 1,
+            "vectors": [{
+                "name": "vni",
+                "config": {"method": 1, "auto_capitalize": true},
+                "events": [{"code": 0, "char": "a", "time": 0}, {"code": 18, "shift": true, "time": 40}],
+                "expect": "A!"
+            }]
+        }"#;
+        let vectors = parse_vectors(text).unwrap();
+        assert_eq!(vectors.len(), 1);
+        let v = &vectors[0];
+        assert_eq!(v.config.method, 1);
+        assert!(v.config.auto_capitalize);
+        assert!(v.config.modern_tone, "unset fields keep defaults");
+        assert_eq!(v.events[0].ch, Some('a'));
+        assert_eq!(v.events[1].time, Some(40));
+        assert!(v.events[1].shift);
+    }
+
+    #[test]
+    fn test_parse_vectors_errors() {
+        assert_eq!(parse_vectors("{"), Err(VectorError::Syntax(1)));
+        assert_eq!(
+            parse_vectors(r#"{"version": 2, "vectors": []}"#),
+            Err(schema("version", "unsupported"))
+        );
+        assert_eq!(
+            parse_vectors(
+                r#"{"vectors": [{"name": "x", "events": [{"char": "a"}], "expect": ""}]}"#
+            ),
+            Err(schema("vectors[0].events[0].code", "missing"))
+        );
+        assert_eq!(
+            parse_vectors(
+                r#"{"vectors": [{"name": "x", "events": [{"code": 0, "char": "ab"}], "expect": ""}]}"#
+            ),
+            Err(schema(
+                "vectors[0].events[0].char",
+                "expected one character"
+            ))
+        );
+    }
+}
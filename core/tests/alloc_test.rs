@@ -0,0 +1,107 @@
+//! Allocations per keystroke
+//!
+//! Needs the `alloc-tracking` feature (`cargo test --features alloc-tracking
+//! --test alloc_test`): `CountingAlloc` is installed as the global allocator
+//! and counts what each key makes on this thread. Paths that pass keys
+//! through must not allocate; composing keys stay within a fixed budget, and
+//! nothing grows with session length.
+
+use gonhanh_core::alloc_track::{allocations, CountingAlloc};
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::char_to_key;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Most allocations one composing key may make (syllable parsing and
+/// validation build small vectors; the worst Telex key today makes ~25)
+const KEY_BUDGET: usize = 32;
+
+/// Allocations of each key in `input`
+fn per_key(e: &mut Engine, input: &str) -> Vec<usize> {
+    input
+        .chars()
+        .map(|c| {
+            let key = char_to_key(c);
+            allocations(|| e.on_key(key, c.is_uppercase(), false))
+        })
+        .collect()
+}
+
+#[test]
+fn secure_field_never_allocates() {
+    let mut e = Engine::new();
+    e.set_secure_mode(true);
+    assert_eq!(per_key(&mut e, "vieetj nguowif "), vec![0; 15]);
+}
+
+#[test]
+fn disabled_letters_never_allocate() {
+    let mut e = Engine::new();
+    e.set_enabled(false);
+    per_key(&mut e, "warm ");
+    assert_eq!(per_key(&mut e, "vieetj"), vec![0; 6]);
+}
+
+#[test]
+fn idle_keys_never_allocate() {
+    let mut e = Engine::new();
+    assert_eq!(per_key(&mut e, "<<<"), vec![0; 3]);
+    for key in [keys::LEFT, keys::RIGHT, keys::UP, keys::DOWN] {
+        assert_eq!(allocations(|| e.on_key(key, false, false)), 0, "key {key}");
+    }
+}
+
+#[test]
+fn composing_keys_within_budget() {
+    for (method, words) in [
+        (
+            0,
+            [
+                "vieetj ",
+                "nguowif ",
+                "dduwowngf ",
+                "Text ",
+                "khoong<<<ong ",
+            ],
+        ),
+        (
+            1,
+            [
+                "vie65t ",
+                "ngu7o72i ",
+                "d9u7o7ng2 ",
+                "Text ",
+                "kho6ng<<<ong ",
+            ],
+        ),
+    ] {
+        let mut e = Engine::new();
+        e.set_method(method);
+        for word in words {
+            per_key(&mut e, word);
+            let counts = per_key(&mut e, word);
+            let worst = counts.iter().max().copied().unwrap_or(0);
+            assert!(
+                worst <= KEY_BUDGET,
+                "{word:?} (method {method}): {counts:?} over budget {KEY_BUDGET}"
+            );
+        }
+    }
+}
+
+#[test]
+fn allocations_do_not_grow_with_session() {
+    let mut e = Engine::new();
+    let word = |e: &mut Engine| per_key(e, "nguowif ").iter().sum::<usize>();
+    let first = word(&mut e);
+    for _ in 0..300 {
+        word(&mut e);
+    }
+    let last = word(&mut e);
+    assert!(
+        last <= first + 2,
+        "first word {first}, after 300 words {last}"
+    );
+}
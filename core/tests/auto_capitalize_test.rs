@@ -12,6 +12,7 @@
 mod common;
 use common::telex_auto_capitalize;
 use gonhanh_core::data::keys;
+use gonhanh_core::engine::shortcut::{CaseMode, Shortcut};
 use gonhanh_core::engine::Engine;
 use gonhanh_core::utils::type_word;
 
@@ -517,3 +518,88 @@ fn clear_all_resets_saw_sentence_ending() {
         "After clear_all(), space+letter should NOT capitalize - expecting Action::None"
     );
 }
+
+// ============================================================
+// SHORTCUT EXPANSION AT SENTENCE START
+// ============================================================
+
+fn engine_with_snippets() -> Engine {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    let mut brb = Shortcut::new("brb", "be right back");
+    brb.case_mode = CaseMode::Exact;
+    e.shortcuts_mut().add(brb);
+    e.shortcuts_mut().add(Shortcut::new("#sig", "regards"));
+    e.shortcuts_mut().add(Shortcut::new("q", "“quote”"));
+    e
+}
+
+#[test]
+fn shortcut_capitalized_at_sentence_start() {
+    let mut e = engine_with_snippets();
+    assert_eq!(type_word(&mut e, "ok. brb "), "ok. Be right back ");
+    e.clear_all();
+    // Symbol-prefixed trigger: no letter was typed to capitalize
+    assert_eq!(type_word(&mut e, "ok. #sig "), "ok. Regards ");
+    e.clear_all();
+    // Leading punctuation is skipped
+    assert_eq!(type_word(&mut e, "ok. q "), "ok. “Quote” ");
+    e.clear_all();
+    // An all-caps trigger still uppercases the whole snippet
+    e.shortcuts_mut()
+        .add(gonhanh_core::engine::shortcut::Shortcut::new(
+            "vn",
+            "Việt Nam",
+        ));
+    assert_eq!(type_word(&mut e, "ok. VN "), "ok. VIỆT NAM ");
+    e.clear_all();
+    assert_eq!(type_word(&mut e, "ok. vn "), "ok. Việt Nam ");
+}
+
+#[test]
+fn shortcut_clears_pending_capitalize() {
+    let mut e = engine_with_snippets();
+    assert_eq!(type_word(&mut e, "ok. #sig va"), "ok. Regards va");
+}
+
+#[test]
+fn shortcut_mid_sentence_not_capitalized() {
+    let mut e = engine_with_snippets();
+    assert_eq!(type_word(&mut e, "ok brb "), "ok be right back ");
+    e.clear_all();
+    assert_eq!(type_word(&mut e, "ok #sig "), "ok regards ");
+}
+
+// ============================================================
+// RESTORED WORDS KEEP AUTO-CAPITALIZE STATE
+// ============================================================
+
+#[test]
+fn deleting_restored_word_rearms_capitalize() {
+    // Backspace into "Ab" restores it; deleting it re-arms capitalization
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    assert_eq!(type_word(&mut e, "ok. ab <<<c"), "ok. C");
+
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    e.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut e, "ok. case <<<<<x"), "ok. X");
+}
+
+#[test]
+fn esc_on_restored_word_keeps_caps() {
+    // The restored word gets back its original keystrokes, caps included
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    e.set_esc_restore(true);
+    assert_eq!(type_word(&mut e, "ok. vieetj <\x1b"), "ok. Vieetj");
+    e.clear_all();
+    assert_eq!(type_word(&mut e, "ok. dduowcj <\x1b"), "ok. Dduowcj");
+}
+
+#[test]
+fn shortcut_before_punctuation_capitalized() {
+    let mut e = engine_with_snippets();
+    assert_eq!(type_word(&mut e, "ok. brb,"), "ok. Be right back,");
+}
@@ -0,0 +1,74 @@
+//! Automatic horn on 'o' after 'ư' ("uwo" → "ươ"): kept while the word is
+//! Vietnamese, taken back for loanwords and names, or switched off
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn run(method: u8, auto: bool, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        e.set_auto_uo_horn(auto);
+        assert_eq!(type_word(&mut e, input), *expected, "auto={auto} {input:?}");
+    }
+}
+
+#[test]
+fn couples_in_vietnamese_words() {
+    run(
+        0,
+        true,
+        &[
+            ("uwo", "ươ"),
+            ("nguwoif", "người"),
+            ("dduwocj", "được"),
+            ("truwongf", "trường"),
+            ("ruwouj", "rượu"),
+            ("huwow", "hươ"),
+        ],
+    );
+    run(
+        1,
+        true,
+        &[("u7o", "ươ"), ("ngu7o2i", "người"), ("d9u7o5c", "được")],
+    );
+}
+
+#[test]
+fn takes_back_when_word_is_not_vietnamese() {
+    run(0, true, &[("uwoman", "ưoman"), ("uwoma", "ưoma")]);
+    run(1, true, &[("u7oman", "ưoman")]);
+}
+
+#[test]
+fn horn_key_makes_it_permanent() {
+    // The explicit 'w' on the 'o' is not taken back
+    run(0, true, &[("uwowma", "ươma")]);
+}
+
+#[test]
+fn off_leaves_o_alone() {
+    run(
+        0,
+        false,
+        &[
+            ("uwo", "ưo"),
+            ("uwoman", "ưoman"),
+            ("uwow", "ươ"),
+            ("nguwowif", "người"),
+            ("dduwowcj", "được"),
+        ],
+    );
+    run(1, false, &[("u7o", "ưo"), ("u7o7", "ươ")]);
+}
+
+#[test]
+fn config_round_trip() {
+    let mut e = Engine::new();
+    assert!(e.config().auto_uo_horn);
+    let mut config = e.config();
+    config.auto_uo_horn = false;
+    e.set_config(&config);
+    assert!(!e.config().auto_uo_horn);
+    assert_eq!(type_word(&mut e, "uwo"), "ưo");
+}
@@ -0,0 +1,53 @@
+//! Backspace counts at the u8 boundary
+//!
+//! Results carry the backspace count in a u8. Words up to 255 chars are
+//! edited in full; longer ones must never produce a count that wrapped
+//! around and deletes the wrong chars.
+
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::type_word;
+
+/// "đ" followed by `len - 1` other chars
+fn long_word(len: usize, fill: &str) -> String {
+    format!("dd{}", fill.repeat(len - 1))
+}
+
+#[test]
+fn strip_edits_words_up_to_255_chars() {
+    for len in [254, 255] {
+        let mut e = Engine::new();
+        type_word(&mut e, &long_word(len, "b"));
+        let r = e.strip_last_word();
+        assert_eq!(r.action, Action::Send as u8);
+        assert_eq!(r.backspace as usize, len, "{len}");
+        assert_eq!(r.count as usize, len, "{len}");
+    }
+}
+
+#[test]
+fn longer_words_are_not_edited_blind() {
+    for len in [256, 300, 511, 512] {
+        let mut e = Engine::new();
+        let screen = type_word(&mut e, &long_word(len, "b"));
+        assert_eq!(screen.chars().count(), len);
+        // Only the chars past the first 255 are tracked, and they carry no
+        // diacritics: nothing to strip, nothing deleted
+        let r = e.strip_last_word();
+        assert_eq!(r.action, Action::None as u8, "{len}");
+        assert!(e.get_buffer_string().chars().count() < 255, "{len}");
+    }
+}
+
+#[test]
+fn committed_word_and_separator_past_255() {
+    // 255 chars and a space: a result can delete 255, not 256
+    let mut e = Engine::new();
+    type_word(&mut e, &format!("ddo{} ", "n".repeat(252)));
+    let r = e.strip_last_word();
+    assert_eq!((r.action, r.backspace), (Action::Send as u8, 255));
+
+    let mut e = Engine::new();
+    type_word(&mut e, &format!("ddo{} ", "n".repeat(253)));
+    let r = e.strip_last_word();
+    assert_eq!((r.action, r.backspace), (Action::None as u8, 0));
+}
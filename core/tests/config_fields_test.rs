@@ -0,0 +1,285 @@
+//! Every `Config` field changes what typing produces
+//!
+//! One engine carries all settings, so a field that is stored but never
+//! read would go unnoticed. Each case flips a single field away from its
+//! base value and checks that the same keys give different text.
+
+use gonhanh_core::api::{keys, Config, Engine, ForeignFinalTone, ToneKeyPolicy, ValidationProfile};
+use gonhanh_core::utils::{keys_from_str, type_word};
+use gonhanh_core::vectors::{replay, KeyEvent};
+
+fn typed(config: &Config, input: &str) -> String {
+    let mut e = Engine::new();
+    e.set_config(config);
+    type_word(&mut e, input)
+}
+
+/// Field name, base settings, the same with one field changed, keys, and
+/// the text with the base and the changed settings
+type Case = (
+    &'static str,
+    Config,
+    Config,
+    &'static str,
+    &'static str,
+    &'static str,
+);
+
+fn cases() -> Vec<Case> {
+    let d = Config::default();
+    let restoring = Config {
+        english_auto_restore: true,
+        ..d.clone()
+    };
+    vec![
+        (
+            "method",
+            d.clone(),
+            Config {
+                method: 1,
+                ..d.clone()
+            },
+            "a1",
+            "a1",
+            "á",
+        ),
+        (
+            "skip_w_shortcut",
+            d.clone(),
+            Config {
+                skip_w_shortcut: true,
+                ..d.clone()
+            },
+            "w",
+            "ư",
+            "w",
+        ),
+        (
+            "auto_uo_horn",
+            d.clone(),
+            Config {
+                auto_uo_horn: false,
+                ..d.clone()
+            },
+            "uwo",
+            "ươ",
+            "ưo",
+        ),
+        (
+            "tag_composition",
+            d.clone(),
+            Config {
+                tag_composition: false,
+                ..d.clone()
+            },
+            "#chaof",
+            "#chào",
+            "#chaof",
+        ),
+        (
+            "bracket_shortcut",
+            d.clone(),
+            Config {
+                bracket_shortcut: true,
+                ..d.clone()
+            },
+            "tr]",
+            "tr]",
+            "trư",
+        ),
+        (
+            "esc_restore",
+            d.clone(),
+            Config {
+                esc_restore: true,
+                ..d.clone()
+            },
+            "vieetj\x1b",
+            "việt",
+            "vieetj",
+        ),
+        (
+            "free_tone",
+            d.clone(),
+            Config {
+                free_tone: true,
+                ..d.clone()
+            },
+            "gels",
+            "gels",
+            "gél",
+        ),
+        (
+            "modern_tone",
+            d.clone(),
+            Config {
+                modern_tone: false,
+                ..d.clone()
+            },
+            "hoaf",
+            "hoà",
+            "hòa",
+        ),
+        (
+            "english_auto_restore",
+            d.clone(),
+            restoring.clone(),
+            "text ",
+            "tẽt ",
+            "text ",
+        ),
+        (
+            "restore_min_length",
+            restoring.clone(),
+            Config {
+                restore_min_length: 4,
+                ..restoring
+            },
+            "per ",
+            "per ",
+            "pẻ ",
+        ),
+        (
+            "auto_capitalize",
+            d.clone(),
+            Config {
+                auto_capitalize: true,
+                ..d.clone()
+            },
+            "a. b",
+            "a. b",
+            "a. B",
+        ),
+        (
+            "validation_profile",
+            d.clone(),
+            Config {
+                validation_profile: ValidationProfile::Tolerant,
+                ..d.clone()
+            },
+            "zas",
+            "zas",
+            "zá",
+        ),
+        (
+            "tone_key_policy",
+            d.clone(),
+            Config {
+                tone_key_policy: ToneKeyPolicy::Literal,
+                ..d.clone()
+            },
+            "roofif",
+            "rồi",
+            "rồif",
+        ),
+        (
+            "foreign_final_tone",
+            d.clone(),
+            Config {
+                foreign_final_tone: ForeignFinalTone::LastVowel,
+                ..d.clone()
+            },
+            "gels",
+            "gels",
+            "gél",
+        ),
+        (
+            "history_capacity",
+            d.clone(),
+            Config {
+                history_capacity: 0,
+                ..d.clone()
+            },
+            "an <s",
+            "án",
+            "ans",
+        ),
+    ]
+}
+
+#[test]
+fn each_field_changes_output() {
+    for (field, base, changed, input, before, after) in cases() {
+        assert_eq!(typed(&base, input), before, "{field}: base {input:?}");
+        assert_eq!(typed(&changed, input), after, "{field}: changed {input:?}");
+    }
+}
+
+#[test]
+fn spell_check_flags_words() {
+    for (enabled, flagged) in [(false, false), (true, true)] {
+        let mut e = Engine::new();
+        e.set_config(&Config {
+            spell_check: enabled,
+            ..Config::default()
+        });
+        type_word(&mut e, "kubectl ");
+        assert_eq!(e.take_spell_flag().is_some(), flagged);
+    }
+}
+
+/// Key events for `keys`, each at its entry of `times` (0 past its end)
+fn events(keys: &[u16], times: &[u64]) -> Vec<KeyEvent> {
+    keys.iter()
+        .zip(times.iter().copied().chain(core::iter::repeat(0)))
+        .map(|(&code, time)| KeyEvent {
+            code,
+            time: Some(time),
+            ..KeyEvent::default()
+        })
+        .collect()
+}
+
+#[test]
+fn mid_word_editing_edits_in_place() {
+    let mut keys = keys_from_str("vit");
+    keys.extend([keys::LEFT, keys::S]);
+    for (enabled, expected) in [(false, "vist"), (true, "vít")] {
+        let mut e = Engine::new();
+        e.set_config(&Config {
+            mid_word_editing: enabled,
+            ..Config::default()
+        });
+        assert_eq!(replay(&mut e, &events(&keys, &[])), expected);
+    }
+}
+
+#[test]
+fn key_timing_reads_repeats() {
+    // The second "a" 30 ms after the first: an auto-repeat by default
+    let key_events = events(&[keys::C, keys::A, keys::A], &[0, 100, 130]);
+    for (repeat_ms, expected) in [(50, "caa"), (0, "câ")] {
+        let mut config = Config::default();
+        config.key_timing.repeat_ms = repeat_ms;
+        let mut e = Engine::new();
+        e.set_config(&config);
+        assert_eq!(replay(&mut e, &key_events), expected);
+    }
+}
+
+#[test]
+fn every_field_has_a_case() {
+    // A new field fails to compile here until it gets a case above
+    let Config {
+        method: _,
+        skip_w_shortcut: _,
+        auto_uo_horn: _,
+        tag_composition: _,
+        bracket_shortcut: _,
+        esc_restore: _,
+        free_tone: _,
+        modern_tone: _,
+        english_auto_restore: _,
+        restore_min_length: _,
+        auto_capitalize: _,
+        spell_check: _,
+        validation_profile: _,
+        tone_key_policy: _,
+        foreign_final_tone: _,
+        history_capacity: _,
+        mid_word_editing: _,
+        key_timing: _,
+    } = Config::default();
+    // spell_check, mid_word_editing and key_timing are covered by their own tests
+    assert_eq!(cases().len(), 15);
+}
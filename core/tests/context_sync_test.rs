@@ -0,0 +1,159 @@
+//! Surrounding text sync
+//!
+//! The app's text before the cursor can drift from what the engine tracked:
+//! Enter inserts CRLF or auto-indents, a picker inserts an emoji, the cursor
+//! moves. `sync_context` checks the engine's view against it so backspace
+//! counts stay one per grapheme on screen.
+
+mod common;
+use common::type_word;
+use gonhanh_core::api::{ContextSync, ResetPolicy, ScreenCheck};
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine};
+
+fn delete(e: &mut Engine) -> u8 {
+    e.on_key(keys::DELETE, false, false).action
+}
+
+#[test]
+fn matching_context_is_in_sync() {
+    let mut e = Engine::new();
+    type_word(&mut e, "tieengs");
+    assert_eq!(e.sync_context("Học tiếng"), ContextSync::InSync);
+    // Hosts may pass only the last few graphemes
+    assert_eq!(e.sync_context("ếng"), ContextSync::InSync);
+    // Decomposed text on screen is the same word
+    assert_eq!(e.sync_context("tie\u{0302}\u{0301}ng"), ContextSync::InSync);
+    assert_eq!(e.get_buffer_string(), "tiếng");
+
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj ");
+    assert_eq!(e.sync_context("Tiếng việt "), ContextSync::InSync);
+}
+
+#[test]
+fn moved_cursor_resets_composing_word() {
+    let mut e = Engine::new();
+    type_word(&mut e, "tieeng");
+    assert_eq!(e.sync_context("hello"), ContextSync::Reset);
+    assert_eq!(e.get_buffer_string(), "");
+    // Next keys start a fresh word instead of editing "tiêng" blind
+    assert_eq!(type_word(&mut e, "as"), "á");
+}
+
+#[test]
+fn extra_separators_are_counted_per_grapheme() {
+    // Enter auto-indented: the engine saw one separator, the app has five
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj");
+    e.on_key(keys::RETURN, false, false);
+    assert_eq!(e.sync_context("việt\r\n    "), ContextSync::Adjusted);
+    for _ in 0..4 {
+        assert_eq!(delete(&mut e), Action::Send as u8);
+        assert_eq!(e.get_buffer_string(), "");
+    }
+    // Fifth backspace removes the CRLF and steps back into the word
+    delete(&mut e);
+    assert_eq!(e.get_buffer_string(), "việt");
+    let r = e.on_key(keys::S, false, false);
+    assert_eq!(r.action, Action::Send as u8);
+    assert_eq!(e.get_buffer_string(), "viết");
+}
+
+#[test]
+fn fewer_separators_restore_sooner() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj  ");
+    assert_eq!(e.sync_context("việt "), ContextSync::Adjusted);
+    delete(&mut e);
+    assert_eq!(e.get_buffer_string(), "việt");
+}
+
+#[test]
+fn inserted_emoji_resets_tracking() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj ");
+    assert_eq!(
+        e.sync_context("việt \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"),
+        ContextSync::Reset
+    );
+    // Backspace deletes the emoji in the app; the engine doesn't step into "việt"
+    assert_eq!(delete(&mut e), Action::None as u8);
+    assert_eq!(e.get_buffer_string(), "");
+}
+
+#[test]
+fn restore_word_is_grapheme_safe() {
+    let mut e = Engine::new();
+    e.restore_word("Vie\u{0302}\u{0323}t");
+    assert_eq!(e.get_buffer_string(), "Việt");
+
+    // Graphemes the buffer can't hold: nothing restored
+    for word in ["a\u{1F600}b", "v1", "e\u{0306}"] {
+        e.restore_word(word);
+        assert_eq!(e.get_buffer_string(), "", "{word:?}");
+    }
+}
+
+#[test]
+fn external_edit_drops_tracking() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj ");
+    e.reset_external(ResetPolicy::Commit);
+    assert!(!e.needs_context());
+    // Backspace no longer steps back into "việt"
+    assert_eq!(delete(&mut e), Action::None as u8);
+    assert_eq!(e.get_buffer_string(), "");
+
+    // The composing word is committed as it is, with no edit to apply
+    type_word(&mut e, "tieesng");
+    e.reset_external(ResetPolicy::Resync);
+    assert_eq!(e.get_buffer_string(), "");
+    assert!(e.needs_context());
+    assert_eq!(e.sync_context("Tiếng"), ContextSync::InSync);
+    assert!(!e.needs_context());
+}
+
+fn chars(r: &gonhanh_core::engine::Result) -> String {
+    (0..r.count as usize)
+        .filter_map(|i| char::from_u32(r.chars[i]))
+        .collect()
+}
+
+#[test]
+fn verified_edit_matches_screen() {
+    let mut e = Engine::new();
+    type_word(&mut e, "tieeng");
+    let mut r = e.on_key(keys::S, false, false);
+    let sent = (r.backspace, chars(&r));
+    assert_eq!(e.verify_result(&mut r, "Học tiêng"), ScreenCheck::Confirmed);
+    assert_eq!((r.backspace, chars(&r)), sent);
+    // Decomposed text is the same word
+    let mut r = e.on_key(keys::S, false, false);
+    assert_eq!(
+        e.verify_result(&mut r, "tie\u{0302}\u{0301}ng"),
+        ScreenCheck::Confirmed
+    );
+}
+
+#[test]
+fn edit_over_rewritten_text_is_dropped() {
+    let mut e = Engine::new();
+    type_word(&mut e, "tieeng");
+    let mut r = e.on_key(keys::S, false, false);
+    // The app autocompleted the word behind the engine
+    assert_eq!(e.verify_result(&mut r, "tiêng Việt"), ScreenCheck::Aborted);
+    assert_eq!(r.action, Action::None as u8);
+    assert_eq!(e.get_buffer_string(), "");
+}
+
+#[test]
+fn edit_resized_to_separators_on_screen() {
+    let mut e = Engine::new();
+    type_word(&mut e, "dduwowngf ");
+    let mut r = e.strip_last_word();
+    assert_eq!((r.backspace, chars(&r).as_str()), (6, "duong "));
+    // Enter was typed as CRLF in the app, not a space
+    assert_eq!(e.verify_result(&mut r, "đường\r\n"), ScreenCheck::Adjusted);
+    assert_eq!((r.backspace, chars(&r).as_str()), (6, "duong\r\n"));
+}
@@ -0,0 +1,69 @@
+//! Deferred horn on 'u' of "uơ": one typed case per row of
+//! `engine::defer::DEFER_RULES`, Telex and VNI
+//!
+//! The breve rows have no typed cases: "aw" applies the breve at once today.
+
+use gonhanh_core::engine::defer::{step, DeferEvent, DeferKind, DeferStep, DEFER_RULES};
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+/// (event, Telex input, Telex output, VNI input, VNI output)
+const U_HORN: &[(DeferEvent, &str, &str, &str, &str)] = &[
+    (DeferEvent::Final, "huowc", "hươc", "huo7c", "hươc"),
+    (DeferEvent::Glide, "huowu", "hươu", "huo7u", "hươu"),
+    (DeferEvent::Vowel, "huowa", "huơa", "huo7a", "huơa"),
+    (DeferEvent::Consonant, "huowl", "huơl", "huo7l", "huơl"),
+    (DeferEvent::Mark, "huowfc", "hườc", "huo72c", "hườc"),
+    (DeferEvent::Switch, "huowoc", "huôc", "huo76c", "huôc"),
+    // The undone modifier is typed as-is
+    (DeferEvent::Undo, "huowwc", "huowc", "huo77c", "huo7c"),
+    (DeferEvent::Remove, "huowzc", "huoc", "huo70c", "huoc"),
+    (DeferEvent::Delete, "huow<c", "huc", "huo7<c", "huc"),
+];
+
+fn typed(method: u8, input: &str) -> String {
+    let mut e = Engine::new();
+    e.set_method(method);
+    type_word(&mut e, input)
+}
+
+#[test]
+fn u_horn_lifecycle() {
+    for &(event, telex, telex_out, vni, vni_out) in U_HORN {
+        assert_eq!(typed(0, telex), telex_out, "{event:?} {telex:?}");
+        assert_eq!(typed(1, vni), vni_out, "{event:?} {vni:?}");
+    }
+}
+
+#[test]
+fn u_horn_cases_cover_the_table() {
+    let rows = DEFER_RULES
+        .iter()
+        .filter(|rule| rule.0 == DeferKind::UHorn)
+        .count();
+    assert_eq!(U_HORN.len(), rows);
+    for &(event, _, telex_out, _, _) in U_HORN {
+        // The 'u' got its horn unless the rule dropped it
+        assert_eq!(
+            telex_out.starts_with("hư"),
+            step(DeferKind::UHorn, event) != DeferStep::Drop,
+            "{event:?}"
+        );
+    }
+}
+
+#[test]
+fn deferred_horn_in_words() {
+    for (input, expected) in [
+        ("huow", "huơ"),
+        ("khuowr", "khuở"),
+        ("thuowr", "thuở"),
+        ("duowcj", "dược"),
+        ("nguowif", "người"),
+        ("huowng", "hương"),
+        ("huow c", "huơ c"),
+        ("duow<owc", "dươc"),
+    ] {
+        assert_eq!(typed(0, input), expected, "{input:?}");
+    }
+}
@@ -1310,6 +1310,57 @@ fn triphthong_yeu_tone_space() {
     ]);
 }
 
+#[test]
+fn triphthong_rare_nuclei_telex() {
+    // uya, uyu, uêu, oăo, ươu: rare nuclei in any key order
+    common::telex(&[
+        ("khuya", "khuya"),
+        ("khuyra", "khuỷa"),
+        ("khuyar", "khuỷa"),
+        ("khuyur", "khuỷu"),
+        ("ngueeuf", "nguều"),
+        ("nguefeu", "nguều"),
+        ("ngueufe", "nguều"),
+        ("ngoawof", "ngoằo"),
+        ("ngoaowf", "ngoằo"),
+        ("ngoaofw", "ngoằo"),
+        ("ngoaow", "ngoăo"),
+        ("ruouwj", "rượu"),
+        ("ruowuj", "rượu"),
+        ("ruwouj", "rượu"),
+        ("rujowu", "rượu"),
+        ("huouw", "hươu"),
+        ("huwou", "hươu"),
+    ]);
+}
+
+#[test]
+fn triphthong_rare_nuclei_vni() {
+    common::vni(&[
+        ("khuya3", "khuỷa"),
+        ("khuy3a", "khuỷa"),
+        ("ngue62u", "nguều"),
+        ("ngueu62", "nguều"),
+        ("ngoa82o", "ngoằo"),
+        ("ngoao82", "ngoằo"),
+        ("ngoao28", "ngoằo"),
+        ("ruou75", "rượu"),
+        ("ru7o7u5", "rượu"),
+        ("huou7", "hươu"),
+    ]);
+}
+
+#[test]
+fn triphthong_rare_nuclei_space() {
+    common::telex_auto_restore(&[
+        ("khuya ", "khuya "),
+        ("ngueeuf ", "nguều "),
+        ("ngoaowf ", "ngoằo "),
+        ("ruouwj ", "rượu "),
+        ("huouw ", "hươu "),
+    ]);
+}
+
 #[test]
 fn telex_double_not_in_whitelist_keeps_buffer() {
     // When word is NOT in telex_doubles whitelist AND had telex transform,
@@ -103,6 +103,16 @@ fn char_to_key(c: char) -> u16 {
     }
 }
 
+/// Telex engine with auto-restore. Each word gets a fresh one: the restore
+/// tie-break reads the words committed before, which would make a result
+/// depend on the words tested earlier.
+fn new_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_method(0); // Telex
+    engine.set_english_auto_restore(true);
+    engine
+}
+
 /// Returns (final_output, buffer_before_space)
 fn type_word_with_space(engine: &mut Engine, word: &str) -> (String, String) {
     engine.clear();
@@ -170,10 +180,6 @@ fn english_100k_failures() {
         })
         .collect();
 
-    let mut engine = Engine::new();
-    engine.set_method(0); // Telex
-    engine.set_english_auto_restore(true);
-
     // Categorized failures: (word, actual_output, buffer)
     let mut tone_failures: Vec<(String, String, String)> = Vec::new();
     let mut vowel_failures: Vec<(String, String, String)> = Vec::new();
@@ -182,7 +188,7 @@ fn english_100k_failures() {
 
     for word in &words {
         let expected = format!("{} ", word);
-        let (actual, buffer) = type_word_with_space(&mut engine, word);
+        let (actual, buffer) = type_word_with_space(&mut new_engine(), word);
 
         if actual != expected {
             let failure = (word.to_string(), actual.trim().to_string(), buffer);
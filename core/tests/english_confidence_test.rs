@@ -0,0 +1,151 @@
+//! English confidence calibration
+//!
+//! Scores both bundled corpora and checks the tiers separate them: Telex
+//! keystrokes of Vietnamese syllables should land in Low, common English
+//! words in Medium/High. The per-tier counts and the misses are written to
+//! tests/data/english_confidence_calibration.txt so hosts choosing their own
+//! thresholds can see where each corpus falls.
+
+use gonhanh_core::api::{english_confidence, ConfidenceTier, HIGH_THRESHOLD, MEDIUM_THRESHOLD};
+use std::fs::File;
+use std::io::Write;
+
+const TOP_ENGLISH: usize = 10_000;
+
+/// Misses listed per corpus in the report
+const REPORT_MISSES: usize = 50;
+
+struct Calibration {
+    name: &'static str,
+    total: usize,
+    tiers: [usize; 3],
+    mean: f64,
+    /// Words in the tier opposite to the corpus language
+    misses: Vec<(&'static str, u8)>,
+}
+
+impl Calibration {
+    fn rate(&self, tier: ConfidenceTier) -> f64 {
+        self.tiers[tier as usize] as f64 / self.total as f64 * 100.0
+    }
+}
+
+fn calibrate(name: &'static str, words: &[&'static str], miss: ConfidenceTier) -> Calibration {
+    let mut tiers = [0; 3];
+    let mut sum = 0usize;
+    let mut misses = Vec::new();
+    for w in words {
+        let c = english_confidence(w);
+        tiers[c.tier as usize] += 1;
+        sum += c.score as usize;
+        if c.tier == miss {
+            misses.push((*w, c.score));
+        }
+    }
+    Calibration {
+        name,
+        total: words.len(),
+        tiers,
+        mean: sum as f64 / words.len() as f64,
+        misses,
+    }
+}
+
+fn vietnamese_corpus() -> Vec<&'static str> {
+    include_str!("data/vietnamese_telex_pairs.txt")
+        .lines()
+        .filter_map(|l| l.split('\t').next())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn english_corpus() -> Vec<&'static str> {
+    include_str!("data/english_100k.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_alphabetic()))
+        .take(TOP_ENGLISH)
+        .collect()
+}
+
+fn write_report(path: &str, results: &[&Calibration]) {
+    let Ok(mut f) = File::create(path) else {
+        return;
+    };
+    let _ = writeln!(
+        f,
+        "# English confidence calibration\n# Tiers: Low < {MEDIUM_THRESHOLD} <= Medium < {HIGH_THRESHOLD} <= High"
+    );
+    for r in results {
+        let _ = writeln!(
+            f,
+            "\n[{}] words={} low={} ({:.2}%) medium={} ({:.2}%) high={} ({:.2}%) mean={:.1}",
+            r.name,
+            r.total,
+            r.tiers[0],
+            r.rate(ConfidenceTier::Low),
+            r.tiers[1],
+            r.rate(ConfidenceTier::Medium),
+            r.tiers[2],
+            r.rate(ConfidenceTier::High),
+            r.mean
+        );
+        for (w, score) in r.misses.iter().take(REPORT_MISSES) {
+            let _ = writeln!(f, "{w}\t{score}");
+        }
+    }
+}
+
+#[test]
+fn english_confidence_calibration() {
+    let vietnamese = calibrate(
+        "vietnamese_telex",
+        &vietnamese_corpus(),
+        ConfidenceTier::High,
+    );
+    let english = calibrate("english_top10k", &english_corpus(), ConfidenceTier::Low);
+    write_report(
+        "tests/data/english_confidence_calibration.txt",
+        &[&vietnamese, &english],
+    );
+
+    assert!(
+        vietnamese.rate(ConfidenceTier::Low) >= 98.0,
+        "Vietnamese Low rate {:.2}%",
+        vietnamese.rate(ConfidenceTier::Low)
+    );
+    assert!(
+        vietnamese.rate(ConfidenceTier::High) <= 0.1,
+        "Vietnamese High rate {:.2}%",
+        vietnamese.rate(ConfidenceTier::High)
+    );
+    assert!(
+        english.rate(ConfidenceTier::Low) <= 1.0,
+        "English Low rate {:.2}%",
+        english.rate(ConfidenceTier::Low)
+    );
+    assert!(
+        english.rate(ConfidenceTier::High) >= 90.0,
+        "English High rate {:.2}%",
+        english.rate(ConfidenceTier::High)
+    );
+    assert!(english.mean - vietnamese.mean >= 50.0);
+}
+
+#[test]
+fn english_confidence_examples() {
+    for (raw, tier) in [
+        ("vieetj", ConfidenceTier::Low),
+        ("dduowcj", ConfidenceTier::Low),
+        ("can", ConfidenceTier::Medium),
+        ("ban", ConfidenceTier::Medium),
+        ("text", ConfidenceTier::High),
+        ("coffee", ConfidenceTier::High),
+        // Dictionaries don't settle these: letter bigrams do
+        ("mit", ConfidenceTier::High),
+        ("kubectl", ConfidenceTier::High),
+        ("sang", ConfidenceTier::Low),
+    ] {
+        assert_eq!(english_confidence(raw).tier, tier, "{raw}");
+    }
+}
@@ -0,0 +1,116 @@
+//! English False-Positive Corpus
+//!
+//! Types the top 10k English words plus common programming identifiers with
+//! Telex + `english_auto_restore` and asserts none keep diacritics after the
+//! word is committed.
+//!
+//! Known failures are tracked in `tests/data/english_false_positive_known.txt`.
+//! A word failing outside that list is a regression; a listed word that now
+//! passes should be removed from it.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Write;
+
+/// Number of most frequent English words to check
+const TOP_ENGLISH: usize = 10_000;
+
+/// Words that must always round-trip, whatever the known list says
+const PINNED: &[&str] = &["issue", "staff", "class", "issues", "classes", "stuff"];
+
+fn corpus() -> Vec<&'static str> {
+    let english = include_str!("data/english_100k.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_alphabetic()))
+        .take(TOP_ENGLISH);
+    let identifiers = include_str!("data/programming_identifiers.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty() && !w.starts_with('#'));
+    let mut seen = BTreeSet::new();
+    english
+        .chain(identifiers)
+        .filter(|w| seen.insert(*w))
+        .collect()
+}
+
+fn known_failures() -> BTreeSet<&'static str> {
+    include_str!("data/english_false_positive_known.txt")
+        .lines()
+        .map(|l| l.split('\t').next().unwrap_or("").trim())
+        .filter(|w| !w.is_empty() && !w.starts_with('#'))
+        .collect()
+}
+
+/// Committed output of a word, or None if it came out without diacritics
+fn false_positive(word: &str) -> Option<String> {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    let out = type_word(&mut e, &format!("{} ", word));
+    let out = out.trim_end();
+    (!out.is_ascii()).then(|| out.to_string())
+}
+
+#[test]
+fn english_false_positive_corpus() {
+    let words = corpus();
+    let known = known_failures();
+    let failures: Vec<(&str, String)> = words
+        .iter()
+        .filter_map(|&w| false_positive(w).map(|out| (w, out)))
+        .collect();
+
+    println!("\n=== English False Positives ===");
+    println!("Corpus: {} words", words.len());
+    println!(
+        "Failed: {} ({:.2}%)",
+        failures.len(),
+        failures.len() as f64 * 100.0 / words.len() as f64
+    );
+
+    if let Ok(mut f) = File::create("tests/data/english_false_positive_failures.txt") {
+        writeln!(
+            f,
+            "# English False Positives (top {TOP_ENGLISH} + identifiers)"
+        )
+        .ok();
+        writeln!(f, "# Format: WORD \\t ACTUAL").ok();
+        writeln!(f, "# Total failures: {}", failures.len()).ok();
+        writeln!(f).ok();
+        for (word, out) in &failures {
+            writeln!(f, "{}\t{}", word, out).ok();
+        }
+    }
+
+    let regressions: Vec<String> = failures
+        .iter()
+        .filter(|(w, _)| !known.contains(w))
+        .map(|(w, out)| format!("{w} → {out}"))
+        .collect();
+    assert!(
+        regressions.is_empty(),
+        "{} new false positives:\n{}",
+        regressions.len(),
+        regressions.join("\n")
+    );
+
+    let fixed: Vec<&str> = known
+        .iter()
+        .copied()
+        .filter(|w| !failures.iter().any(|(f, _)| f == w))
+        .collect();
+    assert!(
+        fixed.is_empty(),
+        "known false positives now pass, remove from english_false_positive_known.txt: {fixed:?}"
+    );
+}
+
+#[test]
+fn english_false_positive_pinned() {
+    for word in PINNED {
+        assert_eq!(false_positive(word), None, "{word} kept diacritics");
+    }
+}
@@ -298,6 +298,16 @@ fn char_to_key(c: char) -> u16 {
     }
 }
 
+/// Telex engine with auto-restore. Each word gets a fresh one: the restore
+/// tie-break reads the words committed before, which would make a result
+/// depend on the words tested earlier.
+fn new_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_method(0); // Telex
+    engine.set_english_auto_restore(true);
+    engine
+}
+
 fn type_word_with_space(engine: &mut Engine, word: &str) -> String {
     engine.clear();
     let mut output = String::new();
@@ -431,10 +441,6 @@ fn test_english_telex_patterns_restore() {
         }
     };
 
-    let mut engine = Engine::new();
-    engine.set_method(0); // Telex
-    engine.set_english_auto_restore(true);
-
     let mut total = 0;
     let mut restored = 0;
     // (input_typed, expected_word, actual_output, vn_buffer)
@@ -459,7 +465,7 @@ fn test_english_telex_patterns_restore() {
             total += 1;
 
             let expected = format!("{} ", word);
-            let actual = type_word_with_space(&mut engine, variant);
+            let actual = type_word_with_space(&mut new_engine(), variant);
 
             if actual == expected {
                 restored += 1;
@@ -538,7 +544,7 @@ fn test_english_telex_patterns_restore() {
         let word = parts[0];
         let patterns = get_telex_patterns(word);
         let expected = format!("{} ", word);
-        let actual = type_word_with_space(&mut engine, word);
+        let actual = type_word_with_space(&mut new_engine(), word);
 
         for (p, _) in &patterns {
             if actual == expected {
@@ -565,10 +571,6 @@ fn test_english_telex_patterns_restore() {
 /// Quick sanity test for specific words - shows which restore vs kept as VN
 #[test]
 fn test_specific_english_telex_words() {
-    let mut engine = Engine::new();
-    engine.set_method(0);
-    engine.set_english_auto_restore(true);
-
     // Words with oo pattern (most should restore - invalid VN ending)
     let oo_words = [
         "book", "good", "food", "look", "took", "cool", "pool", "tool",
@@ -600,7 +602,7 @@ fn test_specific_english_telex_words() {
         println!("--- {} ---", label);
         for word in words {
             let expected = format!("{} ", word);
-            let actual = type_word_with_space(&mut engine, word);
+            let actual = type_word_with_space(&mut new_engine(), word);
 
             if actual == expected {
                 restored += 1;
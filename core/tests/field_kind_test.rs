@@ -0,0 +1,76 @@
+//! Host-reported field kinds: one switch over input, secure mode,
+//! validation and auto-capitalize, with the user's settings kept
+
+use gonhanh_core::api::{DisableReason, Engine, FieldKind, ValidationProfile};
+use gonhanh_core::utils::type_word;
+
+#[test]
+fn address_fields_turn_input_off() {
+    for kind in [FieldKind::Email, FieldKind::Url] {
+        let mut e = Engine::new();
+        e.set_field_kind(kind);
+        assert_eq!(e.disable_reason(), Some(DisableReason::Field));
+        assert_eq!(type_word(&mut e, "vieetj@mail "), "vieetj@mail ");
+
+        e.set_field_kind(FieldKind::Normal);
+        assert_eq!(e.disable_reason(), None);
+        assert_eq!(type_word(&mut e, "vieetj "), "việt ");
+    }
+}
+
+#[test]
+fn password_field_is_secure() {
+    let mut e = Engine::new();
+    e.set_field_kind(FieldKind::Password);
+    assert!(e.is_secure_mode());
+    assert_eq!(e.disable_reason(), Some(DisableReason::SecureField));
+    type_word(&mut e, "hunter2");
+    assert_eq!(e.status().raw_word, "");
+
+    e.set_field_kind(FieldKind::Email);
+    assert!(!e.is_secure_mode());
+    assert_eq!(e.disable_reason(), Some(DisableReason::Field));
+}
+
+#[test]
+fn password_field_keeps_secure_mode() {
+    let mut e = Engine::new();
+    e.set_field_kind(FieldKind::Password);
+    e.set_secure_mode(false);
+    assert!(e.is_secure_mode());
+    assert_eq!(e.disable_reason(), Some(DisableReason::SecureField));
+    assert_eq!(type_word(&mut e, "vieetj"), "vieetj");
+    assert_eq!(e.status().raw_word, "");
+
+    e.set_field_kind(FieldKind::Normal);
+    assert!(!e.is_secure_mode());
+    assert_eq!(type_word(&mut e, "vieetj "), "việt ");
+}
+
+#[test]
+fn code_field_is_strict_and_not_capitalized() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    assert_eq!(type_word(&mut e, "Kroong "), "Krông ");
+    assert_eq!(type_word(&mut e, "ok. vieetj "), "ok. Việt ");
+
+    e.set_field_kind(FieldKind::Code);
+    assert_eq!(type_word(&mut e, "Kroong "), "Kroong ");
+    assert_eq!(type_word(&mut e, "ok. vieetj "), "ok. việt ");
+    assert_eq!(type_word(&mut e, "tieengs "), "tiếng ");
+}
+
+#[test]
+fn user_settings_survive_field_changes() {
+    let mut e = Engine::new();
+    e.set_enabled(false);
+    e.set_auto_capitalize(true);
+    for kind in [FieldKind::Code, FieldKind::Url, FieldKind::Password] {
+        e.set_field_kind(kind);
+        e.set_field_kind(FieldKind::Normal);
+    }
+    assert_eq!(e.field_kind(), FieldKind::Normal);
+    assert_eq!(e.disable_reason(), Some(DisableReason::User));
+    assert!(e.config().auto_capitalize);
+    assert_eq!(e.config().validation_profile, ValidationProfile::Standard);
+}
@@ -4,6 +4,7 @@
 //! the allow_foreign_consonants option is enabled.
 
 mod common;
+use gonhanh_core::engine::validation::ValidationProfile;
 use gonhanh_core::engine::Engine;
 use gonhanh_core::utils::type_word;
 
@@ -272,3 +273,70 @@ fn foreign_fomo_auto_restore() {
         ("fomoo ", "fomo "), // fomô is not valid VN, restore to fomo
     ]);
 }
+
+// ============================================================
+// VALIDATION PROFILES
+// ============================================================
+
+fn telex_profile(profile: ValidationProfile, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_validation_profile(profile);
+        let result = type_word(&mut e, input);
+        assert_eq!(
+            result, *expected,
+            "[Telex {:?}] '{}' → '{}'",
+            profile, input, result
+        );
+    }
+}
+
+#[test]
+fn profile_strict_rejects_minority_clusters() {
+    telex_profile(
+        ValidationProfile::Strict,
+        &[("kroong", "kroong"), ("laks", "laks"), ("zas", "zas")],
+    );
+    // Standard words are unaffected
+    telex_profile(
+        ValidationProfile::Strict,
+        &[("tras", "trá"), ("khas", "khá"), ("bacs", "bác")],
+    );
+}
+
+#[test]
+fn profile_standard_accepts_minority_clusters() {
+    telex_profile(
+        ValidationProfile::Standard,
+        &[("kroong", "krông"), ("laks", "lák"), ("zas", "zas")],
+    );
+}
+
+#[test]
+fn profile_tolerant_accepts_foreign_initials() {
+    telex_profile(
+        ValidationProfile::Tolerant,
+        &[
+            ("kroong", "krông"),
+            ("zas", "zá"),
+            ("fas", "fá"),
+            ("jas", "já"),
+        ],
+    );
+}
+
+#[test]
+fn profile_foreign_toggle_maps_to_tolerant() {
+    let mut e = Engine::new();
+    assert_eq!(e.validation_profile(), ValidationProfile::Standard);
+
+    e.set_allow_foreign_consonants(true);
+    assert_eq!(e.validation_profile(), ValidationProfile::Tolerant);
+
+    e.set_validation_profile(ValidationProfile::Strict);
+    assert!(!e.allow_foreign_consonants());
+
+    // Disabling foreign consonants keeps a stricter profile
+    e.set_allow_foreign_consonants(false);
+    assert_eq!(e.validation_profile(), ValidationProfile::Strict);
+}
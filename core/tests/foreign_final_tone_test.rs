@@ -0,0 +1,78 @@
+//! Tone keys on syllables with a non-Vietnamese final ("xà phòng gel"):
+//! typed as letters, or placed on the last vowel per `ForeignFinalTone`
+
+use gonhanh_core::api::ForeignFinalTone;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn run(policy: ForeignFinalTone, free_tone: bool, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_foreign_final_tone(policy);
+        e.set_free_tone(free_tone);
+        assert_eq!(
+            type_word(&mut e, input),
+            *expected,
+            "{policy:?} free_tone={free_tone} {input:?}"
+        );
+    }
+}
+
+#[test]
+fn reject_is_default() {
+    assert_eq!(Engine::new().foreign_final_tone(), ForeignFinalTone::Reject);
+    run(
+        ForeignFinalTone::Reject,
+        false,
+        &[
+            ("gels", "gels"),
+            ("mails", "mails"),
+            ("xaf phongf gels", "xà phòng gels"),
+        ],
+    );
+    // Free tone places the mark by the usual vowel-pair rules
+    run(
+        ForeignFinalTone::Reject,
+        true,
+        &[("mails", "máil"), ("boils", "bóil")],
+    );
+}
+
+#[test]
+fn last_vowel_takes_the_mark() {
+    for free_tone in [false, true] {
+        run(
+            ForeignFinalTone::LastVowel,
+            free_tone,
+            &[
+                ("gels", "gél"),
+                ("gelf", "gèl"),
+                ("mails", "maíl"),
+                ("boils", "boíl"),
+                ("goals", "goál"),
+                ("xaf phongf gels", "xà phòng gél"),
+                // Same key again takes the mark back
+                ("gelss", "gels"),
+            ],
+        );
+    }
+}
+
+#[test]
+fn vietnamese_finals_unchanged() {
+    run(
+        ForeignFinalTone::LastVowel,
+        false,
+        &[
+            ("toans", "toán"),
+            ("hoaf", "hoà"),
+            ("vieetj", "việt"),
+            ("lists", "lits"),
+        ],
+    );
+}
+
+#[test]
+fn initial_must_still_be_vietnamese() {
+    run(ForeignFinalTone::LastVowel, false, &[("blogs", "blogs")]);
+}
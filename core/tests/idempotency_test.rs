@@ -0,0 +1,115 @@
+//! Idempotency: the engine's own output, fed back as composed text, is
+//! never transformed again
+//!
+//! Hosts re-ingest words that are already on screen (`push_composed`, focus
+//! changes, re-checking a selection). Ending such a word must leave it as it
+//! is: the keys that would type it were never pressed, so auto-restore has
+//! nothing to undo. Double transforms ("respeect") break this.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::utils::{reprocess, type_word};
+use std::fs;
+
+fn words(path: &str) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("Failed to read {path}"))
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Type each Telex word, then feed the output back; collect what changed
+fn typed_output_changes(e: &mut Engine, inputs: &[String]) -> Vec<(String, String, String)> {
+    let mut changed = Vec::new();
+    for input in inputs {
+        e.clear_all();
+        let typed = type_word(e, &format!("{input} "));
+        let output = typed.trim_end();
+        let again = reprocess(e, output);
+        if again.trim_end() != output {
+            changed.push((input.clone(), output.to_string(), again));
+        }
+    }
+    changed
+}
+
+#[test]
+fn reprocessed_words_are_unchanged() {
+    let mut e = Engine::new();
+    for word in ["tiếng", "Việt", "đường", "mể", "sã", "coé", "hello"] {
+        assert_eq!(reprocess(&mut e, word), format!("{word} "));
+    }
+    e.set_english_auto_restore(true);
+    // "mể" is what "mere" types in Telex; on its own it stays
+    for word in ["mể", "sã", "coé", "mục kỉch"] {
+        assert_eq!(reprocess(&mut e, word), format!("{word} "));
+    }
+}
+
+#[test]
+fn reprocessed_word_still_takes_keys() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    for c in "mể".chars() {
+        e.push_composed(c);
+    }
+    // Typed keys make it the user's word again, as if "meer" had been
+    // typed: the hỏi key reverts the mark ("meerr" → "mer")
+    let mut screen = String::from("mể");
+    for (key, ch) in [(keys::R, 'r'), (keys::SPACE, ' ')] {
+        let r = e.on_key(key, false, false);
+        if r.action != Action::Send as u8 {
+            screen.push(ch);
+        } else {
+            for _ in 0..r.backspace {
+                screen.pop();
+            }
+            screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
+        }
+    }
+    assert_eq!(screen, "mer ");
+    let mut typed = Engine::new();
+    typed.set_english_auto_restore(true);
+    assert_eq!(type_word(&mut typed, "meerr "), screen);
+}
+
+#[test]
+fn vietnamese_dictionary_is_idempotent() {
+    let words = words("tests/data/vietnamese_22k.txt");
+    for auto_restore in [false, true] {
+        let mut e = Engine::new();
+        e.set_english_auto_restore(auto_restore);
+        let changed: Vec<_> = words
+            .iter()
+            .filter(|w| reprocess(&mut e, w).trim_end() != w.as_str())
+            .collect();
+        assert!(
+            changed.is_empty(),
+            "auto_restore={auto_restore}: {} words changed, first: {:?}",
+            changed.len(),
+            &changed[..changed.len().min(10)]
+        );
+    }
+}
+
+#[test]
+fn english_output_is_idempotent() {
+    let inputs: Vec<String> = words("tests/data/english_100k.txt")
+        .into_iter()
+        .filter(|w| w.chars().all(|c| c.is_ascii_alphabetic()))
+        .collect();
+    for auto_restore in [false, true] {
+        let mut e = Engine::new();
+        e.set_english_auto_restore(auto_restore);
+        let changed = typed_output_changes(&mut e, &inputs);
+        assert!(
+            changed.is_empty(),
+            "auto_restore={auto_restore}: {} words changed, first: {:?}",
+            changed.len(),
+            &changed[..changed.len().min(10)]
+        );
+    }
+}
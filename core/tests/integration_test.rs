@@ -47,6 +47,59 @@ fn disabled_engine_ignores_auto_restore() {
     }
 }
 
+// ============================================================
+// FLUSH: Finalize the word on focus change / app switch
+// ============================================================
+
+#[test]
+fn flush_restores_english_word() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    type_word(&mut e, "case");
+    assert_eq!(e.get_buffer_string(), "cáe");
+
+    let r = e.flush();
+    assert_eq!(r.action, Action::Send as u8);
+    assert!(r.is_restore());
+    assert_eq!(r.backspace, 3);
+    let output: String = (0..r.count as usize)
+        .filter_map(|i| char::from_u32(r.chars[i]))
+        .collect();
+    assert_eq!(output, "case", "no space: nothing was typed after the word");
+    assert_eq!(e.get_buffer_string(), "");
+}
+
+#[test]
+fn flush_keeps_vietnamese_word() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    type_word(&mut e, "vieetj");
+    assert_eq!(e.flush().action, Action::None as u8);
+    assert_eq!(e.get_buffer_string(), "");
+    // Keys in the new field start a fresh word
+    assert_eq!(type_word(&mut e, "as"), "á");
+}
+
+#[test]
+fn flush_does_not_expand_shortcuts() {
+    use gonhanh_core::engine::shortcut::Shortcut;
+
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("vn", "Việt Nam"));
+    type_word(&mut e, "vn");
+    assert_eq!(e.flush().action, Action::None as u8);
+}
+
+#[test]
+fn flush_drops_backspace_after_space() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj ");
+    e.flush();
+    // Backspace now edits another field: don't step back into "việt"
+    assert_passthrough(&mut e, keys::DELETE);
+    assert_eq!(e.get_buffer_string(), "");
+}
+
 // ============================================================
 // CTRL/CMD: Modifier key handling
 // ============================================================
@@ -1212,6 +1265,42 @@ fn shortcut_not_triggered_by_letter() {
     );
 }
 
+#[test]
+fn shortcut_vietnamese_triggers_typed_with_telex() {
+    for (trigger, typed) in [
+        ("đc", "ddc "),
+        ("cđ", "cdd "),
+        ("ăn", "awn "),
+        ("ơi", "owi "),
+        ("Đc", "DDc "),
+        // Decomposed (NFD) triggers, as some text fields store them
+        ("a\u{0306}n", "awn "),
+        ("o\u{031B}i", "owi "),
+        ("vie\u{0302}\u{0323}t", "vieetj "),
+    ] {
+        let mut e = Engine::new();
+        e.shortcuts_mut().add(Shortcut::new(trigger, "OK"));
+        assert_eq!(type_word(&mut e, typed), "OK ", "trigger {trigger:?}");
+    }
+}
+
+#[test]
+fn shortcut_diacritic_insensitive_triggers() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("đc", "được"));
+    assert_eq!(type_word(&mut e, "dc "), "dc ", "off by default");
+
+    e.shortcuts_mut().set_ignore_diacritics(true);
+    assert_eq!(type_word(&mut e, "dc "), "được ");
+    assert_eq!(type_word(&mut e, "ddc "), "được ");
+    assert_eq!(type_word(&mut e, "Dc "), "Được ");
+
+    // An exact trigger wins over a stripped match
+    e.shortcuts_mut().add(Shortcut::new("dc", "địa chỉ"));
+    assert_eq!(type_word(&mut e, "dc "), "địa chỉ ");
+    assert_eq!(type_word(&mut e, "ddc "), "được ");
+}
+
 // Issue: "search" should not become "seảch" in Telex
 // "ea" is not a valid Vietnamese vowel combination
 #[test]
@@ -1950,14 +2039,77 @@ fn backspace_after_space_tab_clears() {
     assert_eq!(r.action, Action::None as u8, "TAB should clear history");
 }
 
-/// Enter key clears history
+/// Enter is a tracked separator: backspacing over the newline and the
+/// space restores the word
 #[test]
-fn backspace_after_space_enter_clears() {
+fn backspace_after_space_enter_restores() {
     let mut e = Engine::new();
     type_word(&mut e, "du ");
     e.on_key(keys::RETURN, false, false);
     let r = e.on_key(keys::DELETE, false, false);
-    assert_eq!(r.action, Action::None as u8, "ENTER should clear history");
+    assert_eq!(r.backspace, 1, "first backspace deletes the newline");
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!(r.backspace, 1, "second backspace deletes the space");
+    assert_eq!(e.get_buffer_string(), "du", "word restored for editing");
+}
+
+/// Chained restore knows the separators between words (". " is 2 chars)
+#[test]
+fn backspace_chain_across_punctuation() {
+    let mut e = Engine::new();
+    // "việt. đi " → delete " " (restore "đi"), "đi", " ", "." → restore "việt" → sắc
+    assert_eq!(type_word(&mut e, "vieetj. ddi <<<<<s"), "viết");
+}
+
+/// Chained restore across a newline
+#[test]
+fn backspace_chain_across_newline() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj");
+    e.on_key(keys::RETURN, false, false);
+    type_word(&mut e, "ddi ");
+    // " " (restore "đi"), "đi", "\n" → restore "việt"
+    for _ in 0..4 {
+        e.on_key(keys::DELETE, false, false);
+    }
+    assert_eq!(e.get_buffer_string(), "việt");
+}
+
+/// Delete-word while composing keeps the previous word restorable
+#[test]
+fn delete_word_while_composing() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj ddu");
+    e.delete_word();
+    assert_eq!(e.get_buffer_string(), "");
+    // Screen is "việt " again: backspace deletes the space, restores "việt"
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!(r.backspace, 1);
+    assert_eq!(e.get_buffer_string(), "việt");
+}
+
+/// Delete-word after a commit drops that word; backspace reaches the one before
+#[test]
+fn delete_word_after_commit() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj, ddi ");
+    e.delete_word(); // host deleted "đi "
+                     // Screen is "việt, ": backspace ×2 deletes ", " and restores "việt"
+    e.on_key(keys::DELETE, false, false);
+    e.on_key(keys::DELETE, false, false);
+    assert_eq!(e.get_buffer_string(), "việt");
+    assert_eq!(e.history_len(), 0);
+}
+
+/// Delete-word with unknown text before the cursor forgets history
+#[test]
+fn delete_word_clears_untracked_history() {
+    let mut e = Engine::new();
+    type_word(&mut e, "ddi ");
+    e.delete_word(); // "đi " gone, nothing tracked before it
+    assert_eq!(e.history_len(), 0);
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!(r.action, Action::None as u8);
 }
 
 /// ESC key clears history
@@ -2535,6 +2687,37 @@ fn restore_word_empty() {
     assert_eq!(result, "á", "Empty restore should allow fresh typing");
 }
 
+/// restore_word keeps a prefix that isn't a word and rebuilds the word after it
+#[test]
+fn restore_word_keeps_symbol_prefix() {
+    let mut e = Engine::new();
+    e.set_esc_restore(true);
+    assert_eq!(
+        restore_and_type(&mut e, "user@dômain", "\x1b"),
+        "user@doomain"
+    );
+    assert_eq!(
+        restore_and_type(&mut e, "#hashtagViệt", "\x1b"),
+        "#hashtagViet"
+    );
+    assert_eq!(restore_and_type(&mut e, "#việt", "s"), "#viết");
+
+    // The symbols right before the word are the shortcut prefix, as if typed
+    e.restore_word("user@dômain");
+    assert_eq!(e.status().shortcut_prefix, "@");
+    assert_eq!(e.status().current_word, "dômain");
+}
+
+/// Auto-restore rewrites only the word after '@' or '#'
+#[test]
+fn auto_restore_keeps_symbol_prefix() {
+    common::telex_auto_restore(&[
+        ("user@domain ", "user@domain "),
+        ("#hashtagVietnamese ", "#hashtagVietnamese "),
+        ("#text ", "#text "),
+    ]);
+}
+
 /// restore_word with plain ASCII word then add mark
 #[test]
 fn restore_word_then_add_mark() {
@@ -2695,6 +2878,188 @@ fn restore_word_change_mark_then_extend() {
     assert_eq!(result, "cháo", "Should change mark and extend word");
 }
 
+/// restore_word rebuilds the keystrokes of the word, so ESC restores them
+/// like for a typed word, also after the word went through history
+#[test]
+fn restore_word_rebuilds_raw_keystrokes() {
+    let esc = |e: &mut Engine| {
+        let r = e.on_key(keys::ESC, false, false);
+        (0..r.count as usize)
+            .filter_map(|i| char::from_u32(r.chars[i]))
+            .collect::<String>()
+    };
+    let mut e = Engine::new();
+    e.set_esc_restore(true);
+    e.restore_word("việt");
+    assert_eq!(esc(&mut e), "vieetj");
+
+    e.set_method(1);
+    e.restore_word("Việt");
+    assert_eq!(esc(&mut e), "Vie6t5");
+
+    e.set_method(0);
+    e.restore_word("đường");
+    e.on_key(keys::SPACE, false, false);
+    e.on_key(keys::DELETE, false, false);
+    assert_eq!(e.get_buffer_string(), "đường");
+    assert_eq!(esc(&mut e), "dduwowngf");
+}
+
+// ============================================================
+// PUSH_COMPOSED: Pre-composed characters inserted by the host
+// ============================================================
+
+/// Helper: type `before`, let the host insert `composed`, then type `after`
+fn compose_and_type(e: &mut Engine, before: &str, composed: &str, after: &str) -> String {
+    use gonhanh_core::utils::char_to_key;
+
+    let mut screen = type_word(e, before);
+    for c in composed.chars() {
+        e.push_composed(c);
+        screen.push(c);
+    }
+    for c in after.chars() {
+        let r = e.on_key(char_to_key(c), c.is_uppercase(), false);
+        if r.action == Action::Send as u8 {
+            for _ in 0..r.backspace {
+                screen.pop();
+            }
+            for i in 0..r.count as usize {
+                if let Some(ch) = char::from_u32(r.chars[i]) {
+                    screen.push(ch);
+                }
+            }
+        } else {
+            screen.push(c);
+        }
+    }
+    screen
+}
+
+#[test]
+fn push_composed_continues_word() {
+    let mut e = Engine::new();
+    assert_eq!(compose_and_type(&mut e, "ti", "ế", "ng"), "tiếng");
+    e.clear();
+    assert_eq!(compose_and_type(&mut e, "", "Đ", "i"), "Đi");
+    e.clear();
+    assert_eq!(compose_and_type(&mut e, "", "ă", "n"), "ăn");
+}
+
+#[test]
+fn push_composed_takes_tone_keys() {
+    let mut e = Engine::new();
+    assert_eq!(compose_and_type(&mut e, "", "ê", "s"), "ế");
+    e.clear();
+    assert_eq!(compose_and_type(&mut e, "", "ơ", "f"), "ờ");
+    e.clear();
+    // Same key as the composed tone reverts it
+    assert_eq!(compose_and_type(&mut e, "", "é", "s"), "es");
+    e.clear();
+    // Same key as the composed vowel modifier reverts it
+    assert_eq!(compose_and_type(&mut e, "", "e", "ee"), "ee");
+
+    let mut e = Engine::new();
+    e.set_method(1);
+    assert_eq!(compose_and_type(&mut e, "", "ê", "1"), "ế");
+    e.clear();
+    assert_eq!(compose_and_type(&mut e, "ti", "ế", "ng"), "tiếng");
+}
+
+#[test]
+fn push_composed_records_method_keystrokes() {
+    for (method, composed, raw) in [
+        (0, "ế", "ees"),
+        (0, "Đ", "DD"),
+        (0, "ượ", "uwowj"),
+        (0, "Ê", "EE"),
+        (1, "ế", "e61"),
+        (1, "đ", "d9"),
+        (1, "ằ", "a82"),
+        (1, "ư", "u7"),
+    ] {
+        let mut e = Engine::new();
+        e.set_method(method);
+        for c in composed.chars() {
+            e.push_composed(c);
+        }
+        assert_eq!(e.get_buffer_string(), composed);
+        assert_eq!(e.status().raw_word, raw, "method {method}: {composed}");
+    }
+}
+
+#[test]
+fn push_composed_foreign_and_symbols() {
+    let mut e = Engine::new();
+    // Letters outside Vietnamese start a literal word
+    assert_eq!(compose_and_type(&mut e, "", "ü", "ss"), "üss");
+    e.clear();
+    // Symbols end the word: the next keys start fresh
+    assert_eq!(compose_and_type(&mut e, "ab", "→", "as"), "ab→á");
+
+    // Disabled engine ignores it
+    let mut e = Engine::new();
+    e.set_enabled(false);
+    e.push_composed('ế');
+    assert_eq!(e.get_buffer_string(), "");
+}
+
+// ============================================================
+// KEY_TYPED: Characters produced by non-US layouts
+// ============================================================
+
+/// Helper: type (keycode, produced char) pairs, then ESC
+fn type_layout_then_esc(e: &mut Engine, typed: &[(u16, char)]) -> String {
+    for &(key, ch) in typed {
+        e.on_key_typed(key, ch.is_uppercase(), false, false, ch);
+    }
+    let r = e.on_key(keys::ESC, false, false);
+    (0..r.count as usize)
+        .filter_map(|i| char::from_u32(r.chars[i]))
+        .collect()
+}
+
+#[test]
+fn key_typed_char_is_restored() {
+    // AZERTY digit row: the 6 key types "-", the 5 key "("
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.set_esc_restore(true);
+    let typed = [
+        (keys::V, 'v'),
+        (keys::I, 'i'),
+        (keys::E, 'e'),
+        (keys::N6, '-'),
+        (keys::N5, '('),
+        (keys::T, 't'),
+    ];
+    assert_eq!(type_layout_then_esc(&mut e, &typed), "vie-(t");
+    assert_eq!(e.status().raw_word, "");
+}
+
+#[test]
+fn key_typed_transforms_like_keycode() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.on_key_typed(keys::A, false, false, false, 'a');
+    let r = e.on_key_typed(keys::N1, false, false, false, '&');
+    assert_eq!(r.action, Action::Send as u8);
+    assert_eq!(e.get_buffer_string(), "á");
+    assert_eq!(e.status().raw_word, "a&");
+}
+
+#[test]
+fn key_typed_letters_follow_auto_capitalize() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    e.set_esc_restore(true);
+    type_word(&mut e, "ok. ");
+    assert_eq!(
+        type_layout_then_esc(&mut e, &[(keys::A, 'a'), (keys::S, 's')]),
+        "As"
+    );
+}
+
 // ============================================================
 // OIW VS OWI BUG FIX TEST
 // ============================================================
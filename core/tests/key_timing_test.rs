@@ -0,0 +1,172 @@
+//! Timestamped keys (`Engine::on_key_at`): auto-repeat, double space and
+//! double Shift
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::status::InputMode;
+use gonhanh_core::engine::timing::KeyTiming;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::keys_from_str;
+use gonhanh_core::vectors::{replay, KeyEvent};
+
+/// Key events for `text`, `gap` ms apart
+fn timed(text: &str, start: u64, gap: u64) -> Vec<KeyEvent> {
+    keys_from_str(text)
+        .into_iter()
+        .enumerate()
+        .map(|(i, code)| KeyEvent {
+            code,
+            time: Some(start + i as u64 * gap),
+            ..KeyEvent::default()
+        })
+        .collect()
+}
+
+fn key(code: u16, time: u64) -> KeyEvent {
+    KeyEvent {
+        code,
+        time: Some(time),
+        ..KeyEvent::default()
+    }
+}
+
+fn with_timing(f: impl FnOnce(&mut KeyTiming)) -> Engine {
+    let mut e = Engine::new();
+    let mut timing = e.key_timing();
+    f(&mut timing);
+    e.set_key_timing(timing);
+    e
+}
+
+#[test]
+fn held_key_types_itself() {
+    // Held "a": first repeat after the OS delay, then every 30 ms
+    let mut events = timed("c", 0, 0);
+    events.extend([key(keys::A, 100), key(keys::A, 600)]);
+    events.extend((1..=3).map(|i| key(keys::A, 600 + i * 30)));
+    let mut e = Engine::new();
+    assert_eq!(replay(&mut e, &events), "caaaaa");
+    // The rest of the word stays literal
+    assert_eq!(replay(&mut e, &timed("s ", 800, 100)), "s ");
+}
+
+#[test]
+fn held_key_without_transform() {
+    let mut events = timed("b", 0, 0);
+    events.extend((0..3).map(|i| key(keys::O, 100 + i * 30)));
+    assert_eq!(replay(&mut Engine::new(), &events), "booo");
+}
+
+#[test]
+fn deliberate_double_press_still_transforms() {
+    let mut e = Engine::new();
+    assert_eq!(replay(&mut e, &timed("vieetj nam", 0, 90)), "việt nam");
+}
+
+#[test]
+fn double_space_types_period() {
+    let mut e = with_timing(|t| t.double_space_period = true);
+    let mut events = timed("ok", 0, 100);
+    events.extend([key(keys::SPACE, 300), key(keys::SPACE, 450)]);
+    assert_eq!(replay(&mut e, &events), "ok. ");
+
+    // Too slow, or not right after a word
+    let mut e = with_timing(|t| t.double_space_period = true);
+    let mut events = timed("ok", 0, 100);
+    events.extend([key(keys::SPACE, 300), key(keys::SPACE, 1000)]);
+    assert_eq!(replay(&mut e, &events), "ok  ");
+    let mut e = with_timing(|t| t.double_space_period = true);
+    assert_eq!(replay(&mut e, &timed("ok.  ", 0, 100)), "ok.  ");
+
+    // Off by default
+    assert_eq!(replay(&mut Engine::new(), &timed("ok  ", 0, 100)), "ok  ");
+}
+
+#[test]
+fn double_space_period_starts_sentence() {
+    let mut e = with_timing(|t| t.double_space_period = true);
+    e.set_auto_capitalize(true);
+    let mut events = timed("ok", 0, 100);
+    events.extend([key(keys::SPACE, 300), key(keys::SPACE, 400)]);
+    events.extend(timed("ban", 600, 100));
+    assert_eq!(replay(&mut e, &events), "ok. Ban");
+}
+
+#[test]
+fn double_space_period_backspace_restores_word() {
+    let mut e = with_timing(|t| t.double_space_period = true);
+    let mut events = timed("chaof", 0, 100);
+    events.extend([key(keys::SPACE, 600), key(keys::SPACE, 700)]);
+    events.extend([key(keys::DELETE, 900), key(keys::DELETE, 1000)]);
+    // "chào" is back in the buffer: s changes its mark
+    events.push(key(keys::S, 1200));
+    assert_eq!(replay(&mut e, &events), "cháo");
+}
+
+#[test]
+fn double_shift_toggles_input() {
+    let mut e = with_timing(|t| t.double_shift_toggle = true);
+    let events = [key(keys::LSHIFT, 0), key(keys::RSHIFT, 200)];
+    replay(&mut e, &events);
+    assert_eq!(e.mode(), InputMode::English);
+    assert_eq!(replay(&mut e, &timed("vieetj", 1000, 100)), "vieetj");
+
+    // Too slow, or another key in between
+    let mut e = with_timing(|t| t.double_shift_toggle = true);
+    replay(&mut e, &[key(keys::LSHIFT, 0), key(keys::LSHIFT, 800)]);
+    assert_ne!(e.mode(), InputMode::English);
+    replay(
+        &mut e,
+        &[
+            key(keys::LSHIFT, 2000),
+            key(keys::A, 2050),
+            key(keys::LSHIFT, 2100),
+        ],
+    );
+    assert_ne!(e.mode(), InputMode::English);
+
+    // Off by default
+    let mut e = Engine::new();
+    replay(&mut e, &[key(keys::LSHIFT, 0), key(keys::LSHIFT, 100)]);
+    assert_ne!(e.mode(), InputMode::English);
+}
+
+#[test]
+fn idle_word_is_finished() {
+    // "viêt" left alone, then a stray j: a new word, not "việt"
+    let mut e = with_timing(|t| t.idle_commit_ms = 5000);
+    let mut events = timed("vieet", 0, 100);
+    events.push(key(keys::J, 60_000));
+    assert_eq!(replay(&mut e, &events), "viêtj");
+
+    // Within the window the word is still being typed
+    let mut e = with_timing(|t| t.idle_commit_ms = 5000);
+    let mut events = timed("vieet", 0, 100);
+    events.push(key(keys::J, 3000));
+    assert_eq!(replay(&mut e, &events), "việt");
+
+    // Off by default
+    let mut events = timed("vieet", 0, 100);
+    events.push(key(keys::J, 60_000));
+    assert_eq!(replay(&mut Engine::new(), &events), "việt");
+}
+
+#[test]
+fn idle_commit_restores_english() {
+    let mut e = with_timing(|t| t.idle_commit_ms = 5000);
+    e.set_english_auto_restore(true);
+    let mut events = timed("text", 0, 100);
+    events.push(key(keys::S, 10_000));
+    assert_eq!(replay(&mut e, &events), "texts");
+
+    let mut e = with_timing(|t| t.idle_commit_ms = 5000);
+    e.set_english_auto_restore(true);
+    let mut events = timed("text", 0, 100);
+    events.extend([key(keys::DELETE, 10_000), key(keys::SPACE, 10_100)]);
+    assert_eq!(replay(&mut e, &events), "tex ");
+
+    let mut e = with_timing(|t| t.idle_commit_ms = 5000);
+    e.set_english_auto_restore(true);
+    let mut events = timed("text", 0, 100);
+    events.push(key(keys::SPACE, 10_000));
+    assert_eq!(replay(&mut e, &events), "text ");
+}
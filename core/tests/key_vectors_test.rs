@@ -0,0 +1,22 @@
+//! Cross-platform key event vectors (tests/data/key_vectors.json)
+//!
+//! The same file is meant to be replayed by the platform hosts; here every
+//! vector runs through the core engine.
+
+use gonhanh_core::vectors::parse_vectors;
+use std::fs;
+
+#[test]
+fn key_vectors() {
+    let text = fs::read_to_string("tests/data/key_vectors.json").expect("Failed to read vectors");
+    let vectors = parse_vectors(&text).expect("Invalid vectors file");
+    assert!(!vectors.is_empty());
+    let failures: Vec<String> = vectors
+        .iter()
+        .filter_map(|v| {
+            let got = v.run();
+            (got != v.expect).then(|| format!("{}: expected {:?}, got {:?}", v.name, v.expect, got))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,66 @@
+//! Mid-word editing: Left/Right move the caret inside the word being typed
+//! and keys typed there edit the word in place
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::{char_to_key, VirtualScreen};
+
+/// App text with a caret; `<` is Backspace, `[` and `]` are Left and Right
+fn type_keys(e: &mut Engine, input: &str) -> String {
+    let mut screen = VirtualScreen::new();
+    for c in input.chars() {
+        let key = match c {
+            '[' => keys::LEFT,
+            ']' => keys::RIGHT,
+            _ => char_to_key(c),
+        };
+        screen.key(e, key, c.is_uppercase(), false);
+    }
+    screen.text()
+}
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_mid_word_editing(true);
+    e
+}
+
+#[test]
+fn insert_missing_letter() {
+    assert_eq!(type_keys(&mut engine(), "vit[e"), "viet");
+    assert_eq!(type_keys(&mut engine(), "vit[ee"), "viêt");
+    assert_eq!(type_keys(&mut engine(), "vit[ee]j"), "việt");
+    assert_eq!(type_keys(&mut engine(), "cao[[h"), "chao");
+    assert_eq!(type_keys(&mut engine(), "tien[[[[[t"), "ttien");
+}
+
+#[test]
+fn mark_typed_mid_word() {
+    assert_eq!(type_keys(&mut engine(), "vieet[j"), "việt");
+    assert_eq!(type_keys(&mut engine(), "nuoc[w"), "nươc");
+    // Marks typed before the caret stay before it
+    assert_eq!(type_keys(&mut engine(), "ban[s"), "bán");
+    assert_eq!(type_keys(&mut engine(), "hoa[s"), "hóa");
+}
+
+#[test]
+fn backspace_mid_word() {
+    assert_eq!(type_keys(&mut engine(), "vieet[<ee]s"), "viết");
+    assert_eq!(type_keys(&mut engine(), "vieet[<<ie]s"), "viét");
+    assert_eq!(type_keys(&mut engine(), "vieetj[<n"), "vint");
+}
+
+#[test]
+fn arrows_end_word_when_off() {
+    let mut e = Engine::new();
+    assert_eq!(type_keys(&mut e, "vit[ee"), "viêt");
+    assert_eq!(type_keys(&mut Engine::new(), "vieet[j]"), "viêjt");
+}
+
+#[test]
+fn leaving_the_word() {
+    // Past the start the word ends and the next key starts a new one
+    assert_eq!(type_keys(&mut engine(), "ab[[[dd"), "đab");
+    // Right at the end behaves as before
+    assert_eq!(type_keys(&mut engine(), "vieet[]j"), "việt");
+}
@@ -0,0 +1,128 @@
+//! Mixed Vietnamese/English sentence scenarios
+//!
+//! Per-word tests reset the engine between words. These type whole sentences
+//! through one engine, so cross-word state (history, prev_was_number,
+//! pending capitalize, restore on punctuation) is exercised too.
+
+mod common;
+use common::{telex_auto_capitalize, telex_auto_restore, type_word};
+use gonhanh_core::api::{Engine, InputMode};
+
+#[test]
+fn dev_chat() {
+    telex_auto_restore(&[
+        (
+            "deploy cais app nayf leen staging nhes",
+            "deploy cái app này lên staging nhé",
+        ),
+        (
+            "anh check laij pull request giups em, CI ddang fail",
+            "anh check lại pull request giúp em, CI đang fail",
+        ),
+        (
+            "merge xong thif rebase branch feature roofi push leen nhes.",
+            "merge xong thì rebase branch feature rồi push lên nhé.",
+        ),
+        (
+            "bug nayf chir xayr ra treen production, local khoong bij.",
+            "bug này chỉ xảy ra trên production, local không bị.",
+        ),
+    ]);
+}
+
+#[test]
+fn numbers_between_words() {
+    telex_auto_restore(&[
+        (
+            "hoj 3 nguwowif ddi 2 xe, version 2 ddax release",
+            "họ 3 người đi 2 xe, version 2 đã release",
+        ),
+        (
+            "server 10 core, RAM 64 GB nhuwng vaanx chaamj",
+            "server 10 core, RAM 64 GB nhưng vẫn chậm",
+        ),
+    ]);
+}
+
+#[test]
+fn english_after_vietnamese_punctuation() {
+    telex_auto_restore(&[
+        (
+            "tooi thichs cafe. Issue nayf staff xuwr lys roofi.",
+            "tôi thích cafe. Issue này staff xử lý rồi.",
+        ),
+        (
+            "meeting luc 3 giowf, nhows mang laptop: slide, demo, report.",
+            "meeting luc 3 giờ, nhớ mang laptop: slide, demo, report.",
+        ),
+        (
+            "(ddaay laf tesst) vaf \"class\" laf keyword",
+            "(đây là test) và \"class\" là keyword",
+        ),
+    ]);
+}
+
+#[test]
+fn backspace_across_mixed_words() {
+    // Deleting back into the previous word restores it for editing
+    telex_auto_restore(&[
+        ("push leen< nhes", "push lê nhé"),
+        ("ddax merge <<<<<<deploy", "đã deploy"),
+    ]);
+}
+
+#[test]
+fn capitalize_after_sentence_end() {
+    telex_auto_capitalize(&[(
+        "xong roofi. deploy leen staging nhes",
+        "xong rồi. Deploy lên staging nhé",
+    )]);
+}
+
+#[test]
+fn accented_foreign_words_stay_literal() {
+    // Letters from another layout (dead keys, AltGr) end Vietnamese
+    // composition for the rest of the word; the next word composes again
+    telex_auto_restore(&[
+        ("Müller ddax ddeens", "Müller đã đến"),
+        ("crème brûlée ngon quas", "crème brûlée ngon quá"),
+        ("naïve tooi uoongs", "naïve tôi uống"),
+        // Mark key after the foreign letter must not edit the word
+        ("aüs", "aüs"),
+        // Composed text before the foreign letter stays as shown
+        ("tesüs", "téüs"),
+        ("Ñandus", "Ñandus"),
+    ]);
+}
+
+#[test]
+fn accented_foreign_word_status() {
+    let mut e = Engine::new();
+    type_word(&mut e, "Mü");
+    let status = e.status();
+    assert_eq!(status.current_word, "Mü");
+    assert_eq!(status.mode, InputMode::Foreign);
+
+    type_word(&mut e, "ll<< ");
+    assert_eq!(e.status().current_word, "");
+    assert_eq!(e.mode(), InputMode::Vietnamese);
+}
+
+#[test]
+fn preceding_words_decide_ambiguous_word() {
+    telex_auto_restore(&[
+        // Valid in both readings: English after English words
+        ("tell her ", "tell her "),
+        ("pay your tax ", "pay your tax "),
+        ("check your cars ", "check your cars "),
+        // ... and Vietnamese after Vietnamese or on its own
+        ("her ", "hẻ "),
+        ("em yeeu her ", "em yêu hẻ "),
+        ("anh ddi cars ", "anh đi cá "),
+        // One English word isn't enough for a weak English reading
+        ("pay tax ", "pay tã "),
+        // English context never restores a word with no Vietnamese reading
+        ("love you lawwlessness ", "love you lawlessness "),
+        ("hello lawwlessness ", "hello lawlessness "),
+    ]);
+}
@@ -0,0 +1,40 @@
+//! No-output guarantee: library code never prints
+//!
+//! An IME sees every key the user types, passwords included. Nothing outside
+//! test modules may write to stdout/stderr, so a release build can't leak
+//! typed text into logs.
+
+use std::fs;
+use std::path::Path;
+
+const MACROS: [&str; 5] = ["println!", "eprintln!", "print!", "eprint!", "dbg!"];
+
+fn rust_files(dir: &Path, out: &mut Vec<std::path::PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            rust_files(&path, out);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+#[test]
+fn library_code_never_prints() {
+    let mut files = Vec::new();
+    rust_files(Path::new("src"), &mut files);
+    let mut found = Vec::new();
+    for file in files {
+        let text = fs::read_to_string(&file).unwrap();
+        // Everything from the unit test module on is test-only
+        let library = text.split("#[cfg(test)]\nmod tests").next().unwrap();
+        for (n, line) in library.lines().enumerate() {
+            let code = line.split("//").next().unwrap();
+            if MACROS.iter().any(|m| code.contains(m)) {
+                found.push(format!("{}:{}: {}", file.display(), n + 1, line.trim()));
+            }
+        }
+    }
+    assert!(found.is_empty(), "{}", found.join("\n"));
+}
@@ -1,5 +1,7 @@
 mod common;
-use common::{telex_auto_restore, vni};
+use common::{telex_auto_restore, type_word, vni};
+use gonhanh_core::engine::Engine;
+use gonhanh_core::text::convert::compose;
 
 #[test]
 fn paragraph_telex() {
@@ -57,3 +59,44 @@ fn paragraph_smart_auto_restore() {
 
     telex_auto_restore(&[(input, expected)]);
 }
+
+// ============================================================
+// PER-KEY vs BATCH INVARIANCE
+// ============================================================
+
+/// Typing a paragraph key by key must give the same text as the batch API
+fn assert_per_key_matches_batch(corpus: &str, method: u8) {
+    for modern in [true, false] {
+        for auto_restore in [false, true] {
+            let configure = |e: &mut Engine| {
+                e.set_method(method);
+                e.set_modern_tone(modern);
+                e.set_english_auto_restore(auto_restore);
+            };
+            for paragraph in corpus.lines().filter(|l| !l.is_empty()) {
+                let mut per_key = Engine::new();
+                configure(&mut per_key);
+                let typed = type_word(&mut per_key, &format!("{paragraph} "));
+
+                let mut batch = Engine::new();
+                configure(&mut batch);
+                let composed = format!("{} ", compose(&mut batch, paragraph));
+
+                assert_eq!(
+                    typed, composed,
+                    "method={method} modern={modern} auto_restore={auto_restore}\n{paragraph}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn paragraph_per_key_matches_batch_telex() {
+    assert_per_key_matches_batch(include_str!("data/paragraphs_telex.txt"), 0);
+}
+
+#[test]
+fn paragraph_per_key_matches_batch_vni() {
+    assert_per_key_matches_batch(include_str!("data/paragraphs_vni.txt"), 1);
+}
@@ -0,0 +1,138 @@
+//! Named profiles: settings, shortcuts and user dictionary switched together
+
+mod common;
+use common::type_word;
+use gonhanh_core::api::{Config, Engine, Profile, Shortcut, ShortcutTable, DEFAULT_PROFILE};
+
+#[test]
+fn new_engine_is_in_default_profile() {
+    let e = Engine::new();
+    assert_eq!(e.profile(), DEFAULT_PROFILE);
+    assert_eq!(e.profile_names(), [DEFAULT_PROFILE]);
+    assert_eq!(e.config(), Config::default());
+}
+
+#[test]
+fn profiles_keep_separate_shortcuts() {
+    let mut e = Engine::new();
+    e.shortcuts_mut()
+        .add(Shortcut::new("sig", "Best regards, An"));
+
+    assert!(!e.set_profile("work"), "work is created on first use");
+    assert_eq!(type_word(&mut e, "sig "), "sig ");
+    e.shortcuts_mut()
+        .add(Shortcut::new("sig", "Nguyễn An, ACME Corp"));
+    assert_eq!(type_word(&mut e, "sig "), "Nguyễn An, ACME Corp ");
+
+    assert!(e.set_profile(DEFAULT_PROFILE));
+    assert_eq!(type_word(&mut e, "sig "), "Best regards, An ");
+    assert_eq!(e.profile_names(), [DEFAULT_PROFILE, "work"]);
+}
+
+#[test]
+fn profiles_keep_separate_settings() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+
+    e.set_profile("work");
+    assert_eq!(
+        e.config(),
+        Config::default(),
+        "new profile starts at defaults"
+    );
+    e.set_method(1);
+    assert_eq!(type_word(&mut e, "tie6ng1"), "tiếng");
+
+    e.set_profile(DEFAULT_PROFILE);
+    assert_eq!(e.config().method, 0);
+    assert!(e.config().english_auto_restore);
+    assert_eq!(type_word(&mut e, "tieengs"), "tiếng");
+
+    e.set_profile("work");
+    assert_eq!(e.config().method, 1);
+}
+
+#[test]
+fn profiles_keep_history_editing_and_timing() {
+    let mut e = Engine::new();
+    e.set_history_capacity(0);
+    e.set_mid_word_editing(true);
+    let mut timing = e.key_timing();
+    timing.double_space_period = true;
+    e.set_key_timing(timing);
+
+    e.set_profile("work");
+    assert_eq!(type_word(&mut e, "an <s"), "án");
+    assert_eq!(e.key_timing(), Config::default().key_timing);
+
+    e.set_profile(DEFAULT_PROFILE);
+    assert_eq!(type_word(&mut e, "an <s"), "ans");
+    assert!(e.config().mid_word_editing);
+    assert!(e.key_timing().double_space_period);
+}
+
+#[test]
+fn profiles_keep_separate_user_words() {
+    let mut e = Engine::new();
+    e.set_spell_check(true);
+    e.add_user_word("kubectl");
+    type_word(&mut e, "kubectl ");
+    assert!(e.take_spell_flag().is_none());
+
+    e.set_profile("personal");
+    e.set_spell_check(true);
+    type_word(&mut e, "kubectl ");
+    assert!(e.take_spell_flag().is_some());
+}
+
+#[test]
+fn switching_drops_word_in_progress() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj");
+    e.set_profile("work");
+    assert_eq!(e.get_buffer_string(), "");
+    // Switching to the active profile changes nothing
+    type_word(&mut e, "vieetj");
+    assert!(e.set_profile("work"));
+    assert_eq!(e.get_buffer_string(), "việt");
+}
+
+#[test]
+fn add_and_remove_profiles() {
+    let mut e = Engine::new();
+    let mut shortcuts = ShortcutTable::new();
+    shortcuts.add(Shortcut::new("hn", "Hà Nội"));
+    e.add_profile(
+        "travel",
+        Profile {
+            config: Config {
+                method: 1,
+                ..Config::default()
+            },
+            shortcuts,
+            user_words: Default::default(),
+        },
+    );
+    assert!(e.set_profile("travel"));
+    assert_eq!(e.config().method, 1);
+    assert_eq!(type_word(&mut e, "hn "), "Hà Nội ");
+
+    // The active profile can't be removed; inactive ones can
+    assert!(e.remove_profile("travel").is_none());
+    e.set_profile(DEFAULT_PROFILE);
+    assert!(e.remove_profile("travel").is_some());
+    assert_eq!(e.profile_names(), [DEFAULT_PROFILE]);
+
+    // Replacing the active profile applies it immediately
+    e.add_profile(
+        DEFAULT_PROFILE,
+        Profile {
+            config: Config {
+                method: 1,
+                ..Config::default()
+            },
+            ..Profile::default()
+        },
+    );
+    assert_eq!(e.config().method, 1);
+}
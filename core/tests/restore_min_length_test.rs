@@ -0,0 +1,65 @@
+//! Minimum word length for auto-restore, with exceptions
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine(min_length: u8, exceptions: &[&str]) -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_restore_min_length(min_length);
+    for word in exceptions {
+        e.add_restore_exception(word);
+    }
+    e
+}
+
+fn run(e: &mut Engine, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        assert_eq!(type_word(e, input), *expected, "{input:?}");
+        e.clear_all();
+    }
+}
+
+#[test]
+fn no_minimum_by_default() {
+    run(
+        &mut engine(0, &[]),
+        &[("per ", "per "), ("mister ", "mister ")],
+    );
+}
+
+#[test]
+fn short_words_stay_as_typed() {
+    run(
+        &mut engine(4, &[]),
+        &[
+            ("per ", "pẻ "),
+            ("per,", "pẻ,"),
+            ("boss ", "boss "),
+            ("mister ", "mister "),
+        ],
+    );
+}
+
+#[test]
+fn exceptions_keep_the_usual_decision() {
+    run(
+        &mut engine(4, &["PER"]),
+        &[("per ", "per "), ("Per ", "Per "), ("her ", "hẻ ")],
+    );
+    let mut e = engine(4, &["per"]);
+    e.clear_restore_exceptions();
+    run(&mut e, &[("per ", "pẻ ")]);
+}
+
+#[test]
+fn config_round_trip() {
+    let mut e = Engine::new();
+    assert_eq!(e.config().restore_min_length, 0);
+    let mut config = e.config();
+    config.english_auto_restore = true;
+    config.restore_min_length = 4;
+    e.set_config(&config);
+    assert_eq!(e.config().restore_min_length, 4);
+    assert_eq!(type_word(&mut e, "per "), "pẻ ");
+}
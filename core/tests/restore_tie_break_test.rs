@@ -0,0 +1,46 @@
+//! Tie-break for words valid in both readings ("her" → "hẻ")
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn engine() -> Engine {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    e.set_esc_restore(true);
+    e
+}
+
+#[test]
+fn valid_vietnamese_stays_without_history() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "her bars bans "), "hẻ bá bán ");
+}
+
+#[test]
+fn esc_restore_is_remembered() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "her\x1b her. her,"), "her her. her,");
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "tax\x1b tax "), "tax tax ");
+}
+
+#[test]
+fn low_confidence_stays_vietnamese() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "bans\x1b bans "), "bans bán ");
+}
+
+#[test]
+fn only_the_same_keystrokes_count() {
+    let mut e = engine();
+    assert_eq!(type_word(&mut e, "her\x1b hers "), "her hé ");
+}
+
+#[test]
+fn secure_mode_forgets_choices() {
+    let mut e = engine();
+    type_word(&mut e, "her\x1b ");
+    e.set_secure_mode(true);
+    e.set_secure_mode(false);
+    assert_eq!(type_word(&mut e, "her "), "hẻ ");
+}
@@ -0,0 +1,101 @@
+//! Packed shortcut libraries
+//!
+//! Snippet sets imported from text expanders run to tens of thousands of
+//! entries. They load as one packed blob: nothing is decoded until a trigger
+//! is typed, and shortcuts added one by one still take precedence.
+
+mod common;
+use common::type_word;
+use gonhanh_core::api::{
+    pack_shortcuts, Engine, PackError, PackedShortcuts, Shortcut, ShortcutTable,
+};
+
+const LIBRARY_SIZE: usize = 20_000;
+
+fn large_library() -> Vec<Shortcut> {
+    let mut shortcuts: Vec<Shortcut> = (0..LIBRARY_SIZE)
+        .map(|i| Shortcut::new(&format!("sn{i}"), &format!("Snippet số {i}")))
+        .collect();
+    shortcuts.push(Shortcut::new("vn", "Việt Nam"));
+    shortcuts.push(Shortcut::new("đc", "được"));
+    shortcuts.push(Shortcut::immediate("->", "→"));
+    shortcuts
+}
+
+fn engine_with_library() -> Engine {
+    let mut e = Engine::new();
+    let library = PackedShortcuts::from_vec(pack_shortcuts(&large_library())).unwrap();
+    e.shortcuts_mut().set_library(library);
+    e
+}
+
+#[test]
+fn library_expands_triggers() {
+    let mut e = engine_with_library();
+    assert_eq!(e.shortcuts().library().unwrap().len(), LIBRARY_SIZE + 3);
+    assert_eq!(e.shortcuts().len(), 0, "entries stay packed");
+
+    assert_eq!(type_word(&mut e, "vn "), "Việt Nam ");
+    assert_eq!(type_word(&mut e, "VN "), "VIỆT NAM ");
+    assert_eq!(type_word(&mut e, "ddc "), "được ");
+    assert_eq!(type_word(&mut e, "sn0 "), "Snippet số 0 ");
+    assert_eq!(type_word(&mut e, "sn19999 "), "Snippet số 19999 ");
+    assert_eq!(type_word(&mut e, "sn20000 "), "sn20000 ");
+    assert_eq!(type_word(&mut e, "a->"), "a→");
+}
+
+#[test]
+fn added_shortcuts_override_library() {
+    let mut e = engine_with_library();
+    e.shortcuts_mut().add(Shortcut::new("vn", "Vietnam"));
+    assert_eq!(type_word(&mut e, "vn "), "Vietnam ");
+
+    // Clearing added shortcuts keeps the library
+    e.shortcuts_mut().clear();
+    assert_eq!(type_word(&mut e, "vn "), "Việt Nam ");
+
+    assert!(e.shortcuts_mut().take_library().is_some());
+    assert_eq!(type_word(&mut e, "vn "), "vn ");
+}
+
+#[test]
+fn table_round_trips_through_packed_format() {
+    let mut table = ShortcutTable::new();
+    table.add(Shortcut::new("hn", "Hà Nội"));
+    table.add(Shortcut::telex("ko", "không"));
+    let mut disabled = Shortcut::new("tp", "thành phố");
+    disabled.enabled = false;
+    table.add(disabled);
+
+    let library = PackedShortcuts::from_vec(table.to_packed()).unwrap();
+    let mut restored = ShortcutTable::new();
+    restored.set_library(library);
+
+    let mut e = Engine::new();
+    *e.shortcuts_mut() = restored;
+    assert_eq!(type_word(&mut e, "hn "), "Hà Nội ");
+    assert_eq!(
+        type_word(&mut e, "tp "),
+        "tp ",
+        "disabled entries stay disabled"
+    );
+
+    let mut e = Engine::new();
+    e.set_method(1);
+    e.shortcuts_mut()
+        .set_library(PackedShortcuts::from_vec(table.to_packed()).unwrap());
+    assert_eq!(type_word(&mut e, "ko"), "ko", "Telex-only entry");
+}
+
+#[test]
+fn corrupt_library_is_rejected() {
+    let bytes = pack_shortcuts(&large_library());
+    assert_eq!(
+        PackedShortcuts::from_vec(bytes[..bytes.len() / 2].to_vec()).unwrap_err(),
+        PackError::Truncated
+    );
+    assert_eq!(
+        PackedShortcuts::from_vec(b"vn\tViet Nam\n".to_vec()).unwrap_err(),
+        PackError::BadMagic
+    );
+}
@@ -0,0 +1,230 @@
+//! Injection race simulation
+//!
+//! Platforms inject engine output as synthetic backspaces + text. Real target
+//! apps are not perfect: a backspace gets dropped under load, the text lands
+//! before the backspaces are processed, or the app's own autocomplete inserts
+//! characters into the word. Any of these leaves the screen out of sync with
+//! the engine buffer ("respect" → "respeect").
+//!
+//! `FaultyApp` models such a target. `Platform` plays the native layer: it
+//! injects every result, then verifies the word on screen against the engine
+//! buffer (the expected text) and retypes it when they differ. Every test
+//! asserts the final text equals a fault-free run.
+
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine, Result};
+use gonhanh_core::utils::char_to_key;
+
+/// Injection fault applied to one Send event
+#[derive(Clone, Copy, Debug)]
+enum Fault {
+    /// App loses one of the backspaces
+    DropBackspace,
+    /// App applies the text before the backspaces
+    Reorder,
+    /// App autocompletes the word with extra text
+    Autocomplete(&'static str),
+}
+
+/// Target app that applies a fault to the nth Send event it receives
+struct FaultyApp {
+    text: String,
+    faults: Vec<(usize, Fault)>,
+    events: usize,
+}
+
+impl FaultyApp {
+    fn new(faults: &[(usize, Fault)]) -> Self {
+        Self {
+            text: String::new(),
+            faults: faults.to_vec(),
+            events: 0,
+        }
+    }
+
+    fn backspace(&mut self, n: usize) {
+        for _ in 0..n {
+            self.text.pop();
+        }
+    }
+
+    fn inject(&mut self, backspace: usize, output: &str) {
+        let fault = self
+            .faults
+            .iter()
+            .find(|(at, _)| *at == self.events)
+            .map(|&(_, f)| f);
+        self.events += 1;
+        match fault {
+            None => {
+                self.backspace(backspace);
+                self.text.push_str(output);
+            }
+            Some(Fault::DropBackspace) => {
+                self.backspace(backspace.saturating_sub(1));
+                self.text.push_str(output);
+            }
+            Some(Fault::Reorder) => {
+                self.text.push_str(output);
+                self.backspace(backspace);
+            }
+            Some(Fault::Autocomplete(extra)) => {
+                self.backspace(backspace);
+                self.text.push_str(output);
+                self.text.push_str(extra);
+            }
+        }
+    }
+
+    fn type_char(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    /// Word under the cursor: trailing run of non-whitespace
+    fn current_word(&self) -> &str {
+        let start = self
+            .text
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        &self.text[start..]
+    }
+}
+
+/// Native layer: injects engine results and verifies the expected text
+struct Platform {
+    engine: Engine,
+    app: FaultyApp,
+    resyncs: usize,
+}
+
+impl Platform {
+    fn new(faults: &[(usize, Fault)]) -> Self {
+        Self {
+            engine: Engine::new(),
+            app: FaultyApp::new(faults),
+            resyncs: 0,
+        }
+    }
+
+    fn result_string(r: &Result) -> String {
+        (0..r.count as usize)
+            .filter_map(|i| char::from_u32(r.chars[i]))
+            .collect()
+    }
+
+    fn key(&mut self, c: char) {
+        let key = char_to_key(c);
+        let r = self.engine.on_key(key, c.is_uppercase(), false);
+        if r.action == Action::Send as u8 {
+            self.app
+                .inject(r.backspace as usize, &Self::result_string(&r));
+            if keys::is_break(key) && !r.key_consumed() {
+                self.app.type_char(c);
+            }
+        } else if key == keys::DELETE {
+            self.app.backspace(1);
+        } else {
+            self.app.type_char(c);
+        }
+        self.verify();
+    }
+
+    /// Compare the word on screen with the engine buffer; retype on mismatch
+    fn verify(&mut self) {
+        let expected = self.engine.get_buffer_string();
+        if expected.is_empty() {
+            return;
+        }
+        let actual = self.app.current_word().to_string();
+        if actual != expected {
+            self.resyncs += 1;
+            self.app.backspace(actual.chars().count());
+            self.app.text.push_str(&expected);
+        }
+    }
+
+    fn type_text(&mut self, input: &str) -> &str {
+        for c in input.chars() {
+            self.key(c);
+        }
+        &self.app.text
+    }
+}
+
+fn clean(input: &str) -> String {
+    let mut p = Platform::new(&[]);
+    let text = p.type_text(input).to_string();
+    assert_eq!(p.resyncs, 0, "fault-free run needed resync: {input:?}");
+    text
+}
+
+fn simulate(input: &str, faults: &[(usize, Fault)]) -> (String, usize) {
+    let mut p = Platform::new(faults);
+    let text = p.type_text(input).to_string();
+    (text, p.resyncs)
+}
+
+const SENTENCES: &[&str] = &[
+    "respeect",
+    "tooi ddang gox tieengs vieetj",
+    "nguwowif ta nois raats nhieeuf",
+    "khuyeens khichs chuyeenr ddooir",
+    "hoaf bifnh tren theer giowis",
+];
+
+#[test]
+fn fault_free_run_needs_no_resync() {
+    for s in SENTENCES {
+        clean(s);
+    }
+}
+
+#[test]
+fn dropped_backspace_recovers() {
+    // "respe" + 'e' → send(1, "ê"); losing the backspace shows "respeê"
+    let (text, resyncs) = simulate("respeect", &[(0, Fault::DropBackspace)]);
+    assert_eq!(text, clean("respeect"));
+    assert_eq!(resyncs, 1);
+
+    for s in SENTENCES {
+        for at in 0..4 {
+            let (text, _) = simulate(s, &[(at, Fault::DropBackspace)]);
+            assert_eq!(text, clean(s), "drop at event {at}: {s:?}");
+        }
+    }
+}
+
+#[test]
+fn out_of_order_events_recover() {
+    for s in SENTENCES {
+        for at in 0..4 {
+            let (text, _) = simulate(s, &[(at, Fault::Reorder)]);
+            assert_eq!(text, clean(s), "reorder at event {at}: {s:?}");
+        }
+    }
+}
+
+#[test]
+fn autocomplete_insertion_recovers() {
+    for s in SENTENCES {
+        for at in 0..4 {
+            let (text, _) = simulate(s, &[(at, Fault::Autocomplete("ing"))]);
+            assert_eq!(text, clean(s), "autocomplete at event {at}: {s:?}");
+        }
+    }
+}
+
+#[test]
+fn combined_faults_recover() {
+    let faults = [
+        (0, Fault::DropBackspace),
+        (1, Fault::Reorder),
+        (2, Fault::Autocomplete("s")),
+        (5, Fault::DropBackspace),
+    ];
+    for s in SENTENCES {
+        let (text, _) = simulate(s, &faults);
+        assert_eq!(text, clean(s), "combined faults: {s:?}");
+    }
+}
@@ -0,0 +1,144 @@
+//! Examples from docs/vietnamese-language-system.md, typed through the engine
+//!
+//! The spec is what users cite in issues, and it had drifted: it promised
+//! "mufaa → mùaa" while the engine types "muầ". Every typeable example in it
+//! is now a test case:
+//! - Table rows with an `Input` (or `Input (Telex)`) column and an `Output`
+//!   (or `Correct`) column
+//! - Inline `"input" → "output"` pairs
+//!
+//! Inputs that aren't plain keystrokes ("u+o+w", "tà" + "oo") are skipped.
+//! Examples under a heading that mentions VNI are typed with VNI, the rest
+//! with Telex.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+const SPEC: &str = include_str!("../../docs/vietnamese-language-system.md");
+
+/// One example: spec line, method (0=Telex, 1=VNI), input, expected output
+#[derive(Debug)]
+struct Example {
+    line: usize,
+    method: u8,
+    input: String,
+    expected: String,
+}
+
+fn typeable(input: &str) -> bool {
+    !input.is_empty() && input.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn cells(row: &str) -> Vec<String> {
+    let row = row.trim_start_matches('>').trim();
+    row.trim_matches('|')
+        .split('|')
+        .map(|cell| cell.trim().trim_matches('`').trim().to_string())
+        .collect()
+}
+
+/// Inline `"input" → "output"` pairs of one line
+fn inline_pairs(line: &str) -> Vec<(String, String)> {
+    let quoted: Vec<&str> = line.split('"').collect();
+    // Odd indices are inside quotes; a pair is `"a"` ` → ` `"b"`, and
+    // `"x" + "a" → "b"` is not one
+    (1..quoted.len().saturating_sub(2))
+        .step_by(2)
+        .filter(|&i| quoted[i + 1].trim() == "→" && !quoted[i - 1].trim_end().ends_with('+'))
+        .map(|i| (quoted[i].to_string(), quoted[i + 2].to_string()))
+        .collect()
+}
+
+fn extract(spec: &str) -> Vec<Example> {
+    let mut examples = Vec::new();
+    let mut method = 0;
+    // Input and output columns of the table being read
+    let mut columns: Option<(usize, usize)> = None;
+    for (i, line) in spec.lines().enumerate() {
+        let text = line.trim_start_matches('>').trim();
+        if text.starts_with('#') {
+            method = u8::from(text.contains("VNI"));
+        }
+        if !text.starts_with('|') {
+            columns = None;
+        } else if columns.is_none() {
+            let header = cells(text);
+            let input = header
+                .iter()
+                .position(|h| h == "Input" || h == "Input (Telex)");
+            let output = header.iter().position(|h| h == "Output" || h == "Correct");
+            columns = input.zip(output);
+            continue;
+        } else if let Some((input, output)) = columns {
+            let row = cells(text);
+            if let (Some(input), Some(output)) = (row.get(input), row.get(output)) {
+                if typeable(input) {
+                    examples.push(Example {
+                        line: i + 1,
+                        method,
+                        input: input.clone(),
+                        expected: output.clone(),
+                    });
+                }
+            }
+            continue;
+        }
+        for (input, expected) in inline_pairs(text) {
+            if typeable(&input) {
+                examples.push(Example {
+                    line: i + 1,
+                    method,
+                    input,
+                    expected,
+                });
+            }
+        }
+    }
+    examples
+}
+
+#[test]
+fn spec_examples_match_engine() {
+    let examples = extract(SPEC);
+    let failures: Vec<String> = examples
+        .iter()
+        .filter_map(|ex| {
+            let mut e = Engine::new();
+            e.set_method(ex.method);
+            let actual = type_word(&mut e, &ex.input);
+            (actual != ex.expected).then(|| {
+                format!(
+                    "line {}: {:?} → {:?}, engine gives {:?}",
+                    ex.line, ex.input, ex.expected, actual
+                )
+            })
+        })
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn extractor_finds_the_examples() {
+    let examples = extract(SPEC);
+    let find = |input: &str| examples.iter().find(|ex| ex.input == input);
+    // Table rows, Telex and VNI
+    assert_eq!(find("tafoo").map(|ex| ex.expected.as_str()), Some("tàoo"));
+    assert_eq!(find("Vieetj").map(|ex| ex.method), Some(0));
+    assert_eq!(find("Vie65t").map(|ex| ex.method), Some(1));
+    // Inline pair
+    assert_eq!(find("ddense").map(|ex| ex.expected.as_str()), Some("đến"));
+    assert_eq!(find("mufaa").map(|ex| ex.expected.as_str()), Some("muầ"));
+    // Not keystrokes
+    assert!(find("u+o+w").is_none());
+    assert!(examples.len() > 50, "only {} examples", examples.len());
+}
+
+#[test]
+fn extractor_rules() {
+    assert_eq!(
+        inline_pairs(r#"- "ddense" → "đến" (e + ê)"#),
+        [("ddense".to_string(), "đến".to_string())]
+    );
+    assert!(inline_pairs(r#"Ví dụ: "tà" + "oo" → "tàoo""#).is_empty());
+    assert_eq!(cells("> | `as` | á |"), ["as", "á"]);
+}
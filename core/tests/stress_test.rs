@@ -0,0 +1,101 @@
+//! Seeded stress typing
+//!
+//! Reproducible pseudo-random input (`InputGenerator`: syllables, English
+//! words, digits, punctuation, backspaces) typed through Telex and VNI
+//! engines onto a `VirtualScreen`. A failure names its seed; replay it alone
+//! with `STRESS_SEED=<seed> cargo test --test stress_test`. `STRESS_SEEDS`
+//! sets how many seeds run (default 200).
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::{InputGenerator, VirtualScreen};
+use std::panic;
+
+const TOKENS: usize = 80;
+
+fn seeds() -> Vec<u64> {
+    if let Some(seed) = std::env::var("STRESS_SEED")
+        .ok()
+        .and_then(|s| s.parse().ok())
+    {
+        return vec![seed];
+    }
+    let count = std::env::var("STRESS_SEEDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(200);
+    (0..count).collect()
+}
+
+/// Input for `seed` and the screen it leaves
+fn run(seed: u64, vni: bool) -> (String, VirtualScreen) {
+    let input = InputGenerator::new(seed, vni).input(TOKENS);
+    let mut e = Engine::new();
+    e.set_method(vni as u8);
+    // Vary settings with the seed too
+    e.set_english_auto_restore(seed.is_multiple_of(2));
+    e.set_auto_capitalize(seed.is_multiple_of(3));
+    e.set_modern_tone(!seed.is_multiple_of(5));
+    let mut screen = VirtualScreen::new();
+    screen.type_str(&mut e, &input);
+    (input, screen)
+}
+
+fn method(vni: bool) -> &'static str {
+    if vni {
+        "VNI"
+    } else {
+        "Telex"
+    }
+}
+
+#[test]
+fn random_typing_stays_within_the_text() {
+    for seed in seeds() {
+        for vni in [false, true] {
+            let outcome = panic::catch_unwind(|| run(seed, vni));
+            let Ok((input, screen)) = outcome else {
+                panic!(
+                    "engine panicked: seed {seed} ({}), STRESS_SEED={seed}",
+                    method(vni)
+                );
+            };
+            assert_eq!(
+                screen.overrun(),
+                0,
+                "backspaces past the typed text: seed {seed} ({}), STRESS_SEED={seed}\ninput: {input:?}\nscreen: {:?}",
+                method(vni),
+                screen.text()
+            );
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_screen() {
+    for seed in seeds().into_iter().take(20) {
+        for vni in [false, true] {
+            let (input, first) = run(seed, vni);
+            let (again, second) = run(seed, vni);
+            assert_eq!(input, again, "seed {seed}: generator not reproducible");
+            assert_eq!(
+                first.text(),
+                second.text(),
+                "seed {seed} ({}): engine not deterministic",
+                method(vni)
+            );
+        }
+    }
+}
+
+#[test]
+fn generator_mixes_inputs() {
+    let input = InputGenerator::new(7, false).input(400);
+    assert!(input.contains('<'), "backspaces");
+    assert!(input.chars().any(|c| c.is_ascii_digit()), "digits");
+    assert!(
+        input.chars().any(|c| c.is_ascii_punctuation() && c != '<'),
+        "punctuation"
+    );
+    assert!(input.chars().any(|c| c.is_ascii_uppercase()), "capitals");
+    assert_ne!(input, InputGenerator::new(8, false).input(400));
+}
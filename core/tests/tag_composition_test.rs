@@ -0,0 +1,72 @@
+//! Words after '#' or '@': composed for hashtags ("#chào") by
+//! default, or typed as is when tag composition is off
+
+use gonhanh_core::api::Engine;
+use gonhanh_core::utils::type_word;
+
+fn run(method: u8, compose: bool, cases: &[(&str, &str)]) {
+    for auto_restore in [false, true] {
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            e.set_method(method);
+            e.set_tag_composition(compose);
+            e.set_english_auto_restore(auto_restore);
+            assert_eq!(
+                type_word(&mut e, input),
+                *expected,
+                "compose={compose} auto_restore={auto_restore} {input:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn composes_tags_by_default() {
+    run(
+        0,
+        true,
+        &[
+            ("#tieengs ", "#tiếng "),
+            ("@tieengs ", "@tiếng "),
+            ("#chaof ", "#chào "),
+            ("vieetj#vieetj ", "việt#việt "),
+        ],
+    );
+    run(1, true, &[("#tie61ng ", "#tiếng ")]);
+}
+
+#[test]
+fn types_tags_as_is_when_off() {
+    run(
+        0,
+        false,
+        &[
+            ("#tieengs ", "#tieengs "),
+            ("@tieengs ", "@tieengs "),
+            ("#text ", "#text "),
+            ("#vieetj<<j ", "#vieej "),
+            // Only the word after the prefix; the word before it composes
+            ("vieetj#vieetj ", "việt#vieetj "),
+            ("tieengs #vieetj ", "tiếng #vieetj "),
+        ],
+    );
+    run(1, false, &[("#tie61ng ", "#tie61ng ")]);
+}
+
+#[test]
+fn config_round_trip() {
+    let mut e = Engine::new();
+    assert!(e.config().tag_composition);
+    let mut config = e.config();
+    config.tag_composition = false;
+    e.set_config(&config);
+    assert!(!e.config().tag_composition);
+    assert_eq!(type_word(&mut e, "#vieetj "), "#vieetj ");
+
+    // Per app profile: a social app composes tags, the default profile doesn't
+    e.set_profile("social");
+    e.set_tag_composition(true);
+    assert_eq!(type_word(&mut e, "#vieetj "), "#việt ");
+    e.set_profile(gonhanh_core::api::DEFAULT_PROFILE);
+    assert_eq!(type_word(&mut e, "#vieetj "), "#vieetj ");
+}
@@ -0,0 +1,98 @@
+//! Tone and mark keys that change nothing: swallowed or typed per
+//! `ToneKeyPolicy`
+
+use gonhanh_core::api::ToneKeyPolicy;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+fn run(method: u8, policy: ToneKeyPolicy, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        e.set_tone_key_policy(policy);
+        assert_eq!(type_word(&mut e, input), *expected, "{policy:?} {input:?}");
+    }
+}
+
+/// Typed the same under every policy
+const COMMON: &[(&str, &str)] = &[
+    ("dduwowcj", "được"),
+    ("nuwowcs ", "nước "),
+    ("vieetj", "việt"),
+    ("bsa", "bsa"),
+    ("text", "tẽt"),
+    ("hello", "hello"),
+];
+
+#[test]
+fn smart_is_default() {
+    assert_eq!(Engine::new().tone_key_policy(), ToneKeyPolicy::Smart);
+    run(
+        0,
+        ToneKeyPolicy::Smart,
+        &[
+            ("dduwowwcj", "được"),
+            ("roofif", "rồi"),
+            ("vieetjw", "việtw"),
+        ],
+    );
+    run(1, ToneKeyPolicy::Smart, &[("u7o77", "ươ"), ("ba9", "ba9")]);
+}
+
+#[test]
+fn literal_types_repeated_keys() {
+    for policy in [
+        ToneKeyPolicy::Smart,
+        ToneKeyPolicy::Literal,
+        ToneKeyPolicy::Consume,
+    ] {
+        run(0, policy, COMMON);
+    }
+    run(
+        0,
+        ToneKeyPolicy::Literal,
+        &[
+            ("dduwowwcj", "đượwc"),
+            ("roofif", "rồif"),
+            ("vieetjw", "việtw"),
+        ],
+    );
+    // "u7o7" confirms the automatic horn; the third 7 is typed
+    run(
+        1,
+        ToneKeyPolicy::Literal,
+        &[("u7o7", "ươ"), ("u7o77", "ươ7")],
+    );
+}
+
+#[test]
+fn consume_swallows_keys_with_no_target() {
+    run(
+        0,
+        ToneKeyPolicy::Consume,
+        &[("dduwowwcj", "được"), ("vieetjw", "việt"), ("gisis", "gíi")],
+    );
+    // Vowel keys and keys before any vowel are still typed
+    run(
+        0,
+        ToneKeyPolicy::Consume,
+        &[("bias", "bía"), ("bsa", "bsa")],
+    );
+    run(
+        1,
+        ToneKeyPolicy::Consume,
+        &[("viet65", "việt"), ("ba6", "bâ")],
+    );
+}
+
+#[test]
+fn policy_follows_config() {
+    let mut e = Engine::new();
+    e.set_tone_key_policy(ToneKeyPolicy::Literal);
+    assert_eq!(e.config().tone_key_policy, ToneKeyPolicy::Literal);
+    let mut other = Engine::new();
+    other.set_config(&e.config());
+    assert_eq!(other.tone_key_policy(), ToneKeyPolicy::Literal);
+    assert_eq!(ToneKeyPolicy::from_u8(2), ToneKeyPolicy::Consume);
+    assert_eq!(ToneKeyPolicy::from_u8(9), ToneKeyPolicy::Smart);
+}
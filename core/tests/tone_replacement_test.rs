@@ -0,0 +1,136 @@
+//! Tone replacement: last tone wins
+//!
+//! A tone key typed while the syllable already carries a different tone
+//! replaces it in place ("hoas" + 'f' → "hoà"), for every pair of tones.
+//! Typing the same tone key twice is the only way to revert ("hoass" → "hoas").
+//! Stop finals (-c, -ch, -p, -t) only take sắc/nặng, so other tones there
+//! are typed as letters and the existing tone is kept.
+
+mod common;
+use common::{telex, vni};
+
+const TELEX_TONES: [&str; 5] = ["s", "f", "r", "x", "j"];
+const VNI_TONES: [&str; 5] = ["1", "2", "3", "4", "5"];
+
+/// (typed syllable, expected output for sắc/huyền/hỏi/ngã/nặng)
+const TELEX_OPEN: &[(&str, [&str; 5])] = &[
+    ("ha", ["há", "hà", "hả", "hã", "hạ"]),
+    ("hoa", ["hoá", "hoà", "hoả", "hoã", "hoạ"]),
+    ("tieeng", ["tiếng", "tiềng", "tiểng", "tiễng", "tiệng"]),
+    ("nguwowi", ["ngưới", "người", "ngưởi", "ngưỡi", "ngượi"]),
+];
+
+const VNI_OPEN: &[(&str, [&str; 5])] = &[
+    ("ha", ["há", "hà", "hả", "hã", "hạ"]),
+    ("hoa", ["hoá", "hoà", "hoả", "hoã", "hoạ"]),
+    ("tie6ng", ["tiếng", "tiềng", "tiểng", "tiễng", "tiệng"]),
+];
+
+/// Every ordered pair (first, second): the second tone replaces the first,
+/// the same key twice reverts to the letter
+fn pair_cases(tones: &[&str; 5], syllables: &[(&str, [&str; 5])]) -> Vec<(String, String)> {
+    let mut cases = Vec::new();
+    for (base, outputs) in syllables {
+        for (i, first) in tones.iter().enumerate() {
+            for (j, second) in tones.iter().enumerate() {
+                let input = format!("{base}{first}{second}");
+                let expected = if i == j {
+                    format!("{base}{first}")
+                } else {
+                    outputs[j].to_string()
+                };
+                cases.push((input, expected));
+            }
+        }
+    }
+    cases
+}
+
+fn as_refs(cases: &[(String, String)]) -> Vec<(&str, &str)> {
+    cases
+        .iter()
+        .map(|(i, e)| (i.as_str(), e.as_str()))
+        .collect()
+}
+
+#[test]
+fn telex_every_tone_pair_replaces() {
+    telex(&as_refs(&pair_cases(&TELEX_TONES, &TELEX_OPEN[..2])));
+}
+
+#[test]
+fn vni_every_tone_pair_replaces() {
+    vni(&as_refs(&pair_cases(&VNI_TONES, &VNI_OPEN[..2])));
+}
+
+/// Every ordered pair of different tones on a syllable with vowel modifiers
+fn replacement_cases(tones: &[&str; 5], syllables: &[(&str, [&str; 5])]) -> Vec<(String, String)> {
+    let mut cases = Vec::new();
+    for (base, outputs) in syllables {
+        for first in tones {
+            for (second, out) in tones.iter().zip(outputs) {
+                if second != first {
+                    cases.push((format!("{base}{first}{second}"), out.to_string()));
+                }
+            }
+        }
+    }
+    cases
+}
+
+#[test]
+fn replacement_keeps_vowel_modifiers() {
+    // Circumflex and horn survive any tone change
+    telex(&as_refs(&replacement_cases(&TELEX_TONES, &TELEX_OPEN[2..])));
+    vni(&as_refs(&replacement_cases(&VNI_TONES, &VNI_OPEN[2..])));
+}
+
+#[test]
+fn replacement_chains_keep_last_tone() {
+    telex(&[
+        ("hoasfrxj", "hoạ"),
+        ("hoajxrfs", "hoá"),
+        ("tieengsfr", "tiểng"),
+        ("nguwowifsj", "ngượi"),
+    ]);
+    vni(&[
+        ("hoa12345", "hoạ"),
+        ("hoa54321", "hoá"),
+        ("tie6ng123", "tiểng"),
+    ]);
+}
+
+#[test]
+fn stop_final_swaps_only_sac_and_nang() {
+    telex(&[
+        ("vieetsj", "việt"),
+        ("vieetjs", "viết"),
+        ("hocsj", "học"),
+        ("hocjs", "hóc"),
+        // Huyền/hỏi/ngã can't sit on a stop final: typed as letters
+        ("vieetsf", "viếtf"),
+        ("vieetjr", "việtr"),
+        ("vieetjx", "việtx"),
+    ]);
+    vni(&[
+        ("vie6t15", "việt"),
+        ("vie6t51", "viết"),
+        ("vie6t12", "viết2"),
+        ("vie6t53", "việt3"),
+    ]);
+}
+
+#[test]
+fn same_key_twice_reverts() {
+    telex(&[
+        ("hass", "has"),
+        ("haff", "haf"),
+        ("harr", "har"),
+        ("haxx", "hax"),
+        ("hajj", "haj"),
+        // Revert after a replacement reverts the current tone
+        ("hasff", "haf"),
+        ("hafss", "has"),
+    ]);
+    vni(&[("ha11", "ha1"), ("ha55", "ha5"), ("ha122", "ha2")]);
+}
@@ -0,0 +1,30 @@
+//! One tone per syllable: a second tone key must never put a second mark on
+//! another vowel of the same syllable. Only "gi" words could reach that state
+//! ("gis" → "gí", then 'i' + 's' → "gíí"); the key now falls through as a
+//! literal letter. Replacing the existing mark keeps working.
+
+mod common;
+use common::{telex, vni};
+
+#[test]
+fn second_tone_on_other_vowel_is_literal() {
+    telex(&[
+        ("gisis", "gíis"),
+        ("gifif", "gìif"),
+        ("girix", "gỉix"),
+        ("gijisn", "gịisn"),
+    ]);
+    vni(&[("gi1i1", "gíi1"), ("gi2i5", "gìi5")]);
+}
+
+#[test]
+fn tone_replacement_still_works() {
+    telex(&[
+        ("masf", "mà"),
+        ("hoasf", "hoà"),
+        ("muafs", "múa"),
+        ("gisf", "gì"),
+        ("tieengsf", "tiềng"),
+    ]);
+    vni(&[("ma12", "mà"), ("gi12", "gì")]);
+}
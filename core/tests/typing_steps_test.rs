@@ -0,0 +1,37 @@
+//! Screen after every keystroke (`test_typing!`)
+//!
+//! Final-state tables miss words that go wrong mid-way and recover by the
+//! end; these check the text the user sees at each step.
+
+use gonhanh_core::engine::Engine;
+use gonhanh_core::test_typing;
+
+#[test]
+fn telex_steps() {
+    test_typing!("b|b", "a|ba", "n|ban", "f|bàn");
+    test_typing!("v|v", "i|vi", "e|vie", "e|viê", "t|viêt", "j|việt");
+    // A repeated modifier reverts, then types itself
+    test_typing!("a|a", "a|â", "a|aa");
+    test_typing!("d|d", "d|đ", "u|đu", "w|đư", "o|đươ", "ng|đương", "f|đường");
+}
+
+#[test]
+fn vni_steps() {
+    let mut e = Engine::new();
+    e.set_method(1);
+    test_typing!(e; "vie|vie", "6|viê", "t|viêt", "5|việt");
+}
+
+#[test]
+fn steps_across_words() {
+    // Backspace after a space steps back into the committed word
+    test_typing!("vieetj|việt", " |việt ", "<|việt", "s|viết");
+    test_typing!("ddi |đi ", "hocj|đi học");
+}
+
+#[test]
+fn english_restored_at_the_boundary() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    test_typing!(e; "te|te", "x|tẽ", "t|text", " |text ");
+}
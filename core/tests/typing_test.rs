@@ -1375,6 +1375,31 @@ const VNI_INVALID_BREVE_DIPHTHONG: &[(&str, &str)] = &[
     ("la8i", "lăi"),
 ];
 
+// ============================================================
+// INVALID BREVE + FINAL (ănh, ăch)
+// ============================================================
+// Breve only closes with c, m, n, ng, p, t. When the final turns into nh/ch
+// the breve is reverted and its modifier key typed back as a letter; typing
+// the modifier after such a final leaves it literal.
+
+const TELEX_INVALID_BREVE_FINAL: &[(&str, &str)] = &[
+    ("awnh", "awnh"),
+    ("bawnh", "bawnh"),
+    ("tawch", "tawch"),
+    ("anhw", "anhw"),
+    ("achw", "achw"),
+    // Valid finals keep the breve
+    ("awng", "ăng"),
+    ("tawcs", "tắc"),
+];
+
+const VNI_INVALID_BREVE_FINAL: &[(&str, &str)] = &[
+    ("a8nh", "a8nh"),
+    ("ta8ch", "ta8ch"),
+    ("anh8", "anh8"),
+    ("a8ng", "ăng"),
+];
+
 // ============================================================
 // ENGLISH WORDS WITH AW PATTERN (should NOT transform)
 // ============================================================
@@ -1570,6 +1595,16 @@ fn vni_invalid_breve_diphthong() {
     vni(VNI_INVALID_BREVE_DIPHTHONG);
 }
 
+#[test]
+fn telex_invalid_breve_final() {
+    telex(TELEX_INVALID_BREVE_FINAL);
+}
+
+#[test]
+fn vni_invalid_breve_final() {
+    vni(VNI_INVALID_BREVE_FINAL);
+}
+
 // NOTE: Requires english_auto_restore to be enabled (experimental feature).
 #[test]
 fn telex_english_aw_words() {
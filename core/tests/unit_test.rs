@@ -267,6 +267,14 @@ const VNI_REVERT: &[(&str, &str)] = &[
     ("o77", "o7"),
     ("u77", "u7"),
     ("a88", "a8"),
+    ("d99", "d9"),
+    // Digits after a revert stay literal
+    ("a111", "a11"),
+    ("a112", "a12"),
+    ("a116", "a16"),
+    ("a661", "a61"),
+    ("ta11n", "ta1n"),
+    ("to66n", "to6n"),
 ];
 
 const VNI_UPPERCASE: &[(&str, &str)] = &[
@@ -327,6 +335,13 @@ const TELEX_WORDS: &[(&str, &str)] = &[
     ("hoas", "hoá"),
     ("quyf", "quỳ"),
     ("quys", "quý"),
+    // qu/gi initials: u/i belong to the initial, tone goes on the next vowel
+    ("gif", "gì"),
+    ("gias", "giá"),
+    ("giuowngf", "giường"),
+    ("gioongs", "giống"),
+    ("quyeets", "quyết"),
+    ("quoocs", "quốc"),
     ("mais", "mái"),
     ("maif", "mài"),
     ("ddois", "đói"),
@@ -339,6 +354,11 @@ const TELEX_WORDS: &[(&str, &str)] = &[
     ("ruwowuj", "rượu"),
     ("buwowms", "bướm"),
     ("nuwowcs", "nước"),
+    // Deferred horn on u: "uơ" until a final or glide follows
+    ("huow", "huơ"),
+    ("duowcj", "dược"),
+    ("nguowif", "người"),
+    ("huouw", "hươu"),
     // Compound iê
     ("vieetj", "việt"),
     ("tieengs", "tiếng"),
@@ -0,0 +1,127 @@
+//! Uppercase Tests - all-caps and title-case typing, ĐĐ, ƯƠ and every
+//! toned uppercase vowel, in composing and restore paths
+
+mod common;
+use common::{telex, vni};
+use gonhanh_core::api::{keys_for, mark_of, InputMethod};
+use gonhanh_core::engine::Engine;
+use gonhanh_core::utils::type_word;
+
+const TELEX_CAPS: &[(&str, &str)] = &[
+    ("DD", "Đ"),
+    ("Dd", "Đ"),
+    ("DDUWOWNGF", "ĐƯỜNG"),
+    ("DDuwowngf", "Đường"),
+    ("UWOW", "ƯƠ"),
+    ("NGUOWIF", "NGƯỜI"),
+    ("RUWOWUJ", "RƯỢU"),
+    ("HUWOWU", "HƯƠU"),
+    ("KHUYEENS", "KHUYẾN"),
+    ("QUOOCS", "QUỐC"),
+    ("GIAAYS", "GIẤY"),
+    ("VIEEJT", "VIỆT"),
+    ("VIETEJ", "VIỆT"),
+    ("TRUWOWNGF", "TRƯỜNG"),
+    // Lowercase modifier keys on an uppercase word (Shift, not Caps Lock)
+    ("VIEeTj", "VIỆT"),
+    ("DDUwOwNGf", "ĐƯỜNG"),
+];
+
+const VNI_CAPS: &[(&str, &str)] = &[
+    ("D9", "Đ"),
+    ("D9U7O7NG2", "ĐƯỜNG"),
+    ("D9u7o7ng2", "Đường"),
+    ("NGUO7I2", "NGƯỜI"),
+    ("VIE65T", "VIỆT"),
+    ("HUYE6N2", "HUYỀN"),
+];
+
+#[test]
+fn telex_all_caps() {
+    telex(TELEX_CAPS);
+}
+
+#[test]
+fn vni_all_caps() {
+    vni(VNI_CAPS);
+}
+
+/// Every uppercase vowel form (12 bases × 6 marks), typed from its hint keys
+#[test]
+fn every_uppercase_vowel() {
+    let vowels: Vec<char> = (0..0x2000)
+        .filter_map(char::from_u32)
+        .filter(|&c| c.is_uppercase() && mark_of(c).is_some())
+        .collect();
+    assert_eq!(vowels.len(), 72);
+    for (method, im) in [(0, InputMethod::Telex), (1, InputMethod::Vni)] {
+        for v in &vowels {
+            let word = v.to_string();
+            let keys: String = keys_for(&word, im).iter().map(|h| h.ch).collect();
+            assert!(!keys.is_empty(), "no keys for {v}");
+            let mut e = Engine::new();
+            e.set_method(method);
+            assert_eq!(type_word(&mut e, &keys), word, "method {method}: {keys:?}");
+        }
+    }
+}
+
+/// Dictionary syllables typed in all caps and title case match their
+/// lowercase result
+#[test]
+fn dictionary_syllables_in_caps() {
+    let content = include_str!("data/vietnamese_22k.txt");
+    let mut syllables: Vec<String> = content.split_whitespace().map(str::to_lowercase).collect();
+    syllables.sort();
+    syllables.dedup();
+    for (method, im) in [(0, InputMethod::Telex), (1, InputMethod::Vni)] {
+        let run = |input: &str| {
+            let mut e = Engine::new();
+            e.set_method(method);
+            type_word(&mut e, input)
+        };
+        for s in &syllables {
+            let keys: String = keys_for(s, im).iter().map(|h| h.ch).collect();
+            if keys.is_empty() || run(&keys) != *s {
+                continue;
+            }
+            assert_eq!(run(&keys.to_uppercase()), s.to_uppercase(), "{keys:?}");
+            let mut title = keys.clone();
+            title[..1].make_ascii_uppercase();
+            let mut expected = s.clone();
+            let first = expected.remove(0);
+            expected.insert_str(0, &first.to_uppercase().to_string());
+            assert_eq!(run(&title), expected, "{title:?}");
+        }
+    }
+}
+
+/// Words restored from the screen keep their case in the rebuilt keystrokes
+#[test]
+fn restored_caps_word_keystrokes() {
+    for (method, word, raw) in [
+        (0, "ĐƯỜNG", "DDUWOWNGF"),
+        (0, "VIỆT", "VIEETJ"),
+        (0, "Việt", "Vieetj"),
+        (1, "ĐƯỜNG", "D9U7O7NG2"),
+    ] {
+        let mut e = Engine::new();
+        e.set_method(method);
+        e.restore_word(word);
+        assert_eq!(e.status().raw_word, raw, "method {method}: {word}");
+    }
+}
+
+#[test]
+fn caps_escape_and_backspace_restore() {
+    for (input, expected) in [
+        ("VIEETJ\x1b", "VIEETJ"),
+        ("DDUWOWNGF\x1b", "DDUWOWNGF"),
+        ("DDAAYF <", "ĐẦY"),
+        ("VIEETJ <s", "VIẾT"),
+    ] {
+        let mut e = Engine::new();
+        e.set_esc_restore(true);
+        assert_eq!(type_word(&mut e, input), expected, "{input:?}");
+    }
+}
@@ -432,3 +432,201 @@ fn vietnamese_22k_compound() {
         MIN_PASS_RATE
     );
 }
+
+// ============================================================
+// ROUND-TRIP HARNESS (TELEX + VNI)
+// ============================================================
+
+/// Convert Vietnamese word to VNI input (tone at end of word)
+fn vn_to_vni(word: &str) -> String {
+    let mut base = String::new();
+    let mut tone: Option<char> = None;
+
+    for c in word.chars() {
+        let (base_char, mark, char_tone) = decompose_vn_char(c);
+        base.push(base_char);
+        match mark {
+            Some('a' | 'e' | 'o') => base.push('6'),
+            Some('d') => base.push('9'),
+            Some('w') if base_char.eq_ignore_ascii_case(&'a') => base.push('8'),
+            Some('w') => base.push('7'),
+            _ => {}
+        }
+        if char_tone.is_some() {
+            tone = char_tone;
+        }
+    }
+
+    if let Some(t) = tone {
+        base.push(match t {
+            's' => '1',
+            'f' => '2',
+            'r' => '3',
+            'x' => '4',
+            _ => '5',
+        });
+    }
+
+    base
+}
+
+/// Round-trip result for one input method
+struct RoundTrip {
+    name: &'static str,
+    passed: usize,
+    failures: Vec<(String, String, String)>, // (entry, input, actual)
+}
+
+impl RoundTrip {
+    fn pass_rate(&self) -> f64 {
+        let total = self.passed + self.failures.len();
+        if total > 0 {
+            (self.passed as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Loan words spelled with a double 'o' need "ooo" to type; not round-trippable
+fn is_double_o_loan(word: &str) -> bool {
+    [
+        "oo", "òo", "óo", "ỏo", "õo", "ọo", "ồo", "ốo", "ổo", "ỗo", "ộo",
+    ]
+    .iter()
+    .any(|p| word.contains(p))
+}
+
+/// Transliterate each entry syllable by syllable, type it, expect it back exactly
+fn round_trip(
+    name: &'static str,
+    entries: &[&str],
+    method: u8,
+    to_input: fn(&str) -> String,
+) -> RoundTrip {
+    let mut result = RoundTrip {
+        name,
+        passed: 0,
+        failures: Vec::new(),
+    };
+    for &entry in entries {
+        let input: Vec<String> = entry.split(' ').map(to_input).collect();
+        let input = input.join(" ");
+
+        // The list mixes tone styles (hoá / hóa): the entry must come out
+        // exactly under one of the two settings
+        let typed: Vec<String> = [true, false]
+            .iter()
+            .map(|&modern| {
+                let mut e = Engine::new();
+                e.set_method(method);
+                e.set_modern_tone(modern);
+                type_word(&mut e, &format!("{} ", input))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+
+        if typed.iter().any(|t| t == entry) {
+            result.passed += 1;
+        } else {
+            let actual = typed[0].clone();
+            result.failures.push((entry.to_string(), input, actual));
+        }
+    }
+    result
+}
+
+/// Escape a string for the JSON report
+fn json_str(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Write the machine-readable round-trip report
+fn write_round_trip_report(path: &str, skipped: usize, results: &[&RoundTrip]) {
+    let Ok(mut f) = File::create(path) else {
+        return;
+    };
+    let mut methods = Vec::new();
+    for r in results {
+        let failures: Vec<String> = r
+            .failures
+            .iter()
+            .map(|(entry, input, actual)| {
+                format!(
+                    "        {{\"entry\": {}, \"input\": {}, \"actual\": {}}}",
+                    json_str(entry),
+                    json_str(input),
+                    json_str(actual)
+                )
+            })
+            .collect();
+        methods.push(format!(
+            "    {}: {{\n      \"passed\": {},\n      \"failed\": {},\n      \"pass_rate\": {:.4},\n      \"failures\": [\n{}\n      ]\n    }}",
+            json_str(r.name),
+            r.passed,
+            r.failures.len(),
+            r.pass_rate(),
+            failures.join(",\n")
+        ));
+    }
+    let _ = writeln!(
+        f,
+        "{{\n  \"word_list\": \"tests/data/vietnamese_22k.txt\",\n  \"skipped\": {},\n  \"methods\": {{\n{}\n  }}\n}}",
+        skipped,
+        methods.join(",\n")
+    );
+}
+
+#[test]
+fn vietnamese_22k_round_trip() {
+    let content = include_str!("data/vietnamese_22k.txt");
+    let (entries, skipped): (Vec<&str>, Vec<&str>) = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .partition(|w| !is_double_o_loan(w));
+
+    let telex = round_trip("telex", &entries, 0, vn_to_telex);
+    let vni = round_trip("vni", &entries, 1, vn_to_vni);
+
+    println!("\n=== Vietnamese 22k Round Trip ===");
+    println!("Entries: {} (skipped {})", entries.len(), skipped.len());
+    for r in [&telex, &vni] {
+        println!(
+            "{:<6} passed {} failed {} ({:.2}%)",
+            r.name,
+            r.passed,
+            r.failures.len(),
+            r.pass_rate()
+        );
+    }
+
+    write_round_trip_report(
+        "tests/data/vietnamese_22k_roundtrip.json",
+        skipped.len(),
+        &[&telex, &vni],
+    );
+
+    // CI threshold: remaining failures are list anomalies (mixed tone styles
+    // in one entry, typos, English sentences)
+    const MIN_PASS_RATE: f64 = 99.9;
+    for r in [&telex, &vni] {
+        assert!(
+            r.pass_rate() >= MIN_PASS_RATE,
+            "{} round-trip pass rate {:.2}% is below threshold {:.1}%",
+            r.name,
+            r.pass_rate(),
+            MIN_PASS_RATE
+        );
+    }
+}